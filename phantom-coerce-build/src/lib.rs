@@ -0,0 +1,189 @@
+//! Build-script code generation for phantom-coerce.
+//!
+//! Structs with large coercion matrices can make `#[derive(Coerce)]` slow to expand, and the
+//! derive runs again on every incremental compile of the defining crate. This crate runs the
+//! same code generation from a build script instead, so the cost is only paid when the spec
+//! file changes.
+//!
+//! A spec file is Rust source holding struct definitions with their `#[coerce(...)]` (or
+//! `#[upcast(...)]`) attributes, written without `#[derive(Coerce)]`:
+//!
+//! ```rust,ignore
+//! // src/typed_path.coerce.rs
+//! #[coerce(
+//!     borrowed_from = "TypedPath<Absolute | Relative, File | Directory>",
+//!     borrowed_to = "TypedPath<UnknownBase, UnknownType>"
+//! )]
+//! pub struct TypedPath<Base, Type> {
+//!     base: PhantomData<Base>,
+//!     ty: PhantomData<Type>,
+//!     path: String,
+//! }
+//! ```
+//!
+//! The build script compiles it into `OUT_DIR`:
+//!
+//! ```rust,ignore
+//! // build.rs
+//! fn main() {
+//!     phantom_coerce_build::compile("src/typed_path.coerce.rs").unwrap();
+//! }
+//! ```
+//!
+//! And the crate includes the result wherever the struct should live, with the spec's
+//! dependencies in scope:
+//!
+//! ```rust,ignore
+//! use std::marker::PhantomData;
+//!
+//! include!(concat!(env!("OUT_DIR"), "/typed_path.coerce.rs"));
+//! ```
+//!
+//! Enable this crate's `parking_lot`, `ndarray`, `serde`, `tracing`, `schemars`, `sqlx`, `verify`,
+//! `audit`, `typewit`, or `forbid_unsafe` features to match the features enabled on
+//! `phantom-coerce`.
+
+use quote::ToTokens;
+use std::fmt;
+use std::io;
+use std::path::{Path, PathBuf};
+
+/// A spec file that couldn't be read, written, or expanded.
+#[derive(Debug)]
+pub enum Error {
+    /// Reading the spec or writing the output failed.
+    Io(io::Error),
+    /// The spec isn't valid Rust, or a struct's attributes were rejected.
+    Codegen(syn::Error),
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::Io(err) => write!(f, "phantom-coerce-build: {err}"),
+            Error::Codegen(err) => write!(f, "phantom-coerce-build: {err}"),
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::Io(err) => Some(err),
+            Error::Codegen(err) => Some(err),
+        }
+    }
+}
+
+impl From<io::Error> for Error {
+    fn from(err: io::Error) -> Self {
+        Error::Io(err)
+    }
+}
+
+impl From<syn::Error> for Error {
+    fn from(err: syn::Error) -> Self {
+        Error::Codegen(err)
+    }
+}
+
+/// Generate the spec file at `path` into `OUT_DIR`, under the same file name.
+///
+/// Relative paths are resolved against the crate root. Cargo is told to rerun the build script
+/// when the spec or a file it loads with `include = "..."` changes. Returns the path of the
+/// generated file.
+pub fn compile(path: impl AsRef<Path>) -> Result<PathBuf, Error> {
+    let path = path.as_ref();
+    let spec = match std::env::var_os("CARGO_MANIFEST_DIR") {
+        Some(root) if path.is_relative() => Path::new(&root).join(path),
+        _ => path.to_path_buf(),
+    };
+    println!("cargo:rerun-if-changed={}", spec.display());
+
+    let source = std::fs::read_to_string(&spec)?;
+    let generated = generate(&source)?;
+
+    // Included files are read while generating, so they're tracked like the spec itself
+    let root = std::env::var_os("CARGO_MANIFEST_DIR").unwrap_or_default();
+    for included in included_files(&syn::parse_file(&source)?) {
+        println!(
+            "cargo:rerun-if-changed={}",
+            Path::new(&root).join(included).display()
+        );
+    }
+
+    let out_dir = std::env::var_os("OUT_DIR")
+        .ok_or_else(|| io::Error::other("OUT_DIR is not set; call compile from a build script"))?;
+    let file_name = spec
+        .file_name()
+        .ok_or_else(|| io::Error::other(format!("{} is not a file", spec.display())))?;
+    let output = Path::new(&out_dir).join(file_name);
+    std::fs::write(&output, generated)?;
+    Ok(output)
+}
+
+/// Expand every struct in `source` that carries `#[coerce(...)]` or `#[upcast(...)]`.
+///
+/// The structs are emitted without those attributes, followed by their generated impls. All
+/// other items are kept as written.
+pub fn generate(source: &str) -> Result<String, Error> {
+    let mut file = syn::parse_file(source)?;
+
+    let mut generated = proc_macro2::TokenStream::new();
+    for item in &mut file.items {
+        let syn::Item::Struct(item) = item else {
+            continue;
+        };
+
+        let has = |name: &str| item.attrs.iter().any(|attr| attr.path().is_ident(name));
+        if has("upcast") {
+            generated.extend(phantom_coerce_core::derive_upcast(item.to_token_stream())?);
+        } else if has("coerce") {
+            generated.extend(phantom_coerce_core::derive_coerce(item.to_token_stream())?);
+        } else {
+            continue;
+        }
+
+        // Without a derive to claim them, the helper attributes must not reach the compiler
+        let is_helper = |attr: &syn::Attribute| {
+            attr.path().is_ident("coerce") || attr.path().is_ident("upcast")
+        };
+        item.attrs.retain(|attr| !is_helper(attr));
+        for field in item.fields.iter_mut() {
+            field.attrs.retain(|attr| !is_helper(attr));
+        }
+    }
+
+    let mut output = file.into_token_stream();
+    output.extend(generated);
+    Ok(output.to_string())
+}
+
+/// The `include = "..."` paths named by the spec's structs, relative to the crate root.
+fn included_files(file: &syn::File) -> Vec<String> {
+    let attrs = file.items.iter().flat_map(|item| match item {
+        syn::Item::Struct(item) => item.attrs.as_slice(),
+        _ => &[],
+    });
+    let metas = attrs
+        .filter(|attr| attr.path().is_ident("coerce"))
+        .filter_map(|attr| {
+            attr.parse_args_with(
+                syn::punctuated::Punctuated::<syn::Meta, syn::Token![,]>::parse_terminated,
+            )
+            .ok()
+        })
+        .flatten();
+    metas
+        .filter_map(|meta| match meta {
+            syn::Meta::NameValue(nv) if nv.path.is_ident("include") => match nv.value {
+                syn::Expr::Lit(syn::ExprLit {
+                    lit: syn::Lit::Str(path),
+                    ..
+                }) => Some(path.value()),
+                _ => None,
+            },
+            _ => None,
+        })
+        .collect()
+}
@@ -0,0 +1,3 @@
+fn main() {
+    phantom_coerce_build::compile("src/request.coerce.rs").unwrap();
+}
@@ -0,0 +1,9 @@
+//! Compiles a spec through `phantom_coerce_build` in `build.rs`, so the generated code is
+//! checked by rustc rather than only inspected as text.
+
+// Unlike derive output, included code is linted, and the tests only reach the public traits
+#![allow(dead_code)]
+
+use std::marker::PhantomData;
+
+include!(concat!(env!("OUT_DIR"), "/request.coerce.rs"));
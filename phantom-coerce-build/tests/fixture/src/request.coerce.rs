@@ -0,0 +1,11 @@
+pub struct Validated;
+pub struct Pending;
+pub struct AnyStatus;
+
+#[coerce(trait_vis = "pub")]
+#[coerce(owned_from = "Request<Validated | Pending>", owned_to = "Request<AnyStatus>")]
+#[coerce(include = "coercions/request.coerce")]
+pub struct Request<Status> {
+    pub status: PhantomData<Status>,
+    pub url: String,
+}
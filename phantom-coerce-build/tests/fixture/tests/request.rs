@@ -0,0 +1,28 @@
+use phantom_coerce::Coercible;
+use phantom_coerce_build_fixture::{
+    AnyStatus, CoerceClonedRequest, CoerceOwnedRequest, Pending, Request, Validated,
+};
+use std::marker::PhantomData;
+
+fn request<Status>(url: &str) -> Request<Status> {
+    Request {
+        status: PhantomData,
+        url: url.to_string(),
+    }
+}
+
+#[test]
+fn declared_coercions() {
+    let generic: Request<AnyStatus> = CoerceOwnedRequest::into_coerced(request::<Validated>("/a"));
+    assert_eq!(generic.url, "/a");
+
+    let generic: Request<AnyStatus> = Coercible::into_coerced(request::<Pending>("/b"));
+    assert_eq!(generic.url, "/b");
+}
+
+#[test]
+fn included_coercions() {
+    let pending = request::<Pending>("/c");
+    let generic: Request<AnyStatus> = CoerceClonedRequest::to_coerced(&pending);
+    assert_eq!((generic.url.as_str(), pending.url.as_str()), ("/c", "/c"));
+}
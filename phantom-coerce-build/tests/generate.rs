@@ -0,0 +1,58 @@
+use phantom_coerce_build::{Error, generate};
+
+const SPEC: &str = r#"
+use std::marker::PhantomData;
+
+pub struct Absolute;
+pub struct UnknownBase;
+
+#[coerce(borrowed_from = "TypedPath<Absolute>", borrowed_to = "TypedPath<UnknownBase>")]
+#[coerce(owned_from = "TypedPath<Absolute>", owned_to = "TypedPath<UnknownBase>")]
+pub struct TypedPath<Base> {
+    base: PhantomData<Base>,
+    path: String,
+}
+"#;
+
+#[test]
+fn generates_struct_and_impls() {
+    let output = generate(SPEC).unwrap();
+    let file = syn::parse_file(&output).unwrap();
+
+    let structs: Vec<_> = file
+        .items
+        .iter()
+        .filter_map(|item| match item {
+            syn::Item::Struct(item) => Some(item),
+            _ => None,
+        })
+        .collect();
+    let typed_path = structs
+        .iter()
+        .find(|item| item.ident == "TypedPath")
+        .unwrap();
+    assert!(typed_path.attrs.is_empty());
+
+    assert!(output.contains("CoerceRefTypedPath"));
+    assert!(output.contains("CoerceOwnedTypedPath"));
+}
+
+#[test]
+fn passes_through_plain_items() {
+    let output = generate("pub struct Plain; fn helper() {}").unwrap();
+    let file = syn::parse_file(&output).unwrap();
+    assert_eq!(file.items.len(), 2);
+}
+
+#[test]
+fn reports_invalid_specs() {
+    let spec = r#"
+        #[coerce(borrowed_from = "Present<A>")]
+        struct Present<T> { marker: std::marker::PhantomData<T> }
+    "#;
+    let err = generate(spec).unwrap_err();
+    assert!(matches!(err, Error::Codegen(_)));
+
+    let err = generate("struct {").unwrap_err();
+    assert!(matches!(err, Error::Codegen(_)));
+}
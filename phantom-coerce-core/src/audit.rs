@@ -0,0 +1,100 @@
+//! The `audit` feature's JSON manifest of generated coercions.
+//!
+//! Each derive in a crate adds its coercions to one manifest, rewritten after every
+//! expansion so it's complete once the crate has compiled. The first expansion in a compiler
+//! process starts the manifest afresh, dropping entries for coercions since removed.
+
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+use std::sync::{Mutex, PoisonError};
+
+/// One generated coercion, as listed in the manifest
+pub(crate) struct AuditEntry {
+    pub(crate) struct_name: String,
+    pub(crate) mode: &'static str,
+    pub(crate) source: String,
+    pub(crate) target: String,
+    /// Whether the generated impl reinterprets memory rather than rebuilding the value
+    pub(crate) uses_unsafe: bool,
+}
+
+/// Entries recorded so far in this process, per manifest path
+static RECORDED: Mutex<BTreeMap<PathBuf, Vec<String>>> = Mutex::new(BTreeMap::new());
+
+/// Where the crate being compiled keeps its manifest
+///
+/// `OUT_DIR` when the crate has a build script, and `target/` next to its `Cargo.toml`
+/// otherwise.
+fn manifest_path() -> Option<PathBuf> {
+    let dir = match std::env::var_os("OUT_DIR") {
+        Some(out_dir) => PathBuf::from(out_dir),
+        None => PathBuf::from(std::env::var_os("CARGO_MANIFEST_DIR")?).join("target"),
+    };
+    let name = std::env::var("CARGO_CRATE_NAME")
+        .or_else(|_| std::env::var("CARGO_PKG_NAME").map(|name| name.replace('-', "_")))
+        .ok()?;
+    Some(
+        dir.join("phantom-coerce-audit")
+            .join(format!("{name}.json")),
+    )
+}
+
+/// Add `entries` to the crate's manifest and rewrite it
+///
+/// Outside Cargo there's no crate to attribute the coercions to, so nothing is written.
+pub(crate) fn record(entries: &[AuditEntry]) -> std::io::Result<()> {
+    let Some(path) = manifest_path() else {
+        return Ok(());
+    };
+    let mut recorded = RECORDED.lock().unwrap_or_else(PoisonError::into_inner);
+    let listed = recorded.entry(path.clone()).or_default();
+    listed.extend(entries.iter().map(entry_json));
+
+    let name = path.file_stem().unwrap_or_default().to_string_lossy();
+    let mut json = format!(
+        "{{\n  \"crate\": {},\n  \"coercions\": [",
+        string_json(&name)
+    );
+    for (idx, entry) in listed.iter().enumerate() {
+        json.push_str(if idx == 0 { "\n" } else { ",\n" });
+        json.push_str(entry);
+    }
+    json.push_str(if listed.is_empty() {
+        "]\n}\n"
+    } else {
+        "\n  ]\n}\n"
+    });
+
+    // Written whole and renamed, so tooling never reads half a manifest
+    std::fs::create_dir_all(path.parent().unwrap())?;
+    let partial = path.with_extension("json.partial");
+    std::fs::write(&partial, json)?;
+    std::fs::rename(&partial, &path)
+}
+
+fn entry_json(entry: &AuditEntry) -> String {
+    format!(
+        "    {{\n      \"struct\": {},\n      \"mode\": {},\n      \"source\": {},\n      \"target\": {},\n      \"unsafe\": {}\n    }}",
+        string_json(&entry.struct_name),
+        string_json(entry.mode),
+        string_json(&entry.source),
+        string_json(&entry.target),
+        entry.uses_unsafe
+    )
+}
+
+fn string_json(value: &str) -> String {
+    let mut json = String::with_capacity(value.len() + 2);
+    json.push('"');
+    for c in value.chars() {
+        match c {
+            '"' => json.push_str("\\\""),
+            '\\' => json.push_str("\\\\"),
+            '\n' => json.push_str("\\n"),
+            c if c.is_control() => json.push_str(&format!("\\u{:04x}", c as u32)),
+            c => json.push(c),
+        }
+    }
+    json.push('"');
+    json
+}
@@ -0,0 +1,7790 @@
+//! Pattern parsing and code generation for phantom-coerce.
+//!
+//! Shared by the proc macros in `phantom-coerce-derive` and the build-script code generator in
+//! `phantom-coerce-build`. Each entry point takes the macro input and returns the expansion.
+//! The attribute syntax is documented on the macros themselves; the [`pattern`] module exposes
+//! the pattern parser for tools that inspect declarations.
+
+#[cfg(feature = "audit")]
+mod audit;
+pub mod pattern;
+
+use pattern::{expand_alternatives, normalize_pattern, parse_pattern, tokens_to_pattern};
+use quote::quote;
+use std::collections::HashMap;
+use syn::{
+    Attribute, Data, DeriveInput, Fields, Ident, Meta, PathArguments, Type, TypePath,
+    parse::Parser, spanned::Spanned, visit_mut::VisitMut,
+};
+
+#[derive(Debug, Clone)]
+struct CoercionSpec {
+    /// Source type patterns (parsed from `borrowed_from`, `owned_from`, `cloned_from`)
+    /// Each string may contain `|` for multiple alternatives like "Absolute | Relative"
+    from_patterns: Vec<String>,
+    /// Target type pattern (parsed from `borrowed_to`, `owned_to`, `cloned_to`)
+    to_pattern: String,
+    kind: CoercionMode,
+    /// Span of the `#[coerce(...)]` attribute, for errors found after parsing
+    span: proc_macro2::Span,
+    generate_asref: bool, // for borrowed only
+    /// Smart pointers that also get `AsRef<Target>` impls (`asref(Box, Rc, Arc)`)
+    asref_pointers: Vec<SmartPointer>,
+    /// Keep the source's type holes generic even though the target has none, so a single
+    /// impl covers every source marker (`#[derive(Upcast)]` without `from`)
+    generic_source: bool,
+    /// Hook called with `&Source` before each owned or cloned coercion (`guard = "path"`)
+    guard: Option<syn::Path>,
+    /// Symbol of an `extern "C"` function performing the coercion (`extern_c = "name"`)
+    extern_c: Option<Ident>,
+    /// Note attached to the coercion while it's being phased out (`deprecated = "..."`)
+    deprecated: Option<String>,
+    /// Skip expanded pairs whose source and target are the same type (`allow_noop`)
+    allow_noop: bool,
+    /// Skip the `Send`/`Sync`/`Unpin` equivalence check (`allow_auto_trait_change`)
+    allow_auto_trait_change: bool,
+    /// Const-generic condition under which the coercion applies (`when = "TO <= LEVEL"`)
+    when: Option<syn::Expr>,
+    /// Marker trait whose implementors the sources must all include (`covers_all = "..."`)
+    covers_all: Option<CoversAll>,
+    /// Extra where-clause predicates for the generated impls (`bound = "..."`)
+    bounds: Vec<syn::WherePredicate>,
+}
+
+/// A `covers_all = "[Param:] Trait"` check on one `#[coerce(...)]` attribute
+#[derive(Debug, Clone)]
+struct CoversAll {
+    /// The parameter the trait describes, inferred from the alternatives when absent
+    param: Option<Ident>,
+    marker_trait: syn::Path,
+    span: proc_macro2::Span,
+}
+
+/// Smart pointers whose `AsRef` impls are generated alongside the plain `asref` impl
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum SmartPointer {
+    Box,
+    Rc,
+    Arc,
+}
+
+impl SmartPointer {
+    const ALL: [SmartPointer; 3] = [SmartPointer::Box, SmartPointer::Rc, SmartPointer::Arc];
+
+    fn from_ident(ident: &Ident) -> Option<Self> {
+        match ident.to_string().as_str() {
+            "Box" => Some(SmartPointer::Box),
+            "Rc" => Some(SmartPointer::Rc),
+            "Arc" => Some(SmartPointer::Arc),
+            _ => None,
+        }
+    }
+
+    fn path(self) -> proc_macro2::TokenStream {
+        match self {
+            SmartPointer::Box => quote! { ::std::boxed::Box },
+            SmartPointer::Rc => quote! { ::std::rc::Rc },
+            SmartPointer::Arc => quote! { ::std::sync::Arc },
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+struct ParsedCoercion {
+    /// Source type with type holes resolved to generic parameters
+    source_type: Type,
+    /// Target type with type holes resolved to generic parameters
+    target_type: Type,
+    /// Indices of type parameters that should be preserved (type holes)
+    type_hole_positions: Vec<usize>,
+    /// The source keeps generic parameters the target fixes (see `CoercionSpec::generic_source`)
+    generic_source: bool,
+    /// Hook called before the coercion (see `CoercionSpec::guard`)
+    guard: Option<syn::Path>,
+    /// `extern "C"` shim symbol (see `CoercionSpec::extern_c`)
+    extern_c: Option<Ident>,
+    /// Deprecation note (see `CoercionSpec::deprecated`)
+    deprecated: Option<String>,
+    /// Changes auto traits on purpose (see `CoercionSpec::allow_auto_trait_change`)
+    allow_auto_trait_change: bool,
+    /// Const-generic condition (see `CoercionSpec::when`), checked when the impl is used
+    when: Option<syn::Expr>,
+    /// Const parameters the `when` rule binds by name, with their types
+    const_params: Vec<(Ident, Type)>,
+    /// Extra where-clause predicates (see `CoercionSpec::bounds`)
+    bounds: Vec<syn::WherePredicate>,
+}
+
+/// Which parts of the expansion to emit (`generate = "..."`)
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+enum Generate {
+    /// Traits, impls, and the inherent `coerce`/`into_coerced`/`to_coerced` methods
+    #[default]
+    All,
+    /// Traits and impls only, for structs with methods of the same names (`traits_only`)
+    TraitsOnly,
+    /// `AsRef` impls for every borrowed coercion, without inherent methods (`asref_only`)
+    AsrefOnly,
+}
+
+/// How the coercion traits and their impls appear in rustdoc (`docs = "..."`)
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+enum Docs {
+    /// Undocumented, listed wherever their visibility allows
+    #[default]
+    Plain,
+    /// `#[doc(hidden)]` on the traits, their impls, and the coercions table (`hidden`)
+    Hidden,
+    /// Doc comments on the traits and their methods (`shown`)
+    Shown,
+}
+
+/// Options that apply to the whole struct rather than to a single coercion
+#[derive(Debug, Clone, Default)]
+struct StructOptions {
+    /// Generic parameter whose marker tag is recorded by the generated `tagged` methods
+    tag_param: Option<Ident>,
+    /// Discriminant field read by the generated `Deserialize` for generic targets (`tag_field`)
+    tag_field: Option<(String, proc_macro2::Span)>,
+    /// Enum of the `tag` parameter's markers, parsed from and displayed as tags (`tag_enum`)
+    tag_enum: Option<Ident>,
+    /// Enums of each listed parameter's declared markers (`marker_enum(Param = "...")`)
+    marker_enums: Vec<(Ident, Ident)>,
+    /// `typewit::TypeFn`s over each listed parameter (`type_fn(Param = "...")`)
+    type_fns: Vec<(Ident, Ident)>,
+    /// Type aliases usable in patterns, also emitted as items (`alias(type X = ...;)`)
+    aliases: Vec<syn::ItemType>,
+    /// `AsRef` targets forwarded to the single data field (`forward_asref = "..."`)
+    forward_asref: Vec<(Type, proc_macro2::Span)>,
+    /// Wrapper types treated like `PhantomData` (`phantom_types = "..."`)
+    phantom_types: Vec<Ident>,
+    /// Data field the struct derefs to (`deref_inner = "..."`)
+    deref_inner: Option<Ident>,
+    /// Accessors returning a coerced reference to a single field (`project(...)`)
+    projections: Vec<Projection>,
+    /// Rebuild owned/cloned targets field-by-field instead of transmuting (`safe`)
+    safe: bool,
+    /// Assert matching layouts before each cast in debug builds (`layout_checks`)
+    layout_checks: bool,
+    /// Generate a `Debug` impl that prints the marker names (`debug`)
+    debug: bool,
+    /// `FromIterator<Source>` impls for collections of owned targets (`from_iterator`)
+    from_iterator: Option<proc_macro2::Span>,
+    /// `From<&Source>` impls for `Cow<Target>`, for pairs both borrowed and cloned (`cow`)
+    cow: Option<proc_macro2::Span>,
+    /// Generate a marker-independent `schemars::JsonSchema` impl (`json_schema`)
+    json_schema: Option<proc_macro2::Span>,
+    /// Forward `sqlx::Type`/`Encode`/`Decode` to the single data field (`sqlx`)
+    sqlx: Option<proc_macro2::Span>,
+    /// Canonical fully-generic form, for the `HasAnyMarker` impls (`erased = "..."`)
+    erased: Option<(Type, proc_macro2::Span)>,
+    /// Visibility of the generated traits, which are then sealed (`trait_vis = "pub"`)
+    trait_vis: Option<syn::Visibility>,
+    /// Replaces the struct name in the generated trait names (`trait_name = "..."`)
+    trait_name: Option<Ident>,
+    /// Prefix of the `verify` wrapper symbols, which otherwise collide between structs of the
+    /// same name (`verify_prefix = "..."`)
+    verify_prefix: Option<Ident>,
+    /// `Any` marker per type parameter, upcast from every marker (`any(Param = "...")`)
+    any: Vec<(Ident, Type)>,
+    /// Span of the `any(...)` list, for errors found after parsing
+    any_span: Option<proc_macro2::Span>,
+    /// `any(...)` upcasts may change auto traits (`allow_auto_trait_change` next to `any(...)`)
+    any_auto_trait_change: bool,
+    /// Type parameters no coercion may change (`#[coerce(fixed)]` on the parameter)
+    fixed: Vec<Ident>,
+    /// Which parts of the expansion to emit (`generate = "..."`)
+    generate: Generate,
+    /// How the coercion machinery appears in rustdoc (`docs = "..."`)
+    docs: Docs,
+    /// New names for the inherent `coerce`/`into_coerced`/`to_coerced` methods (`rename(...)`)
+    renames: Vec<(String, Ident)>,
+    /// Span of the `generate` option, for errors found after parsing
+    generate_span: Option<proc_macro2::Span>,
+    /// Coercions loaded from external spec files (`include = "..."`)
+    included: Vec<CoercionSpec>,
+    /// Absolute paths of the included files, tracked so edits trigger a rebuild
+    include_paths: Vec<String>,
+    /// Per-struct strictness of the expansion checks (`allow(...)` / `deny(...)`)
+    lints: Lints,
+    /// Allow `#[phantom_coerce::coercions(...)]` elsewhere in the crate to add coercions
+    /// (`extensible`)
+    extensible: bool,
+    /// Expanding coercions added by `#[phantom_coerce::coercions(...)]`: only the per-coercion
+    /// impls are emitted, since the derive already generated the rest
+    late: bool,
+}
+
+/// Whether a lint rejects the derive or lets it through
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum LintLevel {
+    Allow,
+    Deny,
+}
+
+/// Coercion count above which `deny(large_expansion)` rejects the derive
+const LARGE_EXPANSION_LIMIT: usize = 64;
+
+/// Expansion checks that can be dialed per struct
+#[derive(Debug, Clone, Copy)]
+struct Lints {
+    /// A pair whose source and target are the same type; allowed pairs are skipped
+    noop: LintLevel,
+    /// A pair declared more than once for the same mode; allowed repeats are dropped
+    duplicate_pair: LintLevel,
+    /// More than `LARGE_EXPANSION_LIMIT` coercions after expanding alternatives
+    large_expansion: LintLevel,
+    /// A pair whose changed markers differ in `Send`, `Sync`, or `Unpin`; allowed pairs
+    /// skip the compile-time check
+    auto_trait_change: LintLevel,
+    /// Coercions that lead from a type back to itself, making erasure reversible
+    coercion_cycle: LintLevel,
+    /// A source type in the coercion graph with no path to the `erased` form
+    unreachable_erased: LintLevel,
+}
+
+impl Default for Lints {
+    fn default() -> Self {
+        Self {
+            noop: LintLevel::Deny,
+            duplicate_pair: LintLevel::Deny,
+            large_expansion: LintLevel::Allow,
+            auto_trait_change: LintLevel::Deny,
+            coercion_cycle: LintLevel::Deny,
+            unreachable_erased: LintLevel::Deny,
+        }
+    }
+}
+
+impl Lints {
+    /// Set the level of each lint named in `allow(...)` or `deny(...)`
+    fn configure(&mut self, list: &syn::MetaList) -> syn::Result<()> {
+        let level = if list.path.is_ident("allow") {
+            LintLevel::Allow
+        } else {
+            LintLevel::Deny
+        };
+        let names = list.parse_args_with(
+            syn::punctuated::Punctuated::<Ident, syn::Token![,]>::parse_terminated,
+        )?;
+        if names.is_empty() {
+            return Err(syn::Error::new_spanned(
+                list,
+                "Expected at least one lint name",
+            ));
+        }
+        for name in names {
+            let lint = if name == "noop" {
+                &mut self.noop
+            } else if name == "duplicate_pair" {
+                &mut self.duplicate_pair
+            } else if name == "large_expansion" {
+                &mut self.large_expansion
+            } else if name == "auto_trait_change" {
+                &mut self.auto_trait_change
+            } else if name == "coercion_cycle" {
+                &mut self.coercion_cycle
+            } else if name == "unreachable_erased" {
+                &mut self.unreachable_erased
+            } else {
+                return Err(syn::Error::new_spanned(
+                    &name,
+                    "Unknown lint: expected 'noop', 'duplicate_pair', 'large_expansion', 'auto_trait_change', 'coercion_cycle', or 'unreachable_erased'",
+                ));
+            };
+            *lint = level;
+        }
+        Ok(())
+    }
+}
+
+/// A field accessor returning `&Target` via the field type's borrowed coercion
+#[derive(Debug, Clone)]
+struct Projection {
+    field: Ident,
+    target: Type,
+    /// Accessor name, defaulting to `{field}_coerced`
+    method: Ident,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum CoercionMode {
+    Borrowed,
+    Owned,
+    Cloned,
+}
+
+/// Expand `#[derive(Coerce)]`
+pub fn derive_coerce(input: proc_macro2::TokenStream) -> syn::Result<proc_macro2::TokenStream> {
+    let input = syn::parse2::<DeriveInput>(input)?;
+    impl_coerce(&input, Vec::new())
+}
+
+/// Remove `#[coerce(...)]` attributes from generic parameters, which would otherwise be copied
+/// into every generated impl header where no derive claims them
+fn strip_param_attrs(generics: &mut syn::Generics) {
+    for param in &mut generics.params {
+        if let syn::GenericParam::Type(param) = param {
+            param.attrs.retain(|attr| !attr.path().is_ident("coerce"));
+        }
+    }
+}
+
+/// Expand a struct from its `#[coerce(...)]` attributes plus any already-parsed specs
+fn impl_coerce(
+    input: &DeriveInput,
+    mut coercion_specs: Vec<CoercionSpec>,
+) -> syn::Result<proc_macro2::TokenStream> {
+    let original = input;
+    let Data::Struct(data_struct) = &input.data else {
+        return Err(syn::Error::new_spanned(
+            input,
+            "#[derive(Coerce)] can only be applied to structs",
+        ));
+    };
+
+    let Fields::Named(fields) = &data_struct.fields else {
+        return Err(syn::Error::new_spanned(
+            &data_struct.fields,
+            "#[derive(Coerce)] requires named fields",
+        ));
+    };
+
+    // Validate field-level options up front, even on fields that never change type
+    for field in &fields.named {
+        is_deep_field(field)?;
+    }
+
+    // Parse coerce attributes and expand into concrete coercion instances
+    let mut options = StructOptions::default();
+    for attr in &input.attrs {
+        if attr.path().is_ident("coerce")
+            && let Some(spec) = parse_coerce_attr(attr, &mut options)?
+        {
+            coercion_specs.push(spec);
+        }
+    }
+    for param in input.generics.type_params() {
+        parse_param_attrs(param, &mut options)?;
+    }
+    let mut input = input.clone();
+    strip_param_attrs(&mut input.generics);
+    mark_phantom_fields(&mut input, &options.phantom_types);
+    let input = &input;
+    let Data::Struct(syn::DataStruct {
+        fields: Fields::Named(fields),
+        ..
+    }) = &input.data
+    else {
+        unreachable!("checked above");
+    };
+
+    coercion_specs.append(&mut options.included);
+
+    if let Some(span) = options.any_span {
+        if !coercion_specs.is_empty() {
+            return Err(syn::Error::new(
+                span,
+                "any(...) generates `coerce` and `into_coerced` itself, so it can't be combined with declared coercions",
+            ));
+        }
+        return generate_any_upcasts(input, fields, &options, span);
+    }
+
+    // A struct may only project coerced fields without declaring coercions of its own
+    if coercion_specs.is_empty() && options.projections.is_empty() {
+        return Err(syn::Error::new_spanned(
+            input,
+            "#[derive(Coerce)] requires at least one #[coerce(...)] attribute",
+        ));
+    }
+
+    let extend_macro = generate_extend_macro(original, &coercion_specs, options.extensible);
+    let mut output = generate_coercions(input, fields, &coercion_specs, options)?;
+    output.extend(extend_macro);
+    Ok(output)
+}
+
+/// Name of the hidden macro through which `#[phantom_coerce::coercions]` reaches a struct
+fn extend_macro_name(struct_name: &Ident) -> Ident {
+    Ident::new(
+        &format!("__phantom_coerce_extend_{}", struct_name),
+        struct_name.span(),
+    )
+}
+
+/// Generate the crate-visible `macro_rules!` that `#[phantom_coerce::coercions]` invokes
+///
+/// It hands the original struct definition and its declared modes back to
+/// `__extend_coercions!`, which validates and generates the added coercions the way the derive
+/// would. Structs without `extensible` get a macro that reports how to opt in.
+fn generate_extend_macro(
+    input: &DeriveInput,
+    specs: &[CoercionSpec],
+    extensible: bool,
+) -> proc_macro2::TokenStream {
+    let struct_name = &input.ident;
+    let macro_name = extend_macro_name(struct_name);
+    let body = if extensible {
+        let modes = [
+            (CoercionMode::Borrowed, quote! { borrowed }),
+            (CoercionMode::Owned, quote! { owned }),
+            (CoercionMode::Cloned, quote! { cloned }),
+        ]
+        .into_iter()
+        .filter(|(mode, _)| specs.iter().any(|spec| spec.kind == *mode))
+        .map(|(_, name)| name);
+        quote! {
+            ::phantom_coerce::__extend_coercions! { { #input } [#(#modes)*] $($tokens)* }
+        }
+    } else {
+        let message = format!(
+            "'{struct_name}' doesn't accept coercions declared elsewhere: add #[coerce(extensible)] to its derive"
+        );
+        quote! { ::core::compile_error!(#message); }
+    };
+
+    quote! {
+        #[doc(hidden)]
+        macro_rules! #macro_name {
+            ($($tokens:tt)*) => {
+                #body
+            };
+        }
+
+        #[allow(unused_imports)]
+        pub(crate) use #macro_name;
+    }
+}
+
+/// Expand `#[phantom_coerce::coercions(...)]` on an inherent impl block of a derived struct
+///
+/// The impl block is kept as written; its self type locates the struct's hidden extension
+/// macro and generated traits, the way `for_each_coercion!` finds its macro.
+pub fn coercions(
+    attr: proc_macro2::TokenStream,
+    item: proc_macro2::TokenStream,
+) -> syn::Result<proc_macro2::TokenStream> {
+    let item = syn::parse2::<syn::ItemImpl>(item)?;
+    if let Some((_, path, _)) = &item.trait_ {
+        return Err(syn::Error::new_spanned(
+            path,
+            "#[phantom_coerce::coercions] goes on an inherent impl block, such as `impl<Base, Type> TypedPath<Base, Type> {}`",
+        ));
+    }
+    let Type::Path(TypePath { qself: None, path }) = &*item.self_ty else {
+        return Err(syn::Error::new_spanned(
+            &item.self_ty,
+            "#[phantom_coerce::coercions] expects the impl block's type to be a struct deriving Coerce",
+        ));
+    };
+
+    let mut macro_path = path.clone();
+    let last = macro_path.segments.last_mut().unwrap();
+    last.ident = extend_macro_name(&last.ident);
+    last.arguments = PathArguments::None;
+
+    // The traits are found next to the struct, or in the current module for a bare name
+    let mut module = path.clone();
+    module.segments.pop();
+    module.segments.pop_punct();
+    let module = if module.segments.is_empty() {
+        quote! { self }
+    } else {
+        quote! { #module }
+    };
+
+    Ok(quote! {
+        #item
+        #macro_path! { [#module] #attr }
+    })
+}
+
+/// Struct options `#[phantom_coerce::defaults(...)]` can set for a whole module
+const DEFAULT_OPTIONS: &[&str] = &[
+    "trait_vis",
+    "phantom_types",
+    "generate",
+    "rename",
+    "safe",
+    "layout_checks",
+    "cow",
+    "debug",
+    "json_schema",
+    "sqlx",
+    "extensible",
+    "docs",
+    "verify_prefix",
+    "allow",
+    "deny",
+];
+
+/// Expand `#[phantom_coerce::defaults(...)]` on an inline module
+///
+/// Every struct deriving `Coerce` in the module, including those in nested inline modules,
+/// gets each option it doesn't set itself. The options are inserted ahead of the struct's own
+/// `#[coerce(...)]` attributes so its lint levels still win, and `asref` is added to each of
+/// its borrowed coercions instead.
+pub fn defaults(
+    attr: proc_macro2::TokenStream,
+    item: proc_macro2::TokenStream,
+) -> syn::Result<proc_macro2::TokenStream> {
+    let mut module = syn::parse2::<syn::ItemMod>(item)?;
+    let options = parse_defaults(attr)?;
+    let Some((_, items)) = &mut module.content else {
+        return Err(syn::Error::new_spanned(
+            &module,
+            "#[phantom_coerce::defaults] goes on an inline module, such as `mod paths { ... }`",
+        ));
+    };
+    apply_defaults(items, &options);
+    Ok(quote! { #module })
+}
+
+/// Parse the options of `#[phantom_coerce::defaults(...)]`, rejecting per-struct ones
+fn parse_defaults(attr: proc_macro2::TokenStream) -> syn::Result<Vec<Meta>> {
+    let parser = syn::punctuated::Punctuated::<Meta, syn::Token![,]>::parse_terminated;
+    let options: Vec<Meta> = parser.parse2(attr)?.into_iter().collect();
+    for option in &options {
+        let path = option.path();
+        if !path.is_ident("asref") && !DEFAULT_OPTIONS.iter().any(|name| path.is_ident(name)) {
+            return Err(syn::Error::new_spanned(
+                path,
+                format!(
+                    "Expected 'asref' or a struct option shared across structs: '{}'",
+                    DEFAULT_OPTIONS.join("', '")
+                ),
+            ));
+        }
+    }
+    Ok(options)
+}
+
+/// Seed the defaults into each deriving struct, recursing into nested inline modules
+fn apply_defaults(items: &mut [syn::Item], defaults: &[Meta]) {
+    for item in items {
+        match item {
+            syn::Item::Struct(item) if derives_coerce(&item.attrs) => {
+                seed_defaults(&mut item.attrs, defaults);
+            }
+            syn::Item::Mod(module) => {
+                // A nested `defaults` attribute expands after this one, so hand it the outer
+                // defaults to merge with its own rather than seeding them first
+                let nested = module
+                    .attrs
+                    .iter_mut()
+                    .find_map(|attr| match &mut attr.meta {
+                        Meta::List(list)
+                            if list
+                                .path
+                                .segments
+                                .last()
+                                .is_some_and(|s| s.ident == "defaults") =>
+                        {
+                            Some(list)
+                        }
+                        _ => None,
+                    });
+                if let Some(list) = nested {
+                    merge_defaults(list, defaults);
+                } else if let Some((_, items)) = &mut module.content {
+                    apply_defaults(items, defaults);
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Whether a struct's attributes include `#[derive(..., Coerce, ...)]`
+fn derives_coerce(attrs: &[Attribute]) -> bool {
+    attrs
+        .iter()
+        .filter(|attr| attr.path().is_ident("derive"))
+        .filter_map(|attr| {
+            attr.parse_args_with(
+                syn::punctuated::Punctuated::<syn::Path, syn::Token![,]>::parse_terminated,
+            )
+            .ok()
+        })
+        .flatten()
+        .any(|path| path.segments.last().is_some_and(|s| s.ident == "Coerce"))
+}
+
+/// Whether a default is a lint level, which is layered rather than replaced
+fn is_lint_option(option: &Meta) -> bool {
+    option.path().is_ident("allow") || option.path().is_ident("deny")
+}
+
+/// The options written in a struct's `#[coerce(...)]` attributes
+fn coerce_options(attr: &Attribute) -> Vec<Meta> {
+    if !attr.path().is_ident("coerce") {
+        return Vec::new();
+    }
+    attr.parse_args_with(syn::punctuated::Punctuated::<Meta, syn::Token![,]>::parse_terminated)
+        .map(|options| options.into_iter().collect())
+        .unwrap_or_default()
+}
+
+/// Insert the defaults a struct doesn't set into its attributes
+fn seed_defaults(attrs: &mut Vec<Attribute>, defaults: &[Meta]) {
+    let own: Vec<Meta> = attrs.iter().flat_map(coerce_options).collect();
+    let sets = |option: &Meta| own.iter().any(|own| own.path() == option.path());
+
+    let seeded: Vec<Attribute> = defaults
+        .iter()
+        .filter(|option| !option.path().is_ident("asref"))
+        .filter(|option| is_lint_option(option) || !sets(option))
+        .map(|option| syn::parse_quote! { #[coerce(#option)] })
+        .collect();
+    let position = attrs
+        .iter()
+        .position(|attr| attr.path().is_ident("coerce"))
+        .unwrap_or(attrs.len());
+    attrs.splice(position..position, seeded);
+
+    let Some(asref) = defaults
+        .iter()
+        .find(|option| option.path().is_ident("asref"))
+    else {
+        return;
+    };
+    for attr in attrs.iter_mut() {
+        let mut options = coerce_options(attr);
+        let borrowed = options.iter().any(|option| {
+            option.path().is_ident("borrowed_from") || option.path().is_ident("borrowed_to")
+        });
+        if !borrowed || options.iter().any(|option| option.path().is_ident("asref")) {
+            continue;
+        }
+        options.push(asref.clone());
+        if let Meta::List(list) = &mut attr.meta {
+            list.tokens = quote! { #(#options),* };
+        }
+    }
+}
+
+/// Fold outer defaults into a nested `defaults(...)` list, keeping the nested options
+fn merge_defaults(list: &mut syn::MetaList, defaults: &[Meta]) {
+    let Ok(nested) =
+        list.parse_args_with(syn::punctuated::Punctuated::<Meta, syn::Token![,]>::parse_terminated)
+    else {
+        return;
+    };
+    let options = defaults
+        .iter()
+        .filter(|option| {
+            is_lint_option(option) || !nested.iter().any(|own| own.path() == option.path())
+        })
+        .chain(&nested);
+    list.tokens = quote! { #(#options),* };
+}
+
+/// The input `__extend_coercions!` receives from a struct's extension macro
+struct ExtendCoercions {
+    input: DeriveInput,
+    modes: Vec<CoercionMode>,
+    module: syn::Path,
+    specs: Vec<CoercionSpec>,
+}
+
+impl syn::parse::Parse for ExtendCoercions {
+    fn parse(input: syn::parse::ParseStream) -> syn::Result<Self> {
+        let content;
+        syn::braced!(content in input);
+        let derive_input: DeriveInput = content.parse()?;
+
+        let content;
+        syn::bracketed!(content in input);
+        let mut modes = Vec::new();
+        while !content.is_empty() {
+            let mode: Ident = content.parse()?;
+            modes.push(match mode.to_string().as_str() {
+                "borrowed" => CoercionMode::Borrowed,
+                "owned" => CoercionMode::Owned,
+                _ => CoercionMode::Cloned,
+            });
+        }
+
+        let content;
+        syn::bracketed!(content in input);
+        let module = content.call(syn::Path::parse_mod_style)?;
+
+        let mut specs = Vec::new();
+        while !input.is_empty() {
+            specs.push(parse_block_coercion(input)?);
+        }
+
+        Ok(ExtendCoercions {
+            input: derive_input,
+            modes,
+            module,
+            specs,
+        })
+    }
+}
+
+/// Expand the coercions added by `#[phantom_coerce::coercions(...)]`
+///
+/// Options come from the struct's own attributes, but only the added coercions are generated,
+/// as impls of the traits the derive already defined.
+pub fn extend_coercions(input: proc_macro2::TokenStream) -> syn::Result<proc_macro2::TokenStream> {
+    let ExtendCoercions {
+        input,
+        modes,
+        module,
+        specs,
+    } = syn::parse2(input)?;
+    let struct_name = &input.ident;
+    let Data::Struct(data_struct) = &input.data else {
+        return Err(syn::Error::new_spanned(&input, "Expected a struct"));
+    };
+    let Fields::Named(_) = &data_struct.fields else {
+        return Err(syn::Error::new_spanned(
+            &data_struct.fields,
+            "Expected named fields",
+        ));
+    };
+
+    let mut options = StructOptions::default();
+    for attr in &input.attrs {
+        if attr.path().is_ident("coerce") {
+            parse_coerce_attr(attr, &mut options)?;
+        }
+    }
+    for param in input.generics.type_params() {
+        parse_param_attrs(param, &mut options)?;
+    }
+    let mut input = input.clone();
+    strip_param_attrs(&mut input.generics);
+    mark_phantom_fields(&mut input, &options.phantom_types);
+    let Data::Struct(syn::DataStruct {
+        fields: Fields::Named(fields),
+        ..
+    }) = &input.data
+    else {
+        unreachable!("checked above");
+    };
+    options.late = true;
+
+    if specs.is_empty() {
+        return Err(syn::Error::new(
+            proc_macro2::Span::call_site(),
+            "Expected at least one '<mode> <from> => <to>;' coercion",
+        ));
+    }
+    // Safe mode rebuilds the target from its fields, which may be private to the struct's module
+    if options.safe || cfg!(feature = "forbid_unsafe") {
+        return Err(syn::Error::new(
+            specs[0].span,
+            format!("Coercions of '{struct_name}' can't be added from elsewhere in safe mode"),
+        ));
+    }
+    let mut imports = vec![quote! { #struct_name }];
+    for spec in &specs {
+        if !modes.contains(&spec.kind) {
+            let mode = match spec.kind {
+                CoercionMode::Borrowed => "borrowed",
+                CoercionMode::Owned => "owned",
+                CoercionMode::Cloned => "cloned",
+            };
+            return Err(syn::Error::new(
+                spec.span,
+                format!(
+                    "'{struct_name}' declares no {mode} coercions of its own, so there are no {mode} methods to extend. Declare at least one on the struct"
+                ),
+            ));
+        }
+    }
+    for mode in &modes {
+        let trait_name = coercion_trait_name(struct_name, &options, mode);
+        imports.push(quote! { #trait_name });
+    }
+    if options.extensible {
+        let seal = seal_module_name(struct_name);
+        imports.push(quote! { #seal });
+    }
+
+    let generated = generate_coercions(&input, fields, &specs, options)?;
+    Ok(quote! {
+        const _: () = {
+            #[allow(unused_imports)]
+            use #module::{#(#imports),*};
+            #generated
+        };
+    })
+}
+
+/// Generate the `any(Param = "Marker")` upcasts: every marker of each listed parameter coerces
+/// into that parameter's `Any` marker, independently of the others
+///
+/// Plain impls would overlap wherever a source parameter is already `Any`, so each impl also
+/// takes an index of `Keep`/`Erase` choices (see `__private::AnyParam`). The index is inferred
+/// from the target except where both choices agree (see `phantom_coerce::any`).
+fn generate_any_upcasts(
+    input: &DeriveInput,
+    fields: &syn::FieldsNamed,
+    options: &StructOptions,
+    span: proc_macro2::Span,
+) -> syn::Result<proc_macro2::TokenStream> {
+    let struct_name = &input.ident;
+    let generics = &input.generics;
+
+    let mut markers = Vec::new();
+    for (param, any) in &options.any {
+        let Some(type_param) = generics.type_params().find(|tp| tp.ident == *param) else {
+            return Err(syn::Error::new_spanned(
+                param,
+                format!("'{}' is not a type parameter of '{}'", param, struct_name),
+            ));
+        };
+        if markers
+            .iter()
+            .any(|(listed, _, _): &(&syn::TypeParam, _, _)| listed.ident == *param)
+        {
+            return Err(syn::Error::new_spanned(
+                param,
+                "Parameter listed twice in any(...)",
+            ));
+        }
+        // Only marker parameters can change without touching the data
+        if let Some(field) = fields
+            .named
+            .iter()
+            .find(|field| !is_phantom_field(field) && mentions_ident(quote! { #field }, param))
+        {
+            return Err(syn::Error::new_spanned(
+                field,
+                format!(
+                    "any({} = ...) requires '{}' to appear only in PhantomData fields",
+                    param, param
+                ),
+            ));
+        }
+        markers.push((type_param, any, markers.len()));
+    }
+
+    // Fresh target and index parameters for each listed parameter
+    let mut impl_generics = generics.clone();
+    let mut target_args = Vec::new();
+    let mut indices = Vec::new();
+    let mut bounds = Vec::new();
+    for param in &generics.params {
+        match param {
+            syn::GenericParam::Lifetime(lifetime) => {
+                let lifetime = &lifetime.lifetime;
+                target_args.push(quote! { #lifetime });
+            }
+            syn::GenericParam::Const(param) => {
+                let ident = &param.ident;
+                target_args.push(quote! { #ident });
+            }
+            syn::GenericParam::Type(param) => {
+                let ident = &param.ident;
+                let Some((_, any, position)) = markers.iter().find(|(tp, _, _)| tp.ident == *ident)
+                else {
+                    target_args.push(quote! { #ident });
+                    continue;
+                };
+                let target = quote::format_ident!("__CoerceTarget{}", position);
+                let index = quote::format_ident!("__CoerceIndex{}", position);
+                let target_bounds = &param.bounds;
+                impl_generics
+                    .params
+                    .push(syn::parse_quote! { #target: #target_bounds });
+                impl_generics.params.push(syn::parse_quote! { #index });
+                bounds.push(quote! {
+                    #ident: ::phantom_coerce::__private::AnyParam<#target, #any, #index>
+                });
+                // Whether the parameter is kept or erased, it mustn't gain what `Any` has
+                if options.lints.auto_trait_change == LintLevel::Deny
+                    && !options.any_auto_trait_change
+                {
+                    if mentions_type_param(generics, &[any]) {
+                        return Err(syn::Error::new_spanned(
+                            any,
+                            "any(...) can't compare the auto traits of a generic marker. Add `allow_auto_trait_change` to the any(...) attribute",
+                        ));
+                    }
+                    let source: Type = syn::parse_quote! { #ident };
+                    bounds.extend(
+                        auto_trait_bounds(&[&source], &[any])
+                            .into_iter()
+                            .map(|predicate| quote! { #predicate }),
+                    );
+                }
+                target_args.push(quote! { #target });
+                indices.push(index);
+            }
+        }
+    }
+    if markers.is_empty() {
+        return Err(syn::Error::new(
+            span,
+            "any(...) requires at least one parameter",
+        ));
+    }
+    impl_generics
+        .make_where_clause()
+        .predicates
+        .extend(bounds.iter().map(|bound| -> syn::WherePredicate {
+            syn::parse_quote! { #bound }
+        }));
+
+    let target = quote! { #struct_name<#(#target_args),*> };
+    let index = quote! { (#(#indices,)*) };
+    let (_, ty_generics, where_clause) = generics.split_for_impl();
+    let (any_impl_generics, _, any_where_clause) = impl_generics.split_for_impl();
+    let (impl_generics, _, _) = generics.split_for_impl();
+
+    if options.generate == Generate::AsrefOnly {
+        return Err(syn::Error::new(
+            options.generate_span.unwrap_or(span),
+            "generate = \"asref_only\" needs declared borrowed coercions, which any(...) can't be combined with",
+        ));
+    }
+    let inherent_methods = options.generate == Generate::All;
+
+    let safe = options.safe || cfg!(feature = "forbid_unsafe");
+    let mut output = generate_cast_helpers(struct_name, generics, fields, options, !safe, !safe);
+
+    let vis = &options.trait_vis;
+    if !safe {
+        let trait_name = coercion_trait_name(struct_name, options, &CoercionMode::Borrowed);
+        output.extend(quote! {
+            #vis trait #trait_name<Output: ?::core::marker::Sized, Index> {
+                fn coerce(&self) -> &Output;
+            }
+
+            impl #any_impl_generics ::phantom_coerce::__private::DeclaredCoercion<
+                ::phantom_coerce::__private::Indexed<::phantom_coerce::__private::Borrowed, #index>,
+                #target,
+            > for #struct_name #ty_generics #any_where_clause {}
+
+            impl #any_impl_generics #trait_name<#target, #index> for #struct_name #ty_generics #any_where_clause {
+                fn coerce(&self) -> &#target {
+                    // SAFETY: Only the listed parameters change, and they appear only in
+                    // PhantomData fields (see `__phantom_coerce_cast_ref`).
+                    unsafe {
+                        Self::__phantom_coerce_cast_ref::<
+                            ::phantom_coerce::__private::Indexed<::phantom_coerce::__private::Borrowed, #index>,
+                            #target,
+                        >(self)
+                    }
+                }
+            }
+
+        });
+        if inherent_methods {
+            output.extend(quote! {
+                impl #impl_generics #struct_name #ty_generics #where_clause {
+                    fn coerce<__CoerceTarget, __CoerceIndex>(&self) -> &__CoerceTarget
+                    where
+                        Self: #trait_name<__CoerceTarget, __CoerceIndex>,
+                        __CoerceTarget: ?::core::marker::Sized,
+                    {
+                        #trait_name::coerce(self)
+                    }
+                }
+            });
+        }
+    }
+
+    let trait_name = coercion_trait_name(struct_name, options, &CoercionMode::Owned);
+    let body = if safe {
+        let rebuild = generate_rebuild(struct_name, fields, &[]);
+        quote! {
+            let source = self;
+            #rebuild
+        }
+    } else {
+        quote! {
+            // SAFETY: Only the listed parameters change, and they appear only in PhantomData
+            // fields (see `__phantom_coerce_cast`).
+            unsafe {
+                Self::__phantom_coerce_cast::<
+                    ::phantom_coerce::__private::Indexed<::phantom_coerce::__private::Owned, #index>,
+                    #target,
+                >(self)
+            }
+        }
+    };
+    output.extend(quote! {
+        #vis trait #trait_name<Output, Index> {
+            fn into_coerced(self) -> Output;
+        }
+
+        impl #any_impl_generics ::phantom_coerce::__private::DeclaredCoercion<
+            ::phantom_coerce::__private::Indexed<::phantom_coerce::__private::Owned, #index>,
+            #target,
+        > for #struct_name #ty_generics #any_where_clause {}
+
+        impl #any_impl_generics #trait_name<#target, #index> for #struct_name #ty_generics #any_where_clause {
+            fn into_coerced(self) -> #target {
+                #body
+            }
+        }
+
+    });
+    if inherent_methods {
+        output.extend(quote! {
+            impl #impl_generics #struct_name #ty_generics #where_clause {
+                fn into_coerced<__CoerceTarget, __CoerceIndex>(self) -> __CoerceTarget
+                where
+                    Self: #trait_name<__CoerceTarget, __CoerceIndex>,
+                {
+                    #trait_name::into_coerced(self)
+                }
+            }
+        });
+    }
+
+    output.extend(generate_reflexive_coercible(struct_name, generics, &[]));
+
+    let rows: Vec<String> = options
+        .any
+        .iter()
+        .map(|(param, any)| format!("| `{}` | `{}` |", param, type_to_string(any)))
+        .collect();
+    output.extend(generate_doc_table(
+        struct_name,
+        generics,
+        &format!(
+            "Every marker of each parameter coerces into its `Any` marker, through {}.\n\n| Parameter | Any marker |\n|---|---|",
+            if safe { "`into_coerced`" } else { "`coerce` and `into_coerced`" }
+        ),
+        &rows,
+    ));
+    Ok(output)
+}
+
+/// Generate the coercion traits, impls, and inherent methods for parsed specs
+///
+/// Shared by `#[derive(Coerce)]` and the `#[derive(Upcast)]` and `phantom_coerce!` front-ends.
+fn generate_coercions(
+    input: &DeriveInput,
+    fields: &syn::FieldsNamed,
+    coercion_specs: &[CoercionSpec],
+    mut options: StructOptions,
+) -> syn::Result<proc_macro2::TokenStream> {
+    let struct_name = &input.ident;
+    let generics = &input.generics;
+
+    // Patterns may name the registered aliases in place of the types they stand for
+    let resolved;
+    let coercion_specs = if options.aliases.is_empty() {
+        coercion_specs
+    } else {
+        resolved = coercion_specs
+            .iter()
+            .map(|spec| resolve_aliases(spec, &options.aliases))
+            .collect::<syn::Result<Vec<_>>>()?;
+        &resolved
+    };
+
+    // Identify PhantomData fields and map them to type parameters
+    let phantom_fields: Vec<&Ident> = fields
+        .named
+        .iter()
+        .filter(|field| is_phantom_field(field))
+        .map(|field| field.ident.as_ref().unwrap())
+        .collect();
+
+    // Without a marker field, nothing justifies reinterpreting one instantiation as another.
+    // Const parameters need no field, so they can be markers on their own
+    if phantom_fields.is_empty()
+        && generics.const_params().next().is_none()
+        && !coercion_specs.is_empty()
+    {
+        let mut has_deep = false;
+        for field in &fields.named {
+            has_deep |= is_deep_field(field)?;
+        }
+        if !has_deep {
+            return Err(syn::Error::new_spanned(
+                struct_name,
+                format!(
+                    "'{struct_name}' has no PhantomData fields: coercions may only change marker parameters held in `PhantomData<..>` fields (or in #[coerce(deep)] fields). \
+                     Add a field such as `marker: PhantomData<Marker>`; fields are recognized by the `PhantomData` type name, so list other wrappers in `phantom_types = \"...\"`"
+                ),
+            ));
+        }
+    }
+
+    // The `forbid_unsafe` feature forces safe mode on every derive in the build
+    let forbid_unsafe = cfg!(feature = "forbid_unsafe");
+    options.safe |= forbid_unsafe;
+
+    // Coercions added elsewhere in the crate implement the traits from there
+    if options.extensible && options.trait_vis.is_none() {
+        options.trait_vis = Some(syn::parse_quote!(pub(crate)));
+    }
+
+    // Borrowed coercions can't be expressed without reinterpreting the reference
+    if options.safe
+        && let Some(spec) = coercion_specs
+            .iter()
+            .find(|spec| spec.kind == CoercionMode::Borrowed)
+    {
+        let reason = if forbid_unsafe {
+            "Borrowed coercions are unavailable with the `forbid_unsafe` feature"
+        } else {
+            "Borrowed coercions are unavailable with #[coerce(safe)]"
+        };
+        return Err(syn::Error::new(
+            spec.span,
+            format!(
+                "{reason}: a reference can only be coerced by transmuting it. Use owned_from/owned_to or cloned_from/cloned_to instead"
+            ),
+        ));
+    }
+
+    // An unsized struct can only be reached through a reference
+    let unsized_tail = has_unsized_tail(fields);
+    if unsized_tail
+        && let Some(spec) = coercion_specs
+            .iter()
+            .find(|spec| spec.kind != CoercionMode::Borrowed)
+    {
+        return Err(syn::Error::new(
+            spec.span,
+            format!(
+                "'{struct_name}' ends in an unsized field, so it only supports borrowed coercions"
+            ),
+        ));
+    }
+
+    // A `when` rule may only change const parameters that no data field depends on
+    if let Some(spec) = coercion_specs.iter().find(|spec| spec.when.is_some()) {
+        for param in generics.const_params() {
+            let ident = &param.ident;
+            if let Some(field) = fields
+                .named
+                .iter()
+                .find(|field| !is_phantom_field(field) && mentions_ident(quote! { #field }, ident))
+            {
+                return Err(syn::Error::new(
+                    spec.span,
+                    format!(
+                        "'when' rules can't change const parameter '{}': field '{}' depends on it, so its layout could change",
+                        ident,
+                        field.ident.as_ref().unwrap()
+                    ),
+                ));
+            }
+        }
+    }
+
+    // C callers can only rely on the layout of a `#[repr(C)]` struct
+    if let Some(spec) = coercion_specs.iter().find(|spec| spec.extern_c.is_some()) {
+        if options.safe {
+            return Err(syn::Error::new(
+                spec.span,
+                "extern_c is unavailable in safe mode: exporting an unmangled symbol is unsafe",
+            ));
+        }
+        if !is_repr_c(input) {
+            return Err(syn::Error::new(
+                spec.span,
+                "extern_c requires the struct to be #[repr(C)]",
+            ));
+        }
+    }
+
+    // Expand all specs into concrete coercions
+    let mut borrowed_coercions = Vec::new();
+    let mut owned_coercions = Vec::new();
+    let mut cloned_coercions = Vec::new();
+    let mut generate_asref_for = Vec::new();
+    let mut coverage_checks = proc_macro2::TokenStream::new();
+    let mut covered_traits: Vec<Ident> = Vec::new();
+
+    let data_params = data_params(generics, fields);
+    for spec in coercion_specs {
+        if let Some(covers) = &spec.covers_all {
+            coverage_checks.extend(generate_covers_all(
+                input,
+                spec,
+                covers,
+                &options,
+                &mut covered_traits,
+            )?);
+        }
+        let mut expanded = expand_coercion_spec(spec, generics, &data_params, &options.lints)?;
+        check_fixed_params(&expanded, generics, &options.fixed, spec.span)?;
+        // Generic markers can't be probed by a const assertion, so their impls are bounded
+        if options.lints.auto_trait_change == LintLevel::Deny {
+            for coercion in &mut expanded {
+                let bounds = generic_auto_trait_bounds(generics, coercion, spec.span)?;
+                coercion.bounds.extend(bounds);
+            }
+        }
+
+        // The same pair declared twice would otherwise surface as conflicting impls
+        let declared = match spec.kind {
+            CoercionMode::Borrowed => &borrowed_coercions,
+            CoercionMode::Owned => &owned_coercions,
+            CoercionMode::Cloned => &cloned_coercions,
+        };
+        let mut seen: Vec<(String, String)> = declared.iter().map(coercion_key).collect();
+        let mut unique = Vec::with_capacity(expanded.len());
+        for coercion in expanded {
+            let key = coercion_key(&coercion);
+            if seen.contains(&key) {
+                if options.lints.duplicate_pair == LintLevel::Allow {
+                    continue;
+                }
+                return Err(syn::Error::new(
+                    spec.span,
+                    format!(
+                        "Duplicate coercion: {:?} '{}' => '{}' is declared more than once. Remove the repeat, or add `allow(duplicate_pair)` to skip it",
+                        spec.kind, key.0, key.1
+                    ),
+                ));
+            }
+            seen.push(key);
+            unique.push(coercion);
+        }
+        expanded = unique;
+
+        match spec.kind {
+            CoercionMode::Borrowed => {
+                let start = borrowed_coercions.len();
+                borrowed_coercions.extend(expanded);
+                if spec.generate_asref || options.generate == Generate::AsrefOnly {
+                    // Mark which coercions should also generate AsRef
+                    generate_asref_for.extend(
+                        (start..borrowed_coercions.len()).map(|idx| (idx, &spec.asref_pointers)),
+                    );
+                }
+            }
+            CoercionMode::Owned => owned_coercions.extend(expanded),
+            CoercionMode::Cloned => cloned_coercions.extend(expanded),
+        }
+    }
+
+    // Owned and cloned coercions have no `AsRef` form to fall back on
+    if options.generate == Generate::AsrefOnly
+        && let Some(spec) = coercion_specs
+            .iter()
+            .find(|spec| spec.kind != CoercionMode::Borrowed)
+    {
+        return Err(syn::Error::new(
+            spec.span,
+            "generate = \"asref_only\" only generates AsRef impls, which need borrowed coercions. Use generate = \"traits_only\" to keep owned and cloned coercions",
+        ));
+    }
+    if let Some(span) = options.from_iterator
+        && owned_coercions.is_empty()
+        && !options.late
+    {
+        return Err(syn::Error::new(
+            span,
+            "from_iterator collects through owned coercions, but none are declared",
+        ));
+    }
+    // A `Cow` borrows through the borrowed coercion and clones once it's made owned
+    let cow_coercions: Vec<&ParsedCoercion> = borrowed_coercions
+        .iter()
+        .filter(|borrowed| {
+            let key = coercion_key(borrowed);
+            cloned_coercions
+                .iter()
+                .any(|cloned| coercion_key(cloned) == key)
+        })
+        .collect();
+    if let Some(span) = options.cow {
+        if unsized_tail {
+            return Err(syn::Error::new(
+                span,
+                format!("cow needs a sized struct, but '{struct_name}' ends in an unsized field"),
+            ));
+        }
+        if cow_coercions.is_empty() && !options.late {
+            return Err(syn::Error::new(
+                span,
+                "cow converts pairs declared as both borrowed and cloned coercions, but there are none",
+            ));
+        }
+    }
+    let inherent_methods = options.generate == Generate::All && !options.late;
+    let define_traits = !options.late;
+
+    let total = borrowed_coercions.len() + owned_coercions.len() + cloned_coercions.len();
+    if options.lints.large_expansion == LintLevel::Deny && total > LARGE_EXPANSION_LIMIT {
+        return Err(syn::Error::new_spanned(
+            struct_name,
+            format!(
+                "Large expansion: {total} coercions exceed the limit of {LARGE_EXPANSION_LIMIT}. Split the alternatives across structs, use `_` type holes, or remove `deny(large_expansion)`"
+            ),
+        ));
+    }
+
+    check_coercion_graph(
+        struct_name,
+        &[&borrowed_coercions, &owned_coercions, &cloned_coercions],
+        &options,
+    )?;
+
+    #[cfg(feature = "audit")]
+    record_audit(
+        struct_name,
+        &[
+            (&CoercionMode::Borrowed, &borrowed_coercions),
+            (&CoercionMode::Owned, &owned_coercions),
+            (&CoercionMode::Cloned, &cloned_coercions),
+        ],
+        &options,
+    )?;
+
+    let mut output = coverage_checks;
+    if !options.late {
+        let aliases = &options.aliases;
+        output.extend(quote! { #(#aliases)* });
+        output.extend(generate_seal_module(struct_name, &options));
+        output.extend(generate_cast_helpers(
+            struct_name,
+            generics,
+            fields,
+            &options,
+            !borrowed_coercions.is_empty(),
+            !options.safe && (!owned_coercions.is_empty() || !cloned_coercions.is_empty()),
+        ));
+    }
+
+    // Generate borrowed coercions
+    if !borrowed_coercions.is_empty() {
+        let trait_name = coercion_trait_name(struct_name, &options, &CoercionMode::Borrowed);
+        let vis = &options.trait_vis;
+        let sealed = sealed_bound(struct_name, &options, &CoercionMode::Borrowed);
+
+        let (trait_docs, method_docs) =
+            coercion_trait_docs(struct_name, &options, &CoercionMode::Borrowed);
+        let trait_def = quote! {
+            #trait_docs
+            #vis trait #trait_name<Output: ?::core::marker::Sized> #sealed {
+                #method_docs
+                fn coerce(&self) -> &Output;
+            }
+        };
+
+        let mut impls = Vec::new();
+        let mut asref_impls = Vec::new();
+
+        for (idx, coercion) in borrowed_coercions.iter().enumerate() {
+            let impl_block = generate_borrowed_impl(
+                struct_name,
+                generics,
+                &trait_name,
+                coercion,
+                fields,
+                &phantom_fields,
+                &options,
+            )?;
+            impls.push(impl_block);
+            impls.push(generate_declared_impl(
+                generics,
+                coercion,
+                &CoercionMode::Borrowed,
+            ));
+            impls.push(generate_sealed_impl(
+                struct_name,
+                generics,
+                coercion,
+                &CoercionMode::Borrowed,
+                &options,
+            ));
+            impls.push(generate_extern_c_shim(
+                &input.vis,
+                &trait_name,
+                coercion,
+                &CoercionMode::Borrowed,
+            ));
+            impls.push(generate_verify_wrapper(
+                input,
+                fields,
+                &trait_name,
+                coercion,
+                &CoercionMode::Borrowed,
+                idx,
+                &options,
+            ));
+            impls.push(generate_coercible_ref_impl(generics, &trait_name, coercion));
+
+            // Generate AsRef impl if this coercion was marked for it
+            if let Some((_, pointers)) = generate_asref_for.iter().find(|(i, _)| *i == idx) {
+                let asref_impl =
+                    generate_asref_impl(struct_name, generics, &trait_name, coercion, pointers)?;
+                asref_impls.push(asref_impl);
+            }
+        }
+
+        // Generate inherent method with turbofish support
+        let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+        let guard_methods = generate_guard_methods(&trait_name);
+        let uninit_methods = generate_uninit_methods(&trait_name, &CoercionMode::Borrowed);
+        let manually_drop_methods =
+            generate_manually_drop_methods(&trait_name, &CoercionMode::Borrowed);
+        let ptr_methods = generate_ptr_methods(&trait_name);
+        let ndarray_methods = generate_ndarray_methods(&trait_name, &CoercionMode::Borrowed);
+        // The slice cast skips the trait impls, so `when` rules are checked here too
+        let (slice_bound, slice_check) = if borrowed_coercions
+            .iter()
+            .any(|coercion| coercion.when.is_some())
+        {
+            let declared = quote! {
+                ::phantom_coerce::__private::DeclaredCoercion<::phantom_coerce::__private::Borrowed, __CoerceTarget>
+            };
+            (
+                quote! { + #declared },
+                quote! { let () = <Self as #declared>::PERMITTED; },
+            )
+        } else {
+            (quote! {}, quote! {})
+        };
+        // Slices, slots, and pointers of an unsized struct aren't expressible, or need `Sized`
+        let sized_methods = (!unsized_tail).then(|| {
+            quote! {
+                fn coerce_all_ref<__CoerceTarget>(items: &[Self]) -> &[__CoerceTarget]
+                where
+                    Self: #trait_name<__CoerceTarget> + ::core::marker::Sized #slice_bound,
+                    __CoerceTarget: ::core::marker::Sized,
+                {
+                    #slice_check
+                    // SAFETY: A borrowed coercion is only declared between types that differ
+                    // in PhantomData parameters, so elements share size and alignment.
+                    unsafe {
+                        ::core::slice::from_raw_parts(
+                            items.as_ptr().cast::<__CoerceTarget>(),
+                            items.len(),
+                        )
+                    }
+                }
+
+                fn coerce_cow_slice<'__coerce, __CoerceTarget>(
+                    items: ::std::borrow::Cow<'__coerce, [Self]>,
+                ) -> ::std::borrow::Cow<'__coerce, [__CoerceTarget]>
+                where
+                    Self: #trait_name<__CoerceTarget>
+                        + ::phantom_coerce::Coercible<__CoerceTarget>
+                        + ::core::clone::Clone
+                        #slice_bound,
+                    __CoerceTarget: ::core::clone::Clone,
+                {
+                    match items {
+                        ::std::borrow::Cow::Borrowed(items) => {
+                            ::std::borrow::Cow::Borrowed(Self::coerce_all_ref(items))
+                        }
+                        ::std::borrow::Cow::Owned(items) => ::std::borrow::Cow::Owned(
+                            ::core::iter::Iterator::collect(::core::iter::Iterator::map(
+                                ::core::iter::IntoIterator::into_iter(items),
+                                ::phantom_coerce::Coercible::into_coerced,
+                            )),
+                        ),
+                    }
+                }
+
+                #uninit_methods
+                #manually_drop_methods
+                #ptr_methods
+                #ndarray_methods
+            }
+        });
+        let method = inherent_method_name(&options, "coerce");
+        let inherent_method = quote! {
+            impl #impl_generics #struct_name #ty_generics #where_clause {
+                fn #method<__CoerceTarget>(&self) -> &__CoerceTarget
+                where
+                    Self: #trait_name<__CoerceTarget>,
+                    __CoerceTarget: ?::core::marker::Sized,
+                {
+                    #trait_name::coerce(self)
+                }
+
+                #guard_methods
+                #sized_methods
+            }
+        };
+
+        let inherent_method = inherent_methods.then_some(inherent_method);
+        let trait_def = define_traits.then_some(trait_def);
+        output.extend(quote! {
+            #trait_def
+            #(#impls)*
+            #inherent_method
+            #(#asref_impls)*
+        });
+    }
+
+    // Generate owned coercions
+    if !owned_coercions.is_empty() {
+        let trait_name = coercion_trait_name(struct_name, &options, &CoercionMode::Owned);
+        let vis = &options.trait_vis;
+        let sealed = sealed_bound(struct_name, &options, &CoercionMode::Owned);
+
+        let (trait_docs, method_docs) =
+            coercion_trait_docs(struct_name, &options, &CoercionMode::Owned);
+        let trait_def = quote! {
+            #trait_docs
+            #vis trait #trait_name<Output> #sealed {
+                #method_docs
+                fn into_coerced(self) -> Output;
+                #[doc(hidden)]
+                fn guard(&self);
+            }
+        };
+
+        let mut impls = Vec::new();
+
+        for (idx, coercion) in owned_coercions.iter().enumerate() {
+            let impl_block = generate_owned_impl(
+                struct_name,
+                generics,
+                &trait_name,
+                coercion,
+                fields,
+                &phantom_fields,
+                &options,
+            )?;
+            impls.push(impl_block);
+            impls.push(generate_declared_impl(
+                generics,
+                coercion,
+                &CoercionMode::Owned,
+            ));
+            impls.push(generate_sealed_impl(
+                struct_name,
+                generics,
+                coercion,
+                &CoercionMode::Owned,
+                &options,
+            ));
+            impls.push(generate_extern_c_shim(
+                &input.vis,
+                &trait_name,
+                coercion,
+                &CoercionMode::Owned,
+            ));
+            impls.push(generate_verify_wrapper(
+                input,
+                fields,
+                &trait_name,
+                coercion,
+                &CoercionMode::Owned,
+                idx,
+                &options,
+            ));
+            impls.push(generate_coercible_impl(
+                generics,
+                &trait_name,
+                coercion,
+                &options,
+            ));
+            if options.from_iterator.is_some() {
+                impls.push(generate_from_iterator_impls(generics, coercion));
+            }
+        }
+
+        // Generate inherent method with turbofish support
+        let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+        let uninit_methods = if options.safe {
+            quote! {}
+        } else {
+            generate_uninit_methods(&trait_name, &CoercionMode::Owned)
+        };
+        let manually_drop_methods =
+            generate_manually_drop_methods(&trait_name, &CoercionMode::Owned);
+        let ndarray_methods = generate_ndarray_methods(&trait_name, &CoercionMode::Owned);
+        let method = inherent_method_name(&options, "into_coerced");
+        let inherent_method = quote! {
+            impl #impl_generics #struct_name #ty_generics #where_clause {
+                fn #method<__CoerceTarget>(self) -> __CoerceTarget
+                where
+                    Self: #trait_name<__CoerceTarget>,
+                    __CoerceTarget: ::core::marker::Sized,
+                {
+                    #trait_name::into_coerced(self)
+                }
+
+                fn coerce_all<__CoerceTarget>(items: ::std::vec::Vec<Self>) -> ::std::vec::Vec<__CoerceTarget>
+                where
+                    Self: #trait_name<__CoerceTarget> + ::core::marker::Sized,
+                    __CoerceTarget: ::core::marker::Sized,
+                {
+                    // Same-layout elements let std reuse the allocation in place
+                    ::core::iter::Iterator::collect(::core::iter::Iterator::map(
+                        ::core::iter::IntoIterator::into_iter(items),
+                        #trait_name::into_coerced,
+                    ))
+                }
+
+                #uninit_methods
+                #manually_drop_methods
+                #ndarray_methods
+            }
+        };
+
+        let inherent_method = inherent_methods.then_some(inherent_method);
+        let trait_def = define_traits.then_some(trait_def);
+        output.extend(quote! {
+            #trait_def
+            #(#impls)*
+            #inherent_method
+        });
+    }
+
+    // Generate cloned coercions
+    if !cloned_coercions.is_empty() {
+        let trait_name = coercion_trait_name(struct_name, &options, &CoercionMode::Cloned);
+        let vis = &options.trait_vis;
+        let sealed = sealed_bound(struct_name, &options, &CoercionMode::Cloned);
+
+        let (trait_docs, method_docs) =
+            coercion_trait_docs(struct_name, &options, &CoercionMode::Cloned);
+        let trait_def = quote! {
+            #trait_docs
+            #vis trait #trait_name<Output> #sealed {
+                #method_docs
+                fn to_coerced(&self) -> Output;
+            }
+        };
+
+        let mut impls = Vec::new();
+
+        for coercion in &cloned_coercions {
+            let impl_block = generate_cloned_impl(
+                struct_name,
+                generics,
+                &trait_name,
+                coercion,
+                fields,
+                &phantom_fields,
+                &options,
+            )?;
+            impls.push(impl_block);
+            impls.push(generate_declared_impl(
+                generics,
+                coercion,
+                &CoercionMode::Cloned,
+            ));
+            impls.push(generate_sealed_impl(
+                struct_name,
+                generics,
+                coercion,
+                &CoercionMode::Cloned,
+                &options,
+            ));
+        }
+
+        // Generate inherent method with turbofish support
+        let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+        let clone_helper = define_traits
+            .then(|| generate_clone_data_helper(struct_name, generics, fields, &options));
+        let method = inherent_method_name(&options, "to_coerced");
+        let inherent_method = quote! {
+            impl #impl_generics #struct_name #ty_generics #where_clause {
+                fn #method<__CoerceTarget>(&self) -> __CoerceTarget
+                where
+                    Self: #trait_name<__CoerceTarget>,
+                    __CoerceTarget: ::core::marker::Sized,
+                {
+                    #trait_name::to_coerced(self)
+                }
+            }
+        };
+
+        let inherent_method = inherent_methods.then_some(inherent_method);
+        let trait_def = define_traits.then_some(trait_def);
+        output.extend(quote! {
+            #trait_def
+            #clone_helper
+            #(#impls)*
+            #inherent_method
+        });
+    }
+
+    // Convert references into `Cow`s of their targets
+    if options.cow.is_some() {
+        let trait_name = coercion_trait_name(struct_name, &options, &CoercionMode::Borrowed);
+        for coercion in cow_coercions {
+            output.extend(generate_cow_impl(generics, &trait_name, coercion));
+        }
+    }
+
+    // Custom phantom wrappers must carry no data for the casts to be sound
+    if !(options.safe || cfg!(feature = "forbid_unsafe")) {
+        output.extend(generate_marker_size_checks(
+            generics,
+            fields,
+            &[&borrowed_coercions, &owned_coercions, &cloned_coercions],
+        ));
+    }
+
+    // Late coercions only add impls; the derive generated everything else
+    if options.late {
+        if options.lints.auto_trait_change == LintLevel::Deny {
+            output.extend(generate_auto_trait_checks(
+                generics,
+                &[&borrowed_coercions, &owned_coercions, &cloned_coercions],
+            ));
+        }
+        return hide_coercion_docs(output, struct_name, &options);
+    }
+
+    // Every instantiation coerces into itself, for generic code and container lifting
+    if !unsized_tail {
+        output.extend(generate_reflexive_coercible(
+            struct_name,
+            generics,
+            &owned_coercions,
+        ));
+    }
+
+    // List every declared coercion at the type level
+    output.extend(generate_coercion_list(
+        struct_name,
+        generics,
+        &[
+            (CoercionMode::Borrowed, &borrowed_coercions),
+            (CoercionMode::Owned, &owned_coercions),
+            (CoercionMode::Cloned, &cloned_coercions),
+        ],
+    ));
+
+    // Document the declared coercions on the struct's rustdoc page
+    output.extend(generate_coercion_table(
+        struct_name,
+        generics,
+        &[
+            (CoercionMode::Borrowed, &borrowed_coercions),
+            (CoercionMode::Owned, &owned_coercions),
+            (CoercionMode::Cloned, &cloned_coercions),
+        ],
+    ));
+
+    // Reject coercions that would silently change thread-safety
+    if options.lints.auto_trait_change == LintLevel::Deny {
+        output.extend(generate_auto_trait_checks(
+            generics,
+            &[&borrowed_coercions, &owned_coercions, &cloned_coercions],
+        ));
+    }
+
+    // Expose each type parameter to `coerce!`, which fills `_` holes from the source
+    output.extend(generate_param_impls(struct_name, generics));
+
+    // Replay each coercion to a user macro via `for_each_coercion!`
+    output.extend(generate_for_each_macro(
+        struct_name,
+        &[
+            (CoercionMode::Borrowed, &borrowed_coercions),
+            (CoercionMode::Owned, &owned_coercions),
+            (CoercionMode::Cloned, &cloned_coercions),
+        ],
+    ));
+
+    // Instantiate user code once per specific type via `for_each_specific!`
+    output.extend(generate_for_each_specific_macro(
+        struct_name,
+        &[&borrowed_coercions, &owned_coercions, &cloned_coercions],
+    ));
+
+    // Rebuild when an included spec file changes
+    for path in &options.include_paths {
+        output.extend(quote! {
+            const _: &[u8] = ::core::include_bytes!(#path);
+        });
+    }
+
+    // Forward `AsRef` to the data field, whatever the markers
+    for (target, span) in &options.forward_asref {
+        output.extend(generate_forward_asref(
+            struct_name,
+            generics,
+            fields,
+            target,
+            *span,
+        )?);
+    }
+
+    // Deref to the data field, whatever the markers
+    if let Some(field) = &options.deref_inner {
+        output.extend(generate_deref_inner(struct_name, generics, fields, field)?);
+    }
+
+    // Generate field projection accessors
+    if !options.projections.is_empty() {
+        output.extend(generate_projections(
+            struct_name,
+            generics,
+            fields,
+            &options.projections,
+        )?);
+    }
+
+    // Implement `HasAnyMarker` for the canonical erased form
+    if let Some(erased) = &options.erased {
+        output.extend(generate_erase_impls(
+            struct_name,
+            generics,
+            &options,
+            erased,
+            &borrowed_coercions,
+            &owned_coercions,
+        )?);
+    }
+
+    // Generate the marker-aware `Debug` impl
+    if options.debug {
+        output.extend(generate_debug_impl(struct_name, generics, fields));
+    }
+
+    if let Some(span) = options.json_schema {
+        if !cfg!(feature = "schemars") {
+            return Err(syn::Error::new(
+                span,
+                "json_schema requires the `schemars` feature of phantom-coerce",
+            ));
+        }
+        output.extend(generate_json_schema_impl(struct_name, generics, fields));
+    }
+
+    if let Some(span) = options.sqlx {
+        if !cfg!(feature = "sqlx") {
+            return Err(syn::Error::new(
+                span,
+                "sqlx requires the `sqlx` feature of phantom-coerce",
+            ));
+        }
+        output.extend(generate_sqlx_impls(struct_name, generics, fields, span)?);
+    }
+
+    // Generate marker-tag helpers
+    if let Some(tag_param) = &options.tag_param {
+        output.extend(generate_tag_methods(
+            struct_name,
+            generics,
+            tag_param,
+            &options,
+            !owned_coercions.is_empty(),
+            !cloned_coercions.is_empty(),
+        )?);
+        if let Some(tag_enum) = &options.tag_enum {
+            output.extend(generate_tag_enum(
+                input,
+                tag_param,
+                tag_enum,
+                &[&borrowed_coercions, &owned_coercions, &cloned_coercions],
+            )?);
+        }
+        if let Some((tag_field, span)) = &options.tag_field {
+            if !cfg!(feature = "serde") {
+                return Err(syn::Error::new(
+                    *span,
+                    "tag_field requires the `serde` feature of phantom-coerce",
+                ));
+            }
+            output.extend(generate_tagged_deserialize(
+                input,
+                fields,
+                tag_param,
+                (tag_field, *span),
+                &owned_coercions,
+                &options,
+            )?);
+        }
+        if !options.safe {
+            output.extend(generate_specific_dispatch(
+                input,
+                fields,
+                tag_param,
+                &[&borrowed_coercions, &owned_coercions, &cloned_coercions],
+            )?);
+        }
+    }
+
+    // Generate the per-parameter marker enums
+    for (param, marker_enum) in &options.marker_enums {
+        output.extend(generate_marker_enum(
+            input,
+            param,
+            marker_enum,
+            &[&borrowed_coercions, &owned_coercions, &cloned_coercions],
+        )?);
+    }
+
+    // Generate the `typewit` type functions
+    for (param, type_fn) in &options.type_fns {
+        if !cfg!(feature = "typewit") {
+            return Err(syn::Error::new_spanned(
+                type_fn,
+                "type_fn requires the `typewit` feature of phantom-coerce",
+            ));
+        }
+        output.extend(generate_type_fn(input, param, type_fn)?);
+    }
+
+    if let (None, Some((_, span))) = (&options.tag_param, &options.tag_field) {
+        return Err(syn::Error::new(
+            *span,
+            "tag_field requires a 'tag' parameter, whose marker it selects",
+        ));
+    }
+    if let (None, Some(tag_enum)) = (&options.tag_param, &options.tag_enum) {
+        return Err(syn::Error::new_spanned(
+            tag_enum,
+            "tag_enum requires a 'tag' parameter, whose markers it lists",
+        ));
+    }
+
+    hide_coercion_docs(output, struct_name, &options)
+}
+
+/// Expand `#[derive(Upcast)]`
+pub fn derive_upcast(input: proc_macro2::TokenStream) -> syn::Result<proc_macro2::TokenStream> {
+    let input = syn::parse2::<DeriveInput>(input)?;
+    impl_upcast(&input)
+}
+
+fn impl_upcast(input: &DeriveInput) -> syn::Result<proc_macro2::TokenStream> {
+    let struct_name = &input.ident;
+
+    let Data::Struct(data_struct) = &input.data else {
+        return Err(syn::Error::new_spanned(
+            input,
+            "#[derive(Upcast)] can only be applied to structs",
+        ));
+    };
+
+    let Fields::Named(fields) = &data_struct.fields else {
+        return Err(syn::Error::new_spanned(
+            &data_struct.fields,
+            "#[derive(Upcast)] requires named fields",
+        ));
+    };
+
+    if input.generics.type_params().count() != 1 {
+        return Err(syn::Error::new_spanned(
+            &input.generics,
+            "#[derive(Upcast)] requires exactly one type parameter; use #[derive(Coerce)] for structs with several",
+        ));
+    }
+
+    // Parse `#[upcast(any = "...", from = "...")]`
+    let mut any = None;
+    let mut from = None;
+    let mut cloned = false;
+    let mut allow_auto_trait_change = false;
+    let mut upcast_attr = None;
+    for attr in input
+        .attrs
+        .iter()
+        .filter(|attr| attr.path().is_ident("upcast"))
+    {
+        upcast_attr = Some(attr);
+        let nested = attr.parse_args_with(
+            syn::punctuated::Punctuated::<Meta, syn::Token![,]>::parse_terminated,
+        )?;
+        for meta in nested {
+            let nv = match meta {
+                Meta::Path(path) if path.is_ident("cloned") => {
+                    cloned = true;
+                    continue;
+                }
+                Meta::Path(path) if path.is_ident("allow_auto_trait_change") => {
+                    allow_auto_trait_change = true;
+                    continue;
+                }
+                Meta::NameValue(nv) => nv,
+                other => {
+                    return Err(syn::Error::new_spanned(
+                        other,
+                        "Expected 'any', 'from', 'cloned', or 'allow_auto_trait_change'",
+                    ));
+                }
+            };
+            let slot = if nv.path.is_ident("any") {
+                &mut any
+            } else if nv.path.is_ident("from") {
+                &mut from
+            } else {
+                return Err(syn::Error::new_spanned(
+                    &nv.path,
+                    "Expected 'any', 'from', 'cloned', or 'allow_auto_trait_change'",
+                ));
+            };
+            if slot.is_some() {
+                return Err(syn::Error::new_spanned(&nv.path, "Duplicate upcast option"));
+            }
+            *slot = Some(extract_string_value(&nv)?);
+        }
+    }
+
+    let (Some(attr), Some(any)) = (upcast_attr, any) else {
+        return Err(syn::Error::new_spanned(
+            input,
+            "#[derive(Upcast)] requires #[upcast(any = \"Marker\")] naming the generic marker",
+        ));
+    };
+
+    let to_pattern = normalize_pattern(&format!("{struct_name}<{any}>"));
+    let from_pattern = match &from {
+        Some(from) => normalize_pattern(&format!("{struct_name}<{from}>")),
+        None => format!("{struct_name}<_>"),
+    };
+
+    // Borrowed coercions need a transmute, which `forbid_unsafe` rules out
+    let mut modes = Vec::new();
+    if !cfg!(feature = "forbid_unsafe") {
+        modes.push(CoercionMode::Borrowed);
+    }
+    modes.push(CoercionMode::Owned);
+    if cloned {
+        modes.push(CoercionMode::Cloned);
+    }
+
+    let coercion_specs: Vec<_> = modes
+        .into_iter()
+        .map(|kind| CoercionSpec {
+            from_patterns: vec![from_pattern.clone()],
+            to_pattern: to_pattern.clone(),
+            kind,
+            span: attr.span(),
+            generate_asref: false,
+            asref_pointers: Vec::new(),
+            generic_source: from.is_none(),
+            guard: None,
+            extern_c: None,
+            deprecated: None,
+            allow_noop: false,
+            allow_auto_trait_change,
+            when: None,
+            covers_all: None,
+            bounds: Vec::new(),
+        })
+        .collect();
+
+    generate_coercions(input, fields, &coercion_specs, StructOptions::default())
+}
+
+/// Expand `#[derive(PhantomMarker)]`
+pub fn derive_phantom_marker(
+    input: proc_macro2::TokenStream,
+) -> syn::Result<proc_macro2::TokenStream> {
+    let input = syn::parse2::<DeriveInput>(input)?;
+    impl_phantom_marker(&input)
+}
+
+fn impl_phantom_marker(input: &DeriveInput) -> syn::Result<proc_macro2::TokenStream> {
+    let name = &input.ident;
+
+    let is_unit = matches!(&input.data, Data::Struct(data) if matches!(data.fields, Fields::Unit));
+    if !is_unit {
+        return Err(syn::Error::new_spanned(
+            input,
+            "#[derive(PhantomMarker)] can only be applied to unit structs",
+        ));
+    }
+
+    // Parse `#[phantom_marker(name = "...", id = N)]`
+    let mut marker_name = None;
+    let mut id = None;
+    for attr in input
+        .attrs
+        .iter()
+        .filter(|attr| attr.path().is_ident("phantom_marker"))
+    {
+        let nested = attr.parse_args_with(
+            syn::punctuated::Punctuated::<syn::MetaNameValue, syn::Token![,]>::parse_terminated,
+        )?;
+        for nv in nested {
+            if nv.path.is_ident("name") {
+                if marker_name.is_some() {
+                    return Err(syn::Error::new_spanned(&nv.path, "Duplicate marker option"));
+                }
+                marker_name = Some(extract_string_value(&nv)?);
+            } else if nv.path.is_ident("id") {
+                if id.is_some() {
+                    return Err(syn::Error::new_spanned(&nv.path, "Duplicate marker option"));
+                }
+                let syn::Expr::Lit(syn::ExprLit {
+                    lit: syn::Lit::Int(lit),
+                    ..
+                }) = &nv.value
+                else {
+                    return Err(syn::Error::new_spanned(
+                        &nv.value,
+                        "'id' must be an integer literal",
+                    ));
+                };
+                id = Some(lit.base10_parse::<u64>()?);
+            } else {
+                return Err(syn::Error::new_spanned(&nv.path, "Expected 'name' or 'id'"));
+            }
+        }
+    }
+
+    let marker_name = marker_name.unwrap_or_else(|| name.to_string());
+    let id = match id {
+        Some(id) => quote! { ::core::option::Option::Some(#id) },
+        None => quote! { ::core::option::Option::None },
+    };
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+
+    Ok(quote! {
+        impl #impl_generics ::phantom_coerce::Marker for #name #ty_generics #where_clause {
+            const NAME: &'static str = #marker_name;
+            const ID: ::core::option::Option<u64> = #id;
+        }
+
+        impl #impl_generics ::phantom_coerce::tag::MarkerTag for #name #ty_generics #where_clause {
+            const TAG: &'static str = <Self as ::phantom_coerce::Marker>::NAME;
+        }
+    })
+}
+
+/// Expand a `phantom_coerce! { ... }` block
+pub fn phantom_coerce(input: proc_macro2::TokenStream) -> syn::Result<proc_macro2::TokenStream> {
+    let block = syn::parse2::<CoerceBlock>(input)?;
+    impl_coerce_block(block)
+}
+
+/// The input of `phantom_coerce!`: a struct definition and its `coercions { ... }` block
+struct CoerceBlock {
+    item: syn::ItemStruct,
+    specs: Vec<CoercionSpec>,
+}
+
+impl syn::parse::Parse for CoerceBlock {
+    fn parse(input: syn::parse::ParseStream) -> syn::Result<Self> {
+        let item: syn::ItemStruct = input.parse()?;
+
+        let keyword: Ident = input.parse()?;
+        if keyword != "coercions" {
+            return Err(syn::Error::new_spanned(
+                keyword,
+                "Expected a 'coercions { ... }' block after the struct",
+            ));
+        }
+        let content;
+        syn::braced!(content in input);
+
+        let mut specs = Vec::new();
+        while !content.is_empty() {
+            specs.push(parse_block_coercion(&content)?);
+        }
+
+        Ok(CoerceBlock { item, specs })
+    }
+}
+
+/// Parse one `<mode> <from> => <to>[, asref];` line of a `coercions` block
+fn parse_block_coercion(input: syn::parse::ParseStream) -> syn::Result<CoercionSpec> {
+    let mode_ident: Ident = input.parse()?;
+    let kind = match mode_ident.to_string().as_str() {
+        "borrowed" => CoercionMode::Borrowed,
+        "owned" => CoercionMode::Owned,
+        "cloned" => CoercionMode::Cloned,
+        _ => {
+            return Err(syn::Error::new_spanned(
+                &mode_ident,
+                "Expected 'borrowed', 'owned', or 'cloned'",
+            ));
+        }
+    };
+    let span = mode_ident.span();
+
+    let mut from = proc_macro2::TokenStream::new();
+    while !input.peek(syn::Token![=>]) {
+        if input.is_empty() {
+            return Err(syn::Error::new(
+                span,
+                "Expected '<from> => <to>' after the mode",
+            ));
+        }
+        from.extend([input.parse::<proc_macro2::TokenTree>()?]);
+    }
+    input.parse::<syn::Token![=>]>()?;
+
+    // Angle brackets aren't token groups, so track them to tell a parameter comma from the
+    // one introducing `asref`
+    let mut to = proc_macro2::TokenStream::new();
+    let mut angle_depth = 0usize;
+    loop {
+        let at_end = input.is_empty() || input.peek(syn::Token![;]);
+        if at_end || (angle_depth == 0 && input.peek(syn::Token![,])) {
+            break;
+        }
+        let token: proc_macro2::TokenTree = input.parse()?;
+        if let proc_macro2::TokenTree::Punct(punct) = &token {
+            match punct.as_char() {
+                '<' => angle_depth += 1,
+                '>' => angle_depth = angle_depth.saturating_sub(1),
+                _ => {}
+            }
+        }
+        to.extend([token]);
+    }
+    if to.is_empty() {
+        return Err(syn::Error::new(span, "Missing target type after '=>'"));
+    }
+
+    let mut generate_asref = false;
+    let mut allow_noop = false;
+    let mut allow_auto_trait_change = false;
+    while input.parse::<Option<syn::Token![,]>>()?.is_some() {
+        let marker: Ident = input.parse()?;
+        if marker == "allow_noop" {
+            allow_noop = true;
+            continue;
+        }
+        if marker == "allow_auto_trait_change" {
+            allow_auto_trait_change = true;
+            continue;
+        }
+        if marker != "asref" {
+            return Err(syn::Error::new_spanned(
+                marker,
+                "Expected 'asref', 'allow_noop', or 'allow_auto_trait_change' marker",
+            ));
+        }
+        if kind != CoercionMode::Borrowed {
+            return Err(syn::Error::new_spanned(
+                marker,
+                "asref marker is only valid for borrowed coercions",
+            ));
+        }
+        generate_asref = true;
+    }
+    input.parse::<syn::Token![;]>()?;
+
+    let to_pattern = tokens_to_pattern(&to);
+    let from_pattern = if from.is_empty() {
+        // An `AnyOf<(..)>` target implies a coercion from each of its members
+        any_of_sources(&to_pattern)
+            .ok_or_else(|| syn::Error::new(span, "Missing source type before '=>'"))?
+    } else {
+        tokens_to_pattern(&from)
+    };
+
+    if from_pattern == to_pattern {
+        return Err(syn::Error::new(
+            span,
+            format!(
+                "No-op coercion detected: coercing from '{}' to '{}' (same type). This coercion has no effect and should be removed.",
+                from_pattern, to_pattern
+            ),
+        ));
+    }
+
+    Ok(CoercionSpec {
+        from_patterns: vec![from_pattern],
+        to_pattern,
+        kind,
+        span,
+        generate_asref,
+        asref_pointers: SmartPointer::ALL.to_vec(),
+        generic_source: false,
+        guard: None,
+        extern_c: None,
+        deprecated: None,
+        allow_noop,
+        allow_auto_trait_change,
+        when: None,
+        covers_all: None,
+        bounds: Vec::new(),
+    })
+}
+
+fn impl_coerce_block(mut block: CoerceBlock) -> syn::Result<proc_macro2::TokenStream> {
+    let input = DeriveInput::from(block.item.clone());
+    let generated = impl_coerce(&input, std::mem::take(&mut block.specs))?;
+
+    // Without a derive to claim them, `#[coerce(...)]` attributes must not reach the compiler
+    let is_option = |attr: &Attribute| attr.path().is_ident("coerce");
+    block.item.attrs.retain(|attr| !is_option(attr));
+    for field in block.item.fields.iter_mut() {
+        field.attrs.retain(|attr| !is_option(attr));
+    }
+    strip_param_attrs(&mut block.item.generics);
+
+    let item = block.item;
+    Ok(quote! {
+        #item
+        #generated
+    })
+}
+
+/// Expand `for_each_coercion!(Type, callback)`
+pub fn for_each_coercion(input: proc_macro2::TokenStream) -> syn::Result<proc_macro2::TokenStream> {
+    let input = syn::parse2::<ForEachCoercion>(input)?;
+
+    let mut path = input.ty.path;
+    let last = path.segments.last_mut().unwrap();
+    last.ident = for_each_macro_name(&last.ident);
+    last.arguments = PathArguments::None;
+    let callback = input.callback;
+
+    Ok(quote! { #path!(#callback); })
+}
+
+/// Expand `for_each_specific!(Type, function)`, `(Type, function(args))`, or `(Type, |T| body)`
+pub fn for_each_specific(input: proc_macro2::TokenStream) -> syn::Result<proc_macro2::TokenStream> {
+    let input = syn::parse2::<ForEachSpecific>(input)?;
+
+    let mut path = input.ty.path;
+    let last = path.segments.last_mut().unwrap();
+    last.ident = for_each_specific_macro_name(&last.ident);
+    last.arguments = PathArguments::None;
+
+    // Functions become `|T| function::<T>(args)`, so the per-struct macro has a single form
+    let (alias, body) = match input.callback {
+        syn::Expr::Closure(closure) => {
+            let alias = match closure.inputs.iter().collect::<Vec<_>>().as_slice() {
+                [syn::Pat::Ident(pat)] if pat.by_ref.is_none() && pat.subpat.is_none() => {
+                    pat.ident.clone()
+                }
+                _ => {
+                    return Err(syn::Error::new_spanned(
+                        &closure.inputs,
+                        "for_each_specific closures take a single name for the specific type, as in `|T| ...`",
+                    ));
+                }
+            };
+            let body = closure.body;
+            (alias, quote! { #body })
+        }
+        callback => {
+            let (mut function, args) = match callback {
+                syn::Expr::Path(function) => (function, None),
+                syn::Expr::Call(syn::ExprCall { func, args, .. }) => match *func {
+                    syn::Expr::Path(function) => (function, Some(args)),
+                    other => {
+                        return Err(syn::Error::new_spanned(
+                            other,
+                            "Expected a path to a generic function",
+                        ));
+                    }
+                },
+                other => {
+                    return Err(syn::Error::new_spanned(
+                        other,
+                        "Expected a generic function, a call to one, or a closure `|T| ...`",
+                    ));
+                }
+            };
+            let alias = Ident::new("__PhantomCoerceSpecific", proc_macro2::Span::call_site());
+            let last = function.path.segments.last_mut().unwrap();
+            match &mut last.arguments {
+                PathArguments::AngleBracketed(generic_args) => {
+                    generic_args.args.insert(0, syn::parse_quote! { #alias });
+                }
+                arguments => {
+                    *arguments = PathArguments::AngleBracketed(syn::parse_quote! { ::<#alias> })
+                }
+            }
+            let args = args.into_iter().flatten();
+            (alias.clone(), quote! { #function(#(#args),*) })
+        }
+    };
+
+    Ok(quote! { #path!(#alias => #body) })
+}
+
+/// Expand `coerce!(value as Target)`
+pub fn coerce(input: proc_macro2::TokenStream) -> syn::Result<proc_macro2::TokenStream> {
+    let input = syn::parse2::<CoerceExpr>(input)?;
+
+    let holes = coerce_expr_holes(&input.target)?;
+    let value = &input.value;
+    let target = &input.target;
+
+    // Both the source and the target are held by reference in the `ref` form
+    let (bind, call, source, target_ref) = if input.by_ref {
+        (
+            quote! { let value = &(#value); },
+            quote! { let target: &#target = value.coerce(); },
+            quote! { value },
+            quote! { target },
+        )
+    } else {
+        (
+            quote! { let value = #value; },
+            quote! { let target: #target = value.into_coerced(); },
+            quote! { &value },
+            quote! { &target },
+        )
+    };
+
+    Ok(quote! {
+        {
+            #bind
+            // Found by method call, so a `Box` or guard yields the struct it points to
+            let source = {
+                use ::phantom_coerce::__private::Derived as _;
+                (#source).__phantom_coerce_source()
+            };
+            #call
+            #(::phantom_coerce::__private::same_param::<#holes, _, _>(source, #target_ref);)*
+            target
+        }
+    })
+}
+
+/// The input of `coerce!`: an optional `ref`, then `value as Target`
+struct CoerceExpr {
+    by_ref: bool,
+    value: syn::Expr,
+    target: Type,
+}
+
+impl syn::parse::Parse for CoerceExpr {
+    fn parse(input: syn::parse::ParseStream) -> syn::Result<Self> {
+        let by_ref = input.parse::<Option<syn::Token![ref]>>()?.is_some();
+        // `value as Target` parses as a cast expression
+        let syn::Expr::Cast(cast) = input.parse::<syn::Expr>()? else {
+            return Err(input.error("Expected `value as Target`"));
+        };
+        Ok(Self {
+            by_ref,
+            value: *cast.expr,
+            target: *cast.ty,
+        })
+    }
+}
+
+/// Indices (among type arguments) of the `_` parameters in a `coerce!` target
+fn coerce_expr_holes(target: &Type) -> syn::Result<Vec<usize>> {
+    let Type::Path(TypePath { path, .. }) = target else {
+        return Err(syn::Error::new_spanned(
+            target,
+            "coerce! target must be a struct type such as `TypedPath<UnknownBase, _>`",
+        ));
+    };
+    let Some(PathArguments::AngleBracketed(args)) =
+        path.segments.last().map(|segment| &segment.arguments)
+    else {
+        return Ok(Vec::new());
+    };
+
+    Ok(args
+        .args
+        .iter()
+        .filter_map(|arg| match arg {
+            syn::GenericArgument::Type(ty) => Some(ty),
+            _ => None,
+        })
+        .enumerate()
+        .filter(|(_, ty)| matches!(ty, Type::Infer(_)))
+        .map(|(index, _)| index)
+        .collect())
+}
+
+/// The input of `for_each_coercion!`: a derived type and the macro to call
+/// Expand `match_specific!(tagged, Type { Marker(binding) => arm, .., else => fallback })`
+pub fn match_specific(input: proc_macro2::TokenStream) -> syn::Result<proc_macro2::TokenStream> {
+    let input = syn::parse2::<MatchSpecific>(input)?;
+
+    let mut struct_path = input.ty.path;
+    let last = struct_path.segments.last_mut().unwrap();
+    last.arguments = PathArguments::None;
+    let mut enum_path = struct_path.clone();
+    let last = enum_path.segments.last_mut().unwrap();
+    last.ident = specific_enum_name(&last.ident);
+
+    let tagged = &input.tagged;
+    let arms = input.arms.iter().map(|(marker, binding, arm)| {
+        quote! { ::core::option::Option::Some(#enum_path::#marker(#binding)) => #arm, }
+    });
+    let fallback = &input.fallback;
+    Ok(quote! {
+        match #struct_path::__phantom_coerce_specific(&#tagged) {
+            #(#arms)*
+            ::core::option::Option::None => #fallback,
+        }
+    })
+}
+
+/// The input of `match_specific!`: the tagged value, then the struct and its arms
+struct MatchSpecific {
+    tagged: syn::Expr,
+    ty: TypePath,
+    arms: Vec<(Ident, syn::Pat, syn::Expr)>,
+    fallback: syn::Expr,
+}
+
+impl syn::parse::Parse for MatchSpecific {
+    fn parse(input: syn::parse::ParseStream) -> syn::Result<Self> {
+        let tagged = input.parse()?;
+        input.parse::<syn::Token![,]>()?;
+        let ty = input.parse()?;
+        let content;
+        syn::braced!(content in input);
+
+        let mut arms = Vec::new();
+        let fallback = loop {
+            if content.peek(syn::Token![else]) {
+                content.parse::<syn::Token![else]>()?;
+                content.parse::<syn::Token![=>]>()?;
+                let fallback = content.parse()?;
+                content.parse::<Option<syn::Token![,]>>()?;
+                if !content.is_empty() {
+                    return Err(content.error("The `else` arm must come last"));
+                }
+                break fallback;
+            }
+            if content.is_empty() {
+                return Err(content.error(
+                    "Expected an `else => ...` arm for tags that match no declared marker",
+                ));
+            }
+            let marker: Ident = content.parse()?;
+            let binding;
+            syn::parenthesized!(binding in content);
+            let binding = syn::Pat::parse_single(&binding)?;
+            content.parse::<syn::Token![=>]>()?;
+            let arm = content.parse()?;
+            content.parse::<Option<syn::Token![,]>>()?;
+            arms.push((marker, binding, arm));
+        };
+        input.parse::<Option<syn::Token![,]>>()?;
+
+        Ok(Self {
+            tagged,
+            ty,
+            arms,
+            fallback,
+        })
+    }
+}
+
+/// Expand `dispatch_table!(Type<..> { Marker => handler, .. })`
+pub fn dispatch_table(input: proc_macro2::TokenStream) -> syn::Result<proc_macro2::TokenStream> {
+    let input = syn::parse2::<DispatchTable>(input)?;
+
+    for (index, (marker, _)) in input.routes.iter().enumerate() {
+        if input.routes[..index]
+            .iter()
+            .any(|(other, _)| other == marker)
+        {
+            return Err(syn::Error::new_spanned(
+                marker,
+                format!("dispatch_table: '{marker}' is routed more than once"),
+            ));
+        }
+    }
+
+    let erased = &input.ty;
+    let mut struct_path = input.ty.path.clone();
+    let last = struct_path.segments.last_mut().unwrap();
+    last.arguments = PathArguments::None;
+    let mut enum_path = struct_path.clone();
+    let last = enum_path.segments.last_mut().unwrap();
+    last.ident = specific_enum_name(&last.ident);
+
+    let arms = input.routes.iter().map(|(marker, _)| {
+        quote! { ::core::option::Option::Some(#enum_path::#marker(_)) => {} }
+    });
+    let routes = input.routes.iter().map(|(marker, handler)| {
+        quote! {
+            (
+                <#marker as ::phantom_coerce::tag::MarkerTag>::TAG,
+                (#handler) as fn(&#erased) -> _,
+            )
+        }
+    });
+    Ok(quote! {
+        {
+            // Matching on the specific enum makes a missing or unknown marker a compile error
+            #[allow(dead_code)]
+            fn __phantom_coerce_exhaustive(tagged: &::phantom_coerce::tag::Tagged<#erased>) {
+                match #struct_path::__phantom_coerce_specific(tagged) {
+                    #(#arms)*
+                    ::core::option::Option::None => {}
+                }
+            }
+            ::phantom_coerce::tag::DispatchTable::<#erased, _>::new(&[#(#routes),*])
+        }
+    })
+}
+
+/// The input of `dispatch_table!`: the erased type and a handler per marker
+struct DispatchTable {
+    ty: TypePath,
+    routes: Vec<(Ident, syn::Expr)>,
+}
+
+impl syn::parse::Parse for DispatchTable {
+    fn parse(input: syn::parse::ParseStream) -> syn::Result<Self> {
+        let ty = input.parse()?;
+        let content;
+        syn::braced!(content in input);
+
+        let mut routes = Vec::new();
+        while !content.is_empty() {
+            let marker: Ident = content.parse()?;
+            content.parse::<syn::Token![=>]>()?;
+            let handler = content.parse()?;
+            routes.push((marker, handler));
+            if content.is_empty() {
+                break;
+            }
+            content.parse::<syn::Token![,]>()?;
+        }
+        input.parse::<Option<syn::Token![,]>>()?;
+
+        Ok(Self { ty, routes })
+    }
+}
+
+struct ForEachCoercion {
+    ty: TypePath,
+    callback: syn::Path,
+}
+
+/// The input of `for_each_specific!`: a derived type and the code to instantiate
+struct ForEachSpecific {
+    ty: TypePath,
+    callback: syn::Expr,
+}
+
+impl syn::parse::Parse for ForEachSpecific {
+    fn parse(input: syn::parse::ParseStream) -> syn::Result<Self> {
+        let ty = input.parse()?;
+        input.parse::<syn::Token![,]>()?;
+        let callback = input.parse()?;
+        input.parse::<Option<syn::Token![,]>>()?;
+        Ok(Self { ty, callback })
+    }
+}
+
+impl syn::parse::Parse for ForEachCoercion {
+    fn parse(input: syn::parse::ParseStream) -> syn::Result<Self> {
+        let ty = input.parse()?;
+        input.parse::<syn::Token![,]>()?;
+        let callback = input.parse()?;
+        input.parse::<Option<syn::Token![,]>>()?;
+        Ok(Self { ty, callback })
+    }
+}
+
+/// Whether a field only holds markers: a `PhantomData`, or a type listed in `phantom_types`
+/// (which the derive marks with `#[coerce(phantom)]` before generating code)
+fn is_phantom_field(field: &syn::Field) -> bool {
+    is_phantom_data(&field.ty) || field_coerce_options(field).is_ok_and(|options| options.phantom)
+}
+
+/// How rebuilt values initialize a marker field
+fn phantom_init(field: &syn::Field) -> proc_macro2::TokenStream {
+    if is_phantom_data(&field.ty) {
+        quote! { ::core::marker::PhantomData }
+    } else {
+        quote! { ::core::default::Default::default() }
+    }
+}
+
+/// Mark the fields whose type is one of the struct's `phantom_types` as marker fields
+fn mark_phantom_fields(input: &mut DeriveInput, phantom_types: &[Ident]) {
+    let Data::Struct(data_struct) = &mut input.data else {
+        return;
+    };
+    for field in data_struct.fields.iter_mut() {
+        if let Type::Path(TypePath { path, .. }) = &field.ty
+            && let Some(segment) = path.segments.last()
+            && phantom_types.contains(&segment.ident)
+        {
+            field.attrs.push(syn::parse_quote! { #[coerce(phantom)] });
+        }
+    }
+}
+
+/// The `#[cfg(...)]` attributes on a field, to repeat wherever generated code names it
+fn cfg_attrs(field: &syn::Field) -> Vec<&Attribute> {
+    field
+        .attrs
+        .iter()
+        .filter(|attr| attr.path().is_ident("cfg"))
+        .collect()
+}
+
+/// Whether cloning a data field needs a `Clone` bound in the `where` clause
+///
+/// Bounds can't be `#[cfg]`-gated, so a gated field's type may not exist at all. Its bound is
+/// left out unless the type mentions a struct parameter, and a type that doesn't is checked
+/// directly by the clone call.
+fn needs_clone_bound(generics: &syn::Generics, field: &syn::Field) -> bool {
+    let ty = &field.ty;
+    cfg_attrs(field).is_empty()
+        || generics
+            .type_params()
+            .any(|param| mentions_ident(quote!(#ty), &param.ident))
+}
+
+fn is_phantom_data(ty: &Type) -> bool {
+    if let Type::Path(TypePath { path, .. }) = ty
+        && let Some(segment) = path.segments.last()
+    {
+        return segment.ident == "PhantomData";
+    }
+    false
+}
+
+fn parse_coerce_attr(
+    attr: &Attribute,
+    options: &mut StructOptions,
+) -> syn::Result<Option<CoercionSpec>> {
+    let Meta::List(meta_list) = &attr.meta else {
+        return Ok(None);
+    };
+
+    let nested = meta_list.tokens.clone();
+
+    // Parse as multiple Meta items (NameValue or Path)
+    let parser = syn::punctuated::Punctuated::<syn::Meta, syn::Token![,]>::parse_terminated;
+    let metas = parser.parse2(nested)?;
+
+    let mut mode: Option<CoercionMode> = None;
+    let mut from_patterns: Vec<String> = Vec::new();
+    let mut to_pattern: Option<String> = None;
+    let mut has_asref = false;
+    let mut asref_pointers = SmartPointer::ALL.to_vec();
+    let mut from_mode_seen: Option<CoercionMode> = None;
+    let mut to_mode_seen: Option<CoercionMode> = None;
+    let mut has_struct_option = false;
+    let mut guard: Option<syn::Path> = None;
+    let mut extern_c: Option<Ident> = None;
+    let mut deprecated: Option<String> = None;
+    let mut allow_noop = false;
+    let mut allow_auto_trait_change = false;
+    let mut declares_any = false;
+    let mut when: Option<syn::Expr> = None;
+    let mut covers_all: Option<CoversAll> = None;
+    let mut bounds: Vec<syn::WherePredicate> = Vec::new();
+
+    for meta in metas {
+        match meta {
+            syn::Meta::NameValue(nv) => {
+                // Parse borrowed_from/to, owned_from/to, cloned_from/to
+                if nv.path.is_ident("borrowed_from") {
+                    mode = Some(CoercionMode::Borrowed);
+                    from_mode_seen = Some(CoercionMode::Borrowed);
+                    let value = extract_string_value(&nv)?;
+                    if value.trim().is_empty() {
+                        return Err(syn::Error::new_spanned(
+                            &nv,
+                            "borrowed_from cannot be empty",
+                        ));
+                    }
+                    from_patterns.push(normalize_pattern(&value));
+                } else if nv.path.is_ident("borrowed_to") {
+                    if to_pattern.is_some() {
+                        return Err(syn::Error::new_spanned(
+                            &nv,
+                            "Duplicate 'borrowed_to' attribute: only one target type allowed per #[coerce(...)] attribute",
+                        ));
+                    }
+                    mode = Some(CoercionMode::Borrowed);
+                    to_mode_seen = Some(CoercionMode::Borrowed);
+                    let value = extract_string_value(&nv)?;
+                    if value.trim().is_empty() {
+                        return Err(syn::Error::new_spanned(&nv, "borrowed_to cannot be empty"));
+                    }
+                    to_pattern = Some(normalize_pattern(&value));
+                } else if nv.path.is_ident("owned_from") {
+                    mode = Some(CoercionMode::Owned);
+                    from_mode_seen = Some(CoercionMode::Owned);
+                    let value = extract_string_value(&nv)?;
+                    if value.trim().is_empty() {
+                        return Err(syn::Error::new_spanned(&nv, "owned_from cannot be empty"));
+                    }
+                    from_patterns.push(normalize_pattern(&value));
+                } else if nv.path.is_ident("owned_to") {
+                    if to_pattern.is_some() {
+                        return Err(syn::Error::new_spanned(
+                            &nv,
+                            "Duplicate 'owned_to' attribute: only one target type allowed per #[coerce(...)] attribute",
+                        ));
+                    }
+                    mode = Some(CoercionMode::Owned);
+                    to_mode_seen = Some(CoercionMode::Owned);
+                    let value = extract_string_value(&nv)?;
+                    if value.trim().is_empty() {
+                        return Err(syn::Error::new_spanned(&nv, "owned_to cannot be empty"));
+                    }
+                    to_pattern = Some(normalize_pattern(&value));
+                } else if nv.path.is_ident("cloned_from") {
+                    mode = Some(CoercionMode::Cloned);
+                    from_mode_seen = Some(CoercionMode::Cloned);
+                    let value = extract_string_value(&nv)?;
+                    if value.trim().is_empty() {
+                        return Err(syn::Error::new_spanned(&nv, "cloned_from cannot be empty"));
+                    }
+                    from_patterns.push(normalize_pattern(&value));
+                } else if nv.path.is_ident("cloned_to") {
+                    if to_pattern.is_some() {
+                        return Err(syn::Error::new_spanned(
+                            &nv,
+                            "Duplicate 'cloned_to' attribute: only one target type allowed per #[coerce(...)] attribute",
+                        ));
+                    }
+                    mode = Some(CoercionMode::Cloned);
+                    to_mode_seen = Some(CoercionMode::Cloned);
+                    let value = extract_string_value(&nv)?;
+                    if value.trim().is_empty() {
+                        return Err(syn::Error::new_spanned(&nv, "cloned_to cannot be empty"));
+                    }
+                    to_pattern = Some(normalize_pattern(&value));
+                } else if nv.path.is_ident("tag") {
+                    if options.tag_param.is_some() {
+                        return Err(syn::Error::new_spanned(
+                            &nv,
+                            "Duplicate 'tag' option: only one tagged parameter allowed per struct",
+                        ));
+                    }
+                    let value = extract_string_value(&nv)?;
+                    let param = syn::parse_str::<Ident>(value.trim()).map_err(|_| {
+                        syn::Error::new_spanned(
+                            &nv.value,
+                            "'tag' must name a generic type parameter of the struct",
+                        )
+                    })?;
+                    options.tag_param = Some(param);
+                    has_struct_option = true;
+                } else if nv.path.is_ident("tag_enum") {
+                    let value = extract_string_value(&nv)?;
+                    let mut name = syn::parse_str::<Ident>(value.trim()).map_err(|_| {
+                        syn::Error::new_spanned(&nv.value, "'tag_enum' must be an enum name")
+                    })?;
+                    name.set_span(nv.value.span());
+                    options.tag_enum = Some(name);
+                    has_struct_option = true;
+                } else if nv.path.is_ident("tag_field") {
+                    let value = extract_string_value(&nv)?;
+                    if value.trim().is_empty() {
+                        return Err(syn::Error::new_spanned(&nv, "tag_field cannot be empty"));
+                    }
+                    options.tag_field = Some((value.trim().to_string(), nv.span()));
+                    has_struct_option = true;
+                } else if nv.path.is_ident("guard") {
+                    if guard.is_some() {
+                        return Err(syn::Error::new_spanned(
+                            &nv,
+                            "Duplicate 'guard' option: only one hook allowed per #[coerce(...)] attribute",
+                        ));
+                    }
+                    let value = extract_string_value(&nv)?;
+                    let path = syn::parse_str::<syn::Path>(value.trim()).map_err(|_| {
+                        syn::Error::new_spanned(&nv.value, "'guard' must be a path to a function")
+                    })?;
+                    guard = Some(path);
+                } else if nv.path.is_ident("extern_c") {
+                    let value = extract_string_value(&nv)?;
+                    let symbol = syn::parse_str::<Ident>(value.trim()).map_err(|_| {
+                        syn::Error::new_spanned(
+                            &nv.value,
+                            "'extern_c' must be a valid function name",
+                        )
+                    })?;
+                    extern_c = Some(symbol);
+                } else if nv.path.is_ident("deprecated") {
+                    deprecated = Some(extract_string_value(&nv)?);
+                } else if nv.path.is_ident("bound") {
+                    let value = extract_string_value(&nv)?;
+                    let parser = syn::punctuated::Punctuated::<
+                        syn::WherePredicate,
+                        syn::Token![,],
+                    >::parse_terminated;
+                    let predicates = parser.parse_str(&value).map_err(|_| {
+                        syn::Error::new_spanned(
+                            &nv.value,
+                            "'bound' must be where-clause predicates such as \"Second: Send + 'static\"",
+                        )
+                    })?;
+                    bounds.extend(predicates);
+                } else if nv.path.is_ident("covers_all") {
+                    let value = extract_string_value(&nv)?;
+                    let invalid = || {
+                        syn::Error::new_spanned(
+                            &nv.value,
+                            "'covers_all' must name a marker trait, optionally after the parameter it describes: \"BaseMarker\" or \"Base: BaseMarker\"",
+                        )
+                    };
+                    let (param, marker_trait) = match value.split_once(':') {
+                        Some((param, marker_trait)) if !marker_trait.starts_with(':') => (
+                            Some(syn::parse_str::<Ident>(param.trim()).map_err(|_| invalid())?),
+                            marker_trait,
+                        ),
+                        _ => (None, value.as_str()),
+                    };
+                    let marker_trait =
+                        syn::parse_str::<syn::Path>(marker_trait.trim()).map_err(|_| invalid())?;
+                    covers_all = Some(CoversAll {
+                        param,
+                        marker_trait,
+                        span: nv.value.span(),
+                    });
+                } else if nv.path.is_ident("when") {
+                    let value = extract_string_value(&nv)?;
+                    when = Some(syn::parse_str(&value).map_err(|_| {
+                        syn::Error::new_spanned(
+                            &nv.value,
+                            "'when' must be a const expression such as \"TO <= LEVEL\"",
+                        )
+                    })?);
+                } else if nv.path.is_ident("trait_vis") {
+                    let value = extract_string_value(&nv)?;
+                    let vis = syn::parse_str::<syn::Visibility>(value.trim()).map_err(|_| {
+                        syn::Error::new_spanned(
+                            &nv.value,
+                            "'trait_vis' must be a visibility such as 'pub' or 'pub(crate)'",
+                        )
+                    })?;
+                    options.trait_vis = Some(vis);
+                    has_struct_option = true;
+                } else if nv.path.is_ident("trait_name") {
+                    let value = extract_string_value(&nv)?;
+                    let name = syn::parse_str::<Ident>(value.trim()).map_err(|_| {
+                        syn::Error::new_spanned(
+                            &nv.value,
+                            "'trait_name' must be a valid identifier",
+                        )
+                    })?;
+                    options.trait_name = Some(name);
+                    has_struct_option = true;
+                } else if nv.path.is_ident("verify_prefix") {
+                    let value = extract_string_value(&nv)?;
+                    let prefix = syn::parse_str::<Ident>(value.trim()).map_err(|_| {
+                        syn::Error::new_spanned(
+                            &nv.value,
+                            "'verify_prefix' must be a valid identifier",
+                        )
+                    })?;
+                    options.verify_prefix = Some(prefix);
+                    has_struct_option = true;
+                } else if nv.path.is_ident("phantom_types") {
+                    let value = extract_string_value(&nv)?;
+                    for name in value
+                        .split(',')
+                        .map(str::trim)
+                        .filter(|name| !name.is_empty())
+                    {
+                        let ident = syn::parse_str::<Ident>(name).map_err(|_| {
+                            syn::Error::new_spanned(
+                                &nv.value,
+                                "'phantom_types' must list type names, e.g. \"PhantomCovariant, Tag\"",
+                            )
+                        })?;
+                        options.phantom_types.push(ident);
+                    }
+                    has_struct_option = true;
+                } else if nv.path.is_ident("deref_inner") {
+                    if options.deref_inner.is_some() {
+                        return Err(syn::Error::new_spanned(
+                            &nv,
+                            "Duplicate 'deref_inner' option: a struct derefs to one field",
+                        ));
+                    }
+                    let value = extract_string_value(&nv)?;
+                    let mut field = syn::parse_str::<Ident>(value.trim()).map_err(|_| {
+                        syn::Error::new_spanned(&nv.value, "'deref_inner' must name a field")
+                    })?;
+                    field.set_span(nv.value.span());
+                    options.deref_inner = Some(field);
+                    has_struct_option = true;
+                } else if nv.path.is_ident("forward_asref") {
+                    let value = extract_string_value(&nv)?;
+                    let target = syn::parse_str::<Type>(&value).map_err(|_| {
+                        syn::Error::new_spanned(&nv.value, "'forward_asref' must name a type")
+                    })?;
+                    options.forward_asref.push((target, nv.value.span()));
+                    has_struct_option = true;
+                } else if nv.path.is_ident("docs") {
+                    options.docs = match extract_string_value(&nv)?.trim() {
+                        "hidden" => Docs::Hidden,
+                        "shown" => Docs::Shown,
+                        _ => {
+                            return Err(syn::Error::new_spanned(
+                                &nv.value,
+                                "Expected 'hidden' or 'shown'",
+                            ));
+                        }
+                    };
+                    has_struct_option = true;
+                } else if nv.path.is_ident("generate") {
+                    options.generate = match extract_string_value(&nv)?.trim() {
+                        "traits_only" => Generate::TraitsOnly,
+                        "asref_only" => Generate::AsrefOnly,
+                        _ => {
+                            return Err(syn::Error::new_spanned(
+                                &nv.value,
+                                "Expected 'traits_only' or 'asref_only'",
+                            ));
+                        }
+                    };
+                    options.generate_span = Some(nv.span());
+                    has_struct_option = true;
+                } else if nv.path.is_ident("erased") {
+                    let value = extract_string_value(&nv)?;
+                    let erased =
+                        syn::parse_str::<Type>(&normalize_pattern(&value)).map_err(|_| {
+                            syn::Error::new_spanned(&nv.value, "'erased' must be a concrete type")
+                        })?;
+                    options.erased = Some((erased, nv.value.span()));
+                    has_struct_option = true;
+                } else if nv.path.is_ident("include") {
+                    let value = extract_string_value(&nv)?;
+                    let (specs, path) = parse_coerce_file(&value, nv.span())?;
+                    options.included.extend(specs);
+                    options.include_paths.push(path);
+                    has_struct_option = true;
+                } else {
+                    return Err(syn::Error::new_spanned(
+                        &nv.path,
+                        "Expected 'borrowed_from', 'borrowed_to', 'owned_from', 'owned_to', 'cloned_from', 'cloned_to', 'guard', 'extern_c', 'deprecated', 'bound', 'when', 'tag', 'erased', 'trait_vis', 'trait_name', 'docs', 'generate', or 'include'",
+                    ));
+                }
+            }
+            syn::Meta::Path(path) => {
+                if path.is_ident("asref") {
+                    has_asref = true;
+                } else if path.is_ident("allow_noop") {
+                    allow_noop = true;
+                } else if path.is_ident("allow_auto_trait_change") {
+                    allow_auto_trait_change = true;
+                } else if path.is_ident("safe") {
+                    options.safe = true;
+                    has_struct_option = true;
+                } else if path.is_ident("layout_checks") {
+                    options.layout_checks = true;
+                    has_struct_option = true;
+                } else if path.is_ident("from_iterator") {
+                    options.from_iterator = Some(path.span());
+                    has_struct_option = true;
+                } else if path.is_ident("cow") {
+                    options.cow = Some(path.span());
+                    has_struct_option = true;
+                } else if path.is_ident("debug") {
+                    options.debug = true;
+                    has_struct_option = true;
+                } else if path.is_ident("json_schema") {
+                    options.json_schema = Some(path.span());
+                    has_struct_option = true;
+                } else if path.is_ident("sqlx") {
+                    options.sqlx = Some(path.span());
+                    has_struct_option = true;
+                } else if path.is_ident("extensible") {
+                    options.extensible = true;
+                    has_struct_option = true;
+                } else {
+                    return Err(syn::Error::new_spanned(
+                        &path,
+                        "Expected 'asref' marker (only valid for borrowed coercions), 'allow_noop', 'allow_auto_trait_change', 'safe', 'layout_checks', 'from_iterator', 'cow', 'debug', 'json_schema', 'sqlx', or 'extensible'",
+                    ));
+                }
+            }
+            syn::Meta::List(list) if list.path.is_ident("any") => {
+                let markers = list.parse_args_with(
+                    syn::punctuated::Punctuated::<syn::MetaNameValue, syn::Token![,]>::parse_terminated,
+                )?;
+                for nv in markers {
+                    let Some(param) = nv.path.get_ident() else {
+                        return Err(syn::Error::new_spanned(
+                            &nv.path,
+                            "Expected a type parameter name, e.g. any(Base = \"UnknownBase\")",
+                        ));
+                    };
+                    let marker: Type =
+                        syn::parse_str(&normalize_pattern(&extract_string_value(&nv)?))?;
+                    options.any.push((param.clone(), marker));
+                }
+                options.any_span = Some(list.span());
+                declares_any = true;
+                has_struct_option = true;
+            }
+            syn::Meta::List(list) if list.path.is_ident("rename") => {
+                list.parse_nested_meta(|meta| {
+                    let Some(method) = ["coerce", "into_coerced", "to_coerced"]
+                        .into_iter()
+                        .find(|method| meta.path.is_ident(method))
+                    else {
+                        return Err(meta.error(
+                            "Expected 'coerce', 'into_coerced', or 'to_coerced' in rename(...)",
+                        ));
+                    };
+                    let value: syn::LitStr = meta.value()?.parse()?;
+                    let name = value.parse::<Ident>().map_err(|_| {
+                        syn::Error::new_spanned(&value, "rename(...) values must be method names")
+                    })?;
+                    options.renames.retain(|(renamed, _)| renamed != method);
+                    options.renames.push((method.to_string(), name));
+                    Ok(())
+                })?;
+                has_struct_option = true;
+            }
+            syn::Meta::List(list) if list.path.is_ident("marker_enum") => {
+                options
+                    .marker_enums
+                    .extend(parse_named_per_param(&list, "BaseMarkerKind")?);
+                has_struct_option = true;
+            }
+            syn::Meta::List(list) if list.path.is_ident("alias") => {
+                let aliases = list.parse_args_with(|input: syn::parse::ParseStream| {
+                    let mut aliases = Vec::new();
+                    while !input.is_empty() {
+                        aliases.push(input.parse::<syn::ItemType>()?);
+                    }
+                    Ok(aliases)
+                })?;
+                for alias in aliases {
+                    if !alias.generics.params.is_empty() {
+                        return Err(syn::Error::new_spanned(
+                            &alias.generics,
+                            "Aliases used in patterns can't take parameters: write one alias per instantiation, or use `_` type holes",
+                        ));
+                    }
+                    if options
+                        .aliases
+                        .iter()
+                        .any(|known| known.ident == alias.ident)
+                    {
+                        return Err(syn::Error::new_spanned(
+                            &alias.ident,
+                            format!("Duplicate alias '{}'", alias.ident),
+                        ));
+                    }
+                    options.aliases.push(alias);
+                }
+                has_struct_option = true;
+            }
+            syn::Meta::List(list) if list.path.is_ident("type_fn") => {
+                options
+                    .type_fns
+                    .extend(parse_named_per_param(&list, "TypedPathBase")?);
+                has_struct_option = true;
+            }
+            syn::Meta::List(list) if list.path.is_ident("project") => {
+                options.projections.push(parse_projection(&list)?);
+                has_struct_option = true;
+            }
+            syn::Meta::List(list) if list.path.is_ident("allow") || list.path.is_ident("deny") => {
+                options.lints.configure(&list)?;
+                has_struct_option = true;
+            }
+            syn::Meta::List(list) if list.path.is_ident("asref") => {
+                has_asref = true;
+                let idents = list.parse_args_with(
+                    syn::punctuated::Punctuated::<Ident, syn::Token![,]>::parse_terminated,
+                )?;
+                asref_pointers.clear();
+                for ident in idents {
+                    let Some(pointer) = SmartPointer::from_ident(&ident) else {
+                        return Err(syn::Error::new_spanned(
+                            &ident,
+                            "Expected 'Box', 'Rc', or 'Arc' in asref(...)",
+                        ));
+                    };
+                    asref_pointers.push(pointer);
+                }
+            }
+            _ => {
+                return Err(syn::Error::new_spanned(
+                    &meta,
+                    "Expected name-value pair or path",
+                ));
+            }
+        }
+    }
+
+    // Attributes carrying only struct-level options don't declare a coercion
+    if mode.is_none() && has_struct_option && !has_asref {
+        options.any_auto_trait_change |= declares_any && allow_auto_trait_change;
+        return Ok(None);
+    }
+
+    let mode = mode.ok_or_else(|| {
+        syn::Error::new(
+            attr.span(),
+            "Missing coercion mode: use borrowed_from/to, owned_from/to, or cloned_from/to",
+        )
+    })?;
+
+    // An `AnyOf<(..)>` target implies a coercion from each of its members
+    if from_patterns.is_empty()
+        && let Some(implied) = to_pattern.as_deref().and_then(any_of_sources)
+    {
+        from_patterns.push(implied);
+    }
+
+    if from_patterns.is_empty() {
+        return Err(syn::Error::new(
+            attr.span(),
+            "Missing source types: at least one 'borrowed_from', 'owned_from', or 'cloned_from' required",
+        ));
+    }
+
+    let to_pattern = to_pattern.ok_or_else(|| {
+        syn::Error::new(
+            attr.span(),
+            "Missing target type: 'borrowed_to', 'owned_to', or 'cloned_to' required",
+        )
+    })?;
+
+    // Validate that from_mode and to_mode match
+    if let (Some(from_mode), Some(to_mode)) = (from_mode_seen, to_mode_seen) {
+        if from_mode != to_mode {
+            return Err(syn::Error::new(
+                attr.span(),
+                format!(
+                    "Mismatched coercion modes: from side uses {:?} but to side uses {:?}. Both sides must use the same mode (e.g., borrowed_from with borrowed_to)",
+                    from_mode, to_mode
+                ),
+            ));
+        }
+    }
+
+    // Validate asref is only used with borrowed
+    if has_asref && mode != CoercionMode::Borrowed {
+        return Err(syn::Error::new(
+            attr.span(),
+            "asref marker is only valid for borrowed coercions",
+        ));
+    }
+
+    // A borrowed coercion is a plain reinterpretation with no method body to hook into
+    if guard.is_some() && mode == CoercionMode::Borrowed {
+        return Err(syn::Error::new(
+            attr.span(),
+            "guard is only valid for owned and cloned coercions",
+        ));
+    }
+
+    if extern_c.is_some() && mode == CoercionMode::Cloned {
+        return Err(syn::Error::new(
+            attr.span(),
+            "extern_c is only valid for borrowed and owned coercions",
+        ));
+    }
+
+    // Check for no-op coercions (source == target)
+    // This is a warning-level issue, but we'll make it an error for clarity
+    for from_pattern in &from_patterns {
+        if from_pattern.trim() == to_pattern.trim() {
+            return Err(syn::Error::new(
+                attr.span(),
+                format!(
+                    "No-op coercion detected: coercing from '{}' to '{}' (same type). This coercion has no effect and should be removed.",
+                    from_pattern, to_pattern
+                ),
+            ));
+        }
+    }
+
+    Ok(Some(CoercionSpec {
+        from_patterns,
+        to_pattern,
+        kind: mode,
+        span: attr.span(),
+        generate_asref: has_asref,
+        asref_pointers,
+        generic_source: false,
+        guard,
+        extern_c,
+        deprecated,
+        allow_noop,
+        allow_auto_trait_change,
+        when,
+        covers_all,
+        bounds,
+    }))
+}
+
+/// Load coercions from an external spec file (`include = "path"`)
+///
+/// The path is relative to the crate's manifest directory. Each non-empty line that isn't a
+/// `#` comment declares one coercion as `<mode> <from> => <to>`, where mode is `borrowed`,
+/// `owned`, or `cloned` and the patterns use the same syntax as the attribute strings.
+/// Returns the specs and the file's absolute path.
+fn parse_coerce_file(
+    relative: &str,
+    span: proc_macro2::Span,
+) -> syn::Result<(Vec<CoercionSpec>, String)> {
+    let manifest_dir = std::env::var("CARGO_MANIFEST_DIR").unwrap_or_default();
+    let path = std::path::Path::new(&manifest_dir).join(relative);
+    let contents = std::fs::read_to_string(&path).map_err(|err| {
+        syn::Error::new(
+            span,
+            format!("Failed to read coercion file '{}': {}", relative, err),
+        )
+    })?;
+
+    let mut specs = Vec::new();
+    for (index, line) in contents.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+
+        let line_error = |message: &str| {
+            syn::Error::new(span, format!("{}:{}: {}", relative, index + 1, message))
+        };
+
+        let (mode_word, rest) = line.split_once(char::is_whitespace).unwrap_or((line, ""));
+        let kind = match mode_word {
+            "borrowed" => CoercionMode::Borrowed,
+            "owned" => CoercionMode::Owned,
+            "cloned" => CoercionMode::Cloned,
+            _ => {
+                return Err(line_error(
+                    "Expected a line starting with 'borrowed', 'owned', or 'cloned'",
+                ));
+            }
+        };
+
+        let Some((from, to)) = rest.split_once("=>") else {
+            return Err(line_error("Expected '<from> => <to>' after the mode"));
+        };
+        let (from, to) = (normalize_pattern(from), normalize_pattern(to));
+        if from.is_empty() || to.is_empty() {
+            return Err(line_error("Source and target patterns cannot be empty"));
+        }
+        if from == to {
+            return Err(line_error(&format!(
+                "No-op coercion detected: coercing from '{}' to '{}' (same type). This coercion has no effect and should be removed.",
+                from, to
+            )));
+        }
+
+        specs.push(CoercionSpec {
+            from_patterns: vec![from],
+            to_pattern: to,
+            kind,
+            span,
+            generate_asref: false,
+            asref_pointers: Vec::new(),
+            generic_source: false,
+            guard: None,
+            extern_c: None,
+            deprecated: None,
+            allow_noop: false,
+            allow_auto_trait_change: false,
+            when: None,
+            covers_all: None,
+            bounds: Vec::new(),
+        });
+    }
+
+    Ok((specs, path.to_string_lossy().into_owned()))
+}
+
+/// Replace the registered aliases in a spec's patterns with the types they stand for
+fn resolve_aliases(spec: &CoercionSpec, aliases: &[syn::ItemType]) -> syn::Result<CoercionSpec> {
+    let resolve = |pattern: &str| -> syn::Result<String> {
+        let tokens: proc_macro2::TokenStream = pattern
+            .parse()
+            .map_err(|_| syn::Error::new(spec.span, format!("Invalid pattern '{pattern}'")))?;
+        Ok(tokens_to_pattern(&substitute_aliases(tokens, aliases)))
+    };
+    let mut spec = spec.clone();
+    spec.from_patterns = spec
+        .from_patterns
+        .iter()
+        .map(|pattern| resolve(pattern))
+        .collect::<syn::Result<_>>()?;
+    spec.to_pattern = resolve(&spec.to_pattern)?;
+    Ok(spec)
+}
+
+/// Replace each standalone identifier naming an alias, leaving path segments such as
+/// `module::Alias` alone
+fn substitute_aliases(
+    tokens: proc_macro2::TokenStream,
+    aliases: &[syn::ItemType],
+) -> proc_macro2::TokenStream {
+    use proc_macro2::TokenTree;
+
+    let tokens: Vec<TokenTree> = tokens.into_iter().collect();
+    let is_colon = |token: Option<&TokenTree>| matches!(token, Some(TokenTree::Punct(p)) if p.as_char() == ':');
+    let mut output = proc_macro2::TokenStream::new();
+    for (idx, token) in tokens.iter().enumerate() {
+        match token {
+            TokenTree::Ident(ident)
+                if !is_colon(idx.checked_sub(1).and_then(|prev| tokens.get(prev)))
+                    && !is_colon(tokens.get(idx + 1)) =>
+            {
+                match aliases.iter().find(|alias| alias.ident == *ident) {
+                    Some(alias) => {
+                        let ty = &alias.ty;
+                        output.extend(quote! { #ty });
+                    }
+                    None => output.extend([token.clone()]),
+                }
+            }
+            TokenTree::Group(group) => {
+                let mut replaced = proc_macro2::Group::new(
+                    group.delimiter(),
+                    substitute_aliases(group.stream(), aliases),
+                );
+                replaced.set_span(group.span());
+                output.extend([TokenTree::Group(replaced)]);
+            }
+            _ => output.extend([token.clone()]),
+        }
+    }
+    output
+}
+
+/// Parse `option(Param = "Name", ...)`, naming a generated item per type parameter
+fn parse_named_per_param(list: &syn::MetaList, example: &str) -> syn::Result<Vec<(Ident, Ident)>> {
+    let option = list.path.get_ident().unwrap();
+    let names = list.parse_args_with(
+        syn::punctuated::Punctuated::<syn::MetaNameValue, syn::Token![,]>::parse_terminated,
+    )?;
+    let mut parsed = Vec::new();
+    for nv in names {
+        let Some(param) = nv.path.get_ident() else {
+            return Err(syn::Error::new_spanned(
+                &nv.path,
+                format!("Expected a type parameter name, e.g. {option}(Base = \"{example}\")"),
+            ));
+        };
+        let value = extract_string_value(&nv)?;
+        let mut name = syn::parse_str::<Ident>(value.trim()).map_err(|_| {
+            syn::Error::new_spanned(&nv.value, format!("'{option}' values must be type names"))
+        })?;
+        name.set_span(nv.value.span());
+        parsed.push((param.clone(), name));
+    }
+    Ok(parsed)
+}
+
+/// Parse `project(field = "...", to = "...", name = "...")`
+fn parse_projection(list: &syn::MetaList) -> syn::Result<Projection> {
+    let mut field: Option<Ident> = None;
+    let mut target: Option<Type> = None;
+    let mut method: Option<Ident> = None;
+
+    list.parse_nested_meta(|meta| {
+        let value: syn::LitStr = meta.value()?.parse()?;
+        if meta.path.is_ident("field") {
+            field = Some(value.parse()?);
+        } else if meta.path.is_ident("to") {
+            target = Some(value.parse()?);
+        } else if meta.path.is_ident("name") {
+            method = Some(value.parse()?);
+        } else {
+            return Err(meta.error("Expected 'field', 'to', or 'name' in project(...)"));
+        }
+        Ok(())
+    })?;
+
+    let field = field
+        .ok_or_else(|| syn::Error::new_spanned(list, "project(...) requires 'field = \"...\"'"))?;
+    let target = target
+        .ok_or_else(|| syn::Error::new_spanned(list, "project(...) requires 'to = \"...\"'"))?;
+    let method = method.unwrap_or_else(|| Ident::new(&format!("{}_coerced", field), field.span()));
+
+    Ok(Projection {
+        field,
+        target,
+        method,
+    })
+}
+
+fn extract_string_value(nv: &syn::MetaNameValue) -> syn::Result<String> {
+    let syn::Expr::Lit(expr_lit) = &nv.value else {
+        return Err(syn::Error::new_spanned(
+            &nv.value,
+            "Expected string literal",
+        ));
+    };
+
+    let syn::Lit::Str(lit_str) = &expr_lit.lit else {
+        return Err(syn::Error::new_spanned(
+            &expr_lit.lit,
+            "Expected string literal",
+        ));
+    };
+
+    Ok(lit_str.value())
+}
+
+/// Generic arguments of a pattern's struct, e.g. `Json, File` in `Message<Json, File>`
+fn struct_arguments(
+    ty: &Type,
+) -> Option<&syn::punctuated::Punctuated<syn::GenericArgument, syn::Token![,]>> {
+    let Type::Path(TypePath { path, .. }) = ty else {
+        return None;
+    };
+    match &path.segments.last()?.arguments {
+        PathArguments::AngleBracketed(args) => Some(&args.args),
+        _ => None,
+    }
+}
+
+/// Members of an `AnyOf<(A, B, ..)>` marker, or `None` for any other argument
+fn any_of_members(arg: &syn::GenericArgument) -> Option<Vec<&Type>> {
+    let syn::GenericArgument::Type(ty) = arg else {
+        return None;
+    };
+    let Type::Path(TypePath { path, .. }) = ty else {
+        return None;
+    };
+    let segment = path.segments.last()?;
+    if segment.ident != "AnyOf" {
+        return None;
+    }
+    let PathArguments::AngleBracketed(args) = &segment.arguments else {
+        return None;
+    };
+    let Some(syn::GenericArgument::Type(markers)) = args.args.first() else {
+        return None;
+    };
+    Some(match markers {
+        Type::Tuple(tuple) => tuple.elems.iter().collect(),
+        Type::Paren(paren) => vec![&*paren.elem],
+        other => vec![other],
+    })
+}
+
+fn argument_to_string(arg: &syn::GenericArgument) -> String {
+    match arg {
+        syn::GenericArgument::Type(ty) => type_to_string(ty),
+        other => quote!(#other).to_string(),
+    }
+}
+
+/// The source pattern implied by a target containing `AnyOf` markers: each `AnyOf<(A, B)>`
+/// argument is replaced by the alternatives `A | B`
+fn any_of_sources(to_pattern: &str) -> Option<String> {
+    let target: Type = syn::parse_str(to_pattern).ok()?;
+    let args = struct_arguments(&target)?;
+    if !args.iter().any(|arg| any_of_members(arg).is_some()) {
+        return None;
+    }
+
+    let params: Vec<String> = args
+        .iter()
+        .map(|arg| match any_of_members(arg) {
+            Some(members) => members
+                .into_iter()
+                .map(type_to_string)
+                .collect::<Vec<_>>()
+                .join(" | "),
+            None => argument_to_string(arg),
+        })
+        .collect();
+
+    let mut base = target.clone();
+    if let Type::Path(type_path) = &mut base {
+        type_path.path.segments.last_mut()?.arguments = PathArguments::None;
+    }
+    Some(format!("{}<{}>", type_to_string(&base), params.join(", ")))
+}
+
+/// Reject coercions into `AnyOf<(..)>` from markers that aren't among its members
+fn check_any_of_members(source: &Type, target: &Type, span: proc_macro2::Span) -> syn::Result<()> {
+    let (Some(source_args), Some(target_args)) =
+        (struct_arguments(source), struct_arguments(target))
+    else {
+        return Ok(());
+    };
+
+    for (source_arg, target_arg) in source_args.iter().zip(target_args) {
+        let Some(members) = any_of_members(target_arg) else {
+            continue;
+        };
+        let members: Vec<String> = members.into_iter().map(type_to_string).collect();
+
+        // A narrower `AnyOf` widens into a broader one
+        let source_markers: Vec<String> = match any_of_members(source_arg) {
+            Some(source_members) => source_members.into_iter().map(type_to_string).collect(),
+            None => vec![argument_to_string(source_arg)],
+        };
+
+        if let Some(outsider) = source_markers
+            .iter()
+            .find(|marker| !members.contains(marker))
+        {
+            return Err(syn::Error::new(
+                span,
+                format!(
+                    "'{}' is not a member of 'AnyOf<({})>': only listed markers may coerce into it",
+                    outsider,
+                    members.join(", ")
+                ),
+            ));
+        }
+    }
+
+    Ok(())
+}
+
+/// Type parameters held by data fields, which a coercion can't change
+///
+/// Parameters of `#[coerce(deep)]` fields aren't included, since those fields coerce too.
+fn data_params(generics: &syn::Generics, fields: &syn::FieldsNamed) -> Vec<String> {
+    generics
+        .type_params()
+        .filter(|param| {
+            fields.named.iter().any(|field| {
+                let ty = &field.ty;
+                !is_phantom_field(field)
+                    && !is_deep_field(field).unwrap_or(false)
+                    && mentions_ident(quote!(#ty), &param.ident)
+            })
+        })
+        .map(|param| param.ident.to_string())
+        .collect()
+}
+
+/// Expand a CoercionSpec into concrete ParsedCoercion instances
+/// Handles `|` syntax in from_patterns and generates cartesian product
+///
+/// When alternatives pin a data parameter to several concrete types, only pairs that keep the
+/// same data type are generated: `Container<String | Vec<u8>, Raw>` => `Container<String |
+/// Vec<u8>, AnyState>` pairs `String` with `String` and `Vec<u8>` with `Vec<u8>`.
+fn expand_coercion_spec(
+    spec: &CoercionSpec,
+    generics: &syn::Generics,
+    data_params: &[String],
+    lints: &Lints,
+) -> syn::Result<Vec<ParsedCoercion>> {
+    if let Some(when) = &spec.when {
+        return expand_const_rule(spec, when, generics).map(|coercion| vec![coercion]);
+    }
+
+    // Split the to_pattern by | to get all target alternatives
+    let to_alternatives = expand_alternatives(&spec.to_pattern);
+
+    let mut result = Vec::new();
+    let mut changes_data_params = false;
+
+    // For each from_pattern, split by | and create separate coercions
+    for from_pattern in &spec.from_patterns {
+        let from_alternatives = expand_alternatives(from_pattern);
+        let from_count = from_alternatives.len();
+
+        for from_alternative in from_alternatives {
+            let from_parsed = parse_pattern(&from_alternative, generics)?;
+
+            // For each to alternative, create a coercion (Cartesian product)
+            for to_alternative in &to_alternatives {
+                let to_parsed = parse_pattern(to_alternative, generics)?;
+
+                // A single pair that changes a data parameter is still reported by the field check
+                if to_alternatives.len() > 1 || spec.from_patterns.len() > 1 || from_count > 1 {
+                    let source_bindings = type_param_bindings(generics, &from_parsed.target_type);
+                    let target_bindings = type_param_bindings(generics, &to_parsed.target_type);
+                    let changes_data = data_params.iter().any(|param| {
+                        let source = source_bindings.get(param).map(type_to_string);
+                        source.is_some() && source != target_bindings.get(param).map(type_to_string)
+                    });
+                    if changes_data {
+                        changes_data_params = true;
+                        continue;
+                    }
+                }
+                check_any_of_members(&from_parsed.target_type, &to_parsed.target_type, spec.span)?;
+
+                // Alternatives can expand into a pair that doesn't coerce anything
+                let source = type_to_string(&from_parsed.target_type);
+                if source == type_to_string(&to_parsed.target_type) {
+                    if spec.allow_noop || lints.noop == LintLevel::Allow {
+                        continue;
+                    }
+                    return Err(syn::Error::new(
+                        spec.span,
+                        format!(
+                            "No-op coercion detected: '{}' => '{}' expands to '{}' => '{}' (same type). Narrow the alternatives, or add `allow_noop` (or `allow(noop)` for the whole struct) to skip such pairs",
+                            from_pattern, spec.to_pattern, source, source
+                        ),
+                    ));
+                }
+
+                // Validate that type hole positions match between from and to
+                if !spec.generic_source
+                    && from_parsed.type_hole_positions != to_parsed.type_hole_positions
+                {
+                    return Err(syn::Error::new(
+                        proc_macro2::Span::call_site(),
+                        format!(
+                            "Type hole positions mismatch: from pattern '{}' has type holes at {:?}, but to pattern '{}' has type holes at {:?}",
+                            from_alternative,
+                            from_parsed.type_hole_positions,
+                            to_alternative,
+                            to_parsed.type_hole_positions
+                        ),
+                    ));
+                }
+
+                let mut bounds = spec.bounds.clone();
+                bounds.extend(hole_where_predicates(
+                    generics,
+                    &from_parsed.target_type,
+                    &from_parsed.type_hole_positions,
+                ));
+                result.push(ParsedCoercion {
+                    source_type: from_parsed.target_type.clone(),
+                    target_type: to_parsed.target_type.clone(),
+                    type_hole_positions: from_parsed.type_hole_positions.clone(),
+                    generic_source: spec.generic_source,
+                    guard: spec.guard.clone(),
+                    extern_c: spec.extern_c.clone(),
+                    deprecated: spec.deprecated.clone(),
+                    allow_auto_trait_change: spec.allow_auto_trait_change,
+                    when: None,
+                    const_params: Vec::new(),
+                    bounds,
+                });
+            }
+        }
+    }
+
+    if result.is_empty() && changes_data_params {
+        return Err(syn::Error::new(
+            spec.span,
+            format!(
+                "'{}' => '{}' has no pair that keeps the data parameters ({}) unchanged",
+                spec.from_patterns.join(" | "),
+                spec.to_pattern,
+                data_params.join(", ")
+            ),
+        ));
+    }
+
+    // One symbol names one function, which can't be generic
+    if spec.extern_c.is_some() && (result.len() != 1 || !result[0].type_hole_positions.is_empty()) {
+        return Err(syn::Error::new(
+            spec.span,
+            "extern_c requires a single concrete coercion: remove `|` alternatives and `_` type holes",
+        ));
+    }
+
+    Ok(result)
+}
+
+/// The struct's where-clause predicates that constrain a type hole
+///
+/// Inline bounds travel with the hole's generic parameter, but a hole declared as
+/// `struct View<M, T> where T: Default` still needs `T: Default` on every impl. Other
+/// parameters in a predicate are replaced by the source's concrete arguments.
+fn hole_where_predicates(
+    generics: &syn::Generics,
+    source_type: &Type,
+    type_hole_positions: &[usize],
+) -> Vec<syn::WherePredicate> {
+    let Some(where_clause) = &generics.where_clause else {
+        return Vec::new();
+    };
+    let holes: Vec<&Ident> = generics
+        .params
+        .iter()
+        .filter(|param| !matches!(param, syn::GenericParam::Lifetime(_)))
+        .enumerate()
+        .filter(|(position, _)| type_hole_positions.contains(position))
+        .filter_map(|(_, param)| match param {
+            syn::GenericParam::Type(param) => Some(&param.ident),
+            _ => None,
+        })
+        .collect();
+    let mut bindings = type_param_bindings(generics, source_type);
+    bindings.retain(|name, _| !holes.iter().any(|hole| **hole == *name));
+
+    where_clause
+        .predicates
+        .iter()
+        .filter(|predicate| {
+            holes
+                .iter()
+                .any(|hole| mentions_ident(quote!(#predicate), hole))
+        })
+        .map(|predicate| {
+            let mut predicate = predicate.clone();
+            SubstituteTypeParams {
+                bindings: &bindings,
+            }
+            .visit_where_predicate_mut(&mut predicate);
+            predicate
+        })
+        .collect()
+}
+
+/// Expand a `when` rule into a single coercion generic over the const parameters it names
+///
+/// `Permission<LEVEL> => Permission<TO>` with `when = "TO <= LEVEL"` binds `LEVEL` and `TO`
+/// as const parameters of the struct's const parameter type. Other arguments must be concrete,
+/// since one impl has to cover every pair the condition allows.
+fn expand_const_rule(
+    spec: &CoercionSpec,
+    when: &syn::Expr,
+    generics: &syn::Generics,
+) -> syn::Result<ParsedCoercion> {
+    let [from_pattern] = spec.from_patterns.as_slice() else {
+        return Err(syn::Error::new(
+            spec.span,
+            "'when' rules take a single source pattern",
+        ));
+    };
+    let single = |pattern: &str| {
+        if expand_alternatives(pattern).len() != 1 {
+            return Err(syn::Error::new(
+                spec.span,
+                "'when' rules can't use `|` alternatives: the condition already covers every pair",
+            ));
+        }
+        let ty: Type = syn::parse_str(&normalize_pattern(pattern))?;
+        Ok(ty)
+    };
+    let source_type = single(from_pattern)?;
+    let target_type = single(&spec.to_pattern)?;
+
+    let mut const_params: Vec<(Ident, Type)> = Vec::new();
+    for ty in [&source_type, &target_type] {
+        let Type::Path(TypePath { path, .. }) = ty else {
+            return Err(syn::Error::new_spanned(ty, "Expected a struct type"));
+        };
+        let arguments = match &path.segments.last().unwrap().arguments {
+            PathArguments::AngleBracketed(args) => args.args.iter().collect(),
+            _ => Vec::new(),
+        };
+        let arguments = arguments
+            .into_iter()
+            .filter(|arg| !matches!(arg, syn::GenericArgument::Lifetime(_)));
+        let params = generics
+            .params
+            .iter()
+            .filter(|param| !matches!(param, syn::GenericParam::Lifetime(_)));
+
+        for (param, argument) in params.zip(arguments) {
+            if let syn::GenericArgument::Type(Type::Infer(_)) = argument {
+                return Err(syn::Error::new(
+                    spec.span,
+                    "'when' rules can't use `_` type holes: write the types out",
+                ));
+            }
+            let syn::GenericParam::Const(param) = param else {
+                continue;
+            };
+            // A bare identifier names a const parameter; literals and blocks stay as written
+            let syn::GenericArgument::Type(Type::Path(TypePath { qself: None, path })) = argument
+            else {
+                continue;
+            };
+            if let Some(ident) = path.get_ident()
+                && !const_params.iter().any(|(bound, _)| bound == ident)
+            {
+                const_params.push((ident.clone(), param.ty.clone()));
+            }
+        }
+    }
+
+    if const_params.is_empty() {
+        return Err(syn::Error::new(
+            spec.span,
+            "'when' rules must name const parameters, e.g. `Permission<LEVEL>` => `Permission<TO>`",
+        ));
+    }
+    if spec.extern_c.is_some() {
+        return Err(syn::Error::new(
+            spec.span,
+            "extern_c requires a single concrete coercion, but a 'when' rule is generic",
+        ));
+    }
+
+    Ok(ParsedCoercion {
+        source_type,
+        target_type,
+        type_hole_positions: Vec::new(),
+        generic_source: false,
+        guard: spec.guard.clone(),
+        extern_c: None,
+        deprecated: spec.deprecated.clone(),
+        allow_auto_trait_change: spec.allow_auto_trait_change,
+        when: Some(when.clone()),
+        const_params,
+        bounds: spec.bounds.clone(),
+    })
+}
+
+/// Statement checking a `when` rule's condition once the impl is instantiated
+///
+/// Reads the rule's `DeclaredCoercion::PERMITTED`, whose evaluation fails to compile when the
+/// condition is false. Empty for unconditional coercions.
+fn generate_permitted_check(
+    mode: &CoercionMode,
+    coercion: &ParsedCoercion,
+) -> proc_macro2::TokenStream {
+    if coercion.when.is_none() {
+        return quote! {};
+    }
+    let target_type = &coercion.target_type;
+    let mode = mode_ident(mode);
+    quote! {
+        let () = <Self as ::phantom_coerce::__private::DeclaredCoercion<
+            ::phantom_coerce::__private::#mode,
+            #target_type,
+        >>::PERMITTED;
+    }
+}
+
+/// Source and target of a coercion, for detecting repeated pairs
+fn coercion_key(coercion: &ParsedCoercion) -> (String, String) {
+    (
+        type_to_string(&coercion.source_type),
+        type_to_string(&coercion.target_type),
+    )
+}
+
+/// Check the declared coercions, across all modes, as a graph between types
+///
+/// A cycle means some type can be coerced back into itself through another, so erasure could
+/// be undone. With `erased` set, every source type should have a path to it; one that doesn't
+/// usually means a hierarchy that drifted. Targets without a way on, such as partially erased
+/// forms, are fine. `when` rules and generic sources stand for
+/// many pairs at once and are left out.
+fn check_coercion_graph(
+    struct_name: &Ident,
+    coercions: &[&Vec<ParsedCoercion>],
+    options: &StructOptions,
+) -> syn::Result<()> {
+    let mut edges: Vec<(String, String)> = Vec::new();
+    for coercion in coercions.iter().flat_map(|coercions| coercions.iter()) {
+        if coercion.when.is_some() || coercion.generic_source {
+            continue;
+        }
+        let edge = coercion_key(coercion);
+        if !edges.contains(&edge) {
+            edges.push(edge);
+        }
+    }
+    let mut nodes: Vec<&String> = Vec::new();
+    for (source, target) in &edges {
+        for node in [source, target] {
+            if !nodes.contains(&node) {
+                nodes.push(node);
+            }
+        }
+    }
+    let successors = |node: &str| -> Vec<&String> {
+        edges
+            .iter()
+            .filter(|(source, _)| source == node)
+            .map(|(_, target)| target)
+            .collect()
+    };
+
+    if options.lints.coercion_cycle == LintLevel::Deny {
+        // Depth-first search from each node, looking for a path back to it
+        for start in &nodes {
+            let mut stack = vec![vec![*start]];
+            let mut visited: Vec<&String> = Vec::new();
+            while let Some(path) = stack.pop() {
+                let last = *path.last().unwrap();
+                for next in successors(last) {
+                    if next == *start {
+                        let cycle: Vec<&str> = path
+                            .iter()
+                            .map(|node| node.as_str())
+                            .chain([start.as_str()])
+                            .collect();
+                        return Err(syn::Error::new_spanned(
+                            struct_name,
+                            format!(
+                                "Coercion cycle: {}. Erasure can be undone along it; remove one of the pairs, or add `allow(coercion_cycle)` if that's intended",
+                                cycle.join(" => ")
+                            ),
+                        ));
+                    }
+                    if !visited.contains(&next) {
+                        visited.push(next);
+                        let mut path = path.clone();
+                        path.push(next);
+                        stack.push(path);
+                    }
+                }
+            }
+        }
+    }
+
+    if options.lints.unreachable_erased == LintLevel::Deny
+        && let Some((erased, span)) = &options.erased
+    {
+        let erased = type_to_string(erased);
+        for (node, _) in &edges {
+            if *node == erased {
+                continue;
+            }
+            let mut reached = vec![node];
+            let mut index = 0;
+            while index < reached.len() && !reached.contains(&&erased) {
+                for next in successors(reached[index]) {
+                    if !reached.contains(&next) {
+                        reached.push(next);
+                    }
+                }
+                index += 1;
+            }
+            if !reached.contains(&&erased) {
+                return Err(syn::Error::new(
+                    *span,
+                    format!(
+                        "'{}' can't reach the erased form '{}' through the declared coercions. Declare a coercion on the way, or add `allow(unreachable_erased)`",
+                        node, erased
+                    ),
+                ));
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Assert at compile time that the custom phantom fields of each coercion's types are zero-sized
+///
+/// The shared cast helpers check this after monomorphization, which `cargo check` never reaches,
+/// so concrete source and target types are checked here as well. Generic ones are left to the
+/// helpers.
+fn generate_marker_size_checks(
+    generics: &syn::Generics,
+    fields: &syn::FieldsNamed,
+    coercions: &[&Vec<ParsedCoercion>],
+) -> proc_macro2::TokenStream {
+    let markers: Vec<&syn::Field> = fields
+        .named
+        .iter()
+        .filter(|field| is_phantom_field(field) && !is_phantom_data(&field.ty))
+        .collect();
+    if markers.is_empty() {
+        return quote! {};
+    }
+
+    let params: Vec<&Ident> = generics
+        .params
+        .iter()
+        .map(|param| match param {
+            syn::GenericParam::Type(param) => &param.ident,
+            syn::GenericParam::Lifetime(param) => &param.lifetime.ident,
+            syn::GenericParam::Const(param) => &param.ident,
+        })
+        .collect();
+    let mut checked = Vec::new();
+    let mut checks = Vec::new();
+    for coercion in coercions.iter().flat_map(|coercions| coercions.iter()) {
+        for ty in [&coercion.source_type, &coercion.target_type] {
+            let bindings = type_param_bindings(generics, ty);
+            for field in &markers {
+                let field_ty = substitute_type_params(&field.ty, &bindings);
+                let key = type_to_string(&field_ty);
+                if params
+                    .iter()
+                    .any(|param| mentions_ident(quote!(#field_ty), param))
+                    || checked.contains(&key)
+                {
+                    continue;
+                }
+                let message = format!(
+                    "coerce: marker field `{}` must be zero-sized with alignment 1, but `{key}` isn't",
+                    field.ident.as_ref().unwrap()
+                );
+                checked.push(key);
+                checks.push(quote! {
+                    ::core::assert!(
+                        ::core::mem::size_of::<#field_ty>() == 0
+                            && ::core::mem::align_of::<#field_ty>() == 1,
+                        #message
+                    );
+                });
+            }
+        }
+    }
+
+    if checks.is_empty() {
+        return quote! {};
+    }
+    quote! {
+        const _: () = {
+            #(#checks)*
+        };
+    }
+}
+
+/// The type arguments a coercion changes, as `(source, target)` pairs
+///
+/// Only these are compared for auto traits, since the rest of the struct is shared. Const
+/// parameters bound by a `when` rule parse as types but are skipped along with the other const
+/// arguments.
+fn changed_markers(coercion: &ParsedCoercion) -> Vec<(&Type, &Type)> {
+    let (Some(source_args), Some(target_args)) = (
+        struct_arguments(&coercion.source_type),
+        struct_arguments(&coercion.target_type),
+    ) else {
+        return Vec::new();
+    };
+    source_args
+        .iter()
+        .zip(target_args)
+        .filter_map(|pair| match pair {
+            (syn::GenericArgument::Type(source), syn::GenericArgument::Type(target))
+                if type_to_string(source) != type_to_string(target) =>
+            {
+                Some((source, target))
+            }
+            _ => None,
+        })
+        .filter(|(source, target)| {
+            !coercion
+                .const_params
+                .iter()
+                .any(|(param, _)| mentions_ident(quote!(#source #target), param))
+        })
+        .collect()
+}
+
+/// Whether any of the types mention one of the struct's type parameters
+fn mentions_type_param(generics: &syn::Generics, types: &[&Type]) -> bool {
+    generics
+        .type_params()
+        .any(|param| mentions_ident(quote!(#(#types)*), &param.ident))
+}
+
+/// Where-clause predicates requiring `sources` to have each auto trait the `targets` all have
+///
+/// Used for generic sources, which the const assertions in `generate_auto_trait_checks` can't
+/// probe. The targets must be concrete. Only gaining a trait is rejected: a generic source may
+/// still lose one, since there's no bound for "doesn't implement `Send`".
+fn auto_trait_bounds(sources: &[&Type], targets: &[&Type]) -> Vec<syn::WherePredicate> {
+    let mut predicates = Vec::new();
+    for (name, probe, fallback) in [
+        ("SendIf", "SEND", "NotSend"),
+        ("SyncIf", "SYNC", "NotSync"),
+        ("UnpinIf", "UNPIN", "NotUnpin"),
+    ] {
+        let bound = Ident::new(name, proc_macro2::Span::call_site());
+        let probe = Ident::new(probe, proc_macro2::Span::call_site());
+        let fallback = Ident::new(fallback, proc_macro2::Span::call_site());
+        for source in sources {
+            predicates.push(syn::parse_quote! {
+                #source: ::phantom_coerce::__private::auto_traits::#bound<{
+                    #[allow(unused_imports)]
+                    use ::phantom_coerce::__private::auto_traits::{AutoTraits, #fallback};
+                    true #(&& AutoTraits::<#targets>::#probe)*
+                }>
+            });
+        }
+    }
+    predicates
+}
+
+/// Auto-trait bounds for a coercion whose changed markers are generic
+///
+/// Concrete pairs are left to `generate_auto_trait_checks`. A generic target can't be probed
+/// either way, so it needs `allow_auto_trait_change`.
+fn generic_auto_trait_bounds(
+    generics: &syn::Generics,
+    coercion: &ParsedCoercion,
+    span: proc_macro2::Span,
+) -> syn::Result<Vec<syn::WherePredicate>> {
+    let changed = changed_markers(coercion);
+    let (sources, targets): (Vec<_>, Vec<_>) = changed.into_iter().unzip();
+    let generic_targets = mentions_type_param(generics, &targets);
+    if coercion.allow_auto_trait_change
+        || !(generic_targets || mentions_type_param(generics, &sources))
+    {
+        return Ok(Vec::new());
+    }
+    if generic_targets {
+        let (source, target) = coercion_key(coercion);
+        return Err(syn::Error::new(
+            span,
+            format!(
+                "coerce: '{}' => '{}' changes markers to generic types, whose `Send`, `Sync`, and `Unpin` can't be compared. Add `allow_auto_trait_change` to the coercion",
+                source, target
+            ),
+        ));
+    }
+    Ok(auto_trait_bounds(&sources, &targets))
+}
+
+/// Assert at compile time that each coercion keeps `Send`, `Sync`, and `Unpin`
+///
+/// Only the markers that change are compared (see `changed_markers`). Pairs whose changed
+/// markers are generic, such as an `Upcast` without `from`, are bounded by
+/// `generic_auto_trait_bounds` instead. Pairs declared with `allow_auto_trait_change` are
+/// skipped.
+fn generate_auto_trait_checks(
+    generics: &syn::Generics,
+    coercions: &[&Vec<ParsedCoercion>],
+) -> proc_macro2::TokenStream {
+    let mut checked = Vec::new();
+    let mut checks = Vec::new();
+
+    for coercion in coercions.iter().flat_map(|coercions| coercions.iter()) {
+        let key = coercion_key(coercion);
+        if coercion.allow_auto_trait_change || checked.contains(&key) {
+            continue;
+        }
+        checked.push(key.clone());
+
+        let changed = changed_markers(coercion);
+        let generic = changed
+            .iter()
+            .any(|(source, target)| mentions_type_param(generics, &[*source, *target]));
+        if changed.is_empty() || generic {
+            continue;
+        }
+
+        let (source_markers, target_markers): (Vec<_>, Vec<_>) = changed.into_iter().unzip();
+        for (name, probe) in [("Send", "SEND"), ("Sync", "SYNC"), ("Unpin", "UNPIN")] {
+            let probe = Ident::new(probe, proc_macro2::Span::call_site());
+            let message = format!(
+                "coerce: '{}' => '{}' changes whether the type is {}. Use markers with the same auto traits, or add `allow_auto_trait_change` to the coercion if the marker controls {} on purpose",
+                key.0, key.1, name, name
+            );
+            checks.push(quote! {
+                ::core::assert!(
+                    true #(&& AutoTraits::<#source_markers>::#probe)*
+                        == (true #(&& AutoTraits::<#target_markers>::#probe)*),
+                    #message
+                );
+            });
+        }
+    }
+
+    if checks.is_empty() {
+        return quote! {};
+    }
+
+    quote! {
+        const _: () = {
+            #[allow(unused_imports)]
+            use ::phantom_coerce::__private::auto_traits::{AutoTraits, NotSend, NotSync, NotUnpin};
+            #(#checks)*
+        };
+    }
+}
+
+/// Extract the generic parameters a coercion's impls need: its type holes and `when` consts
+/// Returns a TokenStream like `<Type>`, `<Base, const N: usize>`, `<const LEVEL: u8, const TO: u8>`, or ``
+fn extract_type_hole_generics(
+    generics: &syn::Generics,
+    coercion: &ParsedCoercion,
+) -> proc_macro2::TokenStream {
+    if !coercion.const_params.is_empty() {
+        let consts = coercion
+            .const_params
+            .iter()
+            .map(|(ident, ty)| quote! { const #ident: #ty });
+        return quote! { <#(#consts),*> };
+    }
+
+    let type_hole_positions = &coercion.type_hole_positions;
+    if type_hole_positions.is_empty() {
+        // No type holes means fully concrete types, no generics needed
+        return quote! {};
+    }
+
+    // Hole positions count type and const parameters alike
+    let params: Vec<&syn::GenericParam> = generics
+        .params
+        .iter()
+        .filter(|param| !matches!(param, syn::GenericParam::Lifetime(_)))
+        .collect();
+
+    // Holes keep the struct's bounds, including `?Sized` for unsized markers
+    let type_hole_params: Vec<_> = type_hole_positions
+        .iter()
+        .filter_map(|&pos| params.get(pos))
+        .map(|param| match param {
+            syn::GenericParam::Const(param) => {
+                let ident = &param.ident;
+                let ty = &param.ty;
+                quote! { const #ident: #ty }
+            }
+            syn::GenericParam::Type(param) => {
+                let ident = &param.ident;
+                let bounds = &param.bounds;
+                if bounds.is_empty() {
+                    quote! { #ident }
+                } else {
+                    quote! { #ident: #bounds }
+                }
+            }
+            syn::GenericParam::Lifetime(_) => unreachable!("lifetimes are filtered out"),
+        })
+        .collect();
+
+    if type_hole_params.is_empty() {
+        quote! {}
+    } else {
+        quote! { <#(#type_hole_params),*> }
+    }
+}
+
+fn generate_borrowed_impl(
+    _struct_name: &Ident,
+    generics: &syn::Generics,
+    trait_name: &Ident,
+    coercion: &ParsedCoercion,
+    fields: &syn::FieldsNamed,
+    _phantom_fields: &[&Ident],
+    _options: &StructOptions,
+) -> syn::Result<proc_macro2::TokenStream> {
+    let source_type = &coercion.source_type;
+    let target_type = &coercion.target_type;
+
+    let Type::Path(target_path) = target_type else {
+        return Err(syn::Error::new_spanned(
+            target_type,
+            "Coerce target must be a type path",
+        ));
+    };
+
+    let target_segment = target_path.path.segments.last().unwrap();
+    let PathArguments::AngleBracketed(_target_args) = &target_segment.arguments else {
+        return Err(syn::Error::new_spanned(
+            target_type,
+            "Coerce target must have type parameters",
+        ));
+    };
+
+    // Extract only the generic parameters that appear in type holes
+    // For the impl, we need generics only for the type hole positions
+    let generics_for_impl = extract_type_hole_generics(generics, coercion);
+    let deep = deep_fields(generics, coercion, fields, &CoercionMode::Borrowed)?;
+    let field_bounds = deep_field_bounds(&deep);
+    let trace = generate_trace(&CoercionMode::Borrowed, coercion);
+    let permitted = generate_permitted_check(&CoercionMode::Borrowed, coercion);
+    let note = generate_auto_trait_note(coercion);
+    let bounds = &coercion.bounds;
+
+    Ok(quote! {
+        impl #generics_for_impl #trait_name<#target_type> for #source_type
+        where
+            #(#field_bounds,)*
+            #(#bounds,)*
+        {
+            #note
+            fn coerce(&self) -> &#target_type {
+                #trace
+                #permitted
+                // SAFETY: This coercion is declared, so the types differ only in
+                // PhantomData type parameters (see `__phantom_coerce_cast_ref`).
+                unsafe {
+                    Self::__phantom_coerce_cast_ref::<::phantom_coerce::__private::Borrowed, #target_type>(self)
+                }
+            }
+        }
+    })
+}
+
+fn generate_owned_impl(
+    struct_name: &Ident,
+    generics: &syn::Generics,
+    trait_name: &Ident,
+    coercion: &ParsedCoercion,
+    fields: &syn::FieldsNamed,
+    _phantom_fields: &[&Ident],
+    options: &StructOptions,
+) -> syn::Result<proc_macro2::TokenStream> {
+    let source_type = &coercion.source_type;
+    let target_type = &coercion.target_type;
+
+    let Type::Path(target_path) = target_type else {
+        return Err(syn::Error::new_spanned(
+            target_type,
+            "Coerce target must be a type path",
+        ));
+    };
+
+    let target_segment = target_path.path.segments.last().unwrap();
+    let PathArguments::AngleBracketed(_target_args) = &target_segment.arguments else {
+        return Err(syn::Error::new_spanned(
+            target_type,
+            "Coerce target must have type parameters",
+        ));
+    };
+
+    let generics_for_impl = extract_type_hole_generics(generics, coercion);
+    let deep = deep_fields(generics, coercion, fields, &CoercionMode::Owned)?;
+    let guard_call = generate_guard_call(coercion, quote! { &self });
+    let guard_hook = generate_guard_call(coercion, quote! { self });
+    let trace = generate_trace(&CoercionMode::Owned, coercion);
+    let permitted = generate_permitted_check(&CoercionMode::Owned, coercion);
+    let note = generate_auto_trait_note(coercion);
+    let field_bounds = deep_field_bounds(&deep);
+    let bounds = &coercion.bounds;
+
+    if options.safe {
+        let rebuild = generate_rebuild(struct_name, fields, &deep);
+        return Ok(quote! {
+            impl #generics_for_impl #trait_name<#target_type> for #source_type
+            where
+                #(#field_bounds,)*
+                #(#bounds,)*
+            {
+                #note
+                fn into_coerced(self) -> #target_type {
+                    #trace
+                    #permitted
+                    #guard_call
+                    let source = self;
+                    #rebuild
+                }
+
+                fn guard(&self) {
+                    #permitted
+                    #guard_hook
+                }
+            }
+        });
+    }
+
+    Ok(quote! {
+        impl #generics_for_impl #trait_name<#target_type> for #source_type
+        where
+            #(#field_bounds,)*
+            #(#bounds,)*
+        {
+            #note
+            fn into_coerced(self) -> #target_type {
+                #trace
+                #permitted
+                #guard_call
+                // SAFETY: This coercion is declared, so the types differ only in
+                // PhantomData type parameters (see `__phantom_coerce_cast`).
+                unsafe {
+                    Self::__phantom_coerce_cast::<::phantom_coerce::__private::Owned, #target_type>(self)
+                }
+            }
+
+            fn guard(&self) {
+                #permitted
+                #guard_hook
+            }
+        }
+    })
+}
+
+fn generate_cloned_impl(
+    struct_name: &Ident,
+    generics: &syn::Generics,
+    trait_name: &Ident,
+    coercion: &ParsedCoercion,
+    fields: &syn::FieldsNamed,
+    _phantom_fields: &[&Ident],
+    options: &StructOptions,
+) -> syn::Result<proc_macro2::TokenStream> {
+    let source_type = &coercion.source_type;
+    let target_type = &coercion.target_type;
+
+    let Type::Path(target_path) = target_type else {
+        return Err(syn::Error::new_spanned(
+            target_type,
+            "Coerce target must be a type path",
+        ));
+    };
+
+    let target_segment = target_path.path.segments.last().unwrap();
+    let PathArguments::AngleBracketed(_target_args) = &target_segment.arguments else {
+        return Err(syn::Error::new_spanned(
+            target_type,
+            "Coerce target must have type parameters",
+        ));
+    };
+
+    let generics_for_impl = extract_type_hole_generics(generics, coercion);
+    // The safe rebuild consumes a clone, so deep fields go through their owned coercion
+    let deep_mode = if options.safe {
+        CoercionMode::Owned
+    } else {
+        CoercionMode::Cloned
+    };
+    let deep = deep_fields(generics, coercion, fields, &deep_mode)?;
+    let guard_call = generate_guard_call(coercion, quote! { self });
+    let trace = generate_trace(&CoercionMode::Cloned, coercion);
+    let permitted = generate_permitted_check(&CoercionMode::Cloned, coercion);
+    let note = generate_auto_trait_note(coercion);
+    let field_bounds = deep_field_bounds(&deep);
+
+    // Clone data fields one at a time, so markers don't need to be `Clone`
+    let source_bindings = type_param_bindings(generics, source_type);
+    let clone_bounds = fields
+        .named
+        .iter()
+        .filter(|field| !is_phantom_field(field) && needs_clone_bound(generics, field))
+        .map(|field| {
+            let field_ty = substitute_type_params(&field.ty, &source_bindings);
+            quote! { #field_ty: ::core::clone::Clone }
+        });
+    let clone_source = quote! { let source: Self = Self::__phantom_coerce_clone_data(self); };
+    let bounds = &coercion.bounds;
+    let where_clause = quote! { where #(#clone_bounds,)* #(#field_bounds,)* #(#bounds,)* };
+
+    if options.safe {
+        let rebuild = generate_rebuild(struct_name, fields, &deep);
+        return Ok(quote! {
+            impl #generics_for_impl #trait_name<#target_type> for #source_type #where_clause {
+                #note
+                fn to_coerced(&self) -> #target_type {
+                    #trace
+                    #permitted
+                    #guard_call
+                    #clone_source
+                    #rebuild
+                }
+            }
+        });
+    }
+
+    Ok(quote! {
+        impl #generics_for_impl #trait_name<#target_type> for #source_type #where_clause {
+            #note
+            fn to_coerced(&self) -> #target_type {
+                #trace
+                #permitted
+                #guard_call
+                #clone_source
+                // SAFETY: This coercion is declared, so the types differ only in
+                // PhantomData type parameters (see `__phantom_coerce_cast`).
+                // The source's data fields are cloned and the copy is cast.
+                unsafe {
+                    Self::__phantom_coerce_cast::<::phantom_coerce::__private::Cloned, #target_type>(source)
+                }
+            }
+        }
+    })
+}
+
+/// Generate the helper cloned coercions start from: a copy of `self` with each data field
+/// cloned and fresh `PhantomData` markers, so neither the struct nor its markers need `Clone`
+fn generate_clone_data_helper(
+    struct_name: &Ident,
+    generics: &syn::Generics,
+    fields: &syn::FieldsNamed,
+    options: &StructOptions,
+) -> proc_macro2::TokenStream {
+    let mut bounds = Vec::new();
+    let mut inits = Vec::new();
+    for field in &fields.named {
+        let field_name = field.ident.as_ref().unwrap();
+        let cfgs = cfg_attrs(field);
+        if is_phantom_field(field) {
+            let init = phantom_init(field);
+            inits.push(quote! { #(#cfgs)* #field_name: #init });
+        } else {
+            if needs_clone_bound(generics, field) {
+                let field_ty = &field.ty;
+                bounds.push(quote! { #field_ty: ::core::clone::Clone });
+            }
+            inits.push(
+                quote! { #(#cfgs)* #field_name: ::core::clone::Clone::clone(&self.#field_name) },
+            );
+        }
+    }
+    let vis = helper_vis(options);
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+    quote! {
+        impl #impl_generics #struct_name #ty_generics #where_clause {
+            #[doc(hidden)]
+            #[inline(always)]
+            #vis fn __phantom_coerce_clone_data(&self) -> Self
+            where
+                #(#bounds,)*
+            {
+                Self { #(#inits),* }
+            }
+        }
+    }
+}
+
+/// Visibility of the hidden helpers the coercion impls call: crate-wide for `extensible`
+/// structs, whose coercions may be implemented in other modules
+fn helper_vis(options: &StructOptions) -> proc_macro2::TokenStream {
+    if options.extensible {
+        quote! { pub(crate) }
+    } else {
+        quote! {}
+    }
+}
+
+/// Generate the private cast helpers that every non-safe coercion impl calls
+///
+/// Keeping the destructuring guard, layout checks, and pointer cast in one generic function
+/// per struct (instead of inlining them into each impl) shrinks the generated code and leaves
+/// a single `unsafe` body to audit. The helpers only accept targets the derive declared a
+/// coercion to, through the `DeclaredCoercion` bound, and reject size mismatches at compile
+/// time.
+fn generate_cast_helpers(
+    struct_name: &Ident,
+    generics: &syn::Generics,
+    fields: &syn::FieldsNamed,
+    options: &StructOptions,
+    borrowed: bool,
+    owned: bool,
+) -> proc_macro2::TokenStream {
+    if !borrowed && !owned {
+        return quote! {};
+    }
+
+    // Fields keep their `#[cfg]`s, which the block macro and build scripts don't strip
+    let field_patterns = fields.named.iter().map(|field| {
+        let cfgs = cfg_attrs(field);
+        let field_name = field.ident.as_ref().unwrap();
+        quote! { #(#cfgs)* #field_name: _ }
+    });
+    // `PhantomData` is always zero-sized; `phantom_types` have to be checked
+    let marker_checks = fields
+        .named
+        .iter()
+        .filter(|field| is_phantom_field(field) && !is_phantom_data(&field.ty))
+        .map(|field| {
+            let cfgs = cfg_attrs(field);
+            let ty = &field.ty;
+            let message = format!(
+                "coerce: marker field `{}` must be zero-sized with alignment 1",
+                field.ident.as_ref().unwrap()
+            );
+            quote! {
+                #(#cfgs)*
+                let () = const {
+                    ::core::assert!(
+                        ::core::mem::size_of::<#ty>() == 0 && ::core::mem::align_of::<#ty>() == 1,
+                        #message,
+                    );
+                };
+            }
+        });
+    let destructure = quote! {
+        // Compile-time safety guard: ensure all fields are accounted for
+        let #struct_name { #(#field_patterns),* } = &self;
+        #(#marker_checks)*
+    };
+    let guard = quote! {
+        #destructure
+        const {
+            ::core::assert!(
+                ::core::mem::size_of::<Self>() == ::core::mem::size_of::<__CoerceTarget>(),
+                "coerce: source and target sizes differ",
+            );
+        }
+    };
+    let target: Type = syn::parse_quote! { __CoerceTarget };
+    let vis = helper_vis(options);
+    let declared =
+        quote! { ::phantom_coerce::__private::DeclaredCoercion<__CoerceMode, __CoerceTarget> };
+
+    let cast_ref = (borrowed && has_unsized_tail(fields)).then(|| {
+        let layout_checks = options.layout_checks.then(|| {
+            quote! {
+                ::core::debug_assert_eq!(
+                    ::core::mem::size_of_val(self),
+                    ::core::mem::size_of_val(target),
+                    "coerce: source and target sizes differ",
+                );
+                ::core::debug_assert_eq!(
+                    ::core::mem::align_of_val(self),
+                    ::core::mem::align_of_val(target),
+                    "coerce: source and target alignments differ",
+                );
+            }
+        });
+        quote! {
+            #[doc(hidden)]
+            #[inline(always)]
+            #vis unsafe fn __phantom_coerce_cast_ref<__CoerceMode, __CoerceTarget: ?::core::marker::Sized>(&self) -> &__CoerceTarget
+            where
+                Self: #declared,
+            {
+                #destructure
+                const {
+                    ::core::assert!(
+                        ::core::mem::size_of::<&Self>() == ::core::mem::size_of::<&__CoerceTarget>(),
+                        "coerce: source and target pointer metadata differ",
+                    );
+                }
+                // SAFETY: The caller guarantees the types differ only in PhantomData type
+                // parameters, so they share a layout and the same unsized tail. The fat
+                // pointers therefore carry the same metadata, and copying one as the other
+                // keeps it.
+                let target = unsafe { ::core::mem::transmute_copy::<&Self, &__CoerceTarget>(&self) };
+                #layout_checks
+                target
+            }
+        }
+    });
+    let cast_ref = cast_ref.or_else(|| borrowed.then(|| {
+        let layout_checks = generate_layout_checks(options, &target, true);
+        quote! {
+            #[doc(hidden)]
+            #[inline(always)]
+            #vis unsafe fn __phantom_coerce_cast_ref<__CoerceMode, __CoerceTarget>(&self) -> &__CoerceTarget
+            where
+                Self: #declared,
+            {
+                #guard
+                #layout_checks
+                // SAFETY: The caller guarantees the types differ only in PhantomData type
+                // parameters, so they share a layout.
+                unsafe { &*(self as *const Self).cast::<__CoerceTarget>() }
+            }
+        }
+    }));
+
+    let cast = owned.then(|| {
+        let layout_checks = generate_layout_checks(options, &target, false);
+        quote! {
+            #[doc(hidden)]
+            #[inline(always)]
+            #vis unsafe fn __phantom_coerce_cast<__CoerceMode, __CoerceTarget>(self) -> __CoerceTarget
+            where
+                Self: #declared,
+            {
+                #guard
+                #layout_checks
+                let source = ::core::mem::ManuallyDrop::new(self);
+                // SAFETY: The caller guarantees the types differ only in PhantomData type
+                // parameters, so they share a layout. `source` is never dropped.
+                unsafe { ::core::ptr::read((&*source as *const Self).cast::<__CoerceTarget>()) }
+            }
+        }
+    });
+
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+    quote! {
+        impl #impl_generics #struct_name #ty_generics #where_clause {
+            #cast_ref
+            #cast
+        }
+    }
+}
+
+/// Whether the struct's last field is unsized (`str`, a slice, or a trait object)
+///
+/// Such structs are dynamically sized themselves, so only references to them can be coerced.
+fn has_unsized_tail(fields: &syn::FieldsNamed) -> bool {
+    let Some(last) = fields.named.last() else {
+        return false;
+    };
+    match &last.ty {
+        Type::Slice(_) | Type::TraitObject(_) => true,
+        Type::Path(TypePath { qself: None, path }) => path.is_ident("str"),
+        _ => false,
+    }
+}
+
+/// Name of the per-struct coercion trait for `mode`, e.g. `CoerceRefTypedPath`
+///
+/// `trait_name` replaces the struct name when given.
+fn coercion_trait_name(struct_name: &Ident, options: &StructOptions, mode: &CoercionMode) -> Ident {
+    let base = options.trait_name.as_ref().unwrap_or(struct_name);
+    let prefix = match mode {
+        CoercionMode::Borrowed => "CoerceRef",
+        CoercionMode::Owned => "CoerceOwned",
+        CoercionMode::Cloned => "CoerceCloned",
+    };
+    Ident::new(&format!("{}{}", prefix, base), base.span())
+}
+
+/// Name of an inherent coercion method, after any `rename(...)`
+fn inherent_method_name(options: &StructOptions, method: &str) -> Ident {
+    options
+        .renames
+        .iter()
+        .find(|(renamed, _)| renamed == method)
+        .map(|(_, name)| name.clone())
+        .unwrap_or_else(|| Ident::new(method, proc_macro2::Span::call_site()))
+}
+
+/// Attributes documenting (or hiding) a coercion trait and its method, per `docs = "..."`
+fn coercion_trait_docs(
+    struct_name: &Ident,
+    options: &StructOptions,
+    mode: &CoercionMode,
+) -> (proc_macro2::TokenStream, proc_macro2::TokenStream) {
+    let (summary, method) = match mode {
+        CoercionMode::Borrowed => (
+            format!("Borrowed coercions declared on `{struct_name}`: views `&Self` as `&Output`."),
+            "Borrow `self` as the coercion target.",
+        ),
+        CoercionMode::Owned => (
+            format!("Owned coercions declared on `{struct_name}`: converts `Self` into `Output`."),
+            "Convert `self` into the coercion target.",
+        ),
+        CoercionMode::Cloned => (
+            format!(
+                "Cloned coercions declared on `{struct_name}`: clones the data of `&Self` into `Output`."
+            ),
+            "Clone the data fields of `self` into the coercion target.",
+        ),
+    };
+    let details = format!(
+        "Implemented by `#[derive(Coerce)]` for each declared pair, listed in the Coercions section of `{struct_name}`. It can't be implemented by hand."
+    );
+    match options.docs {
+        Docs::Plain => (quote! {}, quote! {}),
+        Docs::Hidden => (quote! { #[doc(hidden)] }, quote! {}),
+        Docs::Shown => (
+            quote! {
+                #[doc = #summary]
+                #[doc = ""]
+                #[doc = #details]
+            },
+            quote! { #[doc = #method] },
+        ),
+    }
+}
+
+/// Hide the coercion impls and the coercions table from rustdoc (`docs = "hidden"`)
+///
+/// Impls the struct asked for separately, such as `AsRef`, `Debug`, or `HasAnyMarker`, stay
+/// documented.
+fn hide_coercion_docs(
+    output: proc_macro2::TokenStream,
+    struct_name: &Ident,
+    options: &StructOptions,
+) -> syn::Result<proc_macro2::TokenStream> {
+    if options.docs != Docs::Hidden {
+        return Ok(output);
+    }
+
+    let trait_names = [
+        CoercionMode::Borrowed,
+        CoercionMode::Owned,
+        CoercionMode::Cloned,
+    ]
+    .map(|mode| coercion_trait_name(struct_name, options, &mode));
+    let mut file = syn::parse2::<syn::File>(output)?;
+    for item in &mut file.items {
+        let syn::Item::Impl(item) = item else {
+            continue;
+        };
+        let machinery = match &item.trait_ {
+            Some((_, path, _)) => path.segments.last().is_some_and(|segment| {
+                let ident = &segment.ident;
+                trait_names.contains(ident)
+                    || ident == "Coercible"
+                    || ident == "CoercibleRef"
+                    || ident == "CoercionList"
+            }),
+            // The coercions table is the doc comment of an empty inherent impl
+            None => item.items.is_empty(),
+        };
+        if machinery {
+            item.attrs.push(syn::parse_quote! { #[doc(hidden)] });
+        }
+    }
+    Ok(quote! { #file })
+}
+
+/// Private module holding the trait that seals the coercion traits when `trait_vis` is set
+fn seal_module_name(struct_name: &Ident) -> Ident {
+    Ident::new(
+        &format!("__phantom_coerce_sealed_{}", struct_name),
+        struct_name.span(),
+    )
+}
+
+/// Declare the sealing trait for a struct whose coercion traits are made visible
+///
+/// `coerce_all_ref` and the map and set functions rely on every impl of these traits being a
+/// generated one, so dependents must not be able to add their own.
+fn generate_seal_module(struct_name: &Ident, options: &StructOptions) -> proc_macro2::TokenStream {
+    if options.trait_vis.is_none() {
+        return quote! {};
+    }
+
+    let module = seal_module_name(struct_name);
+    let vis = helper_vis(options);
+    quote! {
+        #[doc(hidden)]
+        #[allow(non_snake_case)]
+        #vis mod #module {
+            pub trait Sealed<Mode, Output: ?::core::marker::Sized> {}
+        }
+    }
+}
+
+/// The `: Sealed<Mode, Output>` supertrait bound of a visible coercion trait
+fn sealed_bound(
+    struct_name: &Ident,
+    options: &StructOptions,
+    mode: &CoercionMode,
+) -> proc_macro2::TokenStream {
+    if options.trait_vis.is_none() {
+        return quote! {};
+    }
+
+    let module = seal_module_name(struct_name);
+    let mode = mode_marker(mode);
+    quote! { : #module::Sealed<#mode, Output> }
+}
+
+/// Implement the sealing trait for a declared coercion (with `trait_vis`)
+fn generate_sealed_impl(
+    struct_name: &Ident,
+    generics: &syn::Generics,
+    coercion: &ParsedCoercion,
+    mode: &CoercionMode,
+    options: &StructOptions,
+) -> proc_macro2::TokenStream {
+    if options.trait_vis.is_none() {
+        return quote! {};
+    }
+
+    let module = seal_module_name(struct_name);
+    let mode = mode_marker(mode);
+    let source_type = &coercion.source_type;
+    let target_type = &coercion.target_type;
+    let generics_for_impl = extract_type_hole_generics(generics, coercion);
+    let bounds = &coercion.bounds;
+    quote! {
+        impl #generics_for_impl #module::Sealed<#mode, #target_type> for #source_type
+        where
+            #(#bounds,)*
+        {
+        }
+    }
+}
+
+/// The public marker type for `mode` (see `phantom_coerce::mode`)
+fn mode_marker(mode: &CoercionMode) -> proc_macro2::TokenStream {
+    match mode {
+        CoercionMode::Borrowed => quote! { ::phantom_coerce::mode::Borrowed },
+        CoercionMode::Owned => quote! { ::phantom_coerce::mode::Owned },
+        CoercionMode::Cloned => quote! { ::phantom_coerce::mode::Cloned },
+    }
+}
+
+/// Record a declared coercion in `DeclaredCoercion`, which bounds the cast helpers and answers
+/// `compile_fail_tests!`
+///
+/// Unlike the per-struct coercion traits this one is nameable from anywhere, so negative
+/// assertions can be written outside the struct's module.
+fn generate_declared_impl(
+    generics: &syn::Generics,
+    coercion: &ParsedCoercion,
+    mode: &CoercionMode,
+) -> proc_macro2::TokenStream {
+    let source_type = &coercion.source_type;
+    let target_type = &coercion.target_type;
+    let generics_for_impl = extract_type_hole_generics(generics, coercion);
+    let bounds = &coercion.bounds;
+    let mode = mode_ident(mode);
+    let permitted = coercion.when.as_ref().map(|when| {
+        let message = format!(
+            "coerce: '{}' => '{}' requires `{}`",
+            type_to_string(source_type),
+            type_to_string(target_type),
+            quote!(#when),
+        );
+        quote! {
+            const PERMITTED: () = ::core::assert!(#when, #message);
+        }
+    });
+
+    quote! {
+        impl #generics_for_impl ::phantom_coerce::__private::DeclaredCoercion<
+            ::phantom_coerce::__private::#mode,
+            #target_type,
+        > for #source_type
+        where
+            #(#bounds,)*
+        {
+            #permitted
+        }
+    }
+}
+
+/// Name of a mode's marker type, as found in both `phantom_coerce::mode` and `__private`
+fn mode_ident(mode: &CoercionMode) -> proc_macro2::TokenStream {
+    match mode {
+        CoercionMode::Borrowed => quote! { Borrowed },
+        CoercionMode::Owned => quote! { Owned },
+        CoercionMode::Cloned => quote! { Cloned },
+    }
+}
+
+/// Implement `CoercionList` for every instantiation of the struct
+///
+/// Entries are nested `(Coercion<Mode, Source, Target>, Rest)` pairs ending in `()`. Type holes
+/// are the struct's own parameters, so they're bound by the impl's generics. `when` rules can't
+/// be enumerated and are left out.
+fn generate_coercion_list(
+    struct_name: &Ident,
+    generics: &syn::Generics,
+    coercions: &[(CoercionMode, &Vec<ParsedCoercion>)],
+) -> proc_macro2::TokenStream {
+    let list = coercions
+        .iter()
+        .flat_map(|(mode, coercions)| coercions.iter().map(move |coercion| (mode, coercion)))
+        .filter(|(_, coercion)| coercion.when.is_none())
+        .rev()
+        .fold(quote! { () }, |rest, (mode, coercion)| {
+            let mode = mode_ident(mode);
+            let source_type = &coercion.source_type;
+            let target_type = &coercion.target_type;
+            quote! {
+                (
+                    ::phantom_coerce::Coercion<::phantom_coerce::mode::#mode, #source_type, #target_type>,
+                    #rest
+                )
+            }
+        });
+
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+    quote! {
+        impl #impl_generics ::phantom_coerce::CoercionList for #struct_name #ty_generics #where_clause {
+            type Coercions = #list;
+        }
+    }
+}
+
+/// Generate an empty inherent impl whose doc comment tables every declared coercion
+///
+/// A derive can't add docs to the struct itself, but rustdoc shows inherent impl docs on the
+/// struct's page, next to the generated methods.
+fn generate_coercion_table(
+    struct_name: &Ident,
+    generics: &syn::Generics,
+    coercions: &[(CoercionMode, &Vec<ParsedCoercion>)],
+) -> proc_macro2::TokenStream {
+    let mut rows = Vec::new();
+    for (mode, coercions) in coercions {
+        let method = match mode {
+            CoercionMode::Borrowed => "coerce",
+            CoercionMode::Owned => "into_coerced",
+            CoercionMode::Cloned => "to_coerced",
+        };
+        for coercion in coercions.iter() {
+            let mut notes = Vec::new();
+            if let Some(when) = &coercion.when {
+                notes.push(format!("when `{}`", tokens_to_pattern(&quote!(#when))));
+            }
+            if let Some(guard) = &coercion.guard {
+                notes.push(format!(
+                    "guarded by `{}`",
+                    tokens_to_pattern(&quote!(#guard))
+                ));
+            }
+            if let Some(note) = &coercion.deprecated {
+                notes.push(format!("deprecated: {}", note));
+            }
+            rows.push(format!(
+                "| `{}` | `{}` | `{}` | {} |",
+                method,
+                type_to_string(&coercion.source_type),
+                type_to_string(&coercion.target_type),
+                notes.join("; ")
+            ));
+        }
+    }
+    generate_doc_table(
+        struct_name,
+        generics,
+        "| Method | From | To | Notes |\n|---|---|---|---|",
+        &rows,
+    )
+}
+
+/// Doc comment lines for a `# Coercions` section with the given table
+fn generate_doc_table(
+    struct_name: &Ident,
+    generics: &syn::Generics,
+    header: &str,
+    rows: &[String],
+) -> proc_macro2::TokenStream {
+    let lines = [
+        "# Coercions".to_string(),
+        String::new(),
+        format!("Generated by `#[derive(Coerce)]` for `{}`.", struct_name),
+        String::new(),
+        header.to_string(),
+    ]
+    .into_iter()
+    .chain(rows.iter().cloned());
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+    quote! {
+        #(#[doc = #lines])*
+        impl #impl_generics #struct_name #ty_generics #where_clause {}
+    }
+}
+
+/// Name of the hidden per-struct macro that `for_each_coercion!` forwards to
+fn for_each_macro_name(struct_name: &Ident) -> Ident {
+    Ident::new(
+        &format!("__phantom_coerce_for_each_{}", struct_name),
+        struct_name.span(),
+    )
+}
+
+/// Generate a crate-visible `macro_rules!` that invokes a callback once per declared coercion
+///
+/// `macro_rules!` can't be exported past the crate from a derive, so it's brought into the
+/// struct's module with `pub(crate) use` and found by path from `for_each_coercion!`. Like the
+/// coercion list, it skips `when` rules.
+fn generate_for_each_macro(
+    struct_name: &Ident,
+    coercions: &[(CoercionMode, &Vec<ParsedCoercion>)],
+) -> proc_macro2::TokenStream {
+    let macro_name = for_each_macro_name(struct_name);
+    let invocations = coercions.iter().flat_map(|(mode, coercions)| {
+        let mode = match mode {
+            CoercionMode::Borrowed => quote! { borrowed },
+            CoercionMode::Owned => quote! { owned },
+            CoercionMode::Cloned => quote! { cloned },
+        };
+        coercions
+            .iter()
+            .filter(|coercion| coercion.when.is_none())
+            .map(move |coercion| {
+                let source_type = &coercion.source_type;
+                let target_type = &coercion.target_type;
+                quote! { $($callback)::+! { #mode #source_type => #target_type } }
+            })
+    });
+
+    quote! {
+        #[doc(hidden)]
+        macro_rules! #macro_name {
+            ($($callback:ident)::+) => {
+                #(#invocations)*
+            };
+        }
+
+        #[allow(unused_imports)]
+        pub(crate) use #macro_name;
+    }
+}
+
+/// Name of the hidden per-struct macro that `for_each_specific!` forwards to
+fn for_each_specific_macro_name(struct_name: &Ident) -> Ident {
+    Ident::new(
+        &format!("__phantom_coerce_for_each_specific_{}", struct_name),
+        struct_name.span(),
+    )
+}
+
+/// Generate a crate-visible `macro_rules!` that expands a body once per specific type
+///
+/// The specific types are the concrete sources of the declared coercions, without duplicates;
+/// sources with type holes or `when` rules are generic, so they're skipped. Each expansion gets
+/// its own block with the caller's name aliased to the type.
+fn generate_for_each_specific_macro(
+    struct_name: &Ident,
+    coercions: &[&Vec<ParsedCoercion>],
+) -> proc_macro2::TokenStream {
+    let macro_name = for_each_specific_macro_name(struct_name);
+    let mut specifics: Vec<&Type> = Vec::new();
+    for coercion in coercions.iter().flat_map(|coercions| coercions.iter()) {
+        let generic = !coercion.type_hole_positions.is_empty()
+            || !coercion.const_params.is_empty()
+            || coercion.generic_source;
+        let source = type_to_string(&coercion.source_type);
+        if !generic && !specifics.iter().any(|ty| type_to_string(ty) == source) {
+            specifics.push(&coercion.source_type);
+        }
+    }
+
+    quote! {
+        #[doc(hidden)]
+        macro_rules! #macro_name {
+            ($alias:ident => $body:expr) => {{
+                #({
+                    #[allow(dead_code)]
+                    type $alias = #specifics;
+                    $body;
+                })*
+            }};
+        }
+
+        #[allow(unused_imports)]
+        pub(crate) use #macro_name;
+    }
+}
+
+/// Whether the struct carries `#[repr(C)]` (possibly alongside other repr hints)
+fn is_repr_c(input: &DeriveInput) -> bool {
+    input
+        .attrs
+        .iter()
+        .filter(|attr| attr.path().is_ident("repr"))
+        .any(|attr| {
+            let mut repr_c = false;
+            let _ = attr.parse_nested_meta(|meta| {
+                repr_c |= meta.path.is_ident("C");
+                // Skip arguments such as `align(8)`
+                if meta.input.peek(syn::token::Paren) {
+                    let _content;
+                    syn::parenthesized!(_content in meta.input);
+                }
+                Ok(())
+            });
+            repr_c
+        })
+}
+
+/// Generate an unmangled `extern "C"` function performing the coercion (`extern_c = "name"`)
+///
+/// Borrowed coercions take and return pointers, owned coercions pass the struct by value.
+fn generate_extern_c_shim(
+    vis: &syn::Visibility,
+    trait_name: &Ident,
+    coercion: &ParsedCoercion,
+    mode: &CoercionMode,
+) -> proc_macro2::TokenStream {
+    let Some(symbol) = &coercion.extern_c else {
+        return quote! {};
+    };
+
+    let source_type = &coercion.source_type;
+    let target_type = &coercion.target_type;
+    let deprecated = coercion
+        .deprecated
+        .as_ref()
+        .map(|note| quote! { #[deprecated(note = #note)] });
+    match mode {
+        CoercionMode::Borrowed => quote! {
+            #deprecated
+            #[unsafe(no_mangle)]
+            #vis extern "C" fn #symbol(source: &#source_type) -> &#target_type {
+                <#source_type as #trait_name<#target_type>>::coerce(source)
+            }
+        },
+        CoercionMode::Owned => quote! {
+            #deprecated
+            #[unsafe(no_mangle)]
+            #vis extern "C" fn #symbol(source: #source_type) -> #target_type {
+                <#source_type as #trait_name<#target_type>>::into_coerced(source)
+            }
+        },
+        CoercionMode::Cloned => quote! {},
+    }
+}
+
+/// Generate an unmangled wrapper performing the coercion, for inspecting its machine code
+/// Returns an empty TokenStream unless the `verify` feature is enabled
+///
+/// The symbol is `phantom_coerce_verify_{struct}_{mode}_{index}`, numbered in declaration order
+/// after alternatives are expanded. Only unconditional, concrete coercions get one: generic
+/// functions can't be unmangled, and `when` rules or deep fields may rule a pair out. Safe mode
+/// and late coercions are skipped too.
+fn generate_verify_wrapper(
+    input: &DeriveInput,
+    fields: &syn::FieldsNamed,
+    trait_name: &Ident,
+    coercion: &ParsedCoercion,
+    mode: &CoercionMode,
+    index: usize,
+    options: &StructOptions,
+) -> proc_macro2::TokenStream {
+    if !cfg!(feature = "verify")
+        || options.safe
+        || options.late
+        || coercion.when.is_some()
+        || !coercion.bounds.is_empty()
+        || fields
+            .named
+            .iter()
+            .any(|field| is_deep_field(field).unwrap_or(false))
+        || input.generics.lifetimes().next().is_some()
+        || !extract_type_hole_generics(&input.generics, coercion).is_empty()
+    {
+        return quote! {};
+    }
+
+    let mode_name = match mode {
+        CoercionMode::Borrowed => "borrowed",
+        CoercionMode::Owned => "owned",
+        CoercionMode::Cloned => return quote! {},
+    };
+    let source_type = &coercion.source_type;
+    let target_type = &coercion.target_type;
+    // Unmangled symbols share one namespace across every linked crate
+    let prefix = options
+        .verify_prefix
+        .as_ref()
+        .map(|prefix| format!("{}_", prefix))
+        .unwrap_or_default();
+    let symbol = quote::format_ident!(
+        "phantom_coerce_verify_{}{}_{}_{}",
+        prefix,
+        snake_case(&input.ident),
+        mode_name,
+        index
+    );
+    match mode {
+        CoercionMode::Borrowed => quote! {
+            #[doc(hidden)]
+            #[unsafe(no_mangle)]
+            #[inline(never)]
+            fn #symbol(source: &#source_type) -> &#target_type {
+                <#source_type as #trait_name<#target_type>>::coerce(source)
+            }
+        },
+        CoercionMode::Owned => quote! {
+            #[doc(hidden)]
+            #[unsafe(no_mangle)]
+            #[inline(never)]
+            fn #symbol(source: #source_type) -> #target_type {
+                <#source_type as #trait_name<#target_type>>::into_coerced(source)
+            }
+        },
+        CoercionMode::Cloned => quote! {},
+    }
+}
+
+/// Convert a type name like `TypedPath` to `typed_path`
+fn snake_case(ident: &Ident) -> String {
+    let mut snake = String::new();
+    for (i, ch) in ident.to_string().chars().enumerate() {
+        if ch.is_uppercase() && i > 0 {
+            snake.push('_');
+        }
+        snake.extend(ch.to_lowercase());
+    }
+    snake
+}
+
+/// Implement the public `CoercibleRef` trait for a borrowed coercion, for `Coerced` and bounds
+fn generate_coercible_ref_impl(
+    generics: &syn::Generics,
+    trait_name: &Ident,
+    coercion: &ParsedCoercion,
+) -> proc_macro2::TokenStream {
+    let source_type = &coercion.source_type;
+    let target_type = &coercion.target_type;
+    let generics_for_impl = extract_type_hole_generics(generics, coercion);
+    let bounds = &coercion.bounds;
+
+    // `&Source: Coercible<&Target>`, for bounding borrowed arguments. The runtime crate's
+    // reflexive impl for references already covers every pair a generic source or a `when`
+    // rule could produce with source equal to target, so those would overlap with it
+    let reference_impl = (!coercion.generic_source && coercion.when.is_none()).then(|| {
+        let mut ref_generics: syn::Generics =
+            syn::parse2(generics_for_impl.clone()).expect("hole generics parse as generics");
+        ref_generics
+            .params
+            .insert(0, syn::parse_quote! { '__coerce });
+        quote! {
+            impl #ref_generics ::phantom_coerce::Coercible<&'__coerce #target_type> for &'__coerce #source_type
+            where
+                #source_type: #trait_name<#target_type>,
+                #(#bounds,)*
+            {
+                fn into_coerced(self) -> &'__coerce #target_type {
+                    #trait_name::coerce(self)
+                }
+            }
+        }
+    });
+
+    quote! {
+        impl #generics_for_impl ::phantom_coerce::CoercibleRef<#target_type> for #source_type
+        where
+            #source_type: #trait_name<#target_type>,
+            #(#bounds,)*
+        {
+            fn coerce(&self) -> &#target_type {
+                #trait_name::coerce(self)
+            }
+        }
+
+        #reference_impl
+    }
+}
+
+/// Implement `Coercible<Self>` for every instantiation of the struct
+///
+/// The runtime crate can't provide a blanket reflexive impl alongside its container impls, so
+/// each derive adds its own. A generic-source coercion already covers its target, which is the
+/// only reflexive pair it could provide without overlapping, so it gets the `Coercible` impl
+/// instead (see `generate_coercible_impl`).
+fn generate_reflexive_coercible(
+    struct_name: &Ident,
+    generics: &syn::Generics,
+    owned_coercions: &[ParsedCoercion],
+) -> proc_macro2::TokenStream {
+    if owned_coercions
+        .iter()
+        .any(|coercion| coercion.generic_source)
+    {
+        return quote! {};
+    }
+
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+    quote! {
+        impl #impl_generics ::phantom_coerce::Coercible<#struct_name #ty_generics>
+            for #struct_name #ty_generics #where_clause
+        {
+            fn into_coerced(self) -> Self {
+                self
+            }
+
+            fn into_coerced_box(self: ::std::boxed::Box<Self>) -> ::std::boxed::Box<Self> {
+                self
+            }
+        }
+    }
+}
+
+/// Implement the public `Coercible` trait for an owned coercion, so generic code can bound on it
+///
+/// Outside safe mode, boxed values are coerced inside their existing allocation. Skipped for
+/// `when` rules: an impl over every allowed const pair would include the reflexive one.
+fn generate_coercible_impl(
+    generics: &syn::Generics,
+    trait_name: &Ident,
+    coercion: &ParsedCoercion,
+    options: &StructOptions,
+) -> proc_macro2::TokenStream {
+    if coercion.when.is_some() {
+        return quote! {};
+    }
+
+    let source_type = &coercion.source_type;
+    let target_type = &coercion.target_type;
+    let generics_for_impl = extract_type_hole_generics(generics, coercion);
+    let bounds = &coercion.bounds;
+
+    let into_coerced_box = (!options.safe).then(|| {
+        quote! {
+            fn into_coerced_box(
+                self: ::std::boxed::Box<Self>,
+            ) -> ::std::boxed::Box<#target_type> {
+                const {
+                    ::core::assert!(
+                        ::core::mem::size_of::<Self>() == ::core::mem::size_of::<#target_type>()
+                            && ::core::mem::align_of::<Self>()
+                                == ::core::mem::align_of::<#target_type>(),
+                        "coerce: source and target layouts differ",
+                    );
+                }
+                let raw = ::std::boxed::Box::into_raw(self);
+                // SAFETY: `raw` came from a box and is read exactly once; the allocation is
+                // only used as uninitialized memory afterwards.
+                let source = unsafe { raw.read() };
+                // SAFETY: Source and target have the same size and alignment, so the
+                // allocation fits a `Target`. Holding it as `MaybeUninit` frees it without
+                // dropping the moved-out value if the coercion panics.
+                let mut slot = unsafe {
+                    ::std::boxed::Box::from_raw(raw.cast::<::core::mem::MaybeUninit<#target_type>>())
+                };
+                slot.write(#trait_name::into_coerced(source));
+                // SAFETY: The slot was just initialized.
+                unsafe { ::std::boxed::Box::from_raw(::std::boxed::Box::into_raw(slot).cast::<#target_type>()) }
+            }
+        }
+    });
+
+    quote! {
+        impl #generics_for_impl ::phantom_coerce::Coercible<#target_type> for #source_type
+        where
+            #source_type: #trait_name<#target_type>,
+            #(#bounds,)*
+        {
+            fn into_coerced(self) -> #target_type {
+                #trait_name::into_coerced(self)
+            }
+
+            #into_coerced_box
+        }
+    }
+}
+
+/// Generate `FromIterator<Source>` for collections of the target, so iterators of specific
+/// values `collect` straight into generic collections
+///
+/// Skipped for generic sources and `when` rules: their source can equal the target, which
+/// would overlap with the standard library's `FromIterator<T>` impls.
+fn generate_from_iterator_impls(
+    generics: &syn::Generics,
+    coercion: &ParsedCoercion,
+) -> proc_macro2::TokenStream {
+    if coercion.generic_source || coercion.when.is_some() {
+        return quote! {};
+    }
+
+    let source_type = &coercion.source_type;
+    let target_type = &coercion.target_type;
+    let generics_for_impl = extract_type_hole_generics(generics, coercion);
+    let bounds = &coercion.bounds;
+    let bounds = quote! { #(#bounds,)* };
+    let collections = [
+        quote! { ::std::vec::Vec<#target_type> },
+        quote! { ::std::collections::VecDeque<#target_type> },
+        quote! { ::std::collections::LinkedList<#target_type> },
+        quote! { ::std::boxed::Box<[#target_type]> },
+        quote! { ::std::rc::Rc<[#target_type]> },
+        quote! { ::std::sync::Arc<[#target_type]> },
+    ];
+
+    quote! {
+        #(
+            impl #generics_for_impl ::core::iter::FromIterator<#source_type> for #collections
+            where
+                #bounds
+            {
+                fn from_iter<__I: ::core::iter::IntoIterator<Item = #source_type>>(iter: __I) -> Self {
+                    ::core::iter::Iterator::collect(::core::iter::Iterator::map(
+                        ::core::iter::IntoIterator::into_iter(iter),
+                        ::phantom_coerce::Coercible::<#target_type>::into_coerced,
+                    ))
+                }
+            }
+        )*
+    }
+}
+
+/// Generate `From<&Source>` for `Cow<Target>`, borrowing through the coercion (`cow`)
+///
+/// The pair is also declared as a cloned coercion, so callers can take the source by
+/// reference and leave cloning to `Cow::into_owned`. Skipped for generic sources and `when`
+/// rules, whose source can equal the target.
+fn generate_cow_impl(
+    generics: &syn::Generics,
+    trait_name: &Ident,
+    coercion: &ParsedCoercion,
+) -> proc_macro2::TokenStream {
+    if coercion.generic_source || coercion.when.is_some() {
+        return quote! {};
+    }
+
+    let source_type = &coercion.source_type;
+    let target_type = &coercion.target_type;
+    let mut cow_generics: syn::Generics =
+        syn::parse2(extract_type_hole_generics(generics, coercion))
+            .expect("hole generics parse as generics");
+    cow_generics
+        .params
+        .insert(0, syn::parse_quote! { '__coerce });
+    let bounds = &coercion.bounds;
+
+    quote! {
+        impl #cow_generics ::core::convert::From<&'__coerce #source_type>
+            for ::std::borrow::Cow<'__coerce, #target_type>
+        where
+            #target_type: ::core::clone::Clone,
+            #(#bounds,)*
+        {
+            fn from(source: &'__coerce #source_type) -> Self {
+                ::std::borrow::Cow::Borrowed(#trait_name::coerce(source))
+            }
+        }
+    }
+}
+
+/// Document on the generated method that a coercion may change auto traits on purpose
+fn generate_auto_trait_note(coercion: &ParsedCoercion) -> proc_macro2::TokenStream {
+    if !coercion.allow_auto_trait_change {
+        return quote! {};
+    }
+
+    let note = format!(
+        "Declared with `allow_auto_trait_change`: `{}` may differ from `{}` in `Send`, `Sync`, or `Unpin`, so the coerced value can't be assumed to keep the source's thread-safety.",
+        type_to_string(&coercion.target_type),
+        type_to_string(&coercion.source_type),
+    );
+    quote! { #[doc = #note] }
+}
+
+/// Emit a `tracing` event naming the source and target types (with the `tracing` feature)
+fn generate_trace(mode: &CoercionMode, coercion: &ParsedCoercion) -> proc_macro2::TokenStream {
+    if !cfg!(feature = "tracing") {
+        return quote! {};
+    }
+
+    let target_type = &coercion.target_type;
+
+    let mode = match mode {
+        CoercionMode::Borrowed => "borrowed",
+        CoercionMode::Owned => "owned",
+        CoercionMode::Cloned => "cloned",
+    };
+
+    // Named outside the macro, whose expansion imports a `Value` trait that would shadow a
+    // struct parameter of that name
+    let names = quote! {
+        let source = ::core::any::type_name::<Self>();
+        let target = ::core::any::type_name::<#target_type>();
+    };
+
+    // Deprecated coercions are reported at `warn` so they stand out during a migration
+    if let Some(note) = &coercion.deprecated {
+        return quote! {
+            {
+                #names
+                ::phantom_coerce::__private::tracing::warn!(
+                    mode = #mode,
+                    source,
+                    target,
+                    note = #note,
+                    "deprecated phantom coercion",
+                );
+            }
+        };
+    }
+
+    quote! {
+        {
+            #names
+            ::phantom_coerce::__private::tracing::trace!(
+                mode = #mode,
+                source,
+                target,
+                "phantom coercion",
+            );
+        }
+    }
+}
+
+/// Call the coercion's `guard` hook, if any, with a reference to the source
+fn generate_guard_call(
+    coercion: &ParsedCoercion,
+    source_ref: proc_macro2::TokenStream,
+) -> proc_macro2::TokenStream {
+    match &coercion.guard {
+        Some(guard) => quote! { #guard(#source_ref); },
+        None => quote! {},
+    }
+}
+
+/// Generate debug-only assertions that `Self` and the target share a layout
+///
+/// Emitted in the shared cast helpers when `layout_checks` is set. For borrowed coercions the
+/// reference itself is also checked to be suitably aligned for the target.
+fn generate_layout_checks(
+    options: &StructOptions,
+    target_type: &Type,
+    borrowed: bool,
+) -> proc_macro2::TokenStream {
+    if !options.layout_checks {
+        return quote! {};
+    }
+
+    let pointer_check = borrowed.then(|| {
+        quote! {
+            ::core::debug_assert!(
+                (self as *const Self).cast::<#target_type>().is_aligned(),
+                "coerce: reference is misaligned for the target type",
+            );
+        }
+    });
+
+    quote! {
+        ::core::debug_assert_eq!(
+            ::core::mem::size_of::<Self>(),
+            ::core::mem::size_of::<#target_type>(),
+            "coerce: source and target sizes differ",
+        );
+        ::core::debug_assert_eq!(
+            ::core::mem::align_of::<Self>(),
+            ::core::mem::align_of::<#target_type>(),
+            "coerce: source and target alignments differ",
+        );
+        #pointer_check
+    }
+}
+
+/// Generate the body of a safe (transmute-free) coercion of the owned value `source`
+///
+/// Moves every data field into a freshly built target, with new `PhantomData` values for
+/// phantom fields and deep fields converted through their own owned coercion.
+fn generate_rebuild(
+    struct_name: &Ident,
+    fields: &syn::FieldsNamed,
+    deep: &[DeepField],
+) -> proc_macro2::TokenStream {
+    let mut bindings = Vec::new();
+    let mut inits = Vec::new();
+    for field in &fields.named {
+        let field_name = field.ident.as_ref().unwrap();
+        let cfgs = cfg_attrs(field);
+        if is_phantom_field(field) {
+            let init = phantom_init(field);
+            bindings.push(quote! { #(#cfgs)* #field_name: _ });
+            inits.push(quote! { #(#cfgs)* #field_name: #init });
+        } else if let Some(deep_field) = deep.iter().find(|d| d.name == field_name) {
+            let source = &deep_field.source;
+            let trait_path = &deep_field.trait_path;
+            bindings.push(quote! { #(#cfgs)* #field_name });
+            inits.push(quote! { #(#cfgs)* #field_name: <#source as #trait_path>::into_coerced(#field_name) });
+        } else {
+            bindings.push(quote! { #(#cfgs)* #field_name });
+            inits.push(quote! { #(#cfgs)* #field_name });
+        }
+    }
+
+    quote! {
+        let #struct_name { #(#bindings),* } = source;
+        #struct_name { #(#inits),* }
+    }
+}
+
+/// Parse the `#[coerce(...)]` attributes written on a type parameter
+///
+/// `any = "Marker"` is the same as listing the parameter in the struct's `any(...)`, and
+/// `fixed` rejects any coercion that changes the parameter.
+fn parse_param_attrs(param: &syn::TypeParam, options: &mut StructOptions) -> syn::Result<()> {
+    let ident = &param.ident;
+    for attr in &param.attrs {
+        if !attr.path().is_ident("coerce") {
+            continue;
+        }
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("any") {
+                let value: syn::LitStr = meta.value()?.parse()?;
+                let marker: Type = syn::parse_str(&normalize_pattern(&value.value()))?;
+                options.any.push((ident.clone(), marker));
+                options.any_span.get_or_insert(attr.span());
+                Ok(())
+            } else if meta.path.is_ident("fixed") {
+                options.fixed.push(ident.clone());
+                Ok(())
+            } else {
+                Err(meta.error(
+                    "Expected 'any = \"...\"' or 'fixed' (the parameter-level coerce options)",
+                ))
+            }
+        })?;
+    }
+
+    if options.fixed.contains(ident) && options.any.iter().any(|(param, _)| param == ident) {
+        return Err(syn::Error::new_spanned(
+            ident,
+            format!("'{}' can't be both fixed and erased with any", ident),
+        ));
+    }
+    Ok(())
+}
+
+/// Reject coercions that change a `#[coerce(fixed)]` parameter
+fn check_fixed_params(
+    coercions: &[ParsedCoercion],
+    generics: &syn::Generics,
+    fixed: &[Ident],
+    span: proc_macro2::Span,
+) -> syn::Result<()> {
+    for coercion in coercions {
+        let source = type_param_bindings(generics, &coercion.source_type);
+        let target = type_param_bindings(generics, &coercion.target_type);
+        for param in fixed {
+            let name = param.to_string();
+            let (Some(from), Some(to)) = (source.get(&name), target.get(&name)) else {
+                continue;
+            };
+            if type_to_string(from) != type_to_string(to) {
+                return Err(syn::Error::new(
+                    span,
+                    format!(
+                        "'{}' is #[coerce(fixed)], but '{}' => '{}' changes it from '{}' to '{}'",
+                        param,
+                        type_to_string(&coercion.source_type),
+                        type_to_string(&coercion.target_type),
+                        type_to_string(from),
+                        type_to_string(to)
+                    ),
+                ));
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Whether a field is marked `#[coerce(deep)]`
+fn is_deep_field(field: &syn::Field) -> syn::Result<bool> {
+    Ok(field_coerce_options(field)?.deep)
+}
+
+/// The `#[coerce(...)]` options written on a field
+#[derive(Default)]
+struct FieldOptions {
+    /// Coerce the field's own markers along with the struct's (`deep`)
+    deep: bool,
+    /// The field holds only markers, like a `PhantomData` (`phantom`)
+    phantom: bool,
+}
+
+fn field_coerce_options(field: &syn::Field) -> syn::Result<FieldOptions> {
+    let mut options = FieldOptions::default();
+    for attr in &field.attrs {
+        if !attr.path().is_ident("coerce") {
+            continue;
+        }
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("deep") {
+                options.deep = true;
+                Ok(())
+            } else if meta.path.is_ident("phantom") {
+                options.phantom = true;
+                Ok(())
+            } else {
+                Err(meta.error("Expected 'deep' or 'phantom' (the field-level coerce options)"))
+            }
+        })?;
+    }
+    Ok(options)
+}
+
+/// Map each struct type parameter to the corresponding argument of a concrete coercion type
+fn type_param_bindings(generics: &syn::Generics, ty: &Type) -> HashMap<String, Type> {
+    let mut bindings = HashMap::new();
+    let Type::Path(type_path) = ty else {
+        return bindings;
+    };
+    let Some(segment) = type_path.path.segments.last() else {
+        return bindings;
+    };
+    let PathArguments::AngleBracketed(args) = &segment.arguments else {
+        return bindings;
+    };
+
+    // Const arguments parse as types too, so match positions against every non-lifetime param
+    let arguments = args
+        .args
+        .iter()
+        .filter(|arg| !matches!(arg, syn::GenericArgument::Lifetime(_)));
+    let params = generics
+        .params
+        .iter()
+        .filter(|param| !matches!(param, syn::GenericParam::Lifetime(_)));
+    for (param, arg) in params.zip(arguments) {
+        match (param, arg) {
+            (syn::GenericParam::Type(param), syn::GenericArgument::Type(ty)) => {
+                bindings.insert(param.ident.to_string(), ty.clone());
+            }
+            // Const arguments are substituted into expressions such as array lengths
+            (syn::GenericParam::Const(param), syn::GenericArgument::Type(ty)) => {
+                bindings.insert(param.ident.to_string(), ty.clone());
+            }
+            (syn::GenericParam::Const(param), syn::GenericArgument::Const(expr)) => {
+                bindings.insert(param.ident.to_string(), Type::Verbatim(quote!(#expr)));
+            }
+            _ => {}
+        }
+    }
+    bindings
+}
+
+/// Replaces struct type parameters in a field type with the types bound by a coercion
+struct SubstituteTypeParams<'a> {
+    bindings: &'a HashMap<String, Type>,
+}
+
+impl VisitMut for SubstituteTypeParams<'_> {
+    fn visit_type_mut(&mut self, ty: &mut Type) {
+        if let Type::Path(TypePath { qself: None, path }) = ty
+            && let Some(ident) = path.get_ident()
+            && let Some(bound) = self.bindings.get(&ident.to_string())
+        {
+            *ty = bound.clone();
+            return;
+        }
+        syn::visit_mut::visit_type_mut(self, ty);
+    }
+
+    fn visit_expr_mut(&mut self, expr: &mut syn::Expr) {
+        if let syn::Expr::Path(syn::ExprPath {
+            qself: None, path, ..
+        }) = expr
+            && let Some(ident) = path.get_ident()
+            && let Some(bound) = self.bindings.get(&ident.to_string())
+        {
+            *expr = syn::Expr::Verbatim(quote!(#bound));
+            return;
+        }
+        syn::visit_mut::visit_expr_mut(self, expr);
+    }
+}
+
+/// Render a type the way it would be written in source, for error messages
+fn type_to_string(ty: &Type) -> String {
+    tokens_to_pattern(&quote!(#ty))
+}
+
+fn substitute_type_params(ty: &Type, bindings: &HashMap<String, Type>) -> Type {
+    let mut ty = ty.clone();
+    SubstituteTypeParams { bindings }.visit_type_mut(&mut ty);
+    ty
+}
+
+/// A `#[coerce(deep)]` field whose type changes under a particular coercion
+struct DeepField<'a> {
+    name: &'a Ident,
+    /// Field type with the coercion's source parameters substituted
+    source: Type,
+    /// The field type's generated coercion trait, parameterized by its target type
+    trait_path: syn::Path,
+}
+
+/// Find every non-PhantomData field whose type changes under a coercion
+///
+/// Such fields must be marked `#[coerce(deep)]`; each one's `trait_path` is the same-mode
+/// coercion trait its own type must implement, e.g.
+/// `TypedPath<Absolute, File>: CoerceRefTypedPath<TypedPath<UnknownBase, File>>`
+fn deep_fields<'a>(
+    generics: &syn::Generics,
+    coercion: &ParsedCoercion,
+    fields: &'a syn::FieldsNamed,
+    mode: &CoercionMode,
+) -> syn::Result<Vec<DeepField<'a>>> {
+    let source_bindings = type_param_bindings(generics, &coercion.source_type);
+    let target_bindings = type_param_bindings(generics, &coercion.target_type);
+
+    let mut deep = Vec::new();
+    for field in &fields.named {
+        if is_phantom_field(field) {
+            continue;
+        }
+
+        let field_source = substitute_type_params(&field.ty, &source_bindings);
+        let field_target = substitute_type_params(&field.ty, &target_bindings);
+        if quote!(#field_source).to_string() == quote!(#field_target).to_string() {
+            continue;
+        }
+
+        let field_name = field.ident.as_ref().unwrap();
+        if !is_deep_field(field)? {
+            return Err(syn::Error::new_spanned(
+                &field.ty,
+                format!(
+                    "Field '{}' changes type from '{}' to '{}' in this coercion, but only PhantomData fields may change. \
+                     Mark it #[coerce(deep)] if its type derives Coerce with a matching coercion",
+                    field_name,
+                    type_to_string(&field_source),
+                    type_to_string(&field_target),
+                ),
+            ));
+        }
+
+        let trait_path = field_trait_path(&field.ty, mode, &field_target).ok_or_else(|| {
+            syn::Error::new_spanned(
+                &field.ty,
+                "#[coerce(deep)] fields must have a struct type that derives Coerce",
+            )
+        })?;
+
+        deep.push(DeepField {
+            name: field_name,
+            source: field_source,
+            trait_path,
+        });
+    }
+
+    Ok(deep)
+}
+
+/// Where-predicates requiring each deep field's own coercion
+fn deep_field_bounds(deep: &[DeepField]) -> Vec<proc_macro2::TokenStream> {
+    deep.iter()
+        .map(|field| {
+            let source = &field.source;
+            let trait_path = &field.trait_path;
+            quote! { #source: #trait_path }
+        })
+        .collect()
+}
+
+/// Path to the generated coercion trait of a field's (Coerce-derived) type
+///
+/// `paths::TypedPath<Base, Type>` with `Borrowed` becomes `paths::CoerceRefTypedPath<Target>`.
+/// Returns `None` when the field type isn't a path type.
+fn field_trait_path(field_ty: &Type, mode: &CoercionMode, target: &Type) -> Option<syn::Path> {
+    let Type::Path(TypePath { qself: None, path }) = field_ty else {
+        return None;
+    };
+    let mut trait_path = path.clone();
+    let last = trait_path.segments.last_mut()?;
+    let prefix = match mode {
+        CoercionMode::Borrowed => "CoerceRef",
+        CoercionMode::Owned => "CoerceOwned",
+        CoercionMode::Cloned => "CoerceCloned",
+    };
+    last.ident = Ident::new(&format!("{}{}", prefix, last.ident), last.ident.span());
+    last.arguments = PathArguments::AngleBracketed(syn::parse_quote! { <#target> });
+    Some(trait_path)
+}
+
+/// Generate accessors returning a coerced reference to a single field
+fn generate_projections(
+    struct_name: &Ident,
+    generics: &syn::Generics,
+    fields: &syn::FieldsNamed,
+    projections: &[Projection],
+) -> syn::Result<proc_macro2::TokenStream> {
+    let mut methods = Vec::new();
+    for projection in projections {
+        let Some(field) = fields
+            .named
+            .iter()
+            .find(|f| f.ident.as_ref() == Some(&projection.field))
+        else {
+            return Err(syn::Error::new_spanned(
+                &projection.field,
+                format!(
+                    "project(...) field '{}' does not exist on '{}'",
+                    projection.field, struct_name
+                ),
+            ));
+        };
+
+        let field_name = &projection.field;
+        let field_ty = &field.ty;
+        let target = &projection.target;
+        let method = &projection.method;
+        let trait_path =
+            field_trait_path(field_ty, &CoercionMode::Borrowed, target).ok_or_else(|| {
+                syn::Error::new_spanned(
+                    field_ty,
+                    "project(...) fields must have a struct type that derives Coerce",
+                )
+            })?;
+
+        methods.push(quote! {
+            fn #method(&self) -> &#target
+            where
+                #field_ty: #trait_path,
+            {
+                <#field_ty as #trait_path>::coerce(&self.#field_name)
+            }
+        });
+    }
+
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+    Ok(quote! {
+        impl #impl_generics #struct_name #ty_generics #where_clause {
+            #(#methods)*
+        }
+    })
+}
+
+/// Implement `HasAnyMarker` for the erased type and each source coercing to it (`erased`)
+///
+/// A source needs both a borrowed and an owned coercion to the erased type, for `erase_ref`
+/// and `erase` respectively.
+fn generate_erase_impls(
+    struct_name: &Ident,
+    generics: &syn::Generics,
+    options: &StructOptions,
+    (erased, span): &(Type, proc_macro2::Span),
+    borrowed_coercions: &[ParsedCoercion],
+    owned_coercions: &[ParsedCoercion],
+) -> syn::Result<proc_macro2::TokenStream> {
+    let names_struct = matches!(erased, Type::Path(TypePath { path, .. })
+        if path.segments.last().is_some_and(|segment| segment.ident == *struct_name));
+    if !names_struct {
+        return Err(syn::Error::new(
+            *span,
+            format!("'erased' must be an instantiation of '{}'", struct_name),
+        ));
+    }
+
+    let erased_string = type_to_string(erased);
+    let borrowed_trait = coercion_trait_name(struct_name, options, &CoercionMode::Borrowed);
+    let owned_trait = coercion_trait_name(struct_name, options, &CoercionMode::Owned);
+
+    let mut impls = Vec::new();
+    let mut covers_erased = false;
+    for coercion in borrowed_coercions
+        .iter()
+        .filter(|coercion| type_to_string(&coercion.target_type) == erased_string)
+    {
+        let source_type = &coercion.source_type;
+        let source_string = type_to_string(source_type);
+        let owned = owned_coercions.iter().find(|owned| {
+            type_to_string(&owned.source_type) == source_string
+                && type_to_string(&owned.target_type) == erased_string
+        });
+        let Some(owned) = owned else {
+            return Err(syn::Error::new(
+                *span,
+                format!(
+                    "'erased' needs an owned coercion from '{}' to '{}' to implement HasAnyMarker",
+                    source_string, erased_string
+                ),
+            ));
+        };
+
+        // A generic source also covers the erased type itself
+        covers_erased |= coercion.generic_source;
+        let generics_for_impl = extract_type_hole_generics(generics, coercion);
+        let bounds = coercion.bounds.iter().chain(&owned.bounds);
+        impls.push(quote! {
+            impl #generics_for_impl ::phantom_coerce::HasAnyMarker for #source_type
+            where
+                #(#bounds,)*
+            {
+                type Erased = #erased;
+
+                fn erase(self) -> #erased {
+                    #owned_trait::into_coerced(self)
+                }
+
+                fn erase_ref(&self) -> &#erased {
+                    #borrowed_trait::coerce(self)
+                }
+            }
+        });
+    }
+
+    if impls.is_empty() {
+        return Err(syn::Error::new(
+            *span,
+            format!(
+                "'erased' type '{}' is not the target of any borrowed coercion",
+                erased_string
+            ),
+        ));
+    }
+
+    if !covers_erased {
+        impls.push(quote! {
+            impl ::phantom_coerce::HasAnyMarker for #erased {
+                type Erased = Self;
+
+                fn erase(self) -> Self {
+                    self
+                }
+
+                fn erase_ref(&self) -> &Self {
+                    self
+                }
+            }
+        });
+    }
+
+    Ok(quote! { #(#impls)* })
+}
+
+/// Implement `__private::Param<I>` for each type parameter, so `coerce!` can name it
+fn generate_param_impls(struct_name: &Ident, generics: &syn::Generics) -> proc_macro2::TokenStream {
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+    let impls = generics.type_params().enumerate().map(|(index, param)| {
+        let ident = &param.ident;
+        quote! {
+            impl #impl_generics ::phantom_coerce::__private::Param<#index> for #struct_name #ty_generics #where_clause {
+                type Type = #ident;
+            }
+        }
+    });
+    quote! {
+        impl #impl_generics ::phantom_coerce::__private::Derived for #struct_name #ty_generics #where_clause {}
+        #(#impls)*
+    }
+}
+
+/// Whether `ident` appears anywhere in `tokens`
+fn mentions_ident(tokens: proc_macro2::TokenStream, ident: &Ident) -> bool {
+    tokens.into_iter().any(|tree| match tree {
+        proc_macro2::TokenTree::Ident(other) => other == *ident,
+        proc_macro2::TokenTree::Group(group) => mentions_ident(group.stream(), ident),
+        _ => false,
+    })
+}
+
+/// Generate a `Debug` impl that names the concrete markers (`debug`)
+///
+/// Type parameters appearing in `PhantomData` fields are printed by their `Marker::NAME`,
+/// any others by `type_name`. `PhantomData` fields themselves are left out.
+fn generate_debug_impl(
+    struct_name: &Ident,
+    generics: &syn::Generics,
+    fields: &syn::FieldsNamed,
+) -> proc_macro2::TokenStream {
+    let (phantom, data): (Vec<&syn::Field>, Vec<&syn::Field>) = fields
+        .named
+        .iter()
+        .partition(|field| is_phantom_field(field));
+
+    let mut debug_generics = generics.clone();
+    let where_clause = debug_generics.make_where_clause();
+    let mut param_names = Vec::new();
+    for param in generics.type_params() {
+        let ident = &param.ident;
+        let is_marker = phantom
+            .iter()
+            .any(|field| mentions_ident(quote! { #field }, ident));
+        if is_marker {
+            where_clause
+                .predicates
+                .push(syn::parse_quote! { #ident: ::phantom_coerce::Marker });
+            param_names.push(quote! { <#ident as ::phantom_coerce::Marker>::NAME });
+        } else {
+            param_names.push(quote! { ::core::any::type_name::<#ident>() });
+        }
+    }
+    for field in &data {
+        let ty = &field.ty;
+        where_clause
+            .predicates
+            .push(syn::parse_quote! { #ty: ::core::fmt::Debug });
+    }
+    let (impl_generics, ty_generics, where_clause) = debug_generics.split_for_impl();
+
+    let name = if param_names.is_empty() {
+        quote! { ::std::string::String::from(::core::stringify!(#struct_name)) }
+    } else {
+        quote! {
+            ::std::format!(
+                "{}<{}>",
+                ::core::stringify!(#struct_name),
+                [#(#param_names),*].join(", "),
+            )
+        }
+    };
+    let field_names = data.iter().map(|field| field.ident.as_ref().unwrap());
+
+    quote! {
+        impl #impl_generics ::core::fmt::Debug for #struct_name #ty_generics #where_clause {
+            fn fmt(&self, f: &mut ::core::fmt::Formatter<'_>) -> ::core::fmt::Result {
+                let name = #name;
+                f.debug_struct(&name)
+                    #(.field(::core::stringify!(#field_names), &self.#field_names))*
+                    .finish()
+            }
+        }
+    }
+}
+
+/// Generate a `schemars::JsonSchema` impl shared by every marker instantiation
+///
+/// Markers only live in `PhantomData` fields, which serialize as `null`, so each instantiation
+/// has the same JSON shape and gets the same schema name. Fields marked `#[serde(skip)]` are
+/// left out; other serde attributes aren't interpreted.
+fn generate_json_schema_impl(
+    struct_name: &Ident,
+    generics: &syn::Generics,
+    fields: &syn::FieldsNamed,
+) -> proc_macro2::TokenStream {
+    let schemars = quote! { ::phantom_coerce::__private::schemars };
+    let fields: Vec<&syn::Field> = fields
+        .named
+        .iter()
+        .filter(|field| !is_serde_skipped(field))
+        .collect();
+
+    let mut schema_generics = generics.clone();
+    let where_clause = schema_generics.make_where_clause();
+    for field in fields.iter().filter(|field| !is_phantom_field(field)) {
+        let ty = &field.ty;
+        where_clause
+            .predicates
+            .push(syn::parse_quote! { #ty: #schemars::JsonSchema });
+    }
+    let (impl_generics, ty_generics, where_clause) = schema_generics.split_for_impl();
+
+    // Parameters outside the PhantomData fields still distinguish schemas, as in schemars' derive
+    let data_params: Vec<&Ident> = generics
+        .type_params()
+        .map(|param| &param.ident)
+        .filter(|ident| {
+            fields
+                .iter()
+                .any(|field| !is_phantom_field(field) && mentions_ident(quote! { #field }, ident))
+        })
+        .collect();
+    let (name, id) = if data_params.is_empty() {
+        (
+            quote! { ::std::string::String::from(::core::stringify!(#struct_name)) },
+            quote! { ::std::borrow::Cow::Borrowed(::core::concat!(::core::module_path!(), "::", ::core::stringify!(#struct_name))) },
+        )
+    } else {
+        (
+            quote! {
+                ::std::format!(
+                    "{}_for_{}",
+                    ::core::stringify!(#struct_name),
+                    [#(<#data_params as #schemars::JsonSchema>::schema_name()),*].join("_and_"),
+                )
+            },
+            quote! {
+                ::std::borrow::Cow::Owned(::std::format!(
+                    "{}::{}<{}>",
+                    ::core::module_path!(),
+                    ::core::stringify!(#struct_name),
+                    [#(<#data_params as #schemars::JsonSchema>::schema_id()),*].join(", "),
+                ))
+            },
+        )
+    };
+
+    let properties = fields.iter().map(|field| {
+        let field_name = field.ident.as_ref().unwrap().to_string();
+        let subschema = if is_phantom_field(field) {
+            quote! { generator.subschema_for::<()>() }
+        } else {
+            let ty = &field.ty;
+            quote! { generator.subschema_for::<#ty>() }
+        };
+        let required = (!is_option(&field.ty)).then(|| {
+            quote! { object.required.insert(::std::string::String::from(#field_name)); }
+        });
+        quote! {
+            object
+                .properties
+                .insert(::std::string::String::from(#field_name), #subschema);
+            #required
+        }
+    });
+
+    quote! {
+        impl #impl_generics #schemars::JsonSchema for #struct_name #ty_generics #where_clause {
+            fn schema_name() -> ::std::string::String {
+                #name
+            }
+
+            fn schema_id() -> ::std::borrow::Cow<'static, str> {
+                #id
+            }
+
+            fn json_schema(
+                generator: &mut #schemars::SchemaGenerator,
+            ) -> #schemars::schema::Schema {
+                let mut schema = #schemars::schema::SchemaObject {
+                    instance_type: ::core::option::Option::Some(
+                        ::core::convert::Into::into(#schemars::schema::InstanceType::Object),
+                    ),
+                    ..::core::default::Default::default()
+                };
+                let object = schema.object();
+                #(#properties)*
+                #schemars::schema::Schema::Object(schema)
+            }
+        }
+    }
+}
+
+/// Generate `AsRef<target>` for every instantiation, delegating to the single data field
+fn generate_forward_asref(
+    struct_name: &Ident,
+    generics: &syn::Generics,
+    fields: &syn::FieldsNamed,
+    target: &Type,
+    span: proc_macro2::Span,
+) -> syn::Result<proc_macro2::TokenStream> {
+    let data: Vec<&syn::Field> = fields
+        .named
+        .iter()
+        .filter(|field| !is_phantom_field(field))
+        .collect();
+    let [inner] = data.as_slice() else {
+        return Err(syn::Error::new(
+            span,
+            format!(
+                "forward_asref requires exactly one non-PhantomData field to forward to, but '{}' has {}",
+                struct_name,
+                data.len()
+            ),
+        ));
+    };
+    let inner_name = inner.ident.as_ref().unwrap();
+    let inner_ty = &inner.ty;
+
+    let mut impl_generics = generics.clone();
+    impl_generics
+        .make_where_clause()
+        .predicates
+        .push(syn::parse_quote! { #inner_ty: ::core::convert::AsRef<#target> });
+    let (impl_generics, ty_generics, where_clause) = impl_generics.split_for_impl();
+
+    Ok(quote! {
+        impl #impl_generics ::core::convert::AsRef<#target> for #struct_name #ty_generics #where_clause {
+            fn as_ref(&self) -> &#target {
+                <#inner_ty as ::core::convert::AsRef<#target>>::as_ref(&self.#inner_name)
+            }
+        }
+    })
+}
+
+/// Generate `Deref` to the `deref_inner` field for every instantiation
+///
+/// There's deliberately no `DerefMut`: markers often certify the data, and writing through the
+/// wrapper would keep the marker on data it no longer describes.
+fn generate_deref_inner(
+    struct_name: &Ident,
+    generics: &syn::Generics,
+    fields: &syn::FieldsNamed,
+    field_name: &Ident,
+) -> syn::Result<proc_macro2::TokenStream> {
+    let Some(field) = fields
+        .named
+        .iter()
+        .find(|field| field.ident.as_ref() == Some(field_name))
+    else {
+        return Err(syn::Error::new_spanned(
+            field_name,
+            format!("deref_inner: '{struct_name}' has no field '{field_name}'"),
+        ));
+    };
+    if is_phantom_field(field) {
+        return Err(syn::Error::new_spanned(
+            field_name,
+            format!("deref_inner: '{field_name}' is a marker field; name the data field instead"),
+        ));
+    }
+    let field_ty = &field.ty;
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+
+    Ok(quote! {
+        impl #impl_generics ::core::ops::Deref for #struct_name #ty_generics #where_clause {
+            type Target = #field_ty;
+
+            fn deref(&self) -> &#field_ty {
+                &self.#field_name
+            }
+        }
+    })
+}
+
+/// Generate `sqlx::Type`, `Encode`, and `Decode` impls forwarding to the single data field
+///
+/// The impls are generic over the database and every marker, so `Id<Users>` and `Id<Orders>`
+/// both map to the inner column type.
+fn generate_sqlx_impls(
+    struct_name: &Ident,
+    generics: &syn::Generics,
+    fields: &syn::FieldsNamed,
+    span: proc_macro2::Span,
+) -> syn::Result<proc_macro2::TokenStream> {
+    let sqlx = quote! { ::phantom_coerce::__private::sqlx };
+    let (phantom, data): (Vec<&syn::Field>, Vec<&syn::Field>) = fields
+        .named
+        .iter()
+        .partition(|field| is_phantom_field(field));
+    let [inner] = data.as_slice() else {
+        return Err(syn::Error::new(
+            span,
+            format!(
+                "sqlx requires exactly one non-PhantomData field to forward to, but '{}' has {}",
+                struct_name,
+                data.len()
+            ),
+        ));
+    };
+    let inner_name = inner.ident.as_ref().unwrap();
+    let inner_ty = &inner.ty;
+    let phantom_names = phantom.iter().map(|field| field.ident.as_ref().unwrap());
+    let phantom_inits = phantom.iter().map(|field| phantom_init(field));
+    let (_, ty_generics, _) = generics.split_for_impl();
+
+    // Each impl adds its own database (and lifetime) parameter to the struct's generics
+    let with_database = |lifetime: Option<proc_macro2::TokenStream>,
+                         bound: proc_macro2::TokenStream| {
+        let mut impl_generics = generics.clone();
+        if let Some(lifetime) = lifetime {
+            impl_generics
+                .params
+                .insert(0, syn::parse_quote! { #lifetime });
+        }
+        impl_generics
+            .params
+            .push(syn::parse_quote! { __DB: #sqlx::Database });
+        impl_generics
+            .make_where_clause()
+            .predicates
+            .push(syn::parse_quote! { #inner_ty: #bound });
+        impl_generics
+    };
+
+    let type_generics = with_database(None, quote! { #sqlx::Type<__DB> });
+    let (type_impl, _, type_where) = type_generics.split_for_impl();
+    let encode_generics =
+        with_database(Some(quote! { '__q }), quote! { #sqlx::Encode<'__q, __DB> });
+    let (encode_impl, _, encode_where) = encode_generics.split_for_impl();
+    let decode_generics =
+        with_database(Some(quote! { '__r }), quote! { #sqlx::Decode<'__r, __DB> });
+    let (decode_impl, _, decode_where) = decode_generics.split_for_impl();
+
+    Ok(quote! {
+        impl #type_impl #sqlx::Type<__DB> for #struct_name #ty_generics #type_where {
+            fn type_info() -> <__DB as #sqlx::Database>::TypeInfo {
+                <#inner_ty as #sqlx::Type<__DB>>::type_info()
+            }
+
+            fn compatible(ty: &<__DB as #sqlx::Database>::TypeInfo) -> bool {
+                <#inner_ty as #sqlx::Type<__DB>>::compatible(ty)
+            }
+        }
+
+        impl #encode_impl #sqlx::Encode<'__q, __DB> for #struct_name #ty_generics #encode_where {
+            fn encode_by_ref(
+                &self,
+                buf: &mut <__DB as #sqlx::Database>::ArgumentBuffer<'__q>,
+            ) -> ::core::result::Result<#sqlx::encode::IsNull, #sqlx::error::BoxDynError> {
+                <#inner_ty as #sqlx::Encode<'__q, __DB>>::encode_by_ref(&self.#inner_name, buf)
+            }
+
+            fn produces(&self) -> ::core::option::Option<<__DB as #sqlx::Database>::TypeInfo> {
+                <#inner_ty as #sqlx::Encode<'__q, __DB>>::produces(&self.#inner_name)
+            }
+
+            fn size_hint(&self) -> usize {
+                <#inner_ty as #sqlx::Encode<'__q, __DB>>::size_hint(&self.#inner_name)
+            }
+        }
+
+        impl #decode_impl #sqlx::Decode<'__r, __DB> for #struct_name #ty_generics #decode_where {
+            fn decode(
+                value: <__DB as #sqlx::Database>::ValueRef<'__r>,
+            ) -> ::core::result::Result<Self, #sqlx::error::BoxDynError> {
+                ::core::result::Result::Ok(Self {
+                    #inner_name: <#inner_ty as #sqlx::Decode<'__r, __DB>>::decode(value)?,
+                    #(#phantom_names: #phantom_inits,)*
+                })
+            }
+        }
+    })
+}
+
+/// Whether a field carries `#[serde(skip)]`
+fn is_serde_skipped(field: &syn::Field) -> bool {
+    field.attrs.iter().any(|attr| {
+        let Meta::List(list) = &attr.meta else {
+            return false;
+        };
+        list.path.is_ident("serde")
+            && list.tokens.clone().into_iter().any(
+                |token| matches!(token, proc_macro2::TokenTree::Ident(ident) if ident == "skip"),
+            )
+    })
+}
+
+/// Whether a field's type is `Option<..>`, which serde lets JSON omit
+fn is_option(ty: &Type) -> bool {
+    if let Type::Path(TypePath { path, .. }) = ty
+        && let Some(segment) = path.segments.last()
+    {
+        return segment.ident == "Option";
+    }
+    false
+}
+
+/// Generate `parking_lot` guard-mapping associated functions for borrowed coercions
+/// Returns an empty TokenStream unless the `parking_lot` feature is enabled
+fn generate_guard_methods(trait_name: &Ident) -> proc_macro2::TokenStream {
+    if !cfg!(feature = "parking_lot") {
+        return quote! {};
+    }
+
+    // MutexGuard is deliberately absent: parking_lot only maps it through `&mut`,
+    // which would allow writing a generic value back into the specific one.
+    quote! {
+        fn coerce_read_guard<'__guard, __CoerceTarget>(
+            guard: ::phantom_coerce::__private::parking_lot::RwLockReadGuard<'__guard, Self>,
+        ) -> ::phantom_coerce::__private::parking_lot::MappedRwLockReadGuard<'__guard, __CoerceTarget>
+        where
+            Self: #trait_name<__CoerceTarget>,
+            __CoerceTarget: ?::core::marker::Sized,
+        {
+            ::phantom_coerce::__private::parking_lot::RwLockReadGuard::map(guard, #trait_name::coerce)
+        }
+
+        fn coerce_mapped_read_guard<'__guard, __CoerceTarget>(
+            guard: ::phantom_coerce::__private::parking_lot::MappedRwLockReadGuard<'__guard, Self>,
+        ) -> ::phantom_coerce::__private::parking_lot::MappedRwLockReadGuard<'__guard, __CoerceTarget>
+        where
+            Self: #trait_name<__CoerceTarget>,
+            __CoerceTarget: ?::core::marker::Sized,
+        {
+            ::phantom_coerce::__private::parking_lot::MappedRwLockReadGuard::map(guard, #trait_name::coerce)
+        }
+
+        fn coerce_reentrant_guard<'__guard, __CoerceTarget>(
+            guard: ::phantom_coerce::__private::parking_lot::ReentrantMutexGuard<'__guard, Self>,
+        ) -> ::phantom_coerce::__private::parking_lot::MappedReentrantMutexGuard<'__guard, __CoerceTarget>
+        where
+            Self: #trait_name<__CoerceTarget>,
+            __CoerceTarget: ?::core::marker::Sized,
+        {
+            ::phantom_coerce::__private::parking_lot::ReentrantMutexGuard::map(guard, #trait_name::coerce)
+        }
+    }
+}
+
+/// Generate `coerce_array_view` (borrowed) or `coerce_array` (owned) for `ndarray` arrays
+/// Returns an empty TokenStream unless the `ndarray` feature is enabled
+///
+/// Views are reinterpreted, keeping their shape and strides. Owned arrays are taken apart into
+/// their buffer, which is converted like `coerce_all` converts a `Vec` and rebuilt in the same
+/// shape: reinterpreting `Array<Source, D>` as `Array<Target, D>` would assume the two share a
+/// layout, which Rust doesn't promise. Arrays that are neither C- nor Fortran-contiguous are
+/// rebuilt element by element.
+fn generate_ndarray_methods(trait_name: &Ident, mode: &CoercionMode) -> proc_macro2::TokenStream {
+    if !cfg!(feature = "ndarray") {
+        return quote! {};
+    }
+
+    let ndarray = quote! { ::phantom_coerce::__private::ndarray };
+    match mode {
+        CoercionMode::Borrowed => {
+            let declared = quote! {
+                ::phantom_coerce::__private::DeclaredCoercion<::phantom_coerce::__private::Borrowed, __CoerceTarget>
+            };
+            quote! {
+                fn coerce_array_view<'__coerce, __CoerceTarget, __D>(
+                    view: #ndarray::ArrayView<'__coerce, Self, __D>,
+                ) -> #ndarray::ArrayView<'__coerce, __CoerceTarget, __D>
+                where
+                    Self: #trait_name<__CoerceTarget> + #declared + ::core::marker::Sized,
+                    __D: #ndarray::Dimension,
+                {
+                    let () = <Self as #declared>::PERMITTED;
+                    // SAFETY: A borrowed coercion is only declared between types that differ
+                    // in PhantomData parameters, so elements share size and alignment and the
+                    // view's strides stay valid. The view keeps the source's lifetime.
+                    unsafe { view.raw_view().cast::<__CoerceTarget>().deref_into_view() }
+                }
+            }
+        }
+        _ => {
+            let declared = quote! {
+                ::phantom_coerce::__private::DeclaredCoercion<::phantom_coerce::__private::Owned, __CoerceTarget>
+            };
+            quote! {
+                fn coerce_array<__CoerceTarget, __D>(
+                    array: #ndarray::Array<Self, __D>,
+                ) -> #ndarray::Array<__CoerceTarget, __D>
+                where
+                    Self: #trait_name<__CoerceTarget> + #declared + ::core::marker::Sized,
+                    __D: #ndarray::Dimension,
+                {
+                    let () = <Self as #declared>::PERMITTED;
+                    let dim = array.raw_dim();
+                    let fortran = !array.is_standard_layout() && array.t().is_standard_layout();
+                    if !array.is_standard_layout() && !fortran {
+                        // Other strides can't be rebuilt from the buffer alone
+                        let items = ::core::iter::Iterator::collect(::core::iter::Iterator::map(
+                            ::core::iter::IntoIterator::into_iter(array),
+                            #trait_name::into_coerced,
+                        ));
+                        return #ndarray::Array::from_shape_vec(dim, items)
+                            .expect("coerce: the coerced elements fill the same shape");
+                    }
+
+                    // Contiguous elements sit in memory order, so the buffer converts like a
+                    // `Vec` (reusing the allocation) and keeps the same shape
+                    let (items, offset) = array.into_raw_vec_and_offset();
+                    let mut items: ::std::vec::Vec<__CoerceTarget> =
+                        ::core::iter::Iterator::collect(::core::iter::Iterator::map(
+                            ::core::iter::IntoIterator::into_iter(items),
+                            #trait_name::into_coerced,
+                        ));
+                    // Elements sliced away are still in the buffer
+                    let offset = ::core::option::Option::unwrap_or(offset, 0);
+                    items.truncate(offset + #ndarray::Dimension::size(&dim));
+                    items.drain(..offset);
+                    let shape: #ndarray::StrideShape<__D> = if fortran {
+                        ::core::convert::Into::into(#ndarray::ShapeBuilder::f(dim))
+                    } else {
+                        ::core::convert::Into::into(dim)
+                    };
+                    #ndarray::Array::from_shape_vec(shape, items)
+                        .expect("coerce: the coerced elements fill the same shape")
+                }
+            }
+        }
+    }
+}
+
+/// Generate `coerce_uninit_ref` (borrowed) or `coerce_uninit` (owned) for `MaybeUninit` slots
+///
+/// Both reinterpret the slot, which needs no initialized value. Guard hooks take the value, so
+/// they can't run; `when` rules are still checked.
+fn generate_uninit_methods(trait_name: &Ident, mode: &CoercionMode) -> proc_macro2::TokenStream {
+    let mode_marker = mode_marker(mode);
+    let declared = quote! {
+        ::phantom_coerce::__private::DeclaredCoercion<#mode_marker, __CoerceTarget>
+    };
+    let preamble = quote! {
+        let () = <Self as #declared>::PERMITTED;
+        const {
+            ::core::assert!(
+                ::core::mem::size_of::<Self>() == ::core::mem::size_of::<__CoerceTarget>(),
+                "coerce: source and target sizes differ",
+            );
+        }
+    };
+    match mode {
+        CoercionMode::Borrowed => quote! {
+            fn coerce_uninit_ref<__CoerceTarget>(
+                slot: &::core::mem::MaybeUninit<Self>,
+            ) -> &::core::mem::MaybeUninit<__CoerceTarget>
+            where
+                Self: #trait_name<__CoerceTarget> + #declared + ::core::marker::Sized,
+            {
+                #preamble
+                // SAFETY: A borrowed coercion is only declared between types that differ in
+                // PhantomData parameters, so the slots share a layout.
+                unsafe { &*(slot as *const ::core::mem::MaybeUninit<Self>).cast() }
+            }
+        },
+        _ => quote! {
+            fn coerce_uninit<__CoerceTarget>(
+                slot: ::core::mem::MaybeUninit<Self>,
+            ) -> ::core::mem::MaybeUninit<__CoerceTarget>
+            where
+                Self: #trait_name<__CoerceTarget> + #declared + ::core::marker::Sized,
+            {
+                #preamble
+                // SAFETY: An owned coercion is only declared between types that differ in
+                // PhantomData parameters, so the slots share a layout. `MaybeUninit` never
+                // drops its contents, so reading it out leaves nothing to drop twice.
+                unsafe { ::core::ptr::read((&slot as *const ::core::mem::MaybeUninit<Self>).cast()) }
+            }
+        },
+    }
+}
+
+/// Generate `coerce_ptr`, `coerce_const_ptr`, and `coerce_mut_ptr` for borrowed coercions
+///
+/// The casts themselves are safe; they restrict `cast()` to declared coercions so pointer-level
+/// code keeps the same discipline as references. Nothing is dereferenced, so guard hooks can't
+/// run; `when` rules are still checked. Writing through the result is unsafe code whose
+/// soundness is up to the caller, for the `*mut` and `NonNull` forms alike.
+fn generate_ptr_methods(trait_name: &Ident) -> proc_macro2::TokenStream {
+    let mode_marker = mode_marker(&CoercionMode::Borrowed);
+    let declared = quote! {
+        ::phantom_coerce::__private::DeclaredCoercion<#mode_marker, __CoerceTarget>
+    };
+    let bounds = quote! {
+        where
+            Self: #trait_name<__CoerceTarget> + #declared + ::core::marker::Sized,
+    };
+    let preamble = quote! {
+        let () = <Self as #declared>::PERMITTED;
+        const {
+            ::core::assert!(
+                ::core::mem::size_of::<Self>() == ::core::mem::size_of::<__CoerceTarget>(),
+                "coerce: source and target sizes differ",
+            );
+        }
+    };
+    quote! {
+        fn coerce_ptr<__CoerceTarget>(
+            ptr: ::core::ptr::NonNull<Self>,
+        ) -> ::core::ptr::NonNull<__CoerceTarget>
+        #bounds
+        {
+            #preamble
+            ptr.cast()
+        }
+
+        fn coerce_const_ptr<__CoerceTarget>(ptr: *const Self) -> *const __CoerceTarget
+        #bounds
+        {
+            #preamble
+            ptr.cast()
+        }
+
+        fn coerce_mut_ptr<__CoerceTarget>(ptr: *mut Self) -> *mut __CoerceTarget
+        #bounds
+        {
+            #preamble
+            ptr.cast()
+        }
+    }
+}
+
+/// Generate `coerce_manually_drop_ref` (borrowed) or `coerce_manually_drop` (owned)
+///
+/// Both go through the regular coercion, so guard hooks and `when` rules apply. The owned form
+/// unwraps and rewraps the value, which is free and needs no unsafe code.
+fn generate_manually_drop_methods(
+    trait_name: &Ident,
+    mode: &CoercionMode,
+) -> proc_macro2::TokenStream {
+    match mode {
+        CoercionMode::Borrowed => quote! {
+            fn coerce_manually_drop_ref<__CoerceTarget>(
+                slot: &::core::mem::ManuallyDrop<Self>,
+            ) -> &::core::mem::ManuallyDrop<__CoerceTarget>
+            where
+                Self: #trait_name<__CoerceTarget> + ::core::marker::Sized,
+            {
+                let target: &__CoerceTarget = #trait_name::coerce(&**slot);
+                // SAFETY: `ManuallyDrop` is `repr(transparent)`, so it shares its contents'
+                // layout.
+                unsafe { &*(target as *const __CoerceTarget).cast() }
+            }
+        },
+        _ => quote! {
+            fn coerce_manually_drop<__CoerceTarget>(
+                slot: ::core::mem::ManuallyDrop<Self>,
+            ) -> ::core::mem::ManuallyDrop<__CoerceTarget>
+            where
+                Self: #trait_name<__CoerceTarget> + ::core::marker::Sized,
+            {
+                let value = ::core::mem::ManuallyDrop::into_inner(slot);
+                ::core::mem::ManuallyDrop::new(#trait_name::into_coerced(value))
+            }
+        },
+    }
+}
+
+/// Generate `tagged`/`into_tagged`/`to_tagged` methods recording the marker tag of `tag_param`
+fn generate_tag_methods(
+    struct_name: &Ident,
+    generics: &syn::Generics,
+    tag_param: &Ident,
+    options: &StructOptions,
+    has_owned: bool,
+    has_cloned: bool,
+) -> syn::Result<proc_macro2::TokenStream> {
+    let is_type_param = generics.type_params().any(|tp| tp.ident == *tag_param);
+    if !is_type_param {
+        return Err(syn::Error::new_spanned(
+            tag_param,
+            format!(
+                "'tag' parameter '{}' is not a generic type parameter of '{}'",
+                tag_param, struct_name
+            ),
+        ));
+    }
+
+    let mut tag_generics = generics.clone();
+    tag_generics
+        .make_where_clause()
+        .predicates
+        .push(syn::parse_quote! { #tag_param: ::phantom_coerce::tag::MarkerTag });
+    let (impl_generics, ty_generics, where_clause) = tag_generics.split_for_impl();
+
+    let tag = quote! { <#tag_param as ::phantom_coerce::tag::MarkerTag>::TAG };
+
+    let owned_method = if has_owned {
+        let trait_name = coercion_trait_name(struct_name, options, &CoercionMode::Owned);
+        quote! {
+            fn into_tagged<__CoerceTarget>(self) -> ::phantom_coerce::tag::Tagged<__CoerceTarget>
+            where
+                Self: #trait_name<__CoerceTarget>,
+                __CoerceTarget: ::core::marker::Sized,
+            {
+                ::phantom_coerce::tag::Tagged::new(#tag, #trait_name::into_coerced(self))
+            }
+        }
+    } else {
+        quote! {}
+    };
+
+    let cloned_method = if has_cloned {
+        let trait_name = coercion_trait_name(struct_name, options, &CoercionMode::Cloned);
+        quote! {
+            fn to_tagged<__CoerceTarget>(&self) -> ::phantom_coerce::tag::Tagged<__CoerceTarget>
+            where
+                Self: #trait_name<__CoerceTarget>,
+                __CoerceTarget: ::core::marker::Sized,
+            {
+                ::phantom_coerce::tag::Tagged::new(#tag, #trait_name::to_coerced(self))
+            }
+        }
+    } else {
+        quote! {}
+    };
+
+    Ok(quote! {
+        impl #impl_generics #struct_name #ty_generics #where_clause {
+            fn tagged(self) -> ::phantom_coerce::tag::Tagged<Self> {
+                ::phantom_coerce::tag::Tagged::new(#tag, self)
+            }
+
+            fn tagged_ref(&self) -> ::phantom_coerce::tag::Tagged<&Self> {
+                ::phantom_coerce::tag::Tagged::new(#tag, self)
+            }
+
+            fn marker_name(&self) -> &'static str {
+                #tag
+            }
+
+            #owned_method
+            #cloned_method
+        }
+    })
+}
+
+/// Add the struct's coercions to the `audit` manifest
+#[cfg(feature = "audit")]
+fn record_audit(
+    struct_name: &Ident,
+    coercions: &[(&CoercionMode, &Vec<ParsedCoercion>)],
+    options: &StructOptions,
+) -> syn::Result<()> {
+    let mut entries = Vec::new();
+    for (mode, coercions) in coercions {
+        for coercion in coercions.iter() {
+            entries.push(audit::AuditEntry {
+                struct_name: struct_name.to_string(),
+                mode: match mode {
+                    CoercionMode::Borrowed => "borrowed",
+                    CoercionMode::Owned => "owned",
+                    CoercionMode::Cloned => "cloned",
+                },
+                source: type_to_string(&coercion.source_type),
+                target: type_to_string(&coercion.target_type),
+                // Safe mode rebuilds owned and cloned targets; references are always cast
+                uses_unsafe: **mode == CoercionMode::Borrowed || !options.safe,
+            });
+        }
+    }
+    audit::record(&entries).map_err(|error| {
+        syn::Error::new_spanned(
+            struct_name,
+            format!("audit: couldn't write the coercion manifest: {error}"),
+        )
+    })
+}
+
+/// Generate a `type_fn` struct implementing `typewit::TypeFn<P>` with the struct instantiated
+/// at `P` as its output
+///
+/// The function struct keeps the struct's other parameters, without their bounds, so mapping a
+/// `TypeEq<A, B>` through it relates `Struct<.., A, ..>` to `Struct<.., B, ..>`.
+fn generate_type_fn(
+    input: &DeriveInput,
+    param: &Ident,
+    type_fn: &Ident,
+) -> syn::Result<proc_macro2::TokenStream> {
+    let struct_name = &input.ident;
+    let generics = &input.generics;
+    if !generics.type_params().any(|tp| tp.ident == *param) {
+        return Err(syn::Error::new_spanned(
+            param,
+            format!("type_fn: '{param}' is not a type parameter of '{struct_name}'"),
+        ));
+    }
+
+    // The struct's other parameters, unbounded, with a use for each so none is unused
+    let mut declared = Vec::new();
+    let mut args = Vec::new();
+    let mut uses = Vec::new();
+    for generic in &generics.params {
+        match generic {
+            syn::GenericParam::Lifetime(lifetime) => {
+                let lifetime = &lifetime.lifetime;
+                declared.push(quote! { #lifetime });
+                args.push(quote! { #lifetime });
+                uses.push(quote! { &#lifetime () });
+            }
+            syn::GenericParam::Type(tp) if tp.ident == *param => {}
+            syn::GenericParam::Type(tp) => {
+                let ident = &tp.ident;
+                declared.push(quote! { #ident: ?::core::marker::Sized });
+                args.push(quote! { #ident });
+                uses.push(quote! { *const #ident });
+            }
+            syn::GenericParam::Const(cp) => {
+                let ident = &cp.ident;
+                let ty = &cp.ty;
+                declared.push(quote! { const #ident: #ty });
+                args.push(quote! { #ident });
+            }
+        }
+    }
+
+    let vis = &input.vis;
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+    let doc = format!(
+        "`typewit` type function from a `{param}` marker to `{struct_name}` instantiated with it."
+    );
+    Ok(quote! {
+        #[doc = #doc]
+        #vis struct #type_fn<#(#declared),*>(
+            ::core::marker::PhantomData<fn() -> (#(#uses,)*)>,
+        );
+
+        impl<#(#declared),*> #type_fn<#(#args),*> {
+            /// The type function, for `TypeEq::map` and `TypeEq::project`.
+            #vis const NEW: Self = Self(::core::marker::PhantomData);
+        }
+
+        impl #impl_generics ::phantom_coerce::__private::typewit::TypeFn<#param>
+            for #type_fn<#(#args),*> #where_clause
+        {
+            type Output = #struct_name #ty_generics;
+        }
+    })
+}
+
+/// Generate the `covers_all` check: a `{Struct}Covers{Trait}` trait implemented by every
+/// marker the spec's sources give the covered parameter
+///
+/// Rust can't list a trait's implementors, so the marker trait has to opt in by taking the
+/// generated trait as a supertrait: an implementor missing from the alternatives then fails
+/// to compile at its `impl`. The const item rejects marker traits that haven't opted in.
+fn generate_covers_all(
+    input: &DeriveInput,
+    spec: &CoercionSpec,
+    covers: &CoversAll,
+    options: &StructOptions,
+    covered_traits: &mut Vec<Ident>,
+) -> syn::Result<proc_macro2::TokenStream> {
+    let struct_name = &input.ident;
+    let generics = &input.generics;
+    let marker_trait = &covers.marker_trait;
+    let trait_label = quote! { #marker_trait }.to_string().replace(' ', "");
+    if options.late {
+        return Err(syn::Error::new(
+            covers.span,
+            "covers_all must be declared on the struct: the generated trait has to be nameable as a supertrait",
+        ));
+    }
+    if spec.when.is_some() {
+        return Err(syn::Error::new(
+            covers.span,
+            "covers_all needs the sources spelled out as alternatives, so it can't be combined with 'when'",
+        ));
+    }
+
+    let mut sources = Vec::new();
+    for from_pattern in &spec.from_patterns {
+        for alternative in expand_alternatives(from_pattern) {
+            let parsed = parse_pattern(&alternative, generics)?;
+            sources.push(type_param_bindings(generics, &parsed.target_type));
+        }
+    }
+
+    let param = match &covers.param {
+        Some(param) => {
+            if !generics.type_params().any(|tp| tp.ident == *param) {
+                return Err(syn::Error::new(
+                    covers.span,
+                    format!("covers_all: '{param}' is not a type parameter of '{struct_name}'"),
+                ));
+            }
+            param.clone()
+        }
+        None => {
+            // The covered parameter is the one the alternatives enumerate
+            let varying: Vec<&Ident> = generics
+                .type_params()
+                .map(|tp| &tp.ident)
+                .filter(|ident| {
+                    let mut bound = sources
+                        .iter()
+                        .map(|bindings| bindings.get(&ident.to_string()).map(type_to_string));
+                    let first = bound.next().flatten();
+                    bound.any(|binding| binding != first)
+                })
+                .collect();
+            let candidates: Vec<&Ident> = if varying.is_empty() {
+                generics.type_params().map(|tp| &tp.ident).collect()
+            } else {
+                varying
+            };
+            match candidates.as_slice() {
+                [param] => (*param).clone(),
+                _ => {
+                    return Err(syn::Error::new(
+                        covers.span,
+                        format!(
+                            "covers_all can't tell which parameter '{trait_label}' describes. Name it: covers_all = \"Param: {trait_label}\""
+                        ),
+                    ));
+                }
+            }
+        }
+    };
+
+    let Some(trait_ident) = marker_trait.segments.last().map(|segment| &segment.ident) else {
+        return Err(syn::Error::new(
+            covers.span,
+            "covers_all needs a trait path",
+        ));
+    };
+    let covers_trait = quote::format_ident!("{}Covers{}", struct_name, trait_ident);
+    if covered_traits.contains(&covers_trait) {
+        return Err(syn::Error::new(
+            covers.span,
+            format!("'{trait_label}' is already covered by another coercion of '{struct_name}'"),
+        ));
+    }
+    covered_traits.push(covers_trait.clone());
+
+    // A type hole in the covered position lets every marker through
+    let is_hole = |marker: &Type| {
+        generics
+            .type_params()
+            .any(|tp| tp.ident == type_to_string(marker))
+    };
+    let mut markers: Vec<Type> = Vec::new();
+    let mut blanket = false;
+    for bindings in &sources {
+        let Some(marker) = bindings.get(&param.to_string()) else {
+            continue;
+        };
+        if is_hole(marker) {
+            blanket = true;
+        } else if !markers
+            .iter()
+            .any(|known| type_to_string(known) == type_to_string(marker))
+        {
+            markers.push(marker.clone());
+        }
+    }
+    let impls = if blanket {
+        quote! { impl<__CoverM: ?::core::marker::Sized> #covers_trait for __CoverM {} }
+    } else {
+        quote! { #(impl #covers_trait for #markers {})* }
+    };
+
+    let vis = &input.vis;
+    let doc = format!(
+        "Implemented by the `{trait_label}` markers `{struct_name}` coerces from. \
+         Take it as a supertrait of `{trait_label}` so that new markers must be listed."
+    );
+    let message =
+        format!("`{{Self}}` implements `{trait_label}`, but no `{struct_name}` coercion lists it");
+    let label = format!("missing from the alternatives of `covers_all = \"{trait_label}\"`");
+    let note_list = format!("add it to the `{param}` alternatives of the `{struct_name}` coercion");
+    let note_supertrait = format!(
+        "`{trait_label}` must be declared as `trait {trait_label}: {covers_trait}` for covers_all to see its implementors"
+    );
+    // Points a marker trait without the supertrait at the attribute
+    let check = quote::quote_spanned! {covers.span=>
+        covered::<__CoverM>();
+    };
+    Ok(quote! {
+        #[doc = #doc]
+        #[diagnostic::on_unimplemented(
+            message = #message,
+            label = #label,
+            note = #note_list,
+            note = #note_supertrait
+        )]
+        #vis trait #covers_trait {}
+        #impls
+        const _: () = {
+            #[allow(dead_code)]
+            fn covered<__CoverM: ?::core::marker::Sized + #covers_trait>() {}
+            #[allow(dead_code)]
+            fn check<__CoverM: ?::core::marker::Sized + #marker_trait>() {
+                #check
+            }
+        };
+    })
+}
+
+/// The marker `ty` gives `tag_param`, with its name, when it's a concrete type without arguments
+fn concrete_marker(
+    generics: &syn::Generics,
+    ty: &Type,
+    tag_param: &Ident,
+) -> Option<(Ident, Type)> {
+    let marker = type_param_bindings(generics, ty).remove(&tag_param.to_string())?;
+    let Type::Path(TypePath { qself: None, path }) = &marker else {
+        return None;
+    };
+    let last = path.segments.last().unwrap();
+    let is_param = path.segments.len() == 1
+        && generics
+            .type_params()
+            .any(|param| param.ident == last.ident);
+    if is_param || !matches!(last.arguments, PathArguments::None) {
+        return None;
+    }
+    Some((last.ident.clone(), marker))
+}
+
+/// Concrete source markers of `tag_param`, named, in declaration order
+///
+/// The names become enum variants, so two markers sharing one is an error reported for `what`.
+fn specific_markers(
+    struct_name: &Ident,
+    generics: &syn::Generics,
+    tag_param: &Ident,
+    coercions: &[&Vec<ParsedCoercion>],
+    what: &str,
+) -> syn::Result<Vec<(Ident, Type)>> {
+    let sources = coercions
+        .iter()
+        .flat_map(|coercions| coercions.iter())
+        .map(|coercion| &coercion.source_type);
+    named_markers(struct_name, generics, tag_param, sources, what)
+}
+
+/// Concrete markers `types` give `param`, named, deduplicated in order of appearance
+fn named_markers<'a>(
+    struct_name: &Ident,
+    generics: &syn::Generics,
+    param: &Ident,
+    types: impl Iterator<Item = &'a Type>,
+    what: &str,
+) -> syn::Result<Vec<(Ident, Type)>> {
+    let mut markers: Vec<(Ident, Type)> = Vec::new();
+    for ty in types {
+        let Some((name, marker)) = concrete_marker(generics, ty, param) else {
+            continue;
+        };
+        match markers.iter().find(|(variant, _)| *variant == name) {
+            Some((_, existing)) if type_to_string(existing) != type_to_string(&marker) => {
+                return Err(syn::Error::new_spanned(
+                    struct_name,
+                    format!(
+                        "{what} can't tell '{}' from '{}': enumerated markers need distinct names",
+                        type_to_string(existing),
+                        type_to_string(&marker)
+                    ),
+                ));
+            }
+            Some(_) => {}
+            None => markers.push((name, marker)),
+        }
+    }
+    Ok(markers)
+}
+
+/// Generate the `tag_enum` enum, with a unit variant per concrete source marker of `tag_param`
+///
+/// Variants display as and parse from their marker's `MarkerTag::TAG`, and `ALL` lists them in
+/// declaration order.
+fn generate_tag_enum(
+    input: &DeriveInput,
+    tag_param: &Ident,
+    tag_enum: &Ident,
+    coercions: &[&Vec<ParsedCoercion>],
+) -> syn::Result<proc_macro2::TokenStream> {
+    let struct_name = &input.ident;
+    let markers = specific_markers(
+        struct_name,
+        &input.generics,
+        tag_param,
+        coercions,
+        "tag_enum",
+    )?;
+    if markers.is_empty() {
+        return Err(syn::Error::new_spanned(
+            tag_enum,
+            format!("tag_enum needs a coercion from a concrete '{tag_param}' marker to list"),
+        ));
+    }
+
+    let enum_doc = format!("The markers `{struct_name}` records for `{tag_param}`.");
+    Ok(marker_enum_tokens(
+        &input.vis, tag_enum, &enum_doc, &markers,
+    ))
+}
+
+/// Generate a `marker_enum` enum, with a unit variant per concrete marker any coercion gives
+/// `param`, source or target
+fn generate_marker_enum(
+    input: &DeriveInput,
+    param: &Ident,
+    marker_enum: &Ident,
+    coercions: &[&Vec<ParsedCoercion>],
+) -> syn::Result<proc_macro2::TokenStream> {
+    let struct_name = &input.ident;
+    if !input.generics.type_params().any(|tp| tp.ident == *param) {
+        return Err(syn::Error::new_spanned(
+            param,
+            format!("marker_enum: '{param}' is not a type parameter of '{struct_name}'"),
+        ));
+    }
+    // Sources first, so erased markers come after the ones they're erased from
+    let all = || coercions.iter().flat_map(|coercions| coercions.iter());
+    let types = all()
+        .map(|coercion| &coercion.source_type)
+        .chain(all().map(|coercion| &coercion.target_type));
+    let markers = named_markers(struct_name, &input.generics, param, types, "marker_enum")?;
+    if markers.is_empty() {
+        return Err(syn::Error::new_spanned(
+            marker_enum,
+            format!("marker_enum needs a coercion with a concrete '{param}' marker to list"),
+        ));
+    }
+
+    let enum_doc = format!("The markers `{struct_name}` declares for `{param}`.");
+    Ok(marker_enum_tokens(
+        &input.vis,
+        marker_enum,
+        &enum_doc,
+        &markers,
+    ))
+}
+
+/// The enum behind `tag_enum` and `marker_enum`, displayed as and parsed from the markers' tags
+fn marker_enum_tokens(
+    vis: &syn::Visibility,
+    name: &Ident,
+    enum_doc: &str,
+    markers: &[(Ident, Type)],
+) -> proc_macro2::TokenStream {
+    let count = markers.len();
+    let variants: Vec<&Ident> = markers.iter().map(|(variant, _)| variant).collect();
+    let types: Vec<&Type> = markers.iter().map(|(_, marker)| marker).collect();
+    let tags = markers.iter().map(|(_, marker)| {
+        quote! { <#marker as ::phantom_coerce::tag::MarkerTag>::TAG }
+    });
+    let variant_docs = markers
+        .iter()
+        .map(|(_, marker)| format!("`{}`", type_to_string(marker)));
+
+    quote! {
+        #[doc = #enum_doc]
+        #[derive(
+            ::core::fmt::Debug,
+            ::core::clone::Clone,
+            ::core::marker::Copy,
+            ::core::cmp::PartialEq,
+            ::core::cmp::Eq,
+            ::core::cmp::PartialOrd,
+            ::core::cmp::Ord,
+            ::core::hash::Hash,
+        )]
+        #vis enum #name {
+            #(
+                #[doc = #variant_docs]
+                #variants,
+            )*
+        }
+
+        impl #name {
+            /// Every marker, in declaration order.
+            #vis const ALL: [Self; #count] = [#(Self::#variants),*];
+
+            /// The marker's tag.
+            #vis const fn as_str(self) -> &'static str {
+                match self {
+                    #(Self::#variants => #tags,)*
+                }
+            }
+
+            /// The variant for marker type `M`, if it's one of the listed markers.
+            #vis fn of<M: ?::core::marker::Sized + 'static>() -> ::core::option::Option<Self> {
+                let id = ::core::any::TypeId::of::<M>();
+                #(
+                    if id == ::core::any::TypeId::of::<#types>() {
+                        return ::core::option::Option::Some(Self::#variants);
+                    }
+                )*
+                ::core::option::Option::None
+            }
+        }
+
+        impl ::core::fmt::Display for #name {
+            fn fmt(&self, f: &mut ::core::fmt::Formatter<'_>) -> ::core::fmt::Result {
+                f.write_str(self.as_str())
+            }
+        }
+
+        impl ::core::str::FromStr for #name {
+            type Err = ::phantom_coerce::tag::UnknownTag;
+
+            fn from_str(tag: &str) -> ::core::result::Result<Self, Self::Err> {
+                ::core::iter::Iterator::find(
+                    &mut ::core::iter::IntoIterator::into_iter(Self::ALL),
+                    |marker| marker.as_str() == tag,
+                )
+                .ok_or_else(|| ::phantom_coerce::tag::UnknownTag::new(tag))
+            }
+        }
+    }
+}
+
+/// Name of the hidden enum that `match_specific!` matches on
+fn specific_enum_name(struct_name: &Ident) -> Ident {
+    Ident::new(
+        &format!("__PhantomCoerceSpecific{}", struct_name),
+        struct_name.span(),
+    )
+}
+
+/// Generate the enum and narrowing function behind `match_specific!`
+///
+/// Every concrete marker a coercion erases from `tag_param` gets a variant, named after the
+/// marker, holding a reference with that marker restored. Matching on the enum rather than on
+/// tags makes a newly declared marker a non-exhaustive match. Narrowing trusts the recorded tag,
+/// and is only generated when `tag_param` appears in `PhantomData` fields alone.
+fn generate_specific_dispatch(
+    input: &DeriveInput,
+    fields: &syn::FieldsNamed,
+    tag_param: &Ident,
+    coercions: &[&Vec<ParsedCoercion>],
+) -> syn::Result<proc_macro2::TokenStream> {
+    let struct_name = &input.ident;
+    let generics = &input.generics;
+    if fields
+        .named
+        .iter()
+        .any(|field| !is_phantom_field(field) && mentions_ident(quote! { #field }, tag_param))
+    {
+        return Ok(quote! {});
+    }
+
+    let markers = specific_markers(
+        struct_name,
+        generics,
+        tag_param,
+        coercions,
+        "match_specific",
+    )?;
+    if markers.is_empty() {
+        return Ok(quote! {});
+    }
+
+    // The enum takes every struct parameter except the tag
+    let mut enum_generics = generics.clone();
+    enum_generics.params = generics
+        .params
+        .iter()
+        .filter(|param| !matches!(param, syn::GenericParam::Type(tp) if tp.ident == *tag_param))
+        .cloned()
+        .collect();
+    enum_generics
+        .params
+        .insert(0, syn::parse_quote! { '__coerce });
+    if let Some(where_clause) = &mut enum_generics.where_clause {
+        where_clause.predicates = where_clause
+            .predicates
+            .iter()
+            .filter(|predicate| !mentions_ident(quote! { #predicate }, tag_param))
+            .cloned()
+            .collect();
+    }
+    let (_, enum_ty_generics, enum_where_clause) = enum_generics.split_for_impl();
+
+    let specific_type = |marker: &Type| {
+        let args = generics.params.iter().map(|param| match param {
+            syn::GenericParam::Lifetime(lifetime) => {
+                let lifetime = &lifetime.lifetime;
+                quote! { #lifetime }
+            }
+            syn::GenericParam::Type(param) if param.ident == *tag_param => quote! { #marker },
+            syn::GenericParam::Type(param) => {
+                let ident = &param.ident;
+                quote! { #ident }
+            }
+            syn::GenericParam::Const(param) => {
+                let ident = &param.ident;
+                quote! { #ident }
+            }
+        });
+        quote! { #struct_name<#(#args),*> }
+    };
+
+    let vis = &input.vis;
+    let enum_name = specific_enum_name(struct_name);
+    let variants = markers.iter().map(|(variant, marker)| {
+        let specific = specific_type(marker);
+        quote! { #variant(&'__coerce #specific) }
+    });
+    let checks = markers.iter().map(|(variant, marker)| {
+        let specific = specific_type(marker);
+        quote! {
+            if tagged.is::<#marker>() {
+                const {
+                    ::core::assert!(
+                        ::core::mem::size_of::<Self>() == ::core::mem::size_of::<#specific>(),
+                        "coerce: source and target sizes differ",
+                    );
+                }
+                // SAFETY: The types differ only in the tag parameter, which appears only in
+                // PhantomData fields, so they share a layout.
+                let specific = unsafe { &*(value as *const Self).cast::<#specific>() };
+                return ::core::option::Option::Some(#enum_name::#variant(specific));
+            }
+        }
+    });
+
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+    Ok(quote! {
+        #[doc(hidden)]
+        #vis enum #enum_name #enum_ty_generics #enum_where_clause {
+            #(#variants),*
+        }
+
+        impl #impl_generics #struct_name #ty_generics #where_clause {
+            #[doc(hidden)]
+            fn __phantom_coerce_specific<'__coerce, __CoerceValue>(
+                tagged: &'__coerce ::phantom_coerce::tag::Tagged<__CoerceValue>,
+            ) -> ::core::option::Option<#enum_name #enum_ty_generics>
+            where
+                __CoerceValue: ::core::borrow::Borrow<Self>,
+                Self: '__coerce,
+            {
+                let value: &'__coerce Self = ::core::borrow::Borrow::borrow(tagged.value());
+                #(#checks)*
+                ::core::option::Option::None
+            }
+        }
+    })
+}
+
+/// Generate `serde::Deserialize` for each generic target of an owned coercion (`tag_field`)
+///
+/// The impl reads the data fields and the discriminant `tag_field`, builds the specific
+/// instantiation whose `MarkerTag::TAG` matches, and coerces it, so guard hooks and `when` rules
+/// still run. Tags no concrete source marker writes are rejected, listing the known ones.
+fn generate_tagged_deserialize(
+    input: &DeriveInput,
+    fields: &syn::FieldsNamed,
+    tag_param: &Ident,
+    (tag_field, span): (&str, proc_macro2::Span),
+    owned_coercions: &[ParsedCoercion],
+    options: &StructOptions,
+) -> syn::Result<proc_macro2::TokenStream> {
+    use syn::ext::IdentExt;
+
+    let struct_name = &input.ident;
+    let generics = &input.generics;
+    if fields
+        .named
+        .iter()
+        .any(|field| is_deep_field(field).unwrap_or(false))
+    {
+        return Err(syn::Error::new(
+            span,
+            "tag_field doesn't support #[coerce(deep)] fields",
+        ));
+    }
+
+    // Data fields are read by name; PhantomData fields aren't on the wire
+    let data_fields: Vec<&syn::Field> = fields
+        .named
+        .iter()
+        .filter(|field| !is_phantom_field(field))
+        .collect();
+    if let Some(field) = data_fields
+        .iter()
+        .find(|field| field.ident.as_ref().unwrap().unraw() == tag_field)
+    {
+        return Err(syn::Error::new_spanned(
+            field,
+            format!("tag_field '{tag_field}' collides with a field of '{struct_name}'"),
+        ));
+    }
+
+    // Owned coercions from concrete markers, grouped by their target
+    let mut targets: Vec<(&ParsedCoercion, Vec<(&ParsedCoercion, Type)>)> = Vec::new();
+    for coercion in owned_coercions {
+        let Some((_, marker)) = concrete_marker(generics, &coercion.source_type, tag_param) else {
+            continue;
+        };
+        let target = type_to_string(&coercion.target_type);
+        match targets
+            .iter_mut()
+            .find(|(first, _)| type_to_string(&first.target_type) == target)
+        {
+            Some((_, sources)) => sources.push((coercion, marker)),
+            None => targets.push((coercion, vec![(coercion, marker)])),
+        }
+    }
+    if targets.is_empty() {
+        return Err(syn::Error::new(
+            span,
+            format!(
+                "tag_field needs an owned coercion from a concrete '{tag_param}' marker to deserialize through"
+            ),
+        ));
+    }
+
+    let serde = quote! { ::phantom_coerce::__private::serde };
+    let trait_name = coercion_trait_name(struct_name, options, &CoercionMode::Owned);
+    let expecting = format!("a `{struct_name}` with a `{tag_field}` field");
+    let phantom_fields: Vec<&syn::Field> = fields
+        .named
+        .iter()
+        .filter(|field| is_phantom_field(field))
+        .collect();
+    let phantom_idents: Vec<&Ident> = phantom_fields
+        .iter()
+        .map(|field| field.ident.as_ref().unwrap())
+        .collect();
+    let phantom_inits: Vec<proc_macro2::TokenStream> = phantom_fields
+        .iter()
+        .map(|field| phantom_init(field))
+        .collect();
+    let data_idents: Vec<&Ident> = data_fields
+        .iter()
+        .map(|field| field.ident.as_ref().unwrap())
+        .collect();
+    let vars: Vec<Ident> = data_idents
+        .iter()
+        .map(|ident| quote::format_ident!("__field_{}", ident.unraw()))
+        .collect();
+    let keys: Vec<String> = data_idents
+        .iter()
+        .map(|ident| ident.unraw().to_string())
+        .collect();
+    // Like serde's derive, a missing `Option` field reads as `None`
+    let unwraps = data_fields
+        .iter()
+        .zip(&vars)
+        .zip(&keys)
+        .map(|((field, var), key)| {
+            if is_option(&field.ty) {
+                quote! { let #var = #var.unwrap_or(::core::option::Option::None); }
+            } else {
+                quote! {
+                    let #var = #var.ok_or_else(|| {
+                        <__A::Error as #serde::de::Error>::missing_field(#key)
+                    })?;
+                }
+            }
+        });
+    let unwraps: Vec<_> = unwraps.collect();
+
+    let impls = targets.iter().map(|(first, sources)| {
+        let target_type = &first.target_type;
+        let bindings = type_param_bindings(generics, target_type);
+        let field_types: Vec<Type> = data_fields
+            .iter()
+            .map(|field| substitute_type_params(&field.ty, &bindings))
+            .collect();
+
+        let hole_generics = extract_type_hole_generics(generics, first);
+        let mut de_generics: syn::Generics = if hole_generics.is_empty() {
+            syn::Generics::default()
+        } else {
+            syn::parse2(hole_generics).expect("hole generics parse as generics")
+        };
+        de_generics.params.insert(0, syn::parse_quote! { '__de });
+        let where_clause = de_generics.make_where_clause();
+        for ty in &field_types {
+            where_clause
+                .predicates
+                .push(syn::parse_quote! { #ty: #serde::Deserialize<'__de> });
+        }
+        for (coercion, _) in sources {
+            where_clause.predicates.extend(coercion.bounds.iter().cloned());
+        }
+        let (impl_generics, _, where_clause) = de_generics.split_for_impl();
+
+        let dispatch = sources.iter().map(|(coercion, marker)| {
+            let source_type = &coercion.source_type;
+            let mut constructor = source_type.clone();
+            if let Type::Path(TypePath { path, .. }) = &mut constructor
+                && let PathArguments::AngleBracketed(args) =
+                    &mut path.segments.last_mut().unwrap().arguments
+            {
+                args.colon2_token = Some(Default::default());
+            }
+            quote! {
+                if tag == <#marker as ::phantom_coerce::tag::MarkerTag>::TAG {
+                    let source = #constructor {
+                        #(#data_idents: #vars,)*
+                        #(#phantom_idents: #phantom_inits,)*
+                    };
+                    return ::core::result::Result::Ok(
+                        <#source_type as #trait_name<#target_type>>::into_coerced(source),
+                    );
+                }
+            }
+        });
+        let markers = sources.iter().map(|(_, marker)| marker);
+
+        quote! {
+            impl #impl_generics #serde::Deserialize<'__de> for #target_type #where_clause {
+                fn deserialize<__D>(deserializer: __D) -> ::core::result::Result<Self, __D::Error>
+                where
+                    __D: #serde::Deserializer<'__de>,
+                {
+                    struct __Visitor<__T>(::core::marker::PhantomData<fn() -> __T>);
+
+                    impl #impl_generics #serde::de::Visitor<'__de> for __Visitor<#target_type> #where_clause {
+                        type Value = #target_type;
+
+                        fn expecting(
+                            &self,
+                            formatter: &mut ::core::fmt::Formatter<'_>,
+                        ) -> ::core::fmt::Result {
+                            formatter.write_str(#expecting)
+                        }
+
+                        fn visit_map<__A>(
+                            self,
+                            mut map: __A,
+                        ) -> ::core::result::Result<Self::Value, __A::Error>
+                        where
+                            __A: #serde::de::MapAccess<'__de>,
+                        {
+                            let mut tag: ::core::option::Option<::std::string::String> =
+                                ::core::option::Option::None;
+                            #(
+                                let mut #vars: ::core::option::Option<#field_types> =
+                                    ::core::option::Option::None;
+                            )*
+                            while let ::core::option::Option::Some(key) =
+                                map.next_key::<::std::string::String>()?
+                            {
+                                match key.as_str() {
+                                    #tag_field => {
+                                        if tag.is_some() {
+                                            return ::core::result::Result::Err(
+                                                <__A::Error as #serde::de::Error>::duplicate_field(#tag_field),
+                                            );
+                                        }
+                                        tag = ::core::option::Option::Some(map.next_value()?);
+                                    }
+                                    #(
+                                        #keys => {
+                                            if #vars.is_some() {
+                                                return ::core::result::Result::Err(
+                                                    <__A::Error as #serde::de::Error>::duplicate_field(#keys),
+                                                );
+                                            }
+                                            #vars = ::core::option::Option::Some(map.next_value()?);
+                                        }
+                                    )*
+                                    _ => {
+                                        map.next_value::<#serde::de::IgnoredAny>()?;
+                                    }
+                                }
+                            }
+                            let tag = tag.ok_or_else(|| {
+                                <__A::Error as #serde::de::Error>::missing_field(#tag_field)
+                            })?;
+                            #(#unwraps)*
+                            #(#dispatch)*
+                            const TAGS: &[&str] = &[#(<#markers as ::phantom_coerce::tag::MarkerTag>::TAG),*];
+                            ::core::result::Result::Err(
+                                <__A::Error as #serde::de::Error>::unknown_variant(&tag, TAGS),
+                            )
+                        }
+                    }
+
+                    deserializer.deserialize_map(__Visitor(::core::marker::PhantomData))
+                }
+            }
+        }
+    });
+
+    Ok(quote! { #(#impls)* })
+}
+
+fn generate_asref_impl(
+    _struct_name: &Ident,
+    generics: &syn::Generics,
+    trait_name: &Ident,
+    coercion: &ParsedCoercion,
+    pointers: &[SmartPointer],
+) -> syn::Result<proc_macro2::TokenStream> {
+    let source_type = &coercion.source_type;
+    let target_type = &coercion.target_type;
+    let generics_for_impl = extract_type_hole_generics(generics, coercion);
+    let bounds = &coercion.bounds;
+
+    // Smart pointers holding the source also view it as the target
+    let pointer_impls = pointers.iter().map(|pointer| {
+        let pointer = pointer.path();
+        quote! {
+            impl #generics_for_impl ::core::convert::AsRef<#target_type> for #pointer<#source_type>
+            where
+                #(#bounds,)*
+            {
+                fn as_ref(&self) -> &#target_type {
+                    #trait_name::coerce(&**self)
+                }
+            }
+        }
+    });
+
+    Ok(quote! {
+        impl #generics_for_impl ::core::convert::AsRef<#target_type> for #source_type
+        where
+            #(#bounds,)*
+        {
+            fn as_ref(&self) -> &#target_type {
+                #trait_name::coerce(self)
+            }
+        }
+
+        #(#pointer_impls)*
+    })
+}
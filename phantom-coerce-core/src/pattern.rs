@@ -0,0 +1,465 @@
+//! The pattern language used by `#[coerce(...)]` attributes, `phantom_coerce!` blocks, and
+//! spec files.
+//!
+//! A pattern names one or more instantiations of the struct: `TypedPath<Absolute | Relative, _>`
+//! stands for `TypedPath<Absolute, _>` and `TypedPath<Relative, _>`, where `_` is a type hole
+//! preserving that parameter. Expansion and parsing are exposed separately so tools can inspect
+//! declarations without generating code:
+//!
+//! ```
+//! use phantom_coerce_core::pattern::{expand_alternatives, parse_pattern};
+//!
+//! let generics: syn::Generics = syn::parse_quote!(<Base, Type>);
+//! let alternatives = expand_alternatives("TypedPath<Absolute | Relative, _>");
+//! assert_eq!(alternatives, ["TypedPath<Absolute, _>", "TypedPath<Relative, _>"]);
+//!
+//! let parsed = parse_pattern(&alternatives[0], &generics).unwrap();
+//! assert_eq!(parsed.type_hole_positions, [1]);
+//! ```
+
+use syn::visit_mut::VisitMut;
+use syn::{Ident, Type};
+
+/// Render pattern tokens in the compact form the string-based pattern parser expects
+pub fn tokens_to_pattern(tokens: &proc_macro2::TokenStream) -> String {
+    tokens
+        .to_string()
+        .replace(" < ", "<")
+        .replace("< ", "<")
+        .replace(" >", ">")
+        .replace(" ,", ",")
+        .replace(" :: ", "::")
+}
+
+/// Canonicalize a pattern string written across lines or with irregular spacing
+///
+/// Rustfmt may wrap long attribute strings, so the pattern is re-tokenized and printed the
+/// way `tokens_to_pattern` prints types, and trailing commas before `>` are dropped. Strings
+/// that don't tokenize are returned trimmed, leaving the parser to report them.
+pub fn normalize_pattern(pattern: &str) -> String {
+    match pattern.parse::<proc_macro2::TokenStream>() {
+        Ok(tokens) => tokens_to_pattern(&tokens).replace(",>", ">"),
+        Err(_) => pattern.trim().to_string(),
+    }
+}
+
+/// A single pattern alternative, resolved against the struct's generics
+#[derive(Debug, Clone)]
+pub struct ParsedPattern {
+    /// The type with type holes resolved to generic parameters
+    pub target_type: Type,
+    /// Indices of type parameters that should be preserved (type holes)
+    pub type_hole_positions: Vec<usize>,
+}
+
+/// Rewrite hole shorthands into positional `_` holes
+///
+/// A trailing `..` stands for a hole at every remaining parameter, so
+/// `Pipeline<Validated, ..>` reads as `Pipeline<Validated, _, _, _>`. An argument naming the
+/// struct's own type parameter at that position is a hole too: `Container<T, Raw>` is
+/// `Container<_, Raw>`.
+pub fn expand_hole_shorthands(pattern: &str, generics: &syn::Generics) -> syn::Result<String> {
+    let (Some(start), Some(end)) = (pattern.find('<'), pattern.rfind('>')) else {
+        return Ok(pattern.to_string());
+    };
+
+    // Split the struct's own arguments on top-level commas
+    let mut arguments = Vec::new();
+    let mut current = String::new();
+    let mut depth = 0;
+    for ch in pattern[start + 1..end].chars() {
+        match ch {
+            '<' | '(' | '[' | '{' => depth += 1,
+            '>' | ')' | ']' | '}' => depth -= 1,
+            ',' if depth == 0 => {
+                arguments.push(std::mem::take(&mut current));
+                continue;
+            }
+            _ => {}
+        }
+        current.push(ch);
+    }
+    arguments.push(current);
+    let mut arguments: Vec<&str> = arguments.iter().map(|argument| argument.trim()).collect();
+
+    let params: Vec<&syn::GenericParam> = generics
+        .params
+        .iter()
+        .filter(|param| !matches!(param, syn::GenericParam::Lifetime(_)))
+        .collect();
+    let type_param_name = |param: &syn::GenericParam| match param {
+        syn::GenericParam::Type(param) => Some(param.ident.to_string()),
+        syn::GenericParam::Const(param) => Some(param.ident.to_string()),
+        syn::GenericParam::Lifetime(_) => None,
+    };
+
+    let rest = arguments.iter().position(|argument| *argument == "..");
+    if let Some(rest) = rest {
+        if rest != arguments.len() - 1 {
+            return Err(syn::Error::new(
+                proc_macro2::Span::call_site(),
+                format!("`..` must be the last argument in '{}'", pattern),
+            ));
+        }
+        arguments.pop();
+    }
+
+    let mut expanded = Vec::new();
+    for (position, argument) in arguments.iter().enumerate() {
+        let own_name = params
+            .get(position)
+            .and_then(|param| type_param_name(param));
+        if own_name.as_deref() == Some(*argument) {
+            expanded.push("_".to_string());
+            continue;
+        }
+        // Naming another position's parameter would coerce one parameter into another
+        if let Some(other) = params
+            .iter()
+            .position(|param| type_param_name(param).as_deref() == Some(*argument))
+        {
+            return Err(syn::Error::new(
+                proc_macro2::Span::call_site(),
+                format!(
+                    "'{}' in '{}' names the struct's parameter at position {}, but is written at position {}",
+                    argument, pattern, other, position
+                ),
+            ));
+        }
+        expanded.push(argument.to_string());
+    }
+
+    let Some(rest) = rest else {
+        if expanded == arguments {
+            return Ok(pattern.to_string());
+        }
+        return Ok(format!(
+            "{}<{}>{}",
+            &pattern[..start],
+            expanded.join(", "),
+            &pattern[end + 1..]
+        ));
+    };
+    for _ in params.iter().skip(rest) {
+        expanded.push("_".to_string());
+    }
+
+    Ok(format!(
+        "{}<{}>{}",
+        &pattern[..start],
+        expanded.join(", "),
+        &pattern[end + 1..]
+    ))
+}
+
+/// Parse a single pattern alternative, extracting type hole positions and resolving them
+///
+/// Holes become the struct's own parameters, so `TypedPath<Absolute, _>` on
+/// `TypedPath<Base, Type>` resolves to `TypedPath<Absolute, Type>` with a hole at position 1.
+/// Expand `|` alternatives with [`expand_alternatives`] first.
+pub fn parse_pattern(target_str: &str, generics: &syn::Generics) -> syn::Result<ParsedPattern> {
+    let target_str = &expand_hole_shorthands(target_str, generics)?;
+
+    // Check if contains type holes by looking for standalone _ in type arguments
+    let has_type_hole =
+        target_str.contains("<_") || target_str.contains(", _") || target_str.contains("_>");
+
+    if !has_type_hole {
+        // No type holes, parse normally
+        let mut target_type: Type = syn::parse_str(target_str)?;
+        FoldConstArguments.visit_type_mut(&mut target_type);
+        return Ok(ParsedPattern {
+            target_type,
+            type_hole_positions: Vec::new(),
+        });
+    }
+
+    // Get the generic parameter names; const parameters can be holes too
+    let params: Vec<&Ident> = generics
+        .params
+        .iter()
+        .filter_map(|p| match p {
+            syn::GenericParam::Type(tp) => Some(&tp.ident),
+            syn::GenericParam::Const(cp) => Some(&cp.ident),
+            syn::GenericParam::Lifetime(_) => None,
+        })
+        .collect();
+
+    // Parse by splitting on angle brackets and commas
+    let mut type_hole_positions = Vec::new();
+    let mut resolved_target = String::new();
+    let mut param_index = 0;
+    let mut in_angle_brackets = false;
+    // Brackets opened inside the struct's parameter list, e.g. `AnyOf<(Json, Xml)>`
+    let mut nested_depth = 0;
+    let mut current_token = String::new();
+
+    for ch in target_str.chars() {
+        match ch {
+            // Nested arguments are kept verbatim; only the struct's own parameters can be holes
+            '<' | '(' | '[' | '{' if in_angle_brackets => {
+                nested_depth += 1;
+                current_token.push(ch);
+            }
+            '>' | ')' | ']' | '}' if nested_depth > 0 => {
+                nested_depth -= 1;
+                current_token.push(ch);
+            }
+            ',' if nested_depth > 0 => {
+                current_token.push(ch);
+            }
+            '<' => {
+                // Push accumulated struct name before the angle bracket
+                if !current_token.is_empty() {
+                    resolved_target.push_str(&current_token);
+                    current_token.clear();
+                }
+                resolved_target.push(ch);
+                in_angle_brackets = true;
+                param_index = 0;
+            }
+            '>' => {
+                if !current_token.is_empty() {
+                    if current_token.trim() == "_" {
+                        type_hole_positions.push(param_index);
+                        if param_index < params.len() {
+                            resolved_target.push_str(&params[param_index].to_string());
+                        } else {
+                            return Err(syn::Error::new(
+                                proc_macro2::Span::call_site(),
+                                format!(
+                                    "Type hole at position {} but struct only has {} type and const parameters",
+                                    param_index,
+                                    params.len()
+                                ),
+                            ));
+                        }
+                    } else {
+                        resolved_target.push_str(&current_token);
+                    }
+                    current_token.clear();
+                }
+                resolved_target.push(ch);
+                in_angle_brackets = false;
+            }
+            ',' if in_angle_brackets => {
+                if !current_token.is_empty() {
+                    if current_token.trim() == "_" {
+                        type_hole_positions.push(param_index);
+                        if param_index < params.len() {
+                            resolved_target.push_str(&params[param_index].to_string());
+                        } else {
+                            return Err(syn::Error::new(
+                                proc_macro2::Span::call_site(),
+                                format!(
+                                    "Type hole at position {} but struct only has {} type and const parameters",
+                                    param_index,
+                                    params.len()
+                                ),
+                            ));
+                        }
+                    } else {
+                        resolved_target.push_str(&current_token);
+                    }
+                    current_token.clear();
+                }
+                resolved_target.push(ch);
+                resolved_target.push(' ');
+                param_index += 1;
+            }
+            _ => {
+                current_token.push(ch);
+            }
+        }
+    }
+
+    // Handle any remaining token (for non-generic types at the end)
+    if !current_token.is_empty() {
+        resolved_target.push_str(&current_token);
+    }
+
+    let mut target_type: Type = syn::parse_str(&resolved_target).map_err(|e| {
+        syn::Error::new(
+            proc_macro2::Span::call_site(),
+            format!(
+                "Failed to parse resolved target '{}': {}",
+                resolved_target, e
+            ),
+        )
+    })?;
+    FoldConstArguments.visit_type_mut(&mut target_type);
+
+    Ok(ParsedPattern {
+        target_type,
+        type_hole_positions,
+    })
+}
+
+/// Evaluates integer arithmetic in const arguments, so `Frame<Data, { 4 * 4 }>` and
+/// `Frame<Data, 16>` name the same type
+///
+/// Only literals, parentheses, and `+ - * / % << >>` are folded; anything else (such as a
+/// const parameter) is left as written.
+struct FoldConstArguments;
+
+impl VisitMut for FoldConstArguments {
+    fn visit_generic_argument_mut(&mut self, argument: &mut syn::GenericArgument) {
+        if let syn::GenericArgument::Const(expr) = argument
+            && let Some(value) = fold_const_expr(expr)
+        {
+            let literal = proc_macro2::Literal::u128_unsuffixed(value);
+            *expr = syn::parse_quote!(#literal);
+            return;
+        }
+        syn::visit_mut::visit_generic_argument_mut(self, argument);
+    }
+}
+
+fn fold_const_expr(expr: &syn::Expr) -> Option<u128> {
+    match expr {
+        syn::Expr::Lit(syn::ExprLit {
+            lit: syn::Lit::Int(int),
+            ..
+        }) => int.base10_parse().ok(),
+        syn::Expr::Paren(paren) => fold_const_expr(&paren.expr),
+        syn::Expr::Block(block) => match block.block.stmts.as_slice() {
+            [syn::Stmt::Expr(expr, None)] => fold_const_expr(expr),
+            _ => None,
+        },
+        syn::Expr::Binary(binary) => {
+            let left = fold_const_expr(&binary.left)?;
+            let right = fold_const_expr(&binary.right)?;
+            match binary.op {
+                syn::BinOp::Add(_) => left.checked_add(right),
+                syn::BinOp::Sub(_) => left.checked_sub(right),
+                syn::BinOp::Mul(_) => left.checked_mul(right),
+                syn::BinOp::Div(_) => left.checked_div(right),
+                syn::BinOp::Rem(_) => left.checked_rem(right),
+                syn::BinOp::Shl(_) => left.checked_shl(u32::try_from(right).ok()?),
+                syn::BinOp::Shr(_) => left.checked_shr(u32::try_from(right).ok()?),
+                _ => None,
+            }
+        }
+        _ => None,
+    }
+}
+
+/// Split `s` on `separator` where it isn't nested inside `<>`, `()`, `[]`, or `{}`
+fn split_at_depth_zero(s: &str, separator: char) -> Vec<&str> {
+    let mut parts = Vec::new();
+    let mut depth = 0i32;
+    let mut start = 0;
+    for (index, ch) in s.char_indices() {
+        match ch {
+            '<' | '(' | '[' | '{' => depth += 1,
+            '>' | ')' | ']' | '}' => depth -= 1,
+            _ if ch == separator && depth == 0 => {
+                parts.push(&s[start..index]);
+                start = index + ch.len_utf8();
+            }
+            _ => {}
+        }
+    }
+    parts.push(&s[start..]);
+    parts
+}
+
+/// Byte index of the bracket closing the one that opens `s`
+fn matching_close(s: &str) -> Option<usize> {
+    let mut depth = 0i32;
+    for (index, ch) in s.char_indices() {
+        match ch {
+            '<' | '(' | '[' | '{' => depth += 1,
+            '>' | ')' | ']' | '}' => {
+                depth -= 1;
+                if depth == 0 {
+                    return Some(index);
+                }
+            }
+            _ => {}
+        }
+    }
+    None
+}
+
+/// Every combination of one alternative per slot
+fn cartesian_product(slots: Vec<Vec<String>>) -> Vec<Vec<String>> {
+    let mut results = vec![Vec::new()];
+    for alternatives in slots {
+        let mut next = Vec::new();
+        for result in &results {
+            for alternative in &alternatives {
+                let mut combined = result.clone();
+                combined.push(alternative.clone());
+                next.push(combined);
+            }
+        }
+        results = next;
+    }
+    results
+}
+
+/// Expand `|` alternatives in a pattern into the concrete types it stands for
+///
+/// The grammar, from loosest to tightest binding:
+///
+/// - `|` between complete types: `Msg<Json> | Msg<Xml>`
+/// - `,` between a type's arguments
+/// - `|` within one argument: `Msg<Json | Xml, High>` is two types
+/// - `( ... )` groups alternatives anywhere a type can appear, so
+///   `(Msg<Json> | Msg<Xml>)` and `Msg<(Json | Xml)>` read like their unparenthesized forms.
+///   Parentheses around a top-level comma are a tuple type, whose elements expand in turn.
+///
+/// Alternatives nested in an argument (`Msg<Vec<Json | Xml>>`) expand too, and each level
+/// takes the Cartesian product of its arguments.
+pub fn expand_alternatives(s: &str) -> Vec<String> {
+    split_at_depth_zero(s, '|')
+        .into_iter()
+        .flat_map(|alternative| expand_term(alternative.trim()))
+        .collect()
+}
+
+/// Expand a single alternative: a parenthesized group, a tuple, or a type with arguments
+fn expand_term(term: &str) -> Vec<String> {
+    if term.starts_with('(') && matching_close(term) == Some(term.len() - 1) {
+        let inner = &term[1..term.len() - 1];
+        let elements = split_at_depth_zero(inner, ',');
+        if elements.len() == 1 {
+            return expand_alternatives(inner);
+        }
+
+        // A tuple type; `(A,)` keeps its trailing comma
+        let trailing_comma = elements.last().is_some_and(|last| last.trim().is_empty());
+        let slots = elements
+            .iter()
+            .filter(|element| !element.trim().is_empty())
+            .map(|element| expand_alternatives(element))
+            .collect();
+        return cartesian_product(slots)
+            .into_iter()
+            .map(|elements| match (elements.len(), trailing_comma) {
+                (1, true) => format!("({},)", elements[0]),
+                _ => format!("({})", elements.join(", ")),
+            })
+            .collect();
+    }
+
+    // Only a type whose argument list closes the term is expanded; anything else, like a
+    // path continuing after the arguments, is kept as written
+    let Some(open) = term.find('<') else {
+        return vec![term.to_string()];
+    };
+    if matching_close(&term[open..]).map(|close| open + close) != Some(term.len() - 1) {
+        return vec![term.to_string()];
+    }
+
+    let prefix = &term[..open];
+    let slots = split_at_depth_zero(&term[open + 1..term.len() - 1], ',')
+        .into_iter()
+        .map(expand_alternatives)
+        .collect();
+    cartesian_product(slots)
+        .into_iter()
+        .map(|arguments| format!("{}<{}>", prefix, arguments.join(", ")))
+        .collect()
+}
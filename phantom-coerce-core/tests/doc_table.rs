@@ -0,0 +1,68 @@
+use quote::quote;
+
+/// The doc lines of the generated `# Coercions` impl
+fn coercion_docs(input: proc_macro2::TokenStream) -> Vec<String> {
+    let output = phantom_coerce_core::derive_coerce(input).unwrap();
+    let file: syn::File = syn::parse2(output).unwrap();
+    let docs = |item: &syn::ItemImpl| -> Vec<String> {
+        item.attrs
+            .iter()
+            .filter_map(|attr| match &attr.meta {
+                syn::Meta::NameValue(nv) if nv.path.is_ident("doc") => match &nv.value {
+                    syn::Expr::Lit(syn::ExprLit {
+                        lit: syn::Lit::Str(lit),
+                        ..
+                    }) => Some(lit.value()),
+                    _ => None,
+                },
+                _ => None,
+            })
+            .collect()
+    };
+    file.items
+        .iter()
+        .filter_map(|item| match item {
+            syn::Item::Impl(item) => Some(docs(item)),
+            _ => None,
+        })
+        .find(|docs| docs.first().is_some_and(|line| line == "# Coercions"))
+        .expect("no coercion table")
+}
+
+#[test]
+fn lists_every_coercion() {
+    let docs = coercion_docs(quote! {
+        #[coerce(borrowed_from = "TypedPath<Absolute | Relative, _>", borrowed_to = "TypedPath<UnknownBase, _>")]
+        #[coerce(owned_from = "TypedPath<Absolute, File>", owned_to = "TypedPath<UnknownBase, UnknownType>", deprecated = "use coerce")]
+        struct TypedPath<Base, Type> {
+            base: PhantomData<Base>,
+            ty: PhantomData<Type>,
+            path: String,
+        }
+    });
+    assert_eq!(
+        docs[4..],
+        [
+            "| Method | From | To | Notes |\n|---|---|---|---|",
+            "| `coerce` | `TypedPath<Absolute, Type>` | `TypedPath<UnknownBase, Type>` |  |",
+            "| `coerce` | `TypedPath<Relative, Type>` | `TypedPath<UnknownBase, Type>` |  |",
+            "| `into_coerced` | `TypedPath<Absolute, File>` | `TypedPath<UnknownBase, UnknownType>` | deprecated: use coerce |",
+        ]
+    );
+}
+
+#[test]
+fn lists_any_markers() {
+    let docs = coercion_docs(quote! {
+        #[coerce(any(Base = "UnknownBase", Type = "UnknownType"))]
+        struct TypedPath<Base, Type> {
+            base: PhantomData<Base>,
+            ty: PhantomData<Type>,
+            path: String,
+        }
+    });
+    assert_eq!(
+        docs[5..],
+        ["| `Base` | `UnknownBase` |", "| `Type` | `UnknownType` |"]
+    );
+}
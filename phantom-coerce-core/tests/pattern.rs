@@ -0,0 +1,107 @@
+use phantom_coerce_core::pattern::{
+    expand_alternatives, expand_hole_shorthands, normalize_pattern, parse_pattern,
+};
+use quote::ToTokens;
+
+fn generics(tokens: &str) -> syn::Generics {
+    syn::parse_str(tokens).unwrap()
+}
+
+#[test]
+fn top_level_and_parameter_alternatives() {
+    assert_eq!(
+        expand_alternatives("Msg<Json | Xml, High> | Msg<Protobuf, Low>"),
+        ["Msg<Json, High>", "Msg<Xml, High>", "Msg<Protobuf, Low>"]
+    );
+}
+
+#[test]
+fn cartesian_product_order() {
+    assert_eq!(
+        expand_alternatives("TypedPath<Absolute | Relative, File | Directory>"),
+        [
+            "TypedPath<Absolute, File>",
+            "TypedPath<Absolute, Directory>",
+            "TypedPath<Relative, File>",
+            "TypedPath<Relative, Directory>",
+        ]
+    );
+}
+
+#[test]
+fn groups_and_tuples() {
+    assert_eq!(
+        expand_alternatives("(Msg<(Json | Xml)>)"),
+        ["Msg<Json>", "Msg<Xml>"]
+    );
+    // Parentheses around a comma are a tuple, not a group
+    assert_eq!(
+        expand_alternatives("Msg<AnyOf<(Json, Xml | Yaml)>>"),
+        ["Msg<AnyOf<(Json, Xml)>>", "Msg<AnyOf<(Json, Yaml)>>"]
+    );
+    assert_eq!(expand_alternatives("Msg<(Json,)>"), ["Msg<(Json,)>"]);
+}
+
+#[test]
+fn nested_arguments() {
+    assert_eq!(
+        expand_alternatives("Nested<Wrapped<Json | Xml>>"),
+        ["Nested<Wrapped<Json>>", "Nested<Wrapped<Xml>>"]
+    );
+}
+
+#[test]
+fn normalizes_wrapped_patterns() {
+    assert_eq!(
+        normalize_pattern("TypedPath<\n    Absolute | Relative,\n    _,\n>"),
+        "TypedPath<Absolute | Relative, _>"
+    );
+}
+
+#[test]
+fn hole_shorthands() {
+    let generics = generics("<T, Stage, Format, Speed>");
+    assert_eq!(
+        expand_hole_shorthands("Pipeline<T, Raw, ..>", &generics).unwrap(),
+        "Pipeline<_, Raw, _, _>"
+    );
+    assert!(expand_hole_shorthands("Pipeline<.., Raw>", &generics).is_err());
+    assert!(expand_hole_shorthands("Pipeline<Raw, T, ..>", &generics).is_err());
+}
+
+#[test]
+fn parses_holes_into_parameters() {
+    let generics = generics("<Base, Type>");
+    let parsed = parse_pattern("TypedPath<Absolute, _>", &generics).unwrap();
+    assert_eq!(parsed.type_hole_positions, [1]);
+    assert_eq!(
+        parsed.target_type.to_token_stream().to_string(),
+        "TypedPath < Absolute , Type >"
+    );
+
+    let concrete = parse_pattern("TypedPath<Absolute, File>", &generics).unwrap();
+    assert!(concrete.type_hole_positions.is_empty());
+}
+
+#[test]
+fn rejects_holes_past_the_last_parameter() {
+    let generics = generics("<Base>");
+    assert!(parse_pattern("TypedPath<Absolute, _>", &generics).is_err());
+}
+
+#[test]
+fn const_parameters() {
+    let generics = generics("<Kind, const N: usize>");
+    let symbolic = parse_pattern("Frame<Data, N>", &generics).unwrap();
+    assert_eq!(symbolic.type_hole_positions, [1]);
+    assert_eq!(
+        symbolic.target_type.to_token_stream().to_string(),
+        "Frame < Data , N >"
+    );
+
+    let folded = parse_pattern("Frame<Data, { 4 * (2 + 2) }>", &generics).unwrap();
+    assert_eq!(
+        folded.target_type.to_token_stream().to_string(),
+        "Frame < Data , 16 >"
+    );
+}
@@ -1,36 +1,18 @@
-use proc_macro::TokenStream;
-use quote::quote;
-use syn::{
-    Attribute, Data, DeriveInput, Fields, Ident, Meta, PathArguments, Type, TypePath,
-    parse::Parser, parse_macro_input, spanned::Spanned,
-};
-
-#[derive(Debug, Clone)]
-struct CoercionSpec {
-    /// Source type patterns (parsed from `borrowed_from`, `owned_from`, `cloned_from`)
-    /// Each string may contain `|` for multiple alternatives like "Absolute | Relative"
-    from_patterns: Vec<String>,
-    /// Target type pattern (parsed from `borrowed_to`, `owned_to`, `cloned_to`)
-    to_pattern: String,
-    kind: CoercionMode,
-    generate_asref: bool, // for borrowed only
-}
+//! Proc macros for phantom-coerce.
+//!
+//! The code generation lives in `phantom-coerce-core`, which `phantom-coerce-build` also uses
+//! to generate the same impls from a build script.
 
-#[derive(Debug, Clone)]
-struct ParsedCoercion {
-    /// Source type with type holes resolved to generic parameters
-    source_type: Type,
-    /// Target type with type holes resolved to generic parameters
-    target_type: Type,
-    /// Indices of type parameters that should be preserved (type holes)
-    type_hole_positions: Vec<usize>,
-}
+use proc_macro::TokenStream;
 
-#[derive(Debug, Clone, PartialEq)]
-enum CoercionMode {
-    Borrowed,
-    Owned,
-    Cloned,
+/// Run a core entry point, reporting its error as a compile error
+fn expand(
+    input: TokenStream,
+    codegen: fn(proc_macro2::TokenStream) -> syn::Result<proc_macro2::TokenStream>,
+) -> TokenStream {
+    codegen(input.into())
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
 }
 
 /// Derive macro for safe, zero-cost coercion between types differing only in PhantomData parameters.
@@ -69,10 +51,68 @@ enum CoercionMode {
 /// Type holes prevent unintended cross-parameter coercions by ensuring only specified
 /// parameters change while others remain identical.
 ///
+/// # `AnyOf` Targets
+///
+/// A target parameter of `AnyOf<(A, B)>` accepts only the listed markers as sources. The
+/// `_from` side may be omitted, in which case a coercion is generated from each member:
+/// - `#[coerce(borrowed_to = "Message<AnyOf<(Json, Xml)>>")]`
+///
 /// # Optional Markers
 ///
 /// - `asref`: For borrowed coercions, also generate `AsRef<Target>` implementation
 ///   - Example: `#[coerce(borrowed = "Type<T>", asref)]`
+///   - Also covers `Box<Source>`, `Rc<Source>`, and `Arc<Source>`; restrict with `asref(Box, Arc)`
+/// - `guard = "path::to::fn"`: For owned and cloned coercions, call the function with
+///   `&Source` before coercing (for logging, metrics, or debug-time invariant checks)
+///   - Example: `#[coerce(owned_from = "Type<A>", owned_to = "Type<B>", guard = "audit::erase")]`
+/// - `extern_c = "symbol"`: For a single concrete borrowed or owned coercion on a
+///   `#[repr(C)]` struct, also export an unmangled `extern "C" fn symbol` performing it
+///   - Example: `#[coerce(owned_from = "Handle<A>", owned_to = "Handle<B>", extern_c = "handle_erase")]`
+/// - `deprecated = "note"`: Mark the coercion as being phased out. Its `extern_c` shim gets
+///   `#[deprecated]`, and with the `tracing` feature each use is logged at `warn`
+///   - Example: `#[coerce(owned_from = "Type<Legacy>", owned_to = "Type<B>", deprecated = "use A")]`
+/// - `allow_noop`: Skip pairs where expanding `|` alternatives yields the same source and
+///   target type, instead of rejecting the attribute
+///   - Example: `#[coerce(owned_from = "Type<A | B>", owned_to = "Type<A | B>", allow_noop)]`
+///
+/// # Field Options
+///
+/// - `#[coerce(deep)]`: The field's type derives `Coerce` and may change along with the
+///   struct; each generated impl requires the field's own coercion in the same mode.
+///   Non-`PhantomData` fields without it must keep their type.
+///
+/// # Struct Options
+///
+/// - `project(field = "name", to = "Target")`: Generate a `{field}_coerced(&self) -> &Target`
+///   accessor using the field type's borrowed coercion (rename with `name = "..."`)
+///   - Example: `#[coerce(project(field = "path", to = "TypedPath<UnknownBase, File>"))]`
+/// - `safe`: Implement owned and cloned coercions by rebuilding the target field-by-field
+///   instead of transmuting, so no `unsafe` code is generated. Borrowed coercions are rejected.
+///   - Example: `#[coerce(safe)]`
+///   - Enabling the `forbid_unsafe` cargo feature applies this to every derive
+/// - `layout_checks`: Assert in debug builds that source and target have the same size and
+///   alignment (and, for borrowed coercions, that the reference is aligned) before casting.
+///   - Example: `#[coerce(layout_checks)]`
+/// - `debug`: Implement `Debug` showing the marker names, such as
+///   `TypedPath<Absolute, File> { path: "/x" }`. Parameters used in `PhantomData` fields must
+///   implement `Marker` (see `#[derive(PhantomMarker)]`), and `PhantomData` fields are omitted.
+///   - Example: `#[coerce(debug)]`
+/// - `erased = "Type<Any, ..>"`: Name the struct's canonical fully-generic form and implement
+///   `HasAnyMarker` for it and for every source with both a borrowed and an owned coercion to it
+///   - Example: `#[coerce(erased = "TypedPath<UnknownBase, UnknownType>")]`
+/// - `trait_vis = "pub"`: Give the generated `CoerceRef{Name}`/`CoerceOwned{Name}`/
+///   `CoerceCloned{Name}` traits this visibility so they can be re-exported and used in bounds.
+///   The traits are sealed: only the derive implements them.
+///   - Example: `#[coerce(trait_vis = "pub")]`
+/// - `trait_name = "Name"`: Use `Name` instead of the struct name in the generated trait names.
+///   Deep coercions of this struct as a field then can't find its traits.
+///   - Example: `#[coerce(trait_name = "Path")]` generates `CoerceRefPath`
+/// - `tag = "Param"`: Generate `tagged`/`into_tagged`/`to_tagged` methods that record the
+///   `MarkerTag` of the named parameter alongside the value (see `phantom_coerce::tag`)
+///   - Example: `#[coerce(tag = "Format")]`
+/// - `include = "path"`: Load coercions from a file relative to the crate root, one
+///   `<mode> <from> => <to>` per line (`#` starts a comment); edits trigger a rebuild
+///   - Example: `#[coerce(include = "coercions/typed_path.coerce")]`
 ///
 /// # Turbofish Support
 ///
@@ -135,948 +175,265 @@ enum CoercionMode {
 /// ```
 #[proc_macro_derive(Coerce, attributes(coerce))]
 pub fn derive_coerce(input: TokenStream) -> TokenStream {
-    let input = parse_macro_input!(input as DeriveInput);
-
-    match impl_coerce(&input) {
-        Ok(tokens) => tokens.into(),
-        Err(err) => err.to_compile_error().into(),
-    }
-}
-
-fn impl_coerce(input: &DeriveInput) -> syn::Result<proc_macro2::TokenStream> {
-    let struct_name = &input.ident;
-    let generics = &input.generics;
-
-    let Data::Struct(data_struct) = &input.data else {
-        return Err(syn::Error::new_spanned(
-            input,
-            "#[derive(Coerce)] can only be applied to structs",
-        ));
-    };
-
-    let Fields::Named(fields) = &data_struct.fields else {
-        return Err(syn::Error::new_spanned(
-            &data_struct.fields,
-            "#[derive(Coerce)] requires named fields",
-        ));
-    };
-
-    // Identify PhantomData fields and map them to type parameters
-    let mut phantom_fields = Vec::new();
-    for field in &fields.named {
-        if is_phantom_data(&field.ty) {
-            phantom_fields.push(field.ident.as_ref().unwrap());
-        }
-    }
-
-    // Parse coerce attributes and expand into concrete coercion instances
-    let mut coercion_specs = Vec::new();
-    for attr in &input.attrs {
-        if attr.path().is_ident("coerce")
-            && let Some(spec) = parse_coerce_attr(attr)?
-        {
-            coercion_specs.push(spec);
-        }
-    }
-
-    if coercion_specs.is_empty() {
-        return Err(syn::Error::new_spanned(
-            input,
-            "#[derive(Coerce)] requires at least one #[coerce(...)] attribute",
-        ));
-    }
-
-    // Expand all specs into concrete coercions
-    let mut borrowed_coercions = Vec::new();
-    let mut owned_coercions = Vec::new();
-    let mut cloned_coercions = Vec::new();
-    let mut generate_asref_for = Vec::new();
-
-    for spec in &coercion_specs {
-        let expanded = expand_coercion_spec(spec, generics)?;
-        match spec.kind {
-            CoercionMode::Borrowed => {
-                borrowed_coercions.extend(expanded);
-                if spec.generate_asref {
-                    // Mark which coercions should also generate AsRef
-                    generate_asref_for.extend((0..borrowed_coercions.len()).collect::<Vec<_>>());
-                }
-            }
-            CoercionMode::Owned => owned_coercions.extend(expanded),
-            CoercionMode::Cloned => cloned_coercions.extend(expanded),
-        }
-    }
-
-    let mut output = proc_macro2::TokenStream::new();
-
-    // Generate borrowed coercions
-    if !borrowed_coercions.is_empty() {
-        let trait_name = Ident::new(&format!("CoerceRef{}", struct_name), struct_name.span());
-
-        let trait_def = quote! {
-            trait #trait_name<Output: ?Sized> {
-                fn coerce(&self) -> &Output;
-            }
-        };
-
-        let mut impls = Vec::new();
-        let mut asref_impls = Vec::new();
-
-        for (idx, coercion) in borrowed_coercions.iter().enumerate() {
-            let impl_block = generate_borrowed_impl(
-                struct_name,
-                generics,
-                &trait_name,
-                coercion,
-                fields,
-                &phantom_fields,
-            )?;
-            impls.push(impl_block);
-
-            // Generate AsRef impl if this coercion was marked for it
-            if generate_asref_for.contains(&idx) {
-                let asref_impl = generate_asref_impl(struct_name, generics, &trait_name, coercion)?;
-                asref_impls.push(asref_impl);
-            }
-        }
-
-        // Generate inherent method with turbofish support
-        let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
-        let inherent_method = quote! {
-            impl #impl_generics #struct_name #ty_generics #where_clause {
-                fn coerce<__CoerceTarget>(&self) -> &__CoerceTarget
-                where
-                    Self: #trait_name<__CoerceTarget>,
-                    __CoerceTarget: ?Sized,
-                {
-                    #trait_name::coerce(self)
-                }
-            }
-        };
-
-        output.extend(quote! {
-            #trait_def
-            #(#impls)*
-            #inherent_method
-            #(#asref_impls)*
-        });
-    }
-
-    // Generate owned coercions
-    if !owned_coercions.is_empty() {
-        let trait_name = Ident::new(&format!("CoerceOwned{}", struct_name), struct_name.span());
-
-        let trait_def = quote! {
-            trait #trait_name<Output> {
-                fn into_coerced(self) -> Output;
-            }
-        };
-
-        let mut impls = Vec::new();
-
-        for coercion in &owned_coercions {
-            let impl_block = generate_owned_impl(
-                struct_name,
-                generics,
-                &trait_name,
-                coercion,
-                fields,
-                &phantom_fields,
-            )?;
-            impls.push(impl_block);
-        }
-
-        // Generate inherent method with turbofish support
-        let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
-        let inherent_method = quote! {
-            impl #impl_generics #struct_name #ty_generics #where_clause {
-                fn into_coerced<__CoerceTarget>(self) -> __CoerceTarget
-                where
-                    Self: #trait_name<__CoerceTarget>,
-                    __CoerceTarget: Sized,
-                {
-                    #trait_name::into_coerced(self)
-                }
-            }
-        };
-
-        output.extend(quote! {
-            #trait_def
-            #(#impls)*
-            #inherent_method
-        });
-    }
-
-    // Generate cloned coercions
-    if !cloned_coercions.is_empty() {
-        let trait_name = Ident::new(&format!("CoerceCloned{}", struct_name), struct_name.span());
-
-        let trait_def = quote! {
-            trait #trait_name<Output> {
-                fn to_coerced(&self) -> Output;
-            }
-        };
-
-        let mut impls = Vec::new();
-
-        for coercion in &cloned_coercions {
-            let impl_block = generate_cloned_impl(
-                struct_name,
-                generics,
-                &trait_name,
-                coercion,
-                fields,
-                &phantom_fields,
-            )?;
-            impls.push(impl_block);
-        }
-
-        // Generate inherent method with turbofish support
-        let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
-        let inherent_method = quote! {
-            impl #impl_generics #struct_name #ty_generics #where_clause {
-                fn to_coerced<__CoerceTarget>(&self) -> __CoerceTarget
-                where
-                    Self: #trait_name<__CoerceTarget>,
-                    __CoerceTarget: Sized,
-                {
-                    #trait_name::to_coerced(self)
-                }
-            }
-        };
-
-        output.extend(quote! {
-            #trait_def
-            #(#impls)*
-            #inherent_method
-        });
-    }
-
-    Ok(output)
-}
-
-fn is_phantom_data(ty: &Type) -> bool {
-    if let Type::Path(TypePath { path, .. }) = ty
-        && let Some(segment) = path.segments.last()
-    {
-        return segment.ident == "PhantomData";
-    }
-    false
-}
-
-#[derive(Debug, Clone)]
-struct ParsedPattern {
-    /// The type with type holes resolved to generic parameters
-    target_type: Type,
-    /// Indices of type parameters that should be preserved (type holes)
-    type_hole_positions: Vec<usize>,
-}
-
-/// Parse target type string, extracting type hole positions and resolving them
-fn parse_target_with_type_holes(
-    target_str: &str,
-    generics: &syn::Generics,
-) -> syn::Result<ParsedPattern> {
-    // Check if contains type holes by looking for standalone _ in type arguments
-    let has_type_hole =
-        target_str.contains("<_") || target_str.contains(", _") || target_str.contains("_>");
-
-    if !has_type_hole {
-        // No type holes, parse normally
-        let target_type: Type = syn::parse_str(target_str)?;
-        return Ok(ParsedPattern {
-            target_type,
-            type_hole_positions: Vec::new(),
-        });
-    }
-
-    // Get the generic parameter names
-    let params: Vec<&Ident> = generics
-        .params
-        .iter()
-        .filter_map(|p| {
-            if let syn::GenericParam::Type(tp) = p {
-                Some(&tp.ident)
-            } else {
-                None
-            }
-        })
-        .collect();
-
-    // Parse by splitting on angle brackets and commas
-    let mut type_hole_positions = Vec::new();
-    let mut resolved_target = String::new();
-    let mut param_index = 0;
-    let mut in_angle_brackets = false;
-    let mut current_token = String::new();
-
-    for ch in target_str.chars() {
-        match ch {
-            '<' => {
-                // Push accumulated struct name before the angle bracket
-                if !current_token.is_empty() {
-                    resolved_target.push_str(&current_token);
-                    current_token.clear();
-                }
-                resolved_target.push(ch);
-                in_angle_brackets = true;
-                param_index = 0;
-            }
-            '>' => {
-                if !current_token.is_empty() {
-                    if current_token.trim() == "_" {
-                        type_hole_positions.push(param_index);
-                        if param_index < params.len() {
-                            resolved_target.push_str(&params[param_index].to_string());
-                        } else {
-                            return Err(syn::Error::new(
-                                proc_macro2::Span::call_site(),
-                                format!(
-                                    "Type hole at position {} but struct only has {} type parameters",
-                                    param_index,
-                                    params.len()
-                                ),
-                            ));
-                        }
-                    } else {
-                        resolved_target.push_str(&current_token);
-                    }
-                    current_token.clear();
-                }
-                resolved_target.push(ch);
-                in_angle_brackets = false;
-            }
-            ',' if in_angle_brackets => {
-                if !current_token.is_empty() {
-                    if current_token.trim() == "_" {
-                        type_hole_positions.push(param_index);
-                        if param_index < params.len() {
-                            resolved_target.push_str(&params[param_index].to_string());
-                        } else {
-                            return Err(syn::Error::new(
-                                proc_macro2::Span::call_site(),
-                                format!(
-                                    "Type hole at position {} but struct only has {} type parameters",
-                                    param_index,
-                                    params.len()
-                                ),
-                            ));
-                        }
-                    } else {
-                        resolved_target.push_str(&current_token);
-                    }
-                    current_token.clear();
-                }
-                resolved_target.push(ch);
-                resolved_target.push(' ');
-                param_index += 1;
-            }
-            _ => {
-                current_token.push(ch);
-            }
-        }
-    }
-
-    // Handle any remaining token (for non-generic types at the end)
-    if !current_token.is_empty() {
-        resolved_target.push_str(&current_token);
-    }
-
-    let target_type: Type = syn::parse_str(&resolved_target).map_err(|e| {
-        syn::Error::new(
-            proc_macro2::Span::call_site(),
-            format!(
-                "Failed to parse resolved target '{}': {}",
-                resolved_target, e
-            ),
-        )
-    })?;
-
-    Ok(ParsedPattern {
-        target_type,
-        type_hole_positions,
-    })
+    expand(input, phantom_coerce_core::derive_coerce)
 }
 
-fn parse_coerce_attr(attr: &Attribute) -> syn::Result<Option<CoercionSpec>> {
-    let Meta::List(meta_list) = &attr.meta else {
-        return Ok(None);
-    };
-
-    let nested = meta_list.tokens.clone();
-
-    // Parse as multiple Meta items (NameValue or Path)
-    let parser = syn::punctuated::Punctuated::<syn::Meta, syn::Token![,]>::parse_terminated;
-    let metas = parser.parse2(nested)?;
-
-    let mut mode: Option<CoercionMode> = None;
-    let mut from_patterns: Vec<String> = Vec::new();
-    let mut to_pattern: Option<String> = None;
-    let mut has_asref = false;
-    let mut from_mode_seen: Option<CoercionMode> = None;
-    let mut to_mode_seen: Option<CoercionMode> = None;
-
-    for meta in metas {
-        match meta {
-            syn::Meta::NameValue(nv) => {
-                // Parse borrowed_from/to, owned_from/to, cloned_from/to
-                if nv.path.is_ident("borrowed_from") {
-                    mode = Some(CoercionMode::Borrowed);
-                    from_mode_seen = Some(CoercionMode::Borrowed);
-                    let value = extract_string_value(&nv)?;
-                    if value.trim().is_empty() {
-                        return Err(syn::Error::new_spanned(
-                            &nv,
-                            "borrowed_from cannot be empty",
-                        ));
-                    }
-                    from_patterns.push(value);
-                } else if nv.path.is_ident("borrowed_to") {
-                    if to_pattern.is_some() {
-                        return Err(syn::Error::new_spanned(
-                            &nv,
-                            "Duplicate 'borrowed_to' attribute: only one target type allowed per #[coerce(...)] attribute",
-                        ));
-                    }
-                    mode = Some(CoercionMode::Borrowed);
-                    to_mode_seen = Some(CoercionMode::Borrowed);
-                    let value = extract_string_value(&nv)?;
-                    if value.trim().is_empty() {
-                        return Err(syn::Error::new_spanned(&nv, "borrowed_to cannot be empty"));
-                    }
-                    to_pattern = Some(value);
-                } else if nv.path.is_ident("owned_from") {
-                    mode = Some(CoercionMode::Owned);
-                    from_mode_seen = Some(CoercionMode::Owned);
-                    let value = extract_string_value(&nv)?;
-                    if value.trim().is_empty() {
-                        return Err(syn::Error::new_spanned(&nv, "owned_from cannot be empty"));
-                    }
-                    from_patterns.push(value);
-                } else if nv.path.is_ident("owned_to") {
-                    if to_pattern.is_some() {
-                        return Err(syn::Error::new_spanned(
-                            &nv,
-                            "Duplicate 'owned_to' attribute: only one target type allowed per #[coerce(...)] attribute",
-                        ));
-                    }
-                    mode = Some(CoercionMode::Owned);
-                    to_mode_seen = Some(CoercionMode::Owned);
-                    let value = extract_string_value(&nv)?;
-                    if value.trim().is_empty() {
-                        return Err(syn::Error::new_spanned(&nv, "owned_to cannot be empty"));
-                    }
-                    to_pattern = Some(value);
-                } else if nv.path.is_ident("cloned_from") {
-                    mode = Some(CoercionMode::Cloned);
-                    from_mode_seen = Some(CoercionMode::Cloned);
-                    let value = extract_string_value(&nv)?;
-                    if value.trim().is_empty() {
-                        return Err(syn::Error::new_spanned(&nv, "cloned_from cannot be empty"));
-                    }
-                    from_patterns.push(value);
-                } else if nv.path.is_ident("cloned_to") {
-                    if to_pattern.is_some() {
-                        return Err(syn::Error::new_spanned(
-                            &nv,
-                            "Duplicate 'cloned_to' attribute: only one target type allowed per #[coerce(...)] attribute",
-                        ));
-                    }
-                    mode = Some(CoercionMode::Cloned);
-                    to_mode_seen = Some(CoercionMode::Cloned);
-                    let value = extract_string_value(&nv)?;
-                    if value.trim().is_empty() {
-                        return Err(syn::Error::new_spanned(&nv, "cloned_to cannot be empty"));
-                    }
-                    to_pattern = Some(value);
-                } else {
-                    return Err(syn::Error::new_spanned(
-                        &nv.path,
-                        "Expected 'borrowed_from', 'borrowed_to', 'owned_from', 'owned_to', 'cloned_from', or 'cloned_to'",
-                    ));
-                }
-            }
-            syn::Meta::Path(path) => {
-                if path.is_ident("asref") {
-                    has_asref = true;
-                } else {
-                    return Err(syn::Error::new_spanned(
-                        &path,
-                        "Expected 'asref' marker (only valid for borrowed coercions)",
-                    ));
-                }
-            }
-            _ => {
-                return Err(syn::Error::new_spanned(
-                    &meta,
-                    "Expected name-value pair or path",
-                ));
-            }
-        }
-    }
-
-    let mode = mode.ok_or_else(|| {
-        syn::Error::new(
-            attr.span(),
-            "Missing coercion mode: use borrowed_from/to, owned_from/to, or cloned_from/to",
-        )
-    })?;
-
-    if from_patterns.is_empty() {
-        return Err(syn::Error::new(
-            attr.span(),
-            "Missing source types: at least one 'borrowed_from', 'owned_from', or 'cloned_from' required",
-        ));
-    }
-
-    let to_pattern = to_pattern.ok_or_else(|| {
-        syn::Error::new(
-            attr.span(),
-            "Missing target type: 'borrowed_to', 'owned_to', or 'cloned_to' required",
-        )
-    })?;
-
-    // Validate that from_mode and to_mode match
-    if let (Some(from_mode), Some(to_mode)) = (from_mode_seen, to_mode_seen) {
-        if from_mode != to_mode {
-            return Err(syn::Error::new(
-                attr.span(),
-                format!(
-                    "Mismatched coercion modes: from side uses {:?} but to side uses {:?}. Both sides must use the same mode (e.g., borrowed_from with borrowed_to)",
-                    from_mode, to_mode
-                ),
-            ));
-        }
-    }
-
-    // Validate asref is only used with borrowed
-    if has_asref && mode != CoercionMode::Borrowed {
-        return Err(syn::Error::new(
-            attr.span(),
-            "asref marker is only valid for borrowed coercions",
-        ));
-    }
-
-    // Check for no-op coercions (source == target)
-    // This is a warning-level issue, but we'll make it an error for clarity
-    for from_pattern in &from_patterns {
-        if from_pattern.trim() == to_pattern.trim() {
-            return Err(syn::Error::new(
-                attr.span(),
-                format!(
-                    "No-op coercion detected: coercing from '{}' to '{}' (same type). This coercion has no effect and should be removed.",
-                    from_pattern, to_pattern
-                ),
-            ));
-        }
-    }
-
-    Ok(Some(CoercionSpec {
-        from_patterns,
-        to_pattern,
-        kind: mode,
-        generate_asref: has_asref,
-    }))
+/// Simplified derive for the common case of upcasting a single marker to one "any" marker.
+///
+/// The struct must have exactly one type parameter. `#[upcast(any = "Marker")]` generates
+/// borrowed and owned coercions from every marker to `Marker`, available through the same
+/// `coerce` and `into_coerced` methods as `#[derive(Coerce)]`. Further options:
+///
+/// - `from = "A | B"`: Only accept the listed markers
+/// - `cloned`: Also generate `to_coerced` (requires `Clone`)
+/// - `allow_auto_trait_change`: Accept markers that aren't `Send`, `Sync`, or `Unpin` when
+///   `Marker` is. Without it, such markers can't be upcast
+///
+/// With the `forbid_unsafe` feature the borrowed coercion is omitted. Use
+/// `#[derive(Coerce)]` for anything more involved (several parameters, type holes,
+/// per-mode targets).
+///
+/// # Example
+///
+/// ```rust,ignore
+/// use phantom_coerce::Upcast;
+/// use std::marker::PhantomData;
+///
+/// struct Validated;
+/// struct Unvalidated;
+/// struct AnyStatus;
+///
+/// #[derive(Upcast)]
+/// #[upcast(any = "AnyStatus")]
+/// struct Request<Status> {
+///     marker: PhantomData<Status>,
+///     url: String,
+/// }
+///
+/// fn main() {
+///     let request = Request::<Validated> {
+///         marker: PhantomData,
+///         url: "https://example.com".to_string(),
+///     };
+///     let generic: &Request<AnyStatus> = request.coerce();
+/// }
+/// ```
+#[proc_macro_derive(Upcast, attributes(upcast))]
+pub fn derive_upcast(input: TokenStream) -> TokenStream {
+    expand(input, phantom_coerce_core::derive_upcast)
 }
 
-fn extract_string_value(nv: &syn::MetaNameValue) -> syn::Result<String> {
-    let syn::Expr::Lit(expr_lit) = &nv.value else {
-        return Err(syn::Error::new_spanned(
-            &nv.value,
-            "Expected string literal",
-        ));
-    };
-
-    let syn::Lit::Str(lit_str) = &expr_lit.lit else {
-        return Err(syn::Error::new_spanned(
-            &expr_lit.lit,
-            "Expected string literal",
-        ));
-    };
-
-    Ok(lit_str.value())
+/// Derive `Marker` (and `MarkerTag`) for a unit struct used as a marker.
+///
+/// The name defaults to the struct's identifier. `#[phantom_marker(name = "...")]` overrides it, and
+/// `#[phantom_marker(id = N)]` sets the optional stable `ID`. The same name is used as the
+/// `MarkerTag::TAG` recorded by `Tagged`.
+///
+/// # Example
+///
+/// ```rust,ignore
+/// use phantom_coerce::PhantomMarker;
+///
+/// #[derive(PhantomMarker)]
+/// #[phantom_marker(name = "json", id = 2)]
+/// struct Json;
+/// ```
+#[proc_macro_derive(PhantomMarker, attributes(phantom_marker))]
+pub fn derive_phantom_marker(input: TokenStream) -> TokenStream {
+    expand(input, phantom_coerce_core::derive_phantom_marker)
 }
 
-/// Split a string by top-level `|` only (not inside angle brackets)
-/// Returns vec with single element if no top-level pipes found
-fn split_top_level_pipes(s: &str) -> Vec<String> {
-    let mut result = Vec::new();
-    let mut current = String::new();
-    let mut depth = 0;
-
-    for ch in s.chars() {
-        match ch {
-            '<' => {
-                depth += 1;
-                current.push(ch);
-            }
-            '>' => {
-                depth -= 1;
-                current.push(ch);
-            }
-            '|' if depth == 0 => {
-                // Top-level pipe
-                if !current.trim().is_empty() {
-                    result.push(current.trim().to_string());
-                    current.clear();
-                }
-            }
-            _ => {
-                current.push(ch);
-            }
-        }
-    }
-
-    if !current.trim().is_empty() {
-        result.push(current.trim().to_string());
-    }
-
-    if result.is_empty() {
-        vec![s.to_string()]
-    } else {
-        result
-    }
+/// Declare a struct and its coercions in one block, as an alternative to the derive.
+///
+/// The block holds a struct definition followed by `coercions { ... }`, with one coercion
+/// per line as `<mode> <from> => <to>;`. Patterns are written as plain tokens rather than
+/// strings but otherwise use the same syntax as `#[derive(Coerce)]`: `|` alternatives, `_`
+/// type holes, and `AnyOf<(..)>` targets (whose `<from>` may be omitted). Append `, asref`
+/// to a borrowed coercion to also generate `AsRef`. Struct and field options are still
+/// given as `#[coerce(...)]` attributes on the struct.
+///
+/// # Example
+///
+/// ```rust,ignore
+/// use phantom_coerce::phantom_coerce;
+/// use std::marker::PhantomData;
+///
+/// phantom_coerce! {
+///     #[derive(Clone)]
+///     struct TypedPath<Base, Type> {
+///         base: PhantomData<Base>,
+///         ty: PhantomData<Type>,
+///         path: String,
+///     }
+///
+///     coercions {
+///         borrowed TypedPath<Absolute | Relative, _> => TypedPath<UnknownBase, _>, asref;
+///         owned TypedPath<Absolute, File> => TypedPath<UnknownBase, UnknownType>;
+///         cloned TypedPath<Relative, File> => TypedPath<UnknownBase, File>;
+///     }
+/// }
+/// ```
+#[proc_macro]
+pub fn phantom_coerce(input: TokenStream) -> TokenStream {
+    expand(input, phantom_coerce_core::phantom_coerce)
 }
 
-/// Split a string by `|` at both top-level and parameter-level
-/// Handles nested alternatives like "Type<A | B> | Type<C | D>"
-///
-/// This is a two-stage pipeline:
-/// 1. Split by top-level pipes (outside angle brackets)
-/// 2. For each top-level alternative, expand parameter-level pipes (inside angle brackets)
-/// 3. Flatten the results
-fn split_by_pipe_respecting_brackets(s: &str) -> Vec<String> {
-    // Step 1: Split by top-level pipes (outside angle brackets)
-    let top_level_alternatives = split_top_level_pipes(s);
-
-    // Step 2: For each top-level alternative, expand parameter-level pipes
-    let mut result = Vec::new();
-    for alternative in top_level_alternatives {
-        let expanded = expand_type_parameter_alternatives(&alternative);
-        result.extend(expanded);
-    }
-
-    result
+/// Invoke a macro once for every coercion declared on a derived struct.
+///
+/// `for_each_coercion!(path::to::Type, callback)` expands to one `callback! { <mode> <from> =>
+/// <to> }` per declared coercion, with alternatives already expanded and modes written as
+/// `borrowed`, `owned`, or `cloned`. Type holes appear as the struct's own parameter names.
+/// Types are passed as written in the attributes, so they must resolve where the callback
+/// expands. Only usable within the crate that defines the struct.
+///
+/// # Example
+///
+/// ```rust,ignore
+/// macro_rules! register {
+///     ($mode:ident $source:ty => $target:ty) => {
+///         routes.push((stringify!($mode), type_name::<$source>(), type_name::<$target>()));
+///     };
+/// }
+///
+/// phantom_coerce::for_each_coercion!(TypedPath, register);
+/// ```
+#[proc_macro]
+pub fn for_each_coercion(input: TokenStream) -> TokenStream {
+    expand(input, phantom_coerce_core::for_each_coercion)
 }
 
-/// Expand type parameter alternatives like "TypedPath<Absolute | Relative, _>"
-/// into ["TypedPath<Absolute, _>", "TypedPath<Relative, _>"]
-fn expand_type_parameter_alternatives(s: &str) -> Vec<String> {
-    // Find the angle brackets
-    let start = s.find('<');
-    let end = s.rfind('>');
-
-    if let (Some(start_pos), Some(end_pos)) = (start, end) {
-        let prefix = &s[..start_pos + 1]; // "TypedPath<"
-        let suffix = &s[end_pos..]; // ">"
-        let params = &s[start_pos + 1..end_pos]; // "Absolute | Relative, _"
-
-        // Split parameters by comma
-        let param_parts: Vec<&str> = params.split(',').collect();
-
-        // Find which parameter has | and expand it
-        let mut expanded_params: Vec<Vec<String>> = Vec::new();
-
-        for param in param_parts {
-            if param.contains('|') {
-                // This parameter has alternatives
-                let alternatives: Vec<String> =
-                    param.split('|').map(|s| s.trim().to_string()).collect();
-                expanded_params.push(alternatives);
-            } else {
-                // Single value
-                expanded_params.push(vec![param.trim().to_string()]);
-            }
-        }
-
-        // Generate cartesian product
-        let mut results = vec![String::new()];
-        for alternatives in &expanded_params {
-            let mut new_results = Vec::new();
-            for result in &results {
-                for alt in alternatives {
-                    let mut new_result = result.clone();
-                    if !new_result.is_empty() {
-                        new_result.push_str(", ");
-                    }
-                    new_result.push_str(alt);
-                    new_results.push(new_result);
-                }
-            }
-            results = new_results;
-        }
-
-        // Combine prefix, params, and suffix
-        return results
-            .into_iter()
-            .map(|params| format!("{}{}{}", prefix, params, suffix))
-            .collect();
-    }
-
-    vec![s.to_string()]
+/// Instantiate generic code once for every specific type declared on a derived struct.
+///
+/// The specific types are the concrete sources of the struct's coercions. The second argument is
+/// a generic function, which is called as `function::<Specific>()`; a call, whose arguments are
+/// repeated for each type; or a closure-like `|T| body`, expanded with `T` naming each type.
+/// Only usable within the crate that defines the struct.
+///
+/// # Example
+///
+/// ```rust,ignore
+/// phantom_coerce::for_each_specific!(TypedPath, register_handler(&mut router));
+/// phantom_coerce::for_each_specific!(TypedPath, |P| names.push(type_name::<P>()));
+/// ```
+#[proc_macro]
+pub fn for_each_specific(input: TokenStream) -> TokenStream {
+    expand(input, phantom_coerce_core::for_each_specific)
 }
 
-/// Expand a CoercionSpec into concrete ParsedCoercion instances
-/// Handles `|` syntax in from_patterns and generates cartesian product
-fn expand_coercion_spec(
-    spec: &CoercionSpec,
-    generics: &syn::Generics,
-) -> syn::Result<Vec<ParsedCoercion>> {
-    // Split the to_pattern by | to get all target alternatives
-    let to_alternatives = split_by_pipe_respecting_brackets(&spec.to_pattern);
-
-    let mut result = Vec::new();
-
-    // For each from_pattern, split by | and create separate coercions
-    for from_pattern in &spec.from_patterns {
-        // Split by | but only at the top level (not inside <>)
-        let from_alternatives = split_by_pipe_respecting_brackets(from_pattern);
-
-        for from_alternative in from_alternatives {
-            let from_parsed = parse_target_with_type_holes(&from_alternative, generics)?;
-
-            // For each to alternative, create a coercion (Cartesian product)
-            for to_alternative in &to_alternatives {
-                let to_parsed = parse_target_with_type_holes(to_alternative, generics)?;
-
-                // Validate that type hole positions match between from and to
-                if from_parsed.type_hole_positions != to_parsed.type_hole_positions {
-                    return Err(syn::Error::new(
-                        proc_macro2::Span::call_site(),
-                        format!(
-                            "Type hole positions mismatch: from pattern '{}' has type holes at {:?}, but to pattern '{}' has type holes at {:?}",
-                            from_alternative,
-                            from_parsed.type_hole_positions,
-                            to_alternative,
-                            to_parsed.type_hole_positions
-                        ),
-                    ));
-                }
-
-                result.push(ParsedCoercion {
-                    source_type: from_parsed.target_type.clone(),
-                    target_type: to_parsed.target_type.clone(),
-                    type_hole_positions: from_parsed.type_hole_positions.clone(),
-                });
-            }
-        }
-    }
-
-    Ok(result)
+/// Coerce an expression to a target whose `_` parameters are taken from the source.
+///
+/// `coerce!(value as TypedPath<UnknownBase, _>)` performs the owned coercion (through
+/// `into_coerced`) to `TypedPath<UnknownBase, Type>`, where `Type` is the source's own second
+/// parameter. Prefix the value with `ref` for the borrowed coercion (through `coerce`), which
+/// evaluates to a reference. Only the struct's own top-level parameters can be `_`.
+///
+/// # Example
+///
+/// ```rust,ignore
+/// let generic = coerce!(ref path as TypedPath<UnknownBase, _>);
+/// let owned = coerce!(path as TypedPath<UnknownBase, _>);
+/// ```
+#[proc_macro]
+pub fn coerce(input: TokenStream) -> TokenStream {
+    expand(input, phantom_coerce_core::coerce)
 }
 
-/// Extract only the generic parameters at type hole positions
-/// Returns a TokenStream like `<Type>` or `<Base, Type>` or ``
-fn extract_type_hole_generics(
-    generics: &syn::Generics,
-    type_hole_positions: &[usize],
-) -> proc_macro2::TokenStream {
-    if type_hole_positions.is_empty() {
-        // No type holes means fully concrete types, no generics needed
-        return quote! {};
-    }
-
-    let type_params: Vec<&Ident> = generics
-        .params
-        .iter()
-        .filter_map(|p| {
-            if let syn::GenericParam::Type(tp) = p {
-                Some(&tp.ident)
-            } else {
-                None
-            }
-        })
-        .collect();
-
-    let type_hole_params: Vec<_> = type_hole_positions
-        .iter()
-        .filter_map(|&pos| type_params.get(pos).copied())
-        .collect();
-
-    if type_hole_params.is_empty() {
-        quote! {}
-    } else {
-        quote! { <#(#type_hole_params),*> }
-    }
+/// Match a tagged erased value against each marker its struct erases, as a typed reference.
+///
+/// `match_specific!(tagged, Type { Marker(binding) => arm, .., else => fallback })` reads the
+/// tag of a `Tagged<Type<..>>` (or `Tagged<&Type<..>>`) and binds `&Type<Marker, ..>` in the
+/// arm for that marker. Arms are checked for exhaustiveness against the markers declared as
+/// coercion sources of the `tag` parameter, so declaring a new one breaks every match that
+/// misses it. `else` handles tags that match no marker, such as ones decoded from elsewhere.
+///
+/// # Example
+///
+/// ```rust,ignore
+/// let size = match_specific!(erased, Message {
+///     Json(msg) => parse_json(msg),
+///     Xml(msg) => parse_xml(msg),
+///     else => 0,
+/// });
+/// ```
+#[proc_macro]
+pub fn match_specific(input: TokenStream) -> TokenStream {
+    expand(input, phantom_coerce_core::match_specific)
 }
 
-fn generate_borrowed_impl(
-    struct_name: &Ident,
-    generics: &syn::Generics,
-    trait_name: &Ident,
-    coercion: &ParsedCoercion,
-    fields: &syn::FieldsNamed,
-    _phantom_fields: &[&Ident],
-) -> syn::Result<proc_macro2::TokenStream> {
-    let source_type = &coercion.source_type;
-    let target_type = &coercion.target_type;
-
-    let Type::Path(target_path) = target_type else {
-        return Err(syn::Error::new_spanned(
-            target_type,
-            "Coerce target must be a type path",
-        ));
-    };
-
-    let target_segment = target_path.path.segments.last().unwrap();
-    let PathArguments::AngleBracketed(_target_args) = &target_segment.arguments else {
-        return Err(syn::Error::new_spanned(
-            target_type,
-            "Coerce target must have type parameters",
-        ));
-    };
-
-    // Generate destructuring pattern with type annotations for all fields
-    let field_destructure: Vec<_> = fields
-        .named
-        .iter()
-        .map(|f| {
-            let field_name = f.ident.as_ref().unwrap();
-            quote! { #field_name: _ }
-        })
-        .collect();
-
-    // Extract only the generic parameters that appear in type holes
-    // For the impl, we need generics only for the type hole positions
-    let generics_for_impl = extract_type_hole_generics(generics, &coercion.type_hole_positions);
-
-    Ok(quote! {
-        impl #generics_for_impl #trait_name<#target_type> for #source_type {
-            fn coerce(&self) -> &#target_type {
-                // Compile-time safety guards: ensure all fields are accounted for
-                let #struct_name { #(#field_destructure),* } = self;
-
-                // SAFETY: Types differ only in PhantomData type parameters.
-                // The destructuring pattern above ensures this at compile time.
-                unsafe { std::mem::transmute(self) }
-            }
-        }
-    })
+/// Build a `tag::DispatchTable` routing each marker of a tagged struct to a handler of its erased
+/// type.
+///
+/// `dispatch_table!(Type<Erased> { Marker => handler, .. })` pairs each marker's
+/// `MarkerTag::TAG` with a `fn(&Type<Erased>) -> R`. The markers are checked against those
+/// declared as coercion sources of the `tag` parameter, as for [`match_specific!`](match_specific), so a table
+/// that misses a new marker (or names a removed one) no longer compiles. Each marker must be in
+/// scope by its name.
+///
+/// # Example
+///
+/// ```rust,ignore
+/// static ROUTES: DispatchTable<Message<AnyFormat>, usize> = dispatch_table!(Message<AnyFormat> {
+///     Json => parse_json,
+///     Xml => parse_xml,
+/// });
+///
+/// let size = ROUTES.dispatch_tagged(&erased);
+/// ```
+#[proc_macro]
+pub fn dispatch_table(input: TokenStream) -> TokenStream {
+    expand(input, phantom_coerce_core::dispatch_table)
 }
 
-fn generate_owned_impl(
-    struct_name: &Ident,
-    generics: &syn::Generics,
-    trait_name: &Ident,
-    coercion: &ParsedCoercion,
-    fields: &syn::FieldsNamed,
-    _phantom_fields: &[&Ident],
-) -> syn::Result<proc_macro2::TokenStream> {
-    let source_type = &coercion.source_type;
-    let target_type = &coercion.target_type;
-
-    let Type::Path(target_path) = target_type else {
-        return Err(syn::Error::new_spanned(
-            target_type,
-            "Coerce target must be a type path",
-        ));
-    };
-
-    let target_segment = target_path.path.segments.last().unwrap();
-    let PathArguments::AngleBracketed(_target_args) = &target_segment.arguments else {
-        return Err(syn::Error::new_spanned(
-            target_type,
-            "Coerce target must have type parameters",
-        ));
-    };
-
-    // Generate destructuring pattern for all fields
-    let field_destructure: Vec<_> = fields
-        .named
-        .iter()
-        .map(|f| {
-            let field_name = f.ident.as_ref().unwrap();
-            quote! { #field_name: _ }
-        })
-        .collect();
-
-    let generics_for_impl = extract_type_hole_generics(generics, &coercion.type_hole_positions);
-
-    Ok(quote! {
-        impl #generics_for_impl #trait_name<#target_type> for #source_type {
-            fn into_coerced(self) -> #target_type {
-                // Compile-time safety guard: ensure all fields are accounted for
-                let #struct_name { #(#field_destructure),* } = &self;
-
-                // SAFETY: Types differ only in PhantomData type parameters.
-                // The destructuring pattern above ensures this at compile time.
-                unsafe { std::mem::transmute(self) }
-            }
-        }
-    })
+/// Add coercions to a struct from an impl block elsewhere in the same crate.
+///
+/// The struct must be marked `#[coerce(extensible)]`, and the impl block's type must name it by
+/// a path that reaches its module (or by its bare name in that module). Coercions are written
+/// as in a `phantom_coerce!` block, one `<mode> <from> => <to>;` each, and each mode must already
+/// have a coercion on the struct itself. The impl block is kept as written, so it may be empty.
+///
+/// # Example
+///
+/// ```rust,ignore
+/// #[phantom_coerce::coercions(
+///     borrowed TypedPath<Relative, _> => TypedPath<UnknownBase, _>;
+/// )]
+/// impl<Base, Type> crate::paths::TypedPath<Base, Type> {}
+/// ```
+#[proc_macro_attribute]
+pub fn coercions(attr: TokenStream, item: TokenStream) -> TokenStream {
+    phantom_coerce_core::coercions(attr.into(), item.into())
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
 }
 
-fn generate_cloned_impl(
-    struct_name: &Ident,
-    generics: &syn::Generics,
-    trait_name: &Ident,
-    coercion: &ParsedCoercion,
-    fields: &syn::FieldsNamed,
-    _phantom_fields: &[&Ident],
-) -> syn::Result<proc_macro2::TokenStream> {
-    let source_type = &coercion.source_type;
-    let target_type = &coercion.target_type;
-
-    let Type::Path(target_path) = target_type else {
-        return Err(syn::Error::new_spanned(
-            target_type,
-            "Coerce target must be a type path",
-        ));
-    };
-
-    let target_segment = target_path.path.segments.last().unwrap();
-    let PathArguments::AngleBracketed(_target_args) = &target_segment.arguments else {
-        return Err(syn::Error::new_spanned(
-            target_type,
-            "Coerce target must have type parameters",
-        ));
-    };
-
-    // Generate destructuring pattern for all fields
-    let field_destructure: Vec<_> = fields
-        .named
-        .iter()
-        .map(|f| {
-            let field_name = f.ident.as_ref().unwrap();
-            quote! { #field_name: _ }
-        })
-        .collect();
-
-    let generics_for_impl = extract_type_hole_generics(generics, &coercion.type_hole_positions);
-
-    // Build where clause with Clone bound on the source type
-    let where_clause = quote! { where #source_type: Clone };
-
-    Ok(quote! {
-        impl #generics_for_impl #trait_name<#target_type> for #source_type #where_clause {
-            fn to_coerced(&self) -> #target_type {
-                // Compile-time safety guard: ensure all fields are accounted for
-                let #struct_name { #(#field_destructure),* } = self;
-
-                // SAFETY: Types differ only in PhantomData type parameters.
-                // The destructuring pattern above ensures this at compile time.
-                // The source type is cloned and then transmuted.
-                unsafe { std::mem::transmute(self.clone()) }
-            }
-        }
-    })
+/// Share struct options across every `#[derive(Coerce)]` in an inline module.
+///
+/// Each struct in the module, or in modules nested inside it, gets the listed options unless it
+/// sets them itself. Lint levels are layered instead, with the struct's own `allow(...)` and
+/// `deny(...)` applied last, and `asref` is added to every borrowed coercion. Options naming
+/// one struct, such as `trait_name` or `tag`, can't be defaults. A nested module's own
+/// `defaults` take precedence over the outer ones.
+///
+/// # Example
+///
+/// ```rust,ignore
+/// #[phantom_coerce::defaults(trait_vis = "pub", layout_checks, asref)]
+/// mod paths {
+///     #[derive(Coerce)]
+///     #[coerce(borrowed_from = "TypedPath<Absolute>", borrowed_to = "TypedPath<UnknownBase>")]
+///     pub struct TypedPath<Base> { /* ... */ }
+/// }
+/// ```
+#[proc_macro_attribute]
+pub fn defaults(attr: TokenStream, item: TokenStream) -> TokenStream {
+    phantom_coerce_core::defaults(attr.into(), item.into())
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
 }
 
-fn generate_asref_impl(
-    _struct_name: &Ident,
-    generics: &syn::Generics,
-    _trait_name: &Ident,
-    coercion: &ParsedCoercion,
-) -> syn::Result<proc_macro2::TokenStream> {
-    let source_type = &coercion.source_type;
-    let target_type = &coercion.target_type;
-    let generics_for_impl = extract_type_hole_generics(generics, &coercion.type_hole_positions);
-
-    Ok(quote! {
-        impl #generics_for_impl AsRef<#target_type> for #source_type {
-            fn as_ref(&self) -> &#target_type {
-                self.coerce()
-            }
-        }
-    })
+/// Expand coercions handed back by a struct's extension macro (see [`coercions`]).
+#[doc(hidden)]
+#[proc_macro]
+pub fn __extend_coercions(input: TokenStream) -> TokenStream {
+    expand(input, phantom_coerce_core::extend_coercions)
 }
@@ -0,0 +1,46 @@
+//! Indices for the upcasts generated by `#[coerce(any(...))]`.
+//!
+//! `any(Base = "UnknownBase", Type = "UnknownType")` lets each listed parameter either keep
+//! its marker or erase it into its `Any` marker. The generated `coerce` and `into_coerced`
+//! take the target and a tuple of [`Keep`]/[`Erase`] choices, one per listed parameter. The
+//! choices are inferred from the target, so they only need writing down in two cases:
+//!
+//! - With a turbofish, as `_`: `path.coerce::<TypedPath<UnknownBase, File>, _>()`
+//! - When a parameter is already `Any`, keeping and erasing it give the same type, so name
+//!   the choice: `path.into_coerced::<TypedPath<UnknownBase, UnknownType>, (Keep, Erase)>()`
+//!
+//! ```rust
+//! use std::marker::PhantomData;
+//! use phantom_coerce::Coerce;
+//! use phantom_coerce::any::{Erase, Keep};
+//!
+//! struct Absolute;
+//! struct UnknownBase;
+//! struct File;
+//! struct UnknownType;
+//!
+//! #[derive(Coerce)]
+//! #[coerce(any(Base = "UnknownBase", Type = "UnknownType"))]
+//! struct TypedPath<Base, Type> {
+//!     base: PhantomData<Base>,
+//!     ty: PhantomData<Type>,
+//!     path: String,
+//! }
+//!
+//! # fn main() {
+//! let path = TypedPath::<Absolute, File> {
+//!     base: PhantomData,
+//!     ty: PhantomData,
+//!     path: "/etc/hosts".to_string(),
+//! };
+//! let any_base: TypedPath<UnknownBase, File> = path.into_coerced();
+//! let any = any_base.into_coerced::<TypedPath<UnknownBase, UnknownType>, (Keep, Erase)>();
+//! assert_eq!(any.path, "/etc/hosts");
+//! # }
+//! ```
+
+/// The parameter keeps its marker.
+pub enum Keep {}
+
+/// The parameter's marker is erased into its `Any` marker.
+pub enum Erase {}
@@ -0,0 +1,57 @@
+//! The [`AnyOf`] marker combinator.
+
+use std::fmt;
+use std::marker::PhantomData;
+
+/// A marker meaning "one of the markers in `Markers`", for bounded erasure.
+///
+/// `Markers` is a tuple of marker types, such as `AnyOf<(Json, Xml)>`. Used as a coercion
+/// target, it erases which member a value carried while still ruling out every marker that
+/// isn't listed, without defining a new marker struct for each subset. A target containing
+/// `AnyOf` may omit the `_from` side: the derive generates a coercion from each member.
+///
+/// ```rust
+/// use std::marker::PhantomData;
+/// use phantom_coerce::{AnyOf, Coerce};
+///
+/// struct Json;
+/// struct Xml;
+///
+/// #[derive(Coerce)]
+/// #[coerce(borrowed_to = "Message<AnyOf<(Json, Xml)>>")]
+/// struct Message<Format> {
+///     marker: PhantomData<Format>,
+///     content: String,
+/// }
+///
+/// # fn main() {
+/// let json = Message::<Json> {
+///     marker: PhantomData,
+///     content: "{}".to_string(),
+/// };
+/// let text: &Message<AnyOf<(Json, Xml)>> = json.coerce();
+/// assert_eq!(text.content, "{}");
+/// # }
+/// ```
+pub struct AnyOf<Markers>(PhantomData<Markers>);
+
+// Manual impls so `AnyOf` doesn't require anything of its members
+impl<Markers> Clone for AnyOf<Markers> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<Markers> Copy for AnyOf<Markers> {}
+
+impl<Markers> Default for AnyOf<Markers> {
+    fn default() -> Self {
+        AnyOf(PhantomData)
+    }
+}
+
+impl<Markers> fmt::Debug for AnyOf<Markers> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "AnyOf<{}>", std::any::type_name::<Markers>())
+    }
+}
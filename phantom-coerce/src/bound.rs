@@ -0,0 +1,68 @@
+/// Name a [`Coercible`](crate::Coercible) bound once, for use in function signatures.
+///
+/// `coercible_bound!(Name => Target)` defines a trait `Name` implemented by everything
+/// coercible into `Target`, standing in for a trait alias until those are stable. A reference
+/// target adds a lifetime parameter to the trait, `'a` unless the reference names its own, so
+/// borrowed arguments tie the result to the borrow without spelling out
+/// `Coercible<&'a Target>` by hand:
+///
+/// ```rust
+/// use std::marker::PhantomData;
+/// use phantom_coerce::{Coerce, coercible_bound};
+///
+/// struct Absolute;
+/// struct UnknownBase;
+/// struct File;
+///
+/// #[derive(Coerce)]
+/// #[coerce(borrowed_from = "TypedPath<Absolute, File>", borrowed_to = "TypedPath<UnknownBase, File>")]
+/// #[coerce(owned_from = "TypedPath<Absolute, File>", owned_to = "TypedPath<UnknownBase, File>")]
+/// struct TypedPath<Base, Type> {
+///     base: PhantomData<Base>,
+///     ty: PhantomData<Type>,
+///     path: String,
+/// }
+///
+/// coercible_bound!(AnyFile => TypedPath<UnknownBase, File>);
+/// coercible_bound!(AnyFileRef => &TypedPath<UnknownBase, File>);
+///
+/// fn into_path(file: impl AnyFile) -> String {
+///     file.into_coerced().path
+/// }
+///
+/// fn path_str<'a, P: AnyFileRef<'a>>(file: P) -> &'a str {
+///     &file.into_coerced().path
+/// }
+///
+/// # fn main() {
+/// let file = TypedPath::<Absolute, File> { base: PhantomData, ty: PhantomData, path: "/a".into() };
+/// assert_eq!(path_str(&file), "/a");
+/// assert_eq!(into_path(file), "/a");
+/// # }
+/// ```
+///
+/// A visibility and attributes before the name apply to the trait.
+#[macro_export]
+macro_rules! coercible_bound {
+    ($(#[$attr:meta])* $vis:vis $name:ident => & $lifetime:lifetime $target:ty) => {
+        $(#[$attr])*
+        $vis trait $name<$lifetime>: $crate::Coercible<&$lifetime $target> {}
+
+        impl<$lifetime, __CoerceSource> $name<$lifetime> for __CoerceSource where
+            __CoerceSource: $crate::Coercible<&$lifetime $target>
+        {
+        }
+    };
+    ($(#[$attr:meta])* $vis:vis $name:ident => & $target:ty) => {
+        $crate::coercible_bound!($(#[$attr])* $vis $name => &'a $target);
+    };
+    ($(#[$attr:meta])* $vis:vis $name:ident => $target:ty) => {
+        $(#[$attr])*
+        $vis trait $name: $crate::Coercible<$target> {}
+
+        impl<__CoerceSource> $name for __CoerceSource where
+            __CoerceSource: $crate::Coercible<$target>
+        {
+        }
+    };
+}
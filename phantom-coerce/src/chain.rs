@@ -0,0 +1,58 @@
+/// Coerce a value through several declared coercions in turn.
+///
+/// Each `=> Type` is one hop, checked on its own through [`Coercible`](crate::Coercible) (or
+/// [`CoercibleRef`](crate::CoercibleRef) with the `ref` form). An undeclared hop is reported
+/// as a missing coercion between that hop's source and target, rather than as an inference
+/// failure somewhere in a chain of `into_coerced()` calls:
+///
+/// ```rust
+/// use std::marker::PhantomData;
+/// use phantom_coerce::{Coerce, coerce_chain};
+///
+/// struct Validated;
+/// struct AnyStatus;
+/// struct Get;
+/// struct AnyMethod;
+///
+/// #[derive(Coerce)]
+/// #[coerce(borrowed_from = "Request<Validated, Get>", borrowed_to = "Request<AnyStatus, Get>")]
+/// #[coerce(borrowed_from = "Request<AnyStatus, Get>", borrowed_to = "Request<AnyStatus, AnyMethod>")]
+/// #[coerce(owned_from = "Request<Validated, Get>", owned_to = "Request<AnyStatus, Get>")]
+/// #[coerce(owned_from = "Request<AnyStatus, Get>", owned_to = "Request<AnyStatus, AnyMethod>")]
+/// struct Request<Status, Method> {
+///     status: PhantomData<Status>,
+///     method: PhantomData<Method>,
+///     url: String,
+/// }
+///
+/// # fn main() {
+/// let request = Request::<Validated, Get> {
+///     status: PhantomData,
+///     method: PhantomData,
+///     url: "/a".to_string(),
+/// };
+///
+/// let view = coerce_chain!(ref request => Request<AnyStatus, Get> => Request<AnyStatus, AnyMethod>);
+/// assert_eq!(view.url, "/a");
+///
+/// let erased = coerce_chain!(request => Request<AnyStatus, Get> => Request<AnyStatus, AnyMethod>);
+/// assert_eq!(erased.url, "/a");
+/// # }
+/// ```
+#[macro_export]
+macro_rules! coerce_chain {
+    (ref $value:expr => $($target:ty)=>+) => {{
+        let value = &$value;
+        $(
+            let value: &$target = $crate::CoercibleRef::<$target>::coerce(value);
+        )+
+        value
+    }};
+    ($value:expr => $($target:ty)=>+) => {{
+        let value = $value;
+        $(
+            let value: $target = $crate::Coercible::<$target>::into_coerced(value);
+        )+
+        value
+    }};
+}
@@ -0,0 +1,86 @@
+//! The [`Coerced`] deref adapter.
+
+use std::fmt;
+use std::marker::PhantomData;
+use std::ops::Deref;
+
+use crate::CoercibleRef;
+
+/// A `&'a S` viewed as `&'a T` through a declared borrowed coercion.
+///
+/// Derefs to `T`, so it can be stored in a struct field wherever a generic view is needed
+/// while keeping hold of the specific reference (see [`source`](Coerced::source)):
+///
+/// ```rust
+/// use std::marker::PhantomData;
+/// use phantom_coerce::{Coerce, Coerced};
+///
+/// struct Validated;
+/// struct AnyStatus;
+///
+/// #[derive(Coerce)]
+/// #[coerce(borrowed_from = "Request<Validated>", borrowed_to = "Request<AnyStatus>")]
+/// struct Request<Status> {
+///     marker: PhantomData<Status>,
+///     url: String,
+/// }
+///
+/// struct Route<'a> {
+///     request: Coerced<'a, Request<Validated>, Request<AnyStatus>>,
+/// }
+///
+/// # fn main() {
+/// let validated = Request::<Validated> { marker: PhantomData, url: "/a".to_string() };
+/// let route = Route { request: Coerced::new(&validated) };
+/// let generic: &Request<AnyStatus> = &route.request;
+/// assert_eq!(generic.url, "/a");
+/// # }
+/// ```
+pub struct Coerced<'a, S: ?Sized, T: ?Sized> {
+    source: &'a S,
+    target: PhantomData<fn() -> &'a T>,
+}
+
+impl<'a, S: ?Sized + CoercibleRef<T>, T: ?Sized> Coerced<'a, S, T> {
+    /// View `source` as `T`.
+    pub fn new(source: &'a S) -> Self {
+        Self {
+            source,
+            target: PhantomData,
+        }
+    }
+
+    /// The coerced reference, with the full lifetime of the source.
+    pub fn get(&self) -> &'a T {
+        self.source.coerce()
+    }
+}
+
+impl<'a, S: ?Sized, T: ?Sized> Coerced<'a, S, T> {
+    /// The original, specific reference.
+    pub fn source(&self) -> &'a S {
+        self.source
+    }
+}
+
+impl<S: ?Sized + CoercibleRef<T>, T: ?Sized> Deref for Coerced<'_, S, T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        self.source.coerce()
+    }
+}
+
+impl<S: ?Sized, T: ?Sized> Clone for Coerced<'_, S, T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<S: ?Sized, T: ?Sized> Copy for Coerced<'_, S, T> {}
+
+impl<S: ?Sized + CoercibleRef<T>, T: ?Sized + fmt::Debug> fmt::Debug for Coerced<'_, S, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(&**self, f)
+    }
+}
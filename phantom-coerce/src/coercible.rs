@@ -0,0 +1,220 @@
+//! The public [`Coercible`] trait.
+
+/// A value that can be coerced into `Target`, for bounding generic code.
+///
+/// `#[derive(Coerce)]` implements this for every declared owned coercion, delegating to the
+/// generated `into_coerced`. Each derived type is also coercible into itself, so a function
+/// bounded on `T: Coercible<Generic>` accepts `Generic` as well as each specific type that
+/// erases to it:
+///
+/// ```rust
+/// use std::marker::PhantomData;
+/// use phantom_coerce::{Coerce, Coercible};
+///
+/// struct Validated;
+/// struct AnyStatus;
+///
+/// #[derive(Coerce)]
+/// #[coerce(owned_from = "Request<Validated>", owned_to = "Request<AnyStatus>")]
+/// struct Request<Status> {
+///     marker: PhantomData<Status>,
+///     url: String,
+/// }
+///
+/// fn url(request: impl Coercible<Request<AnyStatus>>) -> String {
+///     request.into_coerced().url
+/// }
+///
+/// # fn main() {
+/// let validated = Request::<Validated> { marker: PhantomData, url: "/a".to_string() };
+/// let generic = Request::<AnyStatus> { marker: PhantomData, url: "/b".to_string() };
+/// assert_eq!(url(validated), "/a");
+/// assert_eq!(url(generic), "/b");
+/// # }
+/// ```
+///
+/// The impls lift through `Option`, `Vec`, `Box`, the `Ok` side of `Result`, `Poll`, the
+/// `Break` side of `ControlFlow`, and tuples, so `Vec<Request<Validated>>` is
+/// `Coercible<Vec<Request<AnyStatus>>>`. Primitives, `String`, and references are coercible
+/// into themselves, for tuples mixing them with derived types. Each borrowed coercion adds
+/// `&'a Source: Coercible<&'a Target>`, so references fit the same bounds. Other types have no
+/// reflexive impl: a blanket `impl<T> Coercible<T> for T` would overlap with the container
+/// impls.
+#[diagnostic::on_unimplemented(
+    message = "no owned coercion from `{Self}` to `{Target}` is declared",
+    label = "`{Self}` can't be coerced into `{Target}`"
+)]
+pub trait Coercible<Target> {
+    /// Coerce `self` into `Target`.
+    fn into_coerced(self) -> Target;
+
+    /// Coerce a boxed value into a boxed `Target`.
+    ///
+    /// Derived coercions override this to coerce the value inside its existing allocation.
+    /// Other impls move it into a new box.
+    fn into_coerced_box(self: Box<Self>) -> Box<Target>
+    where
+        Self: Sized,
+    {
+        Box::new((*self).into_coerced())
+    }
+}
+
+macro_rules! reflexive {
+    ($($ty:ty),* $(,)?) => {
+        $(
+            impl Coercible<$ty> for $ty {
+                fn into_coerced(self) -> $ty {
+                    self
+                }
+            }
+        )*
+    };
+}
+
+reflexive!(
+    (),
+    bool,
+    char,
+    u8,
+    u16,
+    u32,
+    u64,
+    u128,
+    usize,
+    i8,
+    i16,
+    i32,
+    i64,
+    i128,
+    isize,
+    f32,
+    f64,
+    String,
+);
+
+impl<'a, T: ?Sized> Coercible<&'a T> for &'a T {
+    fn into_coerced(self) -> &'a T {
+        self
+    }
+}
+
+impl<T: Coercible<U>, U> Coercible<Option<U>> for Option<T> {
+    fn into_coerced(self) -> Option<U> {
+        self.map(Coercible::into_coerced)
+    }
+}
+
+impl<T: Coercible<U>, U> Coercible<Vec<U>> for Vec<T> {
+    fn into_coerced(self) -> Vec<U> {
+        // Same-layout elements let std reuse the allocation in place
+        self.into_iter().map(Coercible::into_coerced).collect()
+    }
+}
+
+impl<T: Coercible<U>, U> Coercible<Box<U>> for Box<T> {
+    fn into_coerced(self) -> Box<U> {
+        T::into_coerced_box(self)
+    }
+}
+
+/// Only the `Ok` value is coerced, so error types don't need a `Coercible` impl of their own.
+impl<T: Coercible<U>, U, E> Coercible<Result<U, E>> for Result<T, E> {
+    fn into_coerced(self) -> Result<U, E> {
+        self.map(Coercible::into_coerced)
+    }
+}
+
+impl<T: Coercible<U>, U> Coercible<core::task::Poll<U>> for core::task::Poll<T> {
+    fn into_coerced(self) -> core::task::Poll<U> {
+        self.map(Coercible::into_coerced)
+    }
+}
+
+/// Only the `Break` value is coerced, matching `ControlFlow<Source, C>` in visitor loops.
+impl<T: Coercible<U>, U, C> Coercible<core::ops::ControlFlow<U, C>>
+    for core::ops::ControlFlow<T, C>
+{
+    fn into_coerced(self) -> core::ops::ControlFlow<U, C> {
+        match self {
+            core::ops::ControlFlow::Break(value) => {
+                core::ops::ControlFlow::Break(value.into_coerced())
+            }
+            core::ops::ControlFlow::Continue(value) => core::ops::ControlFlow::Continue(value),
+        }
+    }
+}
+
+macro_rules! tuple {
+    ($(($source:ident, $target:ident, $index:tt)),+) => {
+        impl<$($source: Coercible<$target>, $target),+> Coercible<($($target,)+)> for ($($source,)+) {
+            fn into_coerced(self) -> ($($target,)+) {
+                ($(self.$index.into_coerced(),)+)
+            }
+        }
+    };
+}
+
+tuple!((A, A2, 0));
+tuple!((A, A2, 0), (B, B2, 1));
+tuple!((A, A2, 0), (B, B2, 1), (C, C2, 2));
+tuple!((A, A2, 0), (B, B2, 1), (C, C2, 2), (D, D2, 3));
+
+/// A value whose reference can be coerced into `&Target`, the borrowed counterpart of
+/// [`Coercible`].
+///
+/// `#[derive(Coerce)]` implements this for every declared borrowed coercion, delegating to the
+/// generated `coerce`. Unlike [`Coercible`] there's no reflexive impl, so it also covers
+/// `#[derive(Upcast)]` without `from`. It bounds [`Coerced`](crate::Coerced)'s `Deref`.
+#[diagnostic::on_unimplemented(
+    message = "no borrowed coercion from `{Self}` to `{Target}` is declared",
+    label = "`&{Self}` can't be coerced into `&{Target}`"
+)]
+pub trait CoercibleRef<Target: ?Sized> {
+    /// Coerce `&self` into `&Target`.
+    fn coerce(&self) -> &Target;
+}
+
+/// Downcast an erased value to `Specific` and coerce it into `Generic` in one step.
+///
+/// Returns the original box untouched when it doesn't hold a `Specific`, so callers can try
+/// the next candidate:
+///
+/// ```rust
+/// use std::any::Any;
+/// use std::marker::PhantomData;
+/// use phantom_coerce::{Coerce, downcast_coerce};
+///
+/// struct Validated;
+/// struct Unvalidated;
+/// struct AnyStatus;
+///
+/// #[derive(Coerce)]
+/// #[coerce(owned_from = "Request<Validated | Unvalidated>", owned_to = "Request<AnyStatus>")]
+/// struct Request<Status> {
+///     marker: PhantomData<Status>,
+///     url: String,
+/// }
+///
+/// # fn main() {
+/// let plugin: Box<dyn Any> = Box::new(Request::<Unvalidated> {
+///     marker: PhantomData,
+///     url: "/a".to_string(),
+/// });
+///
+/// let generic = downcast_coerce::<Request<Validated>, Request<AnyStatus>>(plugin)
+///     .or_else(downcast_coerce::<Request<Unvalidated>, Request<AnyStatus>>)
+///     .unwrap_or_else(|_| panic!("not a request"));
+/// assert_eq!(generic.url, "/a");
+/// # }
+/// ```
+pub fn downcast_coerce<Specific, Generic>(
+    value: Box<dyn core::any::Any>,
+) -> Result<Box<Generic>, Box<dyn core::any::Any>>
+where
+    Specific: Coercible<Generic> + 'static,
+{
+    value
+        .downcast::<Specific>()
+        .map(Coercible::into_coerced_box)
+}
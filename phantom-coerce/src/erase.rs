@@ -0,0 +1,47 @@
+//! The [`HasAnyMarker`] convention trait.
+
+/// A type with one canonical fully-generic form, for code that stores values erased.
+///
+/// `#[derive(Coerce)]` implements this when the struct names its erased form with
+/// `#[coerce(erased = "...")]`. Each source with both a borrowed and an owned coercion to
+/// that type gets an impl, and so does the erased type itself. Generic plumbing then needs a
+/// single bound instead of knowing each marker:
+///
+/// ```rust
+/// use std::marker::PhantomData;
+/// use phantom_coerce::{Coerce, HasAnyMarker};
+///
+/// struct Validated;
+/// struct Unvalidated;
+/// struct AnyStatus;
+///
+/// #[derive(Coerce)]
+/// #[coerce(borrowed_from = "Request<Validated | Unvalidated>", borrowed_to = "Request<AnyStatus>")]
+/// #[coerce(owned_from = "Request<Validated | Unvalidated>", owned_to = "Request<AnyStatus>")]
+/// #[coerce(erased = "Request<AnyStatus>")]
+/// struct Request<Status> {
+///     marker: PhantomData<Status>,
+///     url: String,
+/// }
+///
+/// fn store<R: HasAnyMarker>(log: &mut Vec<R::Erased>, request: R) {
+///     log.push(request.erase());
+/// }
+///
+/// # fn main() {
+/// let mut log = Vec::new();
+/// store(&mut log, Request::<Validated> { marker: PhantomData, url: "/a".to_string() });
+/// store(&mut log, Request::<Unvalidated> { marker: PhantomData, url: "/b".to_string() });
+/// assert_eq!(log[1].url, "/b");
+/// # }
+/// ```
+pub trait HasAnyMarker {
+    /// The fully-generic form of `Self`.
+    type Erased;
+
+    /// Coerce `self` into its erased form.
+    fn erase(self) -> Self::Erased;
+
+    /// Coerce `&self` into a reference to its erased form.
+    fn erase_ref(&self) -> &Self::Erased;
+}
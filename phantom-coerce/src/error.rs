@@ -0,0 +1,83 @@
+//! The [`CoerceError`] type for failed marker narrowing.
+
+use std::borrow::Cow;
+use std::fmt;
+
+/// A conversion between marker types that was rejected, with the types involved.
+///
+/// Erasure never fails, but going the other way (from a generic marker back to a specific
+/// one) needs a check. `CoerceError` records which conversion failed and why, so validators
+/// don't each need their own error type:
+///
+/// ```rust
+/// use std::marker::PhantomData;
+/// use phantom_coerce::CoerceError;
+///
+/// struct Validated;
+/// struct AnyStatus;
+///
+/// struct Request<Status> {
+///     marker: PhantomData<Status>,
+///     url: String,
+/// }
+///
+/// fn validate(request: Request<AnyStatus>) -> Result<Request<Validated>, CoerceError> {
+///     if !request.url.starts_with('/') {
+///         return Err(CoerceError::new::<Request<AnyStatus>, Request<Validated>>(
+///             "url must be absolute",
+///         ));
+///     }
+///     Ok(Request { marker: PhantomData, url: request.url })
+/// }
+///
+/// # fn main() {
+/// let request = Request::<AnyStatus> { marker: PhantomData, url: "checkout".to_string() };
+/// let err = validate(request).err().unwrap();
+/// assert_eq!(err.message(), "url must be absolute");
+/// assert!(err.target_type().ends_with("Validated>"));
+/// # }
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CoerceError {
+    source_type: &'static str,
+    target_type: &'static str,
+    message: Cow<'static, str>,
+}
+
+impl CoerceError {
+    /// A failed conversion from `Source` to `Target`, explained by `message`.
+    pub fn new<Source: ?Sized, Target: ?Sized>(message: impl Into<Cow<'static, str>>) -> Self {
+        Self {
+            source_type: std::any::type_name::<Source>(),
+            target_type: std::any::type_name::<Target>(),
+            message: message.into(),
+        }
+    }
+
+    /// The type name of the value that failed to convert.
+    pub fn source_type(&self) -> &'static str {
+        self.source_type
+    }
+
+    /// The type name the value was being converted to.
+    pub fn target_type(&self) -> &'static str {
+        self.target_type
+    }
+
+    /// Why the conversion was rejected.
+    pub fn message(&self) -> &str {
+        &self.message
+    }
+}
+
+impl fmt::Display for CoerceError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "cannot coerce {} into {}: {}",
+            self.source_type, self.target_type, self.message
+        )
+    }
+}
+
+impl std::error::Error for CoerceError {}
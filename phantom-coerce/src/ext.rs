@@ -0,0 +1,98 @@
+//! The [`CoerceExt`] trait, lifting coercions through pairs.
+
+use crate::{Coercible, CoercibleRef};
+
+/// Coerce one or both sides of a pair without taking it apart.
+///
+/// [`Coercible`] already lifts through tuples, but only when every element is coercible, which
+/// rules out pairs holding arbitrary data next to a derived type. These methods coerce the
+/// chosen side and pass the other through unchanged:
+///
+/// ```rust
+/// use std::marker::PhantomData;
+/// use phantom_coerce::{Coerce, CoerceExt};
+///
+/// struct Validated;
+/// struct AnyStatus;
+///
+/// #[derive(Coerce)]
+/// #[coerce(borrowed_from = "Request<Validated>", borrowed_to = "Request<AnyStatus>")]
+/// #[coerce(owned_from = "Request<Validated>", owned_to = "Request<AnyStatus>")]
+/// struct Request<Status> {
+///     marker: PhantomData<Status>,
+///     url: String,
+/// }
+///
+/// struct Session(u64);
+///
+/// fn validate(url: &str) -> (Request<Validated>, Session) {
+///     (Request { marker: PhantomData, url: url.to_string() }, Session(7))
+/// }
+///
+/// # fn main() {
+/// let (request, session): (Request<AnyStatus>, Session) = validate("/a").coerce_first();
+/// assert_eq!((request.url.as_str(), session.0), ("/a", 7));
+///
+/// let pair = validate("/b");
+/// let (request, _): (&Request<AnyStatus>, &Session) = (&pair.0, &pair.1).coerce_first();
+/// assert_eq!(request.url, "/b");
+/// # }
+/// ```
+///
+/// Pairs of references coerce through the borrowed coercions, since each one also implements
+/// `&Source: Coercible<&Target>`.
+pub trait CoerceExt<A, B> {
+    /// Coerce the first element, keeping the second.
+    fn coerce_first<T>(self) -> (T, B)
+    where
+        A: Coercible<T>;
+
+    /// Coerce the second element, keeping the first.
+    fn coerce_second<U>(self) -> (A, U)
+    where
+        B: Coercible<U>;
+
+    /// Coerce both elements.
+    fn coerce_both<T, U>(self) -> (T, U)
+    where
+        A: Coercible<T>,
+        B: Coercible<U>;
+
+    /// Borrow both elements as their coerced types.
+    fn coerce_refs<T: ?Sized, U: ?Sized>(&self) -> (&T, &U)
+    where
+        A: CoercibleRef<T>,
+        B: CoercibleRef<U>;
+}
+
+impl<A, B> CoerceExt<A, B> for (A, B) {
+    fn coerce_first<T>(self) -> (T, B)
+    where
+        A: Coercible<T>,
+    {
+        (self.0.into_coerced(), self.1)
+    }
+
+    fn coerce_second<U>(self) -> (A, U)
+    where
+        B: Coercible<U>,
+    {
+        (self.0, self.1.into_coerced())
+    }
+
+    fn coerce_both<T, U>(self) -> (T, U)
+    where
+        A: Coercible<T>,
+        B: Coercible<U>,
+    {
+        (self.0.into_coerced(), self.1.into_coerced())
+    }
+
+    fn coerce_refs<T: ?Sized, U: ?Sized>(&self) -> (&T, &U)
+    where
+        A: CoercibleRef<T>,
+        B: CoercibleRef<U>,
+    {
+        (self.0.coerce(), self.1.coerce())
+    }
+}
@@ -136,5 +136,244 @@
 //! assert_eq!(json_msg.content, r#"{"status": "ok"}"#); // Original still available
 //! # }
 //! ```
+//!
+//! # Upcast
+//!
+//! `#[derive(Upcast)]` covers the common case of one marker parameter and one generic
+//! "any" marker, generating borrowed and owned coercions from every marker to it:
+//!
+//! ```rust
+//! use std::marker::PhantomData;
+//! use phantom_coerce::Upcast;
+//!
+//! # struct Validated;
+//! # struct AnyStatus;  // Generic (subsumes every status)
+//! #
+//! #[derive(Upcast)]
+//! #[upcast(any = "AnyStatus")]
+//! struct Request<Status> {
+//!     marker: PhantomData<Status>,
+//!     url: String,
+//! }
+//!
+//! # fn main() {
+//! let request = Request::<Validated> {
+//!     marker: PhantomData,
+//!     url: "https://example.com".to_string(),
+//! };
+//! let generic: &Request<AnyStatus> = request.coerce();
+//! assert_eq!(generic.url, "https://example.com");
+//! # }
+//! ```
+//!
+//! # Const-Generic Ordering
+//!
+//! A `when` condition turns a coercion into a rule over const parameters, so a numeric
+//! capability lattice doesn't need every pair spelled out:
+//!
+//! ```rust
+//! use phantom_coerce::Coerce;
+//!
+//! #[derive(Coerce)]
+//! #[coerce(borrowed_from = "Permission<LEVEL>", borrowed_to = "Permission<TO>", when = "TO <= LEVEL")]
+//! struct Permission<const LEVEL: u8> {
+//!     holder: String,
+//! }
+//!
+//! # fn main() {
+//! let admin = Permission::<3> { holder: "alice".to_string() };
+//! let reader: &Permission<1> = admin.coerce();
+//! # }
+//! ```
+//!
+//! The condition is checked when a coercion is instantiated, so raising a level fails to build
+//! (`cargo check` doesn't instantiate it and won't report the error):
+//!
+//! ```rust,compile_fail
+//! # use phantom_coerce::Coerce;
+//! # #[derive(Coerce)]
+//! # #[coerce(borrowed_from = "Permission<LEVEL>", borrowed_to = "Permission<TO>", when = "TO <= LEVEL")]
+//! # struct Permission<const LEVEL: u8> {
+//! #     holder: String,
+//! # }
+//! # fn main() {
+//! let reader = Permission::<1> { holder: "mallory".to_string() };
+//! let admin: &Permission<3> = reader.coerce();
+//! # }
+//! ```
+//!
+//! # `parking_lot` Guard Mapping
+//!
+//! With the `parking_lot` feature enabled, borrowed coercions also generate associated
+//! functions that map read guards onto the coerced type, so a locked value can be handed
+//! out as its more generic type without releasing the lock:
+//!
+//! - `coerce_read_guard(RwLockReadGuard<Source>) -> MappedRwLockReadGuard<Target>`
+//! - `coerce_mapped_read_guard(MappedRwLockReadGuard<Source>) -> MappedRwLockReadGuard<Target>`
+//! - `coerce_reentrant_guard(ReentrantMutexGuard<Source>) -> MappedReentrantMutexGuard<Target>`
+//!
+//! `MutexGuard` is intentionally not supported: `parking_lot` only maps it through `&mut`,
+//! and a mutable view as the generic type would allow writing a generic value back into
+//! the specific one.
+
+#[doc(hidden)]
+pub use phantom_coerce_derive::__extend_coercions;
+pub use phantom_coerce_derive::{
+    Coerce, PhantomMarker, Upcast, coerce, coercions, defaults, dispatch_table, for_each_coercion,
+    for_each_specific, match_specific, phantom_coerce,
+};
+
+pub mod any;
+mod any_of;
+mod bound;
+mod chain;
+mod coerced;
+mod coercible;
+mod erase;
+mod error;
+mod ext;
+mod list;
+mod marker;
+pub mod mode;
+mod query;
+#[cfg(feature = "registry")]
+pub mod registry;
+#[cfg(feature = "futures")]
+pub mod stream;
+pub mod tag;
+mod testing;
+#[cfg(feature = "typewit")]
+pub mod witness;
+
+pub use any_of::AnyOf;
+pub use coerced::Coerced;
+pub use coercible::{Coercible, CoercibleRef, downcast_coerce};
+pub use erase::HasAnyMarker;
+pub use error::CoerceError;
+pub use ext::CoerceExt;
+pub use list::{Coercion, CoercionList};
+pub use marker::Marker;
+
+#[doc(hidden)]
+pub mod __private {
+    #[cfg(feature = "ndarray")]
+    pub use ndarray;
+    #[cfg(feature = "parking_lot")]
+    pub use parking_lot;
+    #[cfg(feature = "schemars")]
+    pub use schemars;
+    #[cfg(feature = "serde")]
+    pub use serde;
+    #[cfg(feature = "sqlx")]
+    pub use sqlx;
+    #[cfg(feature = "tracing")]
+    pub use tracing;
+    #[cfg(feature = "typewit")]
+    pub use typewit;
+
+    /// Implemented by the derive for every declared coercion
+    ///
+    /// Besides answering `compile_fail_tests!`, this bounds the derive's unsafe cast helpers
+    /// (`__phantom_coerce_cast_ref`/`__phantom_coerce_cast`), which rely on every impl
+    /// relating two instantiations of one struct that differ only in `PhantomData` parameters.
+    /// Implementing it outside the derive is unsound. It isn't an `unsafe trait` only because
+    /// the derive also implements it in `#![forbid(unsafe_code)]` crates, where an
+    /// `unsafe impl` would be rejected.
+    pub trait DeclaredCoercion<Mode, Target: ?Sized> {
+        /// Fails to evaluate when a `when` rule's condition doesn't hold for this pair
+        const PERMITTED: () = ();
+    }
+
+    pub use crate::mode::{Borrowed, Cloned, Owned};
+
+    /// Relates a parameter of an `any(...)` struct to its target: kept as is, or erased into
+    /// the `Any` marker
+    ///
+    /// Both impls apply when the source is already `Any`, so the index keeps them coherent.
+    pub trait AnyParam<Target: ?Sized, Any: ?Sized, Index> {}
+
+    impl<T: ?Sized, Any: ?Sized> AnyParam<T, Any, crate::any::Keep> for T {}
+    impl<T: ?Sized, Any: ?Sized> AnyParam<Any, Any, crate::any::Erase> for T {}
+
+    /// A coercion mode paired with the `AnyParam` indices selecting an `any(...)` impl
+    pub struct Indexed<Mode, Index>(::core::marker::PhantomData<(Mode, Index)>);
+
+    /// Compile-time auto-trait probes for the derive's equivalence checks
+    ///
+    /// `AutoTraits::<T>::SEND` resolves to the inherent const when `T: Send`, and to the
+    /// fallback trait's `false` otherwise. This only works for concrete types, so pairs with a
+    /// generic source instead bound it with `SendIf<{ AutoTraits::<Target>::SEND }>` (and the
+    /// `Sync`/`Unpin` equivalents), which requires the trait only when the target has it.
+    pub mod auto_traits {
+        use core::marker::PhantomData;
+
+        pub struct AutoTraits<T: ?Sized>(PhantomData<T>);
+
+        impl<T: ?Sized + Send> AutoTraits<T> {
+            pub const SEND: bool = true;
+        }
+
+        impl<T: ?Sized + Sync> AutoTraits<T> {
+            pub const SYNC: bool = true;
+        }
+
+        impl<T: ?Sized + Unpin> AutoTraits<T> {
+            pub const UNPIN: bool = true;
+        }
+
+        pub trait NotSend {
+            const SEND: bool = false;
+        }
+
+        pub trait NotSync {
+            const SYNC: bool = false;
+        }
+
+        pub trait NotUnpin {
+            const UNPIN: bool = false;
+        }
+
+        impl<T: ?Sized> NotSend for AutoTraits<T> {}
+        impl<T: ?Sized> NotSync for AutoTraits<T> {}
+        impl<T: ?Sized> NotUnpin for AutoTraits<T> {}
+
+        /// Implemented by every type when `TARGET` is false, and by `Send` types otherwise
+        pub trait SendIf<const TARGET: bool> {}
+
+        /// Implemented by every type when `TARGET` is false, and by `Sync` types otherwise
+        pub trait SyncIf<const TARGET: bool> {}
+
+        /// Implemented by every type when `TARGET` is false, and by `Unpin` types otherwise
+        pub trait UnpinIf<const TARGET: bool> {}
+
+        impl<T: ?Sized + Send> SendIf<true> for T {}
+        impl<T: ?Sized> SendIf<false> for T {}
+        impl<T: ?Sized + Sync> SyncIf<true> for T {}
+        impl<T: ?Sized> SyncIf<false> for T {}
+        impl<T: ?Sized + Unpin> UnpinIf<true> for T {}
+        impl<T: ?Sized> UnpinIf<false> for T {}
+    }
+
+    /// Implemented by the derive for each type parameter, so `coerce!` can fill `_` holes
+    pub trait Param<const INDEX: usize> {
+        type Type: ?Sized;
+    }
+
+    /// Implemented by the derive for each struct, so `coerce!` can find the source through
+    /// autoderef the way the `coerce` method does
+    pub trait Derived {
+        fn __phantom_coerce_source(&self) -> ::core::marker::PhantomData<*const Self> {
+            ::core::marker::PhantomData
+        }
+    }
 
-pub use phantom_coerce_derive::Coerce;
+    /// Require the source and target of `coerce!` to agree on parameter `INDEX`
+    pub fn same_param<const INDEX: usize, Source, Target>(
+        _: ::core::marker::PhantomData<*const Source>,
+        _: &Target,
+    ) where
+        Source: ?Sized + Param<INDEX>,
+        Target: ?Sized + Param<INDEX, Type = <Source as Param<INDEX>>::Type>,
+    {
+    }
+}
@@ -0,0 +1,67 @@
+//! Type-level lists of each struct's declared coercions.
+
+use std::marker::PhantomData;
+
+/// Lists every coercion declared on a `#[derive(Coerce)]` struct as a type.
+///
+/// `Coercions` is a cons list of [`Coercion`] entries ending in `()`, in declaration order
+/// (borrowed, then owned, then cloned):
+///
+/// ```text
+/// (Coercion<Borrowed, S1, T1>, (Coercion<Owned, S2, T2>, ()))
+/// ```
+///
+/// The impl covers every instantiation of the struct, so `<TypedPath<A, B> as CoercionList>`
+/// names the same list for any `A` and `B`. Type holes appear as the struct's own parameters.
+/// Other macros can emit code that walks the list with a recursive trait, instead of parsing
+/// `#[coerce(...)]` attributes themselves:
+///
+/// ```rust
+/// use std::marker::PhantomData;
+/// use phantom_coerce::mode::{Borrowed, Owned};
+/// use phantom_coerce::{Coerce, Coercion, CoercionList};
+///
+/// struct Validated;
+/// struct AnyStatus;
+///
+/// #[derive(Coerce)]
+/// #[coerce(borrowed_from = "Request<Validated>", borrowed_to = "Request<AnyStatus>")]
+/// #[coerce(owned_from = "Request<Validated>", owned_to = "Request<AnyStatus>")]
+/// struct Request<Status> {
+///     marker: PhantomData<Status>,
+/// }
+///
+/// trait Count {
+///     const COUNT: usize;
+/// }
+///
+/// impl Count for () {
+///     const COUNT: usize = 0;
+/// }
+///
+/// impl<Mode, Source, Target, Rest: Count> Count for (Coercion<Mode, Source, Target>, Rest) {
+///     const COUNT: usize = 1 + Rest::COUNT;
+/// }
+///
+/// # fn main() {
+/// assert_eq!(<<Request<Validated> as CoercionList>::Coercions as Count>::COUNT, 2);
+///
+/// type Expected = (
+///     Coercion<Borrowed, Request<Validated>, Request<AnyStatus>>,
+///     (Coercion<Owned, Request<Validated>, Request<AnyStatus>>, ()),
+/// );
+/// let _: PhantomData<<Request<AnyStatus> as CoercionList>::Coercions> = PhantomData::<Expected>;
+/// # }
+/// ```
+pub trait CoercionList {
+    /// The declared coercions, as nested `(Coercion<..>, Rest)` pairs ending in `()`.
+    type Coercions;
+}
+
+/// One declared coercion in a [`CoercionList`]: its mode (see [`mode`](crate::mode)), source,
+/// and target.
+///
+/// Only used at the type level, so it has no public constructor.
+pub struct Coercion<Mode, Source: ?Sized, Target: ?Sized>(
+    PhantomData<(Mode, *const Source, *const Target)>,
+);
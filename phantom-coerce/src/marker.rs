@@ -0,0 +1,35 @@
+//! The [`Marker`] trait describing marker types.
+
+/// Stable metadata for a marker type.
+///
+/// Features that need to name a marker (serialized tags, debug output) read it from here
+/// instead of each choosing their own scheme. `#[derive(PhantomMarker)]` implements it for
+/// unit structs, using the type's name unless `#[phantom_marker(name = "...")]` overrides it, and
+/// also implements [`MarkerTag`](crate::tag::MarkerTag) with the same name:
+///
+/// ```rust
+/// use phantom_coerce::{Marker, PhantomMarker};
+/// use phantom_coerce::tag::MarkerTag;
+///
+/// #[derive(PhantomMarker)]
+/// struct Validated;
+///
+/// #[derive(PhantomMarker)]
+/// #[phantom_marker(name = "json", id = 2)]
+/// struct Json;
+///
+/// # fn main() {
+/// assert_eq!(Validated::NAME, "Validated");
+/// assert_eq!(Validated::ID, None);
+/// assert_eq!(Json::NAME, "json");
+/// assert_eq!(Json::ID, Some(2));
+/// assert_eq!(Json::TAG, "json");
+/// # }
+/// ```
+pub trait Marker {
+    /// The marker's name, as shown in tags and debug output.
+    const NAME: &'static str;
+
+    /// An optional stable identifier, for encodings that shouldn't depend on names.
+    const ID: Option<u64> = None;
+}
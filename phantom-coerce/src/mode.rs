@@ -0,0 +1,10 @@
+//! Marker types naming the three coercion modes at the type level.
+
+/// `&Source -> &Target`, declared with `borrowed_from`/`borrowed_to`.
+pub struct Borrowed;
+
+/// `Source -> Target`, declared with `owned_from`/`owned_to`.
+pub struct Owned;
+
+/// `&Source -> Target` by cloning, declared with `cloned_from`/`cloned_to`.
+pub struct Cloned;
@@ -0,0 +1,72 @@
+/// Whether a coercion was declared, as a `bool` usable in const contexts.
+///
+/// Takes a mode (`borrowed`, `owned`, or `cloned`), a source type, and a target type, and
+/// evaluates to `true` when `#[derive(Coerce)]` (or another front-end) declared that
+/// coercion. Downstream macros and const assertions can branch on it instead of attempting
+/// the call and failing to compile:
+///
+/// ```rust
+/// use std::marker::PhantomData;
+/// use phantom_coerce::{Coerce, is_coercible};
+///
+/// struct Validated;
+/// struct AnyStatus;
+///
+/// #[derive(Coerce)]
+/// #[coerce(borrowed_from = "Request<Validated>", borrowed_to = "Request<AnyStatus>")]
+/// struct Request<Status> {
+///     marker: PhantomData<Status>,
+///     url: String,
+/// }
+///
+/// const _: () = assert!(is_coercible!(borrowed Request<Validated> => Request<AnyStatus>));
+/// const _: () = assert!(!is_coercible!(borrowed Request<AnyStatus> => Request<Validated>));
+/// const _: () = assert!(!is_coercible!(owned Request<Validated> => Request<AnyStatus>));
+/// # fn main() {}
+/// ```
+///
+/// The types must be concrete: answering `false` for a generic parameter would need
+/// specialization, so there is no `const fn is_coercible::<S, T>()` equivalent.
+#[macro_export]
+macro_rules! is_coercible {
+    ($mode:ident $source:ty => $target:ty) => {{
+        // An inherent const with a bound shadows the trait's fallback only when it holds
+        trait NotDeclared {
+            const DECLARED: bool = false;
+        }
+        impl<T: ?Sized> NotDeclared for T {}
+
+        struct Probe<T: ?Sized>(::core::marker::PhantomData<T>);
+        impl<T> Probe<T>
+        where
+            T: ?Sized
+                + $crate::__private::DeclaredCoercion<$crate::__coercion_mode!($mode), $target>,
+        {
+            #[allow(dead_code)]
+            const DECLARED: bool = true;
+        }
+
+        <Probe<$source>>::DECLARED
+    }};
+}
+
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __coercion_mode {
+    (borrowed) => {
+        $crate::__private::Borrowed
+    };
+    (owned) => {
+        $crate::__private::Owned
+    };
+    (cloned) => {
+        $crate::__private::Cloned
+    };
+    ($other:ident) => {
+        ::core::compile_error!(::core::concat!(
+            "Unknown coercion mode '",
+            ::core::stringify!($other),
+            "': expected borrowed, owned, or cloned"
+        ))
+    };
+}
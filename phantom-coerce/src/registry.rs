@@ -0,0 +1,129 @@
+//! A runtime registry of borrowed coercions, for code that only knows types by `TypeId`.
+//!
+//! Declared coercions are resolved at compile time, which doesn't help a scripting layer that
+//! receives type names at runtime. Register the pairs it may need once at startup, then look
+//! them up with [`dyn_coerce_ref`]:
+//!
+//! ```rust
+//! use std::any::{Any, TypeId};
+//! use std::marker::PhantomData;
+//! use phantom_coerce::Coerce;
+//! use phantom_coerce::registry::{dyn_coerce_ref, register};
+//!
+//! struct Validated;
+//! struct AnyStatus;
+//!
+//! #[derive(Coerce)]
+//! #[coerce(borrowed_from = "Request<Validated>", borrowed_to = "Request<AnyStatus>")]
+//! struct Request<Status> {
+//!     marker: PhantomData<Status>,
+//!     url: String,
+//! }
+//!
+//! # fn main() {
+//! register::<Request<Validated>, Request<AnyStatus>>();
+//!
+//! let request = Request::<Validated> { marker: PhantomData, url: "/a".to_string() };
+//! let value: &dyn Any = &request;
+//! let generic = dyn_coerce_ref(value, TypeId::of::<Request<AnyStatus>>()).unwrap();
+//! assert_eq!(generic.downcast_ref::<Request<AnyStatus>>().unwrap().url, "/a");
+//! # }
+//! ```
+//!
+//! Registered pairs chain: with `A -> B` and `B -> C` registered, a value of type `A` reaches
+//! `C` through `B`. Generic sources can't be registered, since a `TypeId` names a single
+//! concrete type.
+
+use crate::CoercibleRef;
+use std::any::{Any, TypeId};
+use std::collections::{HashMap, VecDeque};
+use std::sync::{OnceLock, PoisonError, RwLock};
+
+/// Downcasts to the registered source and coerces it, erased again
+type Entry = for<'a> fn(&'a dyn Any) -> Option<&'a dyn Any>;
+
+fn registry() -> &'static RwLock<HashMap<(TypeId, TypeId), Entry>> {
+    static REGISTRY: OnceLock<RwLock<HashMap<(TypeId, TypeId), Entry>>> = OnceLock::new();
+    REGISTRY.get_or_init(Default::default)
+}
+
+fn coerce_erased<Source, Target>(value: &dyn Any) -> Option<&dyn Any>
+where
+    Source: CoercibleRef<Target> + Any,
+    Target: Any,
+{
+    value
+        .downcast_ref::<Source>()
+        .map(|source| source.coerce() as &dyn Any)
+}
+
+/// Make the borrowed coercion from `Source` to `Target` available to [`dyn_coerce_ref`].
+///
+/// Registering a pair again has no effect.
+pub fn register<Source, Target>()
+where
+    Source: CoercibleRef<Target> + Any,
+    Target: Any,
+{
+    registry()
+        .write()
+        .unwrap_or_else(PoisonError::into_inner)
+        .insert(
+            (TypeId::of::<Source>(), TypeId::of::<Target>()),
+            coerce_erased::<Source, Target>,
+        );
+}
+
+/// Whether a coercion from `source` to `target` has been registered.
+pub fn is_registered(source: TypeId, target: TypeId) -> bool {
+    registry()
+        .read()
+        .unwrap_or_else(PoisonError::into_inner)
+        .contains_key(&(source, target))
+}
+
+/// Coerce `value` to the type identified by `target`, following registered coercions.
+///
+/// Takes the shortest chain of registered pairs from the value's type to `target`. Returns
+/// `value` itself when it already has the target type, and `None` when no chain of registered
+/// coercions leads from its type to `target`.
+pub fn dyn_coerce_ref(value: &dyn Any, target: TypeId) -> Option<&dyn Any> {
+    let path = find_path(
+        &registry().read().unwrap_or_else(PoisonError::into_inner),
+        value.type_id(),
+        target,
+    )?;
+    path.into_iter()
+        .try_fold(value, |value, entry| entry(value))
+}
+
+/// The entries leading from `source` to `target`, breadth-first so the chain is shortest
+fn find_path(
+    registry: &HashMap<(TypeId, TypeId), Entry>,
+    source: TypeId,
+    target: TypeId,
+) -> Option<Vec<Entry>> {
+    // Each reached type, with the type and entry it was reached through
+    let mut reached: HashMap<TypeId, (TypeId, Entry)> = HashMap::new();
+    let mut queue = VecDeque::from([source]);
+    while let Some(current) = queue.pop_front() {
+        if current == target {
+            let mut path = Vec::new();
+            let mut at = target;
+            while at != source {
+                let (from, entry) = reached[&at];
+                path.push(entry);
+                at = from;
+            }
+            path.reverse();
+            return Some(path);
+        }
+        for (&(from, to), &entry) in registry {
+            if from == current && to != source && !reached.contains_key(&to) {
+                reached.insert(to, (from, entry));
+                queue.push_back(to);
+            }
+        }
+    }
+    None
+}
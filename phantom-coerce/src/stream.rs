@@ -0,0 +1,85 @@
+//! Coercing the items of a [`Stream`], behind the `futures` feature.
+//!
+//! [`CoerceStreamExt::coerce_items`] adapts a stream of values into a stream of their coerced
+//! form through [`Coercible`]. Borrowed coercions implement `Coercible` between references, so
+//! a stream of `&Source` coerces into a stream of `&Target` the same way:
+//!
+//! ```rust
+//! use std::marker::PhantomData;
+//! use futures::StreamExt;
+//! use phantom_coerce::Coerce;
+//! use phantom_coerce::stream::CoerceStreamExt;
+//!
+//! struct Validated;
+//! struct AnyStatus;
+//!
+//! #[derive(Coerce)]
+//! #[coerce(owned_from = "Request<Validated>", owned_to = "Request<AnyStatus>")]
+//! struct Request<Status> {
+//!     marker: PhantomData<Status>,
+//!     url: String,
+//! }
+//!
+//! # fn main() {
+//! let validated = futures::stream::iter([Request::<Validated> {
+//!     marker: PhantomData,
+//!     url: "/a".to_string(),
+//! }]);
+//! let generic = validated.coerce_items::<Request<AnyStatus>>();
+//! let urls: Vec<String> = futures::executor::block_on(generic.map(|r| r.url).collect());
+//! assert_eq!(urls, ["/a"]);
+//! # }
+//! ```
+
+use core::marker::PhantomData;
+use core::pin::Pin;
+use core::task::{Context, Poll};
+
+use futures_core::Stream;
+
+use crate::Coercible;
+
+/// Adds [`coerce_items`](CoerceStreamExt::coerce_items) to every [`Stream`].
+pub trait CoerceStreamExt: Stream + Sized {
+    /// Coerce each item into `Target`.
+    fn coerce_items<Target>(self) -> CoerceItems<Self, Target>
+    where
+        Self::Item: Coercible<Target>,
+    {
+        CoerceItems {
+            stream: self,
+            target: PhantomData,
+        }
+    }
+}
+
+impl<S: Stream> CoerceStreamExt for S {}
+
+pin_project_lite::pin_project! {
+    /// Stream returned by [`CoerceStreamExt::coerce_items`].
+    #[must_use = "streams do nothing unless polled"]
+    pub struct CoerceItems<S, Target> {
+        #[pin]
+        stream: S,
+        target: PhantomData<fn() -> Target>,
+    }
+}
+
+impl<S, Target> Stream for CoerceItems<S, Target>
+where
+    S: Stream,
+    S::Item: Coercible<Target>,
+{
+    type Item = Target;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Target>> {
+        self.project()
+            .stream
+            .poll_next(cx)
+            .map(|item| item.map(Coercible::into_coerced))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.stream.size_hint()
+    }
+}
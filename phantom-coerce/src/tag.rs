@@ -0,0 +1,198 @@
+//! Marker tags for recording which specific marker produced an erased value.
+//!
+//! Coercing to a generic marker discards the specific one. When that information needs to
+//! survive (for example across a JSON round-trip), implement [`MarkerTag`] for your markers
+//! and add `#[coerce(tag = "Param")]` to the derive, naming the generic parameter whose
+//! marker should be recorded. The derive then generates:
+//!
+//! - `tagged(self) -> Tagged<Self>` and `tagged_ref(&self) -> Tagged<&Self>`
+//! - `marker_name(&self) -> &'static str`, the marker's [`MarkerTag::TAG`]
+//! - `into_tagged::<Target>(self) -> Tagged<Target>` when owned coercions are declared
+//! - `to_tagged::<Target>(&self) -> Tagged<Target>` when cloned coercions are declared
+//!
+//! `tag_enum = "Name"` also generates an enum with a variant per marker, which displays as and
+//! parses from the tag (failing with [`UnknownTag`]) and lists every marker in `Name::ALL`.
+//!
+//! With the `serde` feature, [`Tagged`] serializes as `{"tag": ..., "value": ...}`, and
+//! deserializing into `Tagged<Generic>` keeps the tag that was written by the specific type.
+//! With the `schemars` feature it also implements `JsonSchema` for that shape. Adding
+//! `tag_field = "..."` instead makes the generic type itself deserialize from an internally
+//! tagged format, dispatching on that field to the matching specific type before coercing.
+//!
+//! ```rust
+//! use std::marker::PhantomData;
+//! use phantom_coerce::Coerce;
+//! use phantom_coerce::tag::MarkerTag;
+//!
+//! struct Json;
+//! struct AnyFormat;
+//!
+//! impl MarkerTag for Json {
+//!     const TAG: &'static str = "json";
+//! }
+//!
+//! #[derive(Coerce)]
+//! #[coerce(owned_from = "Message<Json>", owned_to = "Message<AnyFormat>")]
+//! #[coerce(tag = "Format")]
+//! struct Message<Format> {
+//!     marker: PhantomData<Format>,
+//!     content: String,
+//! }
+//!
+//! # fn main() {
+//! let msg = Message::<Json> {
+//!     marker: PhantomData,
+//!     content: "{}".to_string(),
+//! };
+//! let erased = msg.into_tagged::<Message<AnyFormat>>();
+//! assert_eq!(erased.tag(), "json");
+//! assert!(erased.is::<Json>());
+//! # }
+//! ```
+
+use crate::CoercibleRef;
+use std::borrow::{Borrow, Cow};
+use std::fmt;
+
+/// A stable string name for a marker type, recorded by [`Tagged`].
+pub trait MarkerTag {
+    /// The tag written alongside values carrying this marker.
+    const TAG: &'static str;
+}
+
+/// A value paired with the tag of the specific marker that produced it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub struct Tagged<T> {
+    tag: Cow<'static, str>,
+    value: T,
+}
+
+impl<T> Tagged<T> {
+    /// Pair `value` with `tag`.
+    pub fn new(tag: impl Into<Cow<'static, str>>, value: T) -> Self {
+        Self {
+            tag: tag.into(),
+            value,
+        }
+    }
+
+    /// The recorded marker tag.
+    pub fn tag(&self) -> &str {
+        &self.tag
+    }
+
+    /// Whether the value was produced by marker `M`, compared by tag.
+    pub fn is<M: MarkerTag + ?Sized>(&self) -> bool {
+        self.tag == M::TAG
+    }
+
+    /// The tagged value.
+    pub fn value(&self) -> &T {
+        &self.value
+    }
+
+    /// Discard the tag and return the value.
+    pub fn into_value(self) -> T {
+        self.value
+    }
+
+    /// Split into the recorded tag and the value.
+    pub fn into_parts(self) -> (Cow<'static, str>, T) {
+        (self.tag, self.value)
+    }
+}
+
+/// The error from parsing a `tag_enum` enum from a tag none of its markers writes.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UnknownTag {
+    tag: String,
+}
+
+impl UnknownTag {
+    /// Record the tag that failed to parse.
+    pub fn new(tag: impl Into<String>) -> Self {
+        Self { tag: tag.into() }
+    }
+
+    /// The tag that failed to parse.
+    pub fn tag(&self) -> &str {
+        &self.tag
+    }
+}
+
+impl fmt::Display for UnknownTag {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "unknown marker tag `{}`", self.tag)
+    }
+}
+
+impl std::error::Error for UnknownTag {}
+
+/// A marker tag and the handler routed to it, in a [`DispatchTable`].
+pub type Route<T, R> = (&'static str, fn(&T) -> R);
+
+/// Handlers of an erased type, keyed by the [`MarkerTag::TAG`] of each specific marker.
+///
+/// Built by [`dispatch_table!`](crate::dispatch_table), which checks the routed markers against
+/// the ones the struct declares, so the table can be a `static` that can't go stale.
+pub struct DispatchTable<T: ?Sized + 'static, R: 'static> {
+    routes: &'static [Route<T, R>],
+}
+
+impl<T: ?Sized, R> DispatchTable<T, R> {
+    #[doc(hidden)]
+    pub const fn new(routes: &'static [Route<T, R>]) -> Self {
+        Self { routes }
+    }
+
+    /// Every route, as `(tag, handler)` pairs in the order they were written.
+    pub fn routes(&self) -> &'static [Route<T, R>] {
+        self.routes
+    }
+
+    /// The handler for `tag`, if one is routed.
+    pub fn get(&self, tag: &str) -> Option<fn(&T) -> R> {
+        self.routes
+            .iter()
+            .find(|(route, _)| *route == tag)
+            .map(|&(_, handler)| handler)
+    }
+
+    /// Call the handler for `tag` with `value`.
+    pub fn dispatch(&self, tag: &str, value: &T) -> Option<R> {
+        self.get(tag).map(|handler| handler(value))
+    }
+
+    /// Call the handler for the recorded tag with the tagged value.
+    pub fn dispatch_tagged<V: Borrow<T>>(&self, tagged: &Tagged<V>) -> Option<R> {
+        self.dispatch(tagged.tag(), tagged.value().borrow())
+    }
+
+    /// Coerce a specific value through its borrowed coercion and call the handler for its tag.
+    ///
+    /// Takes the value as returned by the derive's `tagged_ref()`.
+    pub fn dispatch_specific<S>(&self, tagged: Tagged<&S>) -> Option<R>
+    where
+        S: CoercibleRef<T> + ?Sized,
+    {
+        self.dispatch(tagged.tag(), tagged.value().coerce())
+    }
+}
+
+impl<T: ?Sized, R> Clone for DispatchTable<T, R> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T: ?Sized, R> Copy for DispatchTable<T, R> {}
+
+impl<T: ?Sized, R> fmt::Debug for DispatchTable<T, R> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list()
+            .entries(self.routes.iter().map(|(tag, _)| tag))
+            .finish()
+    }
+}
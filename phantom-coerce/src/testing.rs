@@ -0,0 +1,66 @@
+/// Assert at compile time that coercions were *not* declared.
+///
+/// Each line names a mode (`borrowed`, `owned`, or `cloned`), a source type, and a target
+/// type. The surrounding crate fails to compile if `#[derive(Coerce)]` (or
+/// `#[derive(Upcast)]`) declared any of the listed coercions, which pins forbidden
+/// coercions in CI without writing `trybuild` cases:
+///
+/// ```rust
+/// use std::marker::PhantomData;
+/// use phantom_coerce::Coerce;
+///
+/// struct Validated;
+/// struct Unvalidated;
+/// struct AnyStatus;
+///
+/// #[derive(Coerce)]
+/// #[coerce(borrowed_from = "Request<Validated>", borrowed_to = "Request<AnyStatus>")]
+/// struct Request<Status> {
+///     marker: PhantomData<Status>,
+///     url: String,
+/// }
+///
+/// phantom_coerce::compile_fail_tests! {
+///     // Erasure never goes the other way
+///     borrowed Request<AnyStatus> => Request<Validated>;
+///     // Only validated requests may be erased
+///     borrowed Request<Unvalidated> => Request<AnyStatus>;
+///     owned Request<Validated> => Request<AnyStatus>;
+/// }
+/// # fn main() {}
+/// ```
+///
+/// A declared coercion is reported as "type annotations needed" at the offending line. The
+/// check looks at declarations only: a declared deep coercion whose field bounds don't hold
+/// still counts as declared.
+#[macro_export]
+macro_rules! compile_fail_tests {
+    ($($mode:ident $source:ty => $target:ty;)*) => {
+        $(
+            $crate::__assert_not_declared!($mode, $source, $target);
+        )*
+    };
+}
+
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __assert_not_declared {
+    ($mode:ident, $source:ty, $target:ty) => {
+        const _: () = {
+            // `some_item` is ambiguous (and fails to infer) only when the second impl applies
+            trait AmbiguousIfDeclared<A> {
+                fn some_item() {}
+            }
+            impl<T: ?Sized> AmbiguousIfDeclared<()> for T {}
+
+            struct Declared;
+            impl<T> AmbiguousIfDeclared<Declared> for T where
+                T: ?Sized
+                    + $crate::__private::DeclaredCoercion<$crate::__coercion_mode!($mode), $target>
+            {
+            }
+
+            let _ = <$source as AmbiguousIfDeclared<_>>::some_item;
+        };
+    };
+}
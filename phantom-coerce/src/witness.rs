@@ -0,0 +1,99 @@
+//! Proof objects for declared coercions, and `typewit` type functions over marker parameters.
+//!
+//! A witness is a `Copy` value standing for "this coercion was declared". It's created where
+//! the trait bound can be checked and carried into code that can't name the bound, such as a
+//! GAT's body or a function dispatching on a const generic. Since that code can't call through
+//! the bound, the witness holds the coercion as a function pointer: it's pointer-sized, and
+//! each call through it is indirect unless the optimizer sees which function it holds.
+//!
+//! ```rust
+//! use std::marker::PhantomData;
+//! use phantom_coerce::Coerce;
+//! use phantom_coerce::witness::CoerceRefWitness;
+//!
+//! struct Validated;
+//! struct AnyStatus;
+//!
+//! #[derive(Coerce)]
+//! #[coerce(borrowed_from = "Request<Validated>", borrowed_to = "Request<AnyStatus>")]
+//! struct Request<Status> {
+//!     marker: PhantomData<Status>,
+//!     url: String,
+//! }
+//!
+//! // No `CoercibleRef` bound: the witness is the proof
+//! fn erase_all<'a, S, T>(values: &'a [S], erase: CoerceRefWitness<S, T>) -> Vec<&'a T> {
+//!     values.iter().map(|value| erase.coerce(value)).collect()
+//! }
+//!
+//! # fn main() {
+//! const ERASE: CoerceRefWitness<Request<Validated>, Request<AnyStatus>> = CoerceRefWitness::NEW;
+//! let requests = [Request::<Validated> { marker: PhantomData, url: "/a".to_string() }];
+//! assert_eq!(erase_all(&requests, ERASE)[0].url, "/a");
+//! # }
+//! ```
+//!
+//! For the opposite direction, `#[coerce(type_fn(Param = "..."))]` implements `typewit`'s
+//! `TypeFn` for the struct over one parameter. Mapping a `TypeEq` between markers through it
+//! gives a `TypeEq` between the struct's instantiations, which turns a value with a generic
+//! marker into the specific one without any unsafe code.
+
+use crate::{Coercible, CoercibleRef};
+
+/// Proof that `&Source` coerces into `&Target`: a declared borrowed coercion.
+pub struct CoerceRefWitness<Source: ?Sized, Target: ?Sized> {
+    coerce: for<'a> fn(&'a Source) -> &'a Target,
+}
+
+impl<Source, Target> CoerceRefWitness<Source, Target>
+where
+    Source: ?Sized + CoercibleRef<Target>,
+    Target: ?Sized,
+{
+    /// The witness, available wherever the coercion is declared.
+    pub const NEW: Self = Self {
+        coerce: <Source as CoercibleRef<Target>>::coerce,
+    };
+}
+
+impl<Source: ?Sized, Target: ?Sized> CoerceRefWitness<Source, Target> {
+    /// Coerce `value` through the coercion this witness stands for.
+    pub fn coerce(self, value: &Source) -> &Target {
+        (self.coerce)(value)
+    }
+}
+
+impl<Source: ?Sized, Target: ?Sized> Clone for CoerceRefWitness<Source, Target> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<Source: ?Sized, Target: ?Sized> Copy for CoerceRefWitness<Source, Target> {}
+
+/// Proof that `Source` coerces into `Target`: a declared owned coercion.
+pub struct CoerceWitness<Source, Target> {
+    into_coerced: fn(Source) -> Target,
+}
+
+impl<Source: Coercible<Target>, Target> CoerceWitness<Source, Target> {
+    /// The witness, available wherever the coercion is declared.
+    pub const NEW: Self = Self {
+        into_coerced: <Source as Coercible<Target>>::into_coerced,
+    };
+}
+
+impl<Source, Target> CoerceWitness<Source, Target> {
+    /// Coerce `value` through the coercion this witness stands for.
+    pub fn into_coerced(self, value: Source) -> Target {
+        (self.into_coerced)(value)
+    }
+}
+
+impl<Source, Target> Clone for CoerceWitness<Source, Target> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<Source, Target> Copy for CoerceWitness<Source, Target> {}
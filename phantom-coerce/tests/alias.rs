@@ -0,0 +1,52 @@
+use phantom_coerce::Coerce;
+use std::marker::PhantomData;
+
+struct Absolute;
+struct Relative;
+struct UnknownBase;
+struct File;
+struct Directory;
+struct UnknownType;
+
+#[derive(Coerce)]
+#[coerce(alias(
+    /// An absolute file path.
+    type AbsFile = TypedPath<Absolute, File>;
+    type AnyPath = TypedPath<UnknownBase, UnknownType>;
+))]
+#[coerce(borrowed_from = "AbsFile", borrowed_to = "AnyPath")]
+#[coerce(
+    owned_from = "AbsFile | TypedPath<Relative, Directory>",
+    owned_to = "AnyPath"
+)]
+struct TypedPath<Base, Type> {
+    base: PhantomData<Base>,
+    ty: PhantomData<Type>,
+    path: String,
+}
+
+fn abs_file(path: &str) -> AbsFile {
+    TypedPath {
+        base: PhantomData,
+        ty: PhantomData,
+        path: path.to_string(),
+    }
+}
+
+#[test]
+fn aliases_resolve_in_patterns() {
+    let file = abs_file("/etc/hosts");
+    let any: &AnyPath = file.coerce();
+    assert_eq!(any.path, "/etc/hosts");
+}
+
+#[test]
+fn aliases_mix_with_alternatives() {
+    let dir = TypedPath::<Relative, Directory> {
+        base: PhantomData,
+        ty: PhantomData,
+        path: "src".to_string(),
+    };
+    let paths: Vec<AnyPath> = vec![abs_file("/a").into_coerced(), dir.into_coerced()];
+    assert_eq!(paths[1].path, "src");
+}
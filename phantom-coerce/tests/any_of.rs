@@ -0,0 +1,74 @@
+use phantom_coerce::{AnyOf, Coerce};
+use std::marker::PhantomData;
+
+#[derive(Clone)]
+struct Json;
+#[derive(Clone)]
+struct Xml;
+#[derive(Clone)]
+struct Yaml;
+#[derive(Clone)]
+struct File;
+
+// Members are implied as sources when the `_from` side is omitted
+#[derive(Coerce, Clone)]
+#[coerce(borrowed_to = "Message<AnyOf<(Json, Xml)>>")]
+#[coerce(owned_to = "Message<AnyOf<(Json, Xml, Yaml)>>")]
+#[coerce(
+    cloned_from = "Message<AnyOf<(Json, Xml)>>",
+    cloned_to = "Message<AnyOf<(Json, Xml, Yaml)>>"
+)]
+struct Message<Format> {
+    marker: PhantomData<Format>,
+    content: String,
+}
+
+fn message<Format>(content: &str) -> Message<Format> {
+    Message {
+        marker: PhantomData,
+        content: content.to_string(),
+    }
+}
+
+#[test]
+fn members_coerce_into_any_of() {
+    let json = message::<Json>("{}");
+    let xml = message::<Xml>("<a/>");
+
+    let text: Vec<&Message<AnyOf<(Json, Xml)>>> = vec![json.coerce(), xml.coerce()];
+    assert_eq!(text[1].content, "<a/>");
+
+    let yaml: Message<AnyOf<(Json, Xml, Yaml)>> = message::<Yaml>("a: 1").into_coerced();
+    assert_eq!(yaml.content, "a: 1");
+}
+
+#[test]
+fn narrower_any_of_widens() {
+    let json = message::<Json>("{}");
+    let text: &Message<AnyOf<(Json, Xml)>> = json.coerce();
+    let wider: Message<AnyOf<(Json, Xml, Yaml)>> = text.to_coerced();
+    assert_eq!(wider.content, "{}");
+}
+
+// `AnyOf` combines with type holes in other positions
+#[derive(Coerce)]
+#[coerce(
+    borrowed_from = "Document<Json | Yaml, _>",
+    borrowed_to = "Document<AnyOf<(Json, Yaml)>, _>"
+)]
+struct Document<Format, Kind> {
+    format: PhantomData<Format>,
+    kind: PhantomData<Kind>,
+    body: String,
+}
+
+#[test]
+fn any_of_with_type_hole() {
+    let doc = Document::<Yaml, File> {
+        format: PhantomData,
+        kind: PhantomData,
+        body: "a: 1".to_string(),
+    };
+    let generic: &Document<AnyOf<(Json, Yaml)>, File> = doc.coerce();
+    assert_eq!(generic.body, "a: 1");
+}
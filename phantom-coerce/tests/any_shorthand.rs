@@ -0,0 +1,85 @@
+use phantom_coerce::Coerce;
+use phantom_coerce::any::{Erase, Keep};
+use std::marker::PhantomData;
+
+struct Absolute;
+struct Relative;
+struct UnknownBase;
+
+struct File;
+struct Directory;
+struct UnknownType;
+
+#[derive(Coerce)]
+#[coerce(any(Base = "UnknownBase", Type = "UnknownType"))]
+struct TypedPath<Base, Type> {
+    base: PhantomData<Base>,
+    ty: PhantomData<Type>,
+    path: String,
+}
+
+impl<Base, Type> TypedPath<Base, Type> {
+    fn new(path: &str) -> Self {
+        Self {
+            base: PhantomData,
+            ty: PhantomData,
+            path: path.to_string(),
+        }
+    }
+}
+
+#[test]
+fn erase_each_parameter() {
+    let path = TypedPath::<Absolute, File>::new("/etc/hosts");
+
+    let any_base: &TypedPath<UnknownBase, File> = path.coerce();
+    assert_eq!(any_base.path, "/etc/hosts");
+
+    let any_type: &TypedPath<Absolute, UnknownType> = path.coerce();
+    assert_eq!(any_type.path, "/etc/hosts");
+
+    let any: &TypedPath<UnknownBase, UnknownType> = path.coerce();
+    assert_eq!(any.path, "/etc/hosts");
+}
+
+#[test]
+fn erase_owned() {
+    let path = TypedPath::<Relative, Directory>::new("src");
+    let any: TypedPath<UnknownBase, UnknownType> = path.into_coerced();
+    assert_eq!(any.path, "src");
+}
+
+#[test]
+fn erase_remaining_parameter() {
+    // Keeping and erasing an already erased parameter are the same, so the choice is named
+    let path = TypedPath::<UnknownBase, File>::new("notes.txt");
+    let any = path.into_coerced::<TypedPath<UnknownBase, UnknownType>, (Keep, Erase)>();
+    assert_eq!(any.path, "notes.txt");
+}
+
+#[test]
+fn turbofish_names_only_the_target() {
+    let path = TypedPath::<Absolute, Directory>::new("/tmp");
+    let any = path.coerce::<TypedPath<UnknownBase, Directory>, _>();
+    assert_eq!(any.path, "/tmp");
+}
+
+struct Unvalidated;
+struct AnyState;
+
+#[derive(Coerce)]
+#[coerce(any(State = "AnyState"))]
+struct Form<'a, State, const N: usize> {
+    state: PhantomData<State>,
+    fields: [&'a str; N],
+}
+
+#[test]
+fn other_parameters_pass_through() {
+    let form = Form::<'_, Unvalidated, 2> {
+        state: PhantomData,
+        fields: ["name", "email"],
+    };
+    let any: &Form<'_, AnyState, 2> = form.coerce();
+    assert_eq!(any.fields, ["name", "email"]);
+}
@@ -1,5 +1,7 @@
 use phantom_coerce::Coerce;
 use std::marker::PhantomData;
+use std::rc::Rc;
+use std::sync::Arc;
 
 #[derive(Clone)]
 struct WithAsRef;
@@ -46,3 +48,39 @@ fn asref_integration() {
     let turbofish = test.coerce::<AsRefTest<ToAsRef>>();
     assert_eq!(turbofish.get_value(), 123);
 }
+
+fn value_via_asref(value: impl AsRef<AsRefTest<ToAsRef>>) -> i32 {
+    value.as_ref().get_value()
+}
+
+#[test]
+fn asref_smart_pointers() {
+    // Plain `asref` covers Box, Rc, and Arc
+    assert_eq!(value_via_asref(Box::new(AsRefTest::<WithAsRef>::new(1))), 1);
+    assert_eq!(value_via_asref(Rc::new(AsRefTest::<WithAsRef>::new(2))), 2);
+    assert_eq!(value_via_asref(Arc::new(AsRefTest::<WithAsRef>::new(3))), 3);
+}
+
+#[derive(Coerce)]
+#[coerce(
+    borrowed_from = "BoxOnly<WithAsRef>",
+    borrowed_to = "BoxOnly<ToAsRef>",
+    asref(Box)
+)]
+struct BoxOnly<M> {
+    marker: PhantomData<M>,
+    value: i32,
+}
+
+fn box_only_value(value: impl AsRef<BoxOnly<ToAsRef>>) -> i32 {
+    value.as_ref().value
+}
+
+#[test]
+fn asref_configured_smart_pointers() {
+    let boxed = Box::new(BoxOnly::<WithAsRef> {
+        marker: PhantomData,
+        value: 7,
+    });
+    assert_eq!(box_only_value(boxed), 7);
+}
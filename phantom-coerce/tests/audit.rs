@@ -0,0 +1,83 @@
+#![cfg(feature = "audit")]
+
+use phantom_coerce::Coerce;
+use std::marker::PhantomData;
+
+struct Validated;
+struct AnyStatus;
+
+#[derive(Coerce, Clone)]
+#[coerce(
+    borrowed_from = "Request<Validated>",
+    borrowed_to = "Request<AnyStatus>"
+)]
+#[coerce(cloned_from = "Request<Validated>", cloned_to = "Request<AnyStatus>")]
+struct Request<Status> {
+    marker: PhantomData<Status>,
+    url: String,
+}
+
+#[derive(Coerce)]
+#[coerce(owned_from = "Response<Validated>", owned_to = "Response<AnyStatus>")]
+#[coerce(safe)]
+struct Response<Status> {
+    marker: PhantomData<Status>,
+    body: String,
+}
+
+// Written while this crate compiled; no build script, so next to the manifest
+const MANIFEST: &str = concat!(
+    env!("CARGO_MANIFEST_DIR"),
+    "/target/phantom-coerce-audit/audit.json"
+);
+
+fn manifest() -> serde_json::Value {
+    serde_json::from_str(&std::fs::read_to_string(MANIFEST).unwrap()).unwrap()
+}
+
+#[test]
+fn lists_every_coercion() {
+    let manifest = manifest();
+    assert_eq!(manifest["crate"], "audit");
+    assert_eq!(
+        manifest["coercions"],
+        serde_json::json!([
+            {
+                "struct": "Request",
+                "mode": "borrowed",
+                "source": "Request<Validated>",
+                "target": "Request<AnyStatus>",
+                "unsafe": true
+            },
+            {
+                "struct": "Request",
+                "mode": "cloned",
+                "source": "Request<Validated>",
+                "target": "Request<AnyStatus>",
+                "unsafe": true
+            },
+            {
+                "struct": "Response",
+                "mode": "owned",
+                "source": "Response<Validated>",
+                "target": "Response<AnyStatus>",
+                "unsafe": false
+            }
+        ])
+    );
+}
+
+#[test]
+fn generated_code_still_coerces() {
+    let request = Request::<Validated> {
+        marker: PhantomData,
+        url: "/a".to_string(),
+    };
+    assert_eq!(request.coerce::<Request<AnyStatus>>().url, "/a");
+    let response = Response::<Validated> {
+        marker: PhantomData,
+        body: "ok".to_string(),
+    };
+    let response: Response<AnyStatus> = response.into_coerced();
+    assert_eq!(response.body, "ok");
+}
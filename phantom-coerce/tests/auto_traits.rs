@@ -0,0 +1,136 @@
+use phantom_coerce::Coerce;
+use std::marker::PhantomData;
+use std::rc::Rc;
+
+// Neither marker is Send or Sync, so erasing between them keeps the type's thread-safety
+struct LocalOnly(PhantomData<Rc<()>>);
+struct AnyLocal(PhantomData<Rc<()>>);
+
+#[derive(Coerce)]
+#[coerce(owned_from = "Handle<LocalOnly>", owned_to = "Handle<AnyLocal>")]
+struct Handle<Affinity> {
+    marker: PhantomData<Affinity>,
+    id: u64,
+}
+
+#[test]
+fn matching_auto_traits() {
+    let local = Handle::<LocalOnly> {
+        marker: PhantomData,
+        id: 3,
+    };
+    let erased: Handle<AnyLocal> = local.into_coerced();
+    assert_eq!(erased.id, 3);
+}
+
+struct AnyThread;
+
+// Opting out lets the marker decide thread-safety on purpose
+#[derive(Coerce)]
+#[coerce(allow(auto_trait_change))]
+#[coerce(borrowed_from = "Widget<AnyThread>", borrowed_to = "Widget<LocalOnly>")]
+struct Widget<Affinity> {
+    marker: PhantomData<Affinity>,
+    label: String,
+}
+
+fn assert_send<T: Send>(_: &T) {}
+
+#[test]
+fn allowed_auto_trait_change() {
+    let widget = Widget::<AnyThread> {
+        marker: PhantomData,
+        label: "ok".to_string(),
+    };
+    assert_send(&widget);
+
+    let local: &Widget<LocalOnly> = widget.coerce();
+    assert_eq!(local.label, "ok");
+}
+
+struct MainThreadOnly(PhantomData<*const ()>);
+
+// The marker takes thread-safety away on purpose, so this one pair opts in
+#[derive(Coerce)]
+#[coerce(
+    owned_from = "Window<AnyThread>",
+    owned_to = "Window<MainThreadOnly>",
+    allow_auto_trait_change
+)]
+struct Window<Affinity> {
+    marker: PhantomData<Affinity>,
+    title: String,
+}
+
+#[test]
+fn coercion_level_opt_in() {
+    let window = Window::<AnyThread> {
+        marker: PhantomData,
+        title: "main".to_string(),
+    };
+    assert_send(&window);
+
+    let pinned: Window<MainThreadOnly> = window.into_coerced();
+    assert_eq!(pinned.title, "main");
+}
+
+phantom_coerce::phantom_coerce! {
+    struct Surface<Affinity> {
+        marker: PhantomData<Affinity>,
+        id: u32,
+    }
+
+    coercions {
+        borrowed Surface<AnyThread> => Surface<MainThreadOnly>, allow_auto_trait_change;
+    }
+}
+
+#[test]
+fn block_opt_in() {
+    let surface = Surface::<AnyThread> {
+        marker: PhantomData,
+        id: 5,
+    };
+    let pinned: &Surface<MainThreadOnly> = surface.coerce();
+    assert_eq!(pinned.id, 5);
+}
+
+// The upcast covers every marker, so the `!Send` one needs the opt-in to reach `AnyThread`
+#[derive(phantom_coerce::Upcast)]
+#[upcast(any = "AnyThread", allow_auto_trait_change)]
+struct Task<Affinity> {
+    marker: PhantomData<Affinity>,
+    name: String,
+}
+
+#[test]
+fn upcast_opt_in() {
+    let task = Task::<MainThreadOnly> {
+        marker: PhantomData,
+        name: "render".to_string(),
+    };
+
+    let erased: Task<AnyThread> = task.into_coerced();
+    assert_send(&erased);
+    assert_eq!(erased.name, "render");
+}
+
+// `any(...)` upcasts are generic too, so they take the same opt-in
+#[derive(Coerce)]
+#[coerce(any(Affinity = "AnyThread"), allow_auto_trait_change)]
+struct Job<Affinity> {
+    marker: PhantomData<Affinity>,
+    id: u64,
+}
+
+#[test]
+fn any_opt_in() {
+    let job = Job::<MainThreadOnly> {
+        marker: PhantomData,
+        id: 9,
+    };
+
+    let erased: Job<AnyThread> = job.into_coerced();
+    assert_send(&erased);
+    assert_eq!(erased.id, 9);
+}
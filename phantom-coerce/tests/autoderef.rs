@@ -0,0 +1,81 @@
+// `CoercibleRef` in scope must not shadow the inherent method on pointers
+#[allow(unused_imports)]
+use phantom_coerce::CoercibleRef;
+use phantom_coerce::{Coerce, coerce};
+use std::cell::RefCell;
+use std::marker::PhantomData;
+use std::rc::Rc;
+use std::sync::{Arc, Mutex, RwLock};
+
+struct Absolute;
+struct UnknownBase;
+struct File;
+
+#[derive(Coerce)]
+#[coerce(
+    borrowed_from = "TypedPath<Absolute, _>",
+    borrowed_to = "TypedPath<UnknownBase, _>"
+)]
+struct TypedPath<Base, Type> {
+    base: PhantomData<Base>,
+    ty: PhantomData<Type>,
+    path: String,
+}
+
+fn typed_path() -> TypedPath<Absolute, File> {
+    TypedPath {
+        base: PhantomData,
+        ty: PhantomData,
+        path: "/etc/hosts".to_string(),
+    }
+}
+
+#[test]
+fn method_through_pointers_and_guards() {
+    let boxed = Box::new(typed_path());
+    assert_eq!(
+        boxed.coerce::<TypedPath<UnknownBase, File>>().path,
+        "/etc/hosts"
+    );
+
+    let shared = Arc::new(typed_path());
+    let generic: &TypedPath<UnknownBase, File> = shared.coerce();
+    assert_eq!(generic.path, "/etc/hosts");
+
+    let lock = Mutex::new(typed_path());
+    let guard = lock.lock().unwrap();
+    assert_eq!(
+        guard.coerce::<TypedPath<UnknownBase, File>>().path,
+        "/etc/hosts"
+    );
+
+    let nested = Box::new(Rc::new(typed_path()));
+    let generic: &TypedPath<UnknownBase, File> = nested.coerce();
+    assert_eq!(generic.path, "/etc/hosts");
+
+    let cell = RefCell::new(typed_path());
+    let borrowed = cell.borrow();
+    assert_eq!(
+        borrowed.coerce::<TypedPath<UnknownBase, File>>().path,
+        "/etc/hosts"
+    );
+
+    let lock = RwLock::new(typed_path());
+    let guard = lock.read().unwrap();
+    assert_eq!(
+        guard.coerce::<TypedPath<UnknownBase, File>>().path,
+        "/etc/hosts"
+    );
+}
+
+#[test]
+fn macros_through_pointers() {
+    let boxed = Box::new(typed_path());
+    let generic = coerce!(ref boxed as TypedPath<UnknownBase, _>);
+    let _: &TypedPath<UnknownBase, File> = generic;
+    assert_eq!(generic.path, "/etc/hosts");
+
+    let shared = Arc::new(typed_path());
+    let generic = coerce!(ref shared as TypedPath<UnknownBase, _>);
+    assert_eq!(generic.path, "/etc/hosts");
+}
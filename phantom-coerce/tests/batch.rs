@@ -0,0 +1,51 @@
+use phantom_coerce::Coerce;
+use std::marker::PhantomData;
+
+struct Validated;
+struct AnyStatus;
+
+#[derive(Coerce)]
+#[coerce(
+    borrowed_from = "Request<Validated>",
+    borrowed_to = "Request<AnyStatus>"
+)]
+#[coerce(owned_from = "Request<Validated>", owned_to = "Request<AnyStatus>")]
+struct Request<Status> {
+    marker: PhantomData<Status>,
+    url: String,
+}
+
+fn requests(urls: &[&str]) -> Vec<Request<Validated>> {
+    urls.iter()
+        .map(|url| Request {
+            marker: PhantomData,
+            url: url.to_string(),
+        })
+        .collect()
+}
+
+#[test]
+fn coerce_all_ref_views_slice() {
+    let validated = requests(&["/a", "/b"]);
+    let generic: &[Request<AnyStatus>] = Request::coerce_all_ref(&validated);
+    assert_eq!(generic.len(), 2);
+    assert_eq!(generic[1].url, "/b");
+    assert_eq!(
+        generic.as_ptr().cast::<()>(),
+        validated.as_ptr().cast::<()>()
+    );
+}
+
+#[test]
+fn coerce_all_converts_vec() {
+    let validated = requests(&["/a", "/b", "/c"]);
+    let generic = Request::coerce_all::<Request<AnyStatus>>(validated);
+    let urls: Vec<&str> = generic.iter().map(|request| request.url.as_str()).collect();
+    assert_eq!(urls, ["/a", "/b", "/c"]);
+}
+
+#[test]
+fn coerce_all_empty() {
+    let generic: Vec<Request<AnyStatus>> = Request::coerce_all(Vec::<Request<Validated>>::new());
+    assert!(generic.is_empty());
+}
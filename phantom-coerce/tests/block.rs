@@ -0,0 +1,81 @@
+use phantom_coerce::{AnyOf, phantom_coerce};
+use std::marker::PhantomData;
+
+#[derive(Clone)]
+struct Absolute;
+#[derive(Clone)]
+struct Relative;
+#[derive(Clone)]
+struct UnknownBase;
+#[derive(Clone)]
+struct File;
+#[derive(Clone)]
+struct UnknownType;
+
+phantom_coerce! {
+    #[derive(Clone)]
+    struct TypedPath<Base, Type> {
+        base: PhantomData<Base>,
+        ty: PhantomData<Type>,
+        path: String,
+    }
+
+    coercions {
+        borrowed TypedPath<Absolute | Relative, _> => TypedPath<UnknownBase, _>, asref;
+        owned TypedPath<Absolute, File> => TypedPath<UnknownBase, UnknownType>;
+        cloned TypedPath<Relative, File> => TypedPath<UnknownBase, File>;
+    }
+}
+
+fn path<Base>(path: &str) -> TypedPath<Base, File> {
+    TypedPath {
+        base: PhantomData,
+        ty: PhantomData,
+        path: path.to_string(),
+    }
+}
+
+#[test]
+fn block_coercions() {
+    let absolute = path::<Absolute>("/etc/hosts");
+    let borrowed: &TypedPath<UnknownBase, File> = absolute.coerce();
+    assert_eq!(borrowed.path, "/etc/hosts");
+
+    let via_asref: &TypedPath<UnknownBase, File> = absolute.as_ref();
+    assert_eq!(via_asref.path, "/etc/hosts");
+
+    let relative = path::<Relative>("hosts");
+    let cloned = relative.to_coerced::<TypedPath<UnknownBase, File>>();
+    assert_eq!(cloned.path, "hosts");
+
+    let owned: TypedPath<UnknownBase, UnknownType> = absolute.into_coerced();
+    assert_eq!(owned.path, "/etc/hosts");
+}
+
+#[derive(Clone)]
+struct Json;
+#[derive(Clone)]
+struct Xml;
+
+// Struct options and `AnyOf` targets work as with the derive
+phantom_coerce! {
+    #[coerce(safe)]
+    struct Message<Format> {
+        marker: PhantomData<Format>,
+        content: String,
+    }
+
+    coercions {
+        owned => Message<AnyOf<(Json, Xml)>>;
+    }
+}
+
+#[test]
+fn block_with_options() {
+    let json = Message::<Json> {
+        marker: PhantomData,
+        content: "{}".to_string(),
+    };
+    let text: Message<AnyOf<(Json, Xml)>> = json.into_coerced();
+    assert_eq!(text.content, "{}");
+}
@@ -0,0 +1,71 @@
+use phantom_coerce::{Coerce, Coercible};
+use std::marker::PhantomData;
+use std::thread;
+
+struct Ready;
+struct Pending;
+struct AnyState;
+
+#[derive(Coerce)]
+#[coerce(
+    borrowed_from = "Job<Ready | Pending, _>",
+    borrowed_to = "Job<AnyState, _>",
+    bound = "Output: Send + 'static",
+    asref
+)]
+#[coerce(
+    owned_from = "Job<Ready, _>",
+    owned_to = "Job<AnyState, _>",
+    bound = "Output: Send"
+)]
+#[coerce(
+    owned_from = "Job<Pending, Output>",
+    owned_to = "Job<AnyState, Output>"
+)]
+struct Job<State, Output> {
+    state: PhantomData<State>,
+    output: PhantomData<Output>,
+    name: String,
+}
+
+fn job<State, Output>(name: &str) -> Job<State, Output> {
+    Job {
+        state: PhantomData,
+        output: PhantomData,
+        name: name.to_string(),
+    }
+}
+
+// Sending needs the bound the impls carry, so it can be required of the hole parameter
+fn spawn<Output: Send + 'static>(job: Job<Ready, Output>) -> String {
+    let job: Job<AnyState, Output> = job.into_coerced();
+    thread::spawn(move || job.name).join().unwrap()
+}
+
+fn queue<J, Output>(job: J) -> Job<AnyState, Output>
+where
+    J: Coercible<Job<AnyState, Output>>,
+{
+    job.into_coerced()
+}
+
+#[test]
+fn bounded_impls_apply_when_the_bound_holds() {
+    assert_eq!(spawn(job::<Ready, u32>("build")), "build");
+
+    let ready = job::<Ready, String>("test");
+    let generic: &Job<AnyState, String> = ready.coerce();
+    assert_eq!(generic.name, "test");
+    let generic: &Job<AnyState, String> = ready.as_ref();
+    assert_eq!(generic.name, "test");
+
+    assert_eq!(queue(ready).name, "test");
+}
+
+#[test]
+fn bounds_are_per_coercion() {
+    // The pending coercion has no bound, so it accepts any output type
+    let pending = job::<Pending, std::rc::Rc<()>>("deploy");
+    let generic: Job<AnyState, std::rc::Rc<()>> = pending.into_coerced();
+    assert_eq!(generic.name, "deploy");
+}
@@ -0,0 +1,89 @@
+//! The block macro sees fields before `#[cfg]` stripping, unlike the derive
+
+use phantom_coerce::phantom_coerce;
+use std::marker::PhantomData;
+
+struct Absolute;
+struct UnknownBase;
+struct File;
+
+phantom_coerce! {
+    struct TypedPath<Base, Type> {
+        base: PhantomData<Base>,
+        #[cfg(all())]
+        ty: PhantomData<Type>,
+        #[cfg(not(all()))]
+        ty: PhantomData<(Type, Base)>,
+        path: String,
+        #[cfg(all())]
+        depth: usize,
+        #[cfg(any())]
+        origin: not_compiled::Origin,
+    }
+
+    coercions {
+        borrowed TypedPath<Absolute, File> => TypedPath<UnknownBase, File>;
+        owned TypedPath<Absolute, File> => TypedPath<UnknownBase, File>;
+        cloned TypedPath<Absolute, File> => TypedPath<UnknownBase, File>;
+    }
+}
+
+fn typed_path(path: &str) -> TypedPath<Absolute, File> {
+    TypedPath {
+        base: PhantomData,
+        ty: PhantomData,
+        path: path.to_string(),
+        depth: 1,
+    }
+}
+
+#[test]
+fn gated_fields_in_each_mode() {
+    let path = typed_path("/etc");
+
+    let borrowed: &TypedPath<UnknownBase, File> = path.coerce();
+    assert_eq!((borrowed.path.as_str(), borrowed.depth), ("/etc", 1));
+
+    let cloned: TypedPath<UnknownBase, File> = path.to_coerced();
+    assert_eq!((cloned.path.as_str(), cloned.depth), ("/etc", 1));
+
+    let owned: TypedPath<UnknownBase, File> = path.into_coerced();
+    assert_eq!((owned.path.as_str(), owned.depth), ("/etc", 1));
+}
+
+mod safe {
+    #![forbid(unsafe_code)]
+
+    use super::{Absolute, UnknownBase};
+    use phantom_coerce::phantom_coerce;
+    use std::marker::PhantomData;
+
+    phantom_coerce! {
+        #[coerce(safe)]
+        struct Record<Base> {
+            base: PhantomData<Base>,
+            name: String,
+            #[cfg(any())]
+            extra: not_compiled::Extra,
+        }
+
+        coercions {
+            owned Record<Absolute> => Record<UnknownBase>;
+            cloned Record<Absolute> => Record<UnknownBase>;
+        }
+    }
+
+    #[test]
+    fn safe_mode_rebuilds_gated_fields() {
+        let record = Record::<Absolute> {
+            base: PhantomData,
+            name: "a".to_string(),
+        };
+        let cloned: Record<UnknownBase> = record.to_coerced();
+        let owned: Record<UnknownBase> = record.into_coerced();
+        assert_eq!(
+            (cloned.name, owned.name),
+            ("a".to_string(), "a".to_string())
+        );
+    }
+}
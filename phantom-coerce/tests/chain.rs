@@ -0,0 +1,61 @@
+use phantom_coerce::{Coerce, coerce_chain};
+use std::marker::PhantomData;
+
+struct Absolute;
+struct UnknownBase;
+struct File;
+struct UnknownType;
+
+#[derive(Coerce)]
+#[coerce(
+    borrowed_from = "TypedPath<Absolute, _>",
+    borrowed_to = "TypedPath<UnknownBase, _>"
+)]
+#[coerce(
+    borrowed_from = "TypedPath<_, File>",
+    borrowed_to = "TypedPath<_, UnknownType>"
+)]
+#[coerce(
+    owned_from = "TypedPath<Absolute, _>",
+    owned_to = "TypedPath<UnknownBase, _>"
+)]
+#[coerce(
+    owned_from = "TypedPath<_, File>",
+    owned_to = "TypedPath<_, UnknownType>"
+)]
+struct TypedPath<Base, Type> {
+    base: PhantomData<Base>,
+    ty: PhantomData<Type>,
+    path: String,
+}
+
+fn path() -> TypedPath<Absolute, File> {
+    TypedPath {
+        base: PhantomData,
+        ty: PhantomData,
+        path: "/etc/hosts".to_string(),
+    }
+}
+
+#[test]
+fn owned_chain() {
+    let erased = coerce_chain!(path() => TypedPath<UnknownBase, File> => TypedPath<UnknownBase, UnknownType>);
+    assert_eq!(erased.path, "/etc/hosts");
+
+    // Hops may go through either parameter first
+    let erased = coerce_chain!(path() => TypedPath<Absolute, UnknownType> => TypedPath<UnknownBase, UnknownType>);
+    assert_eq!(erased.path, "/etc/hosts");
+}
+
+#[test]
+fn borrowed_chain() {
+    let path = path();
+    let view: &TypedPath<UnknownBase, UnknownType> = coerce_chain!(ref path => TypedPath<UnknownBase, File> => TypedPath<UnknownBase, UnknownType>);
+    assert_eq!(view.path, "/etc/hosts");
+}
+
+#[test]
+fn single_hop() {
+    let base: TypedPath<UnknownBase, File> = coerce_chain!(path() => TypedPath<UnknownBase, File>);
+    assert_eq!(base.path, "/etc/hosts");
+}
@@ -0,0 +1,59 @@
+use phantom_coerce::{Coerce, coerce};
+use std::marker::PhantomData;
+
+struct Absolute;
+struct Relative;
+struct UnknownBase;
+struct File;
+struct Directory;
+struct UnknownType;
+
+#[derive(Coerce)]
+#[coerce(
+    borrowed_from = "TypedPath<Absolute | Relative, File | Directory>",
+    borrowed_to = "TypedPath<UnknownBase, File | Directory | UnknownType>"
+)]
+#[coerce(
+    owned_from = "TypedPath<Absolute | Relative, File | Directory>",
+    owned_to = "TypedPath<UnknownBase, File | Directory | UnknownType>"
+)]
+struct TypedPath<Base, Type> {
+    base: PhantomData<Base>,
+    ty: PhantomData<Type>,
+    path: String,
+}
+
+fn path<Base, Type>(path: &str) -> TypedPath<Base, Type> {
+    TypedPath {
+        base: PhantomData,
+        ty: PhantomData,
+        path: path.to_string(),
+    }
+}
+
+#[test]
+fn hole_taken_from_source() {
+    let file = path::<Absolute, File>("/etc/hosts");
+    // Without `coerce!`, `TypedPath<UnknownBase, _>` would be ambiguous between three targets
+    let generic: &TypedPath<UnknownBase, File> = coerce!(ref file as TypedPath<UnknownBase, _>);
+    assert_eq!(generic.path, "/etc/hosts");
+
+    let directory = path::<Relative, Directory>("src");
+    let generic: TypedPath<UnknownBase, Directory> =
+        coerce!(directory as TypedPath<UnknownBase, _>);
+    assert_eq!(generic.path, "src");
+}
+
+#[test]
+fn fully_specified_target() {
+    let file = path::<Absolute, File>("/etc/hosts");
+    let erased = coerce!(file as TypedPath<UnknownBase, UnknownType>);
+    assert_eq!(erased.path, "/etc/hosts");
+}
+
+#[test]
+fn ref_of_place_expression() {
+    let paths = [path::<Relative, File>("a"), path::<Relative, File>("b")];
+    let generic = coerce!(ref paths[1] as TypedPath<UnknownBase, _>);
+    assert_eq!(generic.path, "b");
+}
@@ -0,0 +1,84 @@
+use phantom_coerce::{Coerce, Coerced, CoercibleRef, Upcast};
+use std::marker::PhantomData;
+
+#[derive(Debug)]
+struct Validated;
+#[derive(Debug)]
+struct AnyStatus;
+
+#[derive(Coerce, Debug)]
+#[coerce(
+    borrowed_from = "Request<Validated>",
+    borrowed_to = "Request<AnyStatus>"
+)]
+struct Request<Status> {
+    marker: PhantomData<Status>,
+    url: String,
+}
+
+/// Caches the generic view next to a lookup key, borrowing from the specific owner
+struct Route<'a> {
+    path: &'static str,
+    request: Coerced<'a, Request<Validated>, Request<AnyStatus>>,
+}
+
+fn validated(url: &str) -> Request<Validated> {
+    Request {
+        marker: PhantomData,
+        url: url.to_string(),
+    }
+}
+
+#[test]
+fn derefs_to_target() {
+    let owner = validated("/checkout");
+    let route = Route {
+        path: "checkout",
+        request: Coerced::new(&owner),
+    };
+
+    let generic: &Request<AnyStatus> = &route.request;
+    assert_eq!(generic.url, "/checkout");
+    assert_eq!(route.request.url.len(), 9);
+    assert_eq!(route.path, "checkout");
+    assert!(std::ptr::eq(route.request.source(), &owner));
+}
+
+#[test]
+fn get_outlives_adapter() {
+    let owner = validated("/a");
+    let generic: &Request<AnyStatus> = {
+        let view = Coerced::<_, Request<AnyStatus>>::new(&owner);
+        view.get()
+    };
+    assert_eq!(generic.url, "/a");
+}
+
+#[test]
+fn copy_and_debug() {
+    let owner = validated("/b");
+    let view = Coerced::<_, Request<AnyStatus>>::new(&owner);
+    let copy = view;
+    assert_eq!(format!("{:?}", view), format!("{:?}", copy));
+    assert!(format!("{:?}", view).contains("/b"));
+}
+
+#[derive(Upcast)]
+#[upcast(any = "AnyStatus")]
+struct Ticket<Status> {
+    marker: PhantomData<Status>,
+    seat: u32,
+}
+
+fn seat<S: CoercibleRef<Ticket<AnyStatus>>>(ticket: &S) -> u32 {
+    ticket.coerce().seat
+}
+
+#[test]
+fn coercible_ref_covers_generic_upcasts() {
+    let ticket = Ticket::<Validated> {
+        marker: PhantomData,
+        seat: 12,
+    };
+    assert_eq!(seat(&ticket), 12);
+}
@@ -0,0 +1,92 @@
+use phantom_coerce::{Coerce, Coercible};
+use std::marker::PhantomData;
+
+#[derive(Clone)]
+struct Absolute;
+#[derive(Clone)]
+struct Relative;
+#[derive(Clone)]
+struct UnknownBase;
+#[derive(Clone)]
+struct File;
+#[derive(Clone)]
+struct Directory;
+
+#[derive(Coerce, Clone)]
+#[coerce(
+    owned_from = "TypedPath<Absolute | Relative, _>",
+    owned_to = "TypedPath<UnknownBase, _>"
+)]
+struct TypedPath<Base, Type> {
+    base: PhantomData<Base>,
+    ty: PhantomData<Type>,
+    path: String,
+}
+
+fn path<Base, Type>(path: &str) -> TypedPath<Base, Type> {
+    TypedPath {
+        base: PhantomData,
+        ty: PhantomData,
+        path: path.to_string(),
+    }
+}
+
+// One generic function serves specific and already-generic paths alike
+fn join<Type>(paths: Vec<impl Coercible<TypedPath<UnknownBase, Type>>>) -> String {
+    paths
+        .into_iter()
+        .map(|path| path.into_coerced().path)
+        .collect::<Vec<_>>()
+        .join(":")
+}
+
+#[test]
+fn specific_sources() {
+    let absolute = vec![path::<Absolute, File>("/a"), path("/b")];
+    assert_eq!(join(absolute), "/a:/b");
+
+    let relative = vec![path::<Relative, Directory>("src")];
+    assert_eq!(join(relative), "src");
+}
+
+#[test]
+fn reflexive() {
+    let generic = vec![path::<UnknownBase, File>("/c")];
+    assert_eq!(join(generic), "/c");
+
+    let value: u32 = Coercible::<u32>::into_coerced(7u32);
+    assert_eq!(value, 7);
+}
+
+#[derive(Coerce)]
+#[coerce(
+    borrowed_from = "PathRef<Absolute | Relative, _>",
+    borrowed_to = "PathRef<UnknownBase, _>"
+)]
+struct PathRef<Base, Type> {
+    base: PhantomData<Base>,
+    ty: PhantomData<Type>,
+    path: String,
+}
+
+// Borrowed arguments bound at the reference level, so `&PathRef<UnknownBase, _>` fits too
+fn borrowed_path<'a, P: Coercible<&'a PathRef<UnknownBase, File>>>(path: P) -> &'a str {
+    &path.into_coerced().path
+}
+
+#[test]
+fn reference_sources() {
+    let absolute = PathRef::<Absolute, File> {
+        base: PhantomData,
+        ty: PhantomData,
+        path: "/etc".to_string(),
+    };
+    assert_eq!(borrowed_path(&absolute), "/etc");
+
+    let generic: &PathRef<UnknownBase, File> = absolute.coerce();
+    assert_eq!(borrowed_path(generic), "/etc");
+
+    // Lifting applies to references too
+    let paths: Vec<&PathRef<UnknownBase, File>> = vec![&absolute].into_coerced();
+    assert_eq!(paths[0].path, "/etc");
+}
@@ -0,0 +1,77 @@
+use phantom_coerce::{Coerce, coercible_bound};
+use std::marker::PhantomData;
+
+struct Absolute;
+struct Relative;
+struct UnknownBase;
+struct File;
+
+#[derive(Coerce)]
+#[coerce(
+    borrowed_from = "TypedPath<Absolute, File> | TypedPath<Relative, File>",
+    borrowed_to = "TypedPath<UnknownBase, File>"
+)]
+#[coerce(
+    owned_from = "TypedPath<Absolute, File> | TypedPath<Relative, File>",
+    owned_to = "TypedPath<UnknownBase, File>"
+)]
+struct TypedPath<Base, Type> {
+    base: PhantomData<Base>,
+    ty: PhantomData<Type>,
+    path: String,
+}
+
+fn typed_path<Base>(path: &str) -> TypedPath<Base, File> {
+    TypedPath {
+        base: PhantomData,
+        ty: PhantomData,
+        path: path.to_string(),
+    }
+}
+
+coercible_bound!(
+    /// Any file path, by value
+    pub(crate) AnyFile => TypedPath<UnknownBase, File>
+);
+coercible_bound!(AnyFileRef => &TypedPath<UnknownBase, File>);
+coercible_bound!(AnyFileIn => &'path TypedPath<UnknownBase, File>);
+
+fn into_path(file: impl AnyFile) -> String {
+    file.into_coerced().path
+}
+
+fn path_str<'a, P: AnyFileRef<'a>>(file: P) -> &'a str {
+    &file.into_coerced().path
+}
+
+fn longest<'path, P, Q>(a: P, b: Q) -> &'path str
+where
+    P: AnyFileIn<'path>,
+    Q: AnyFileIn<'path>,
+{
+    let (a, b) = (&a.into_coerced().path, &b.into_coerced().path);
+    if a.len() >= b.len() { a } else { b }
+}
+
+#[test]
+fn owned_bound() {
+    assert_eq!(into_path(typed_path::<Absolute>("/a")), "/a");
+    assert_eq!(into_path(typed_path::<Relative>("b")), "b");
+    // Already generic values satisfy the bound through the reflexive impl
+    assert_eq!(into_path(typed_path::<UnknownBase>("c")), "c");
+}
+
+#[test]
+fn borrowed_bound_ties_the_lifetime() {
+    let absolute = typed_path::<Absolute>("/a");
+    let generic = typed_path::<UnknownBase>("c");
+    assert_eq!(path_str(&absolute), "/a");
+    assert_eq!(path_str(&generic), "c");
+}
+
+#[test]
+fn named_lifetime() {
+    let absolute = typed_path::<Absolute>("/usr");
+    let relative = typed_path::<Relative>("bin");
+    assert_eq!(longest(&absolute, &relative), "/usr");
+}
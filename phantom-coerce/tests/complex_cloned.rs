@@ -58,3 +58,30 @@ fn complex_cloned_coercion() {
     assert_eq!(coerced2.get_count(), 42);
     assert_eq!(coerced2.get_data_len(), 3);
 }
+
+struct Unclonable;
+struct Erased;
+
+// Neither the struct nor its markers implement Clone: only data fields are cloned
+#[derive(Coerce)]
+#[coerce(
+    cloned_from = "Labeled<Unclonable, _>",
+    cloned_to = "Labeled<Erased, _>"
+)]
+struct Labeled<Marker, T> {
+    marker: PhantomData<Marker>,
+    label: String,
+    value: T,
+}
+
+#[test]
+fn cloned_without_clone_markers() {
+    let labeled = Labeled::<Unclonable, Vec<u8>> {
+        marker: PhantomData,
+        label: "bytes".to_string(),
+        value: vec![1, 2],
+    };
+    let erased: Labeled<Erased, Vec<u8>> = labeled.to_coerced();
+    assert_eq!(erased.label, "bytes");
+    assert_eq!(erased.value, labeled.value);
+}
@@ -0,0 +1,72 @@
+use phantom_coerce::Coerce;
+use std::marker::PhantomData;
+
+struct Raw;
+struct Parsed;
+struct AnyState;
+
+#[derive(Coerce)]
+#[coerce(
+    borrowed_from = "Container<String, Raw>",
+    borrowed_to = "Container<String, AnyState>"
+)]
+#[coerce(
+    owned_from = "Container<String, Raw>",
+    owned_to = "Container<String, AnyState>"
+)]
+#[coerce(
+    cloned_from = "Container<String, Raw>",
+    cloned_to = "Container<String, AnyState>"
+)]
+struct Container<T, State> {
+    data: T,
+    state: PhantomData<State>,
+}
+
+fn container<T, State>(data: T) -> Container<T, State> {
+    Container {
+        data,
+        state: PhantomData,
+    }
+}
+
+#[test]
+fn pinned_data_type() {
+    let raw = container::<String, Raw>("payload".to_string());
+
+    let borrowed: &Container<String, AnyState> = raw.coerce();
+    assert_eq!(borrowed.data, "payload");
+
+    let cloned: Container<String, AnyState> = raw.to_coerced();
+    assert_eq!(cloned.data, "payload");
+
+    let owned: Container<String, AnyState> = raw.into_coerced();
+    assert_eq!(owned.data, "payload");
+}
+
+#[derive(Coerce)]
+#[coerce(
+    borrowed_from = "Message<String | Vec<u8>, Raw | Parsed>",
+    borrowed_to = "Message<String | Vec<u8>, AnyState>"
+)]
+struct Message<Body, State> {
+    body: Body,
+    state: PhantomData<State>,
+}
+
+#[test]
+fn alternatives_pair_matching_data_types() {
+    let text = Message::<String, Parsed> {
+        body: "hi".to_string(),
+        state: PhantomData,
+    };
+    let bytes = Message::<Vec<u8>, Raw> {
+        body: vec![1, 2],
+        state: PhantomData,
+    };
+
+    let text: &Message<String, AnyState> = text.coerce();
+    let bytes: &Message<Vec<u8>, AnyState> = bytes.coerce();
+    assert_eq!(text.body, "hi");
+    assert_eq!(bytes.body, [1, 2]);
+}
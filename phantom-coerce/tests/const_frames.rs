@@ -0,0 +1,54 @@
+use phantom_coerce::Coerce;
+use std::marker::PhantomData;
+
+#[derive(Clone)]
+struct Handshake;
+#[derive(Clone)]
+struct Data;
+#[derive(Clone)]
+struct AnyKind;
+
+#[derive(Coerce, Clone)]
+#[coerce(
+    borrowed_from = "Frame<Handshake | Data, N>",
+    borrowed_to = "Frame<AnyKind, N>"
+)]
+#[coerce(owned_from = "Frame<Handshake, _>", owned_to = "Frame<AnyKind, _>")]
+#[coerce(
+    cloned_from = "Frame<Data, { 4 * 4 }>",
+    cloned_to = "Frame<AnyKind, 16>"
+)]
+struct Frame<Kind, const N: usize> {
+    kind: PhantomData<Kind>,
+    bytes: [u8; N],
+}
+
+impl<Kind, const N: usize> Frame<Kind, N> {
+    fn new(bytes: [u8; N]) -> Self {
+        Self {
+            kind: PhantomData,
+            bytes,
+        }
+    }
+}
+
+#[test]
+fn length_stays_symbolic() {
+    let handshake = Frame::<Handshake, 4>::new([1, 2, 3, 4]);
+    let any: &Frame<AnyKind, 4> = handshake.coerce();
+    assert_eq!(any.bytes, [1, 2, 3, 4]);
+
+    let data = Frame::<Data, 2>::new([5, 6]);
+    let any: &Frame<AnyKind, 2> = data.coerce();
+    assert_eq!(any.bytes, [5, 6]);
+
+    let owned: Frame<AnyKind, 8> = Frame::<Handshake, 8>::new([0; 8]).into_coerced();
+    assert_eq!(owned.bytes.len(), 8);
+}
+
+#[test]
+fn concrete_length_expression() {
+    let data = Frame::<Data, 16>::new([7; 16]);
+    let any: Frame<AnyKind, 16> = data.to_coerced();
+    assert_eq!(any.bytes, [7; 16]);
+}
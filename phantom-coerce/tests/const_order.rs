@@ -0,0 +1,62 @@
+use phantom_coerce::Coerce;
+
+#[derive(Coerce, Clone)]
+#[coerce(
+    borrowed_from = "Permission<LEVEL>",
+    borrowed_to = "Permission<TO>",
+    when = "TO <= LEVEL"
+)]
+#[coerce(
+    owned_from = "Permission<LEVEL>",
+    owned_to = "Permission<TO>",
+    when = "LEVEL >= TO"
+)]
+#[coerce(
+    cloned_from = "Permission<LEVEL>",
+    cloned_to = "Permission<TO>",
+    when = "TO < LEVEL"
+)]
+struct Permission<const LEVEL: u8> {
+    holder: String,
+}
+
+impl<const LEVEL: u8> Permission<LEVEL> {
+    fn new(holder: &str) -> Self {
+        Self {
+            holder: holder.to_string(),
+        }
+    }
+}
+
+fn read_only(permission: &Permission<1>) -> &str {
+    &permission.holder
+}
+
+#[test]
+fn coerce_to_any_lower_level() {
+    let admin = Permission::<3>::new("alice");
+    assert_eq!(read_only(admin.coerce()), "alice");
+
+    let editor: &Permission<2> = admin.coerce();
+    assert_eq!(editor.holder, "alice");
+
+    // Equal levels are allowed by `<=`
+    let same: &Permission<3> = admin.coerce();
+    assert_eq!(same.holder, "alice");
+
+    let lowered: Permission<2> = admin.to_coerced();
+    assert_eq!(lowered.holder, "alice");
+
+    let owned: Permission<0> = admin.into_coerced();
+    assert_eq!(owned.holder, "alice");
+}
+
+#[test]
+fn slices_and_batches() {
+    let admins = vec![Permission::<3>::new("alice"), Permission::<3>::new("bob")];
+    let readers: &[Permission<1>] = Permission::coerce_all_ref(&admins);
+    assert_eq!(readers[1].holder, "bob");
+
+    let owned: Vec<Permission<1>> = Permission::coerce_all(admins);
+    assert_eq!(owned[0].holder, "alice");
+}
@@ -0,0 +1,78 @@
+use phantom_coerce::Coerce;
+use std::marker::PhantomData;
+
+trait Status: RequestCoversStatus {}
+
+struct Pending;
+struct Approved;
+struct Rejected;
+struct AnyStatus;
+
+impl Status for Pending {}
+impl Status for Approved {}
+impl Status for Rejected {}
+
+#[derive(Coerce)]
+#[coerce(
+    borrowed_from = "Request<Pending | Approved | Rejected>",
+    borrowed_to = "Request<AnyStatus>",
+    covers_all = "Status"
+)]
+struct Request<State> {
+    marker: PhantomData<State>,
+    id: u32,
+}
+
+fn request<State>(id: u32) -> Request<State> {
+    Request {
+        marker: PhantomData,
+        id,
+    }
+}
+
+#[test]
+fn every_status_coerces() {
+    let ids: Vec<u32> = [
+        request::<Pending>(1).coerce::<Request<AnyStatus>>().id,
+        request::<Approved>(2).coerce::<Request<AnyStatus>>().id,
+        request::<Rejected>(3).coerce::<Request<AnyStatus>>().id,
+    ]
+    .to_vec();
+    assert_eq!(ids, [1, 2, 3]);
+}
+
+mod named_param {
+    use phantom_coerce::Coerce;
+    use std::marker::PhantomData;
+
+    pub trait Unit: ReadingCoversUnit {}
+
+    pub struct Celsius;
+    pub struct Kelvin;
+    pub struct AnyUnit;
+    pub struct Sensor;
+
+    impl Unit for Celsius {}
+    impl Unit for Kelvin {}
+
+    #[derive(Coerce)]
+    #[coerce(
+        owned_from = "Reading<Celsius | Kelvin, Sensor>",
+        owned_to = "Reading<AnyUnit, Sensor>",
+        covers_all = "U: Unit"
+    )]
+    pub struct Reading<U, Source> {
+        marker: PhantomData<(U, Source)>,
+        pub value: f64,
+    }
+
+    #[test]
+    fn covers_named_parameter() {
+        let reading = Reading::<Kelvin, Sensor> {
+            marker: PhantomData,
+            value: 273.15,
+        };
+        let generic: Reading<AnyUnit, Sensor> = reading.into_coerced();
+        assert_eq!(generic.value, 273.15);
+    }
+}
@@ -0,0 +1,69 @@
+use phantom_coerce::Coerce;
+use std::borrow::Cow;
+use std::marker::PhantomData;
+
+#[derive(Clone)]
+struct Absolute;
+#[derive(Clone)]
+struct Relative;
+#[derive(Clone)]
+struct UnknownBase;
+#[derive(Clone)]
+struct File;
+
+#[derive(Coerce, Clone)]
+#[coerce(cow)]
+#[coerce(
+    borrowed_from = "TypedPath<Absolute | Relative, _>",
+    borrowed_to = "TypedPath<UnknownBase, _>"
+)]
+#[coerce(
+    cloned_from = "TypedPath<Absolute, _>",
+    cloned_to = "TypedPath<UnknownBase, _>"
+)]
+struct TypedPath<Base, Type> {
+    base: PhantomData<Base>,
+    ty: PhantomData<Type>,
+    path: String,
+}
+
+fn path<Base>(path: &str) -> TypedPath<Base, File> {
+    TypedPath {
+        base: PhantomData,
+        ty: PhantomData,
+        path: path.to_string(),
+    }
+}
+
+// The callee decides whether it needs its own copy
+fn remember<'a>(
+    path: impl Into<Cow<'a, TypedPath<UnknownBase, File>>>,
+    keep: bool,
+) -> Option<TypedPath<UnknownBase, File>> {
+    let path = path.into();
+    assert!(matches!(path, Cow::Borrowed(_)));
+    keep.then(|| path.into_owned())
+}
+
+#[test]
+fn references_convert_into_borrowed_cows() {
+    let absolute = path::<Absolute>("/etc");
+    let cow: Cow<'_, TypedPath<UnknownBase, File>> = Cow::from(&absolute);
+    assert_eq!(cow.path, "/etc");
+
+    assert!(remember(&absolute, false).is_none());
+    assert_eq!(remember(&absolute, true).unwrap().path, "/etc");
+
+    // Still usable, since the conversion only borrowed it
+    assert_eq!(absolute.path, "/etc");
+}
+
+#[test]
+fn generic_targets_accept_references() {
+    fn has_cow<'a, S: 'a>()
+    where
+        Cow<'a, TypedPath<UnknownBase, File>>: From<&'a S>,
+    {
+    }
+    has_cow::<TypedPath<Absolute, File>>();
+}
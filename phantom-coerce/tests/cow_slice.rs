@@ -0,0 +1,46 @@
+use phantom_coerce::Coerce;
+use std::borrow::Cow;
+use std::marker::PhantomData;
+
+#[derive(Clone)]
+struct Keyword;
+#[derive(Clone)]
+struct AnyMode;
+
+#[derive(Coerce, Clone)]
+#[coerce(borrowed_from = "Token<Keyword>", borrowed_to = "Token<AnyMode>")]
+#[coerce(owned_from = "Token<Keyword>", owned_to = "Token<AnyMode>")]
+struct Token<Mode> {
+    marker: PhantomData<Mode>,
+    text: &'static str,
+}
+
+fn tokens() -> Vec<Token<Keyword>> {
+    ["let", "fn"]
+        .into_iter()
+        .map(|text| Token {
+            marker: PhantomData,
+            text,
+        })
+        .collect()
+}
+
+#[test]
+fn borrowed_stays_borrowed() {
+    let tokens = tokens();
+    let generic: Cow<[Token<AnyMode>]> = Token::coerce_cow_slice(Cow::Borrowed(&tokens[..]));
+    let Cow::Borrowed(generic) = generic else {
+        panic!("expected a borrowed slice");
+    };
+    assert!(std::ptr::addr_eq(generic.as_ptr(), tokens.as_ptr()));
+    assert_eq!(generic[1].text, "fn");
+}
+
+#[test]
+fn owned_stays_owned() {
+    let generic: Cow<[Token<AnyMode>]> = Token::coerce_cow_slice(Cow::Owned(tokens()));
+    let Cow::Owned(generic) = generic else {
+        panic!("expected an owned vec");
+    };
+    assert_eq!(generic[0].text, "let");
+}
@@ -0,0 +1,74 @@
+use phantom_coerce::{Coerce, PhantomMarker};
+use std::marker::PhantomData;
+
+#[derive(PhantomMarker)]
+struct Absolute;
+#[derive(PhantomMarker)]
+#[phantom_marker(name = "any")]
+struct UnknownBase;
+#[derive(PhantomMarker)]
+struct File;
+
+#[derive(Coerce)]
+#[coerce(
+    borrowed_from = "TypedPath<Absolute, File>",
+    borrowed_to = "TypedPath<UnknownBase, File>"
+)]
+#[coerce(debug)]
+struct TypedPath<Base, Type> {
+    base: PhantomData<Base>,
+    ty: PhantomData<Type>,
+    path: String,
+}
+
+#[derive(Coerce)]
+#[coerce(
+    borrowed_from = "Labeled<Absolute, _>",
+    borrowed_to = "Labeled<UnknownBase, _>"
+)]
+#[coerce(debug)]
+struct Labeled<Base, Label> {
+    base: PhantomData<Base>,
+    label: Label,
+}
+
+fn path(path: &str) -> TypedPath<Absolute, File> {
+    TypedPath {
+        base: PhantomData,
+        ty: PhantomData,
+        path: path.to_string(),
+    }
+}
+
+#[test]
+fn debug_names_markers() {
+    let path = path("/x");
+    assert_eq!(
+        format!("{path:?}"),
+        r#"TypedPath<Absolute, File> { path: "/x" }"#
+    );
+
+    let generic: &TypedPath<UnknownBase, File> = path.coerce();
+    assert_eq!(
+        format!("{generic:?}"),
+        r#"TypedPath<any, File> { path: "/x" }"#
+    );
+}
+
+#[test]
+fn debug_pretty() {
+    assert_eq!(
+        format!("{:#?}", path("/x")),
+        "TypedPath<Absolute, File> {\n    path: \"/x\",\n}"
+    );
+}
+
+#[test]
+fn non_marker_parameters_use_type_name() {
+    let labeled = Labeled::<Absolute, u32> {
+        base: PhantomData,
+        label: 7,
+    };
+    let generic: &Labeled<UnknownBase, u32> = labeled.coerce();
+    assert_eq!(format!("{generic:?}"), "Labeled<any, u32> { label: 7 }");
+}
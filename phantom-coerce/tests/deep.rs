@@ -0,0 +1,87 @@
+use phantom_coerce::Coerce;
+use std::marker::PhantomData;
+
+#[derive(Clone)]
+struct Absolute;
+#[derive(Clone)]
+struct UnknownBase;
+#[derive(Clone)]
+struct File;
+struct UnknownType;
+
+#[derive(Clone)]
+struct Queued;
+#[derive(Clone)]
+struct AnyStatus;
+
+#[derive(Coerce, Clone)]
+#[coerce(
+    borrowed_from = "TypedPath<Absolute, File>",
+    borrowed_to = "TypedPath<UnknownBase, UnknownType>"
+)]
+#[coerce(
+    owned_from = "TypedPath<Absolute, _>",
+    owned_to = "TypedPath<UnknownBase, _>"
+)]
+#[coerce(
+    cloned_from = "TypedPath<Absolute, File>",
+    cloned_to = "TypedPath<UnknownBase, File>"
+)]
+struct TypedPath<Base, Type> {
+    base: PhantomData<Base>,
+    ty: PhantomData<Type>,
+    path: String,
+}
+
+#[derive(Coerce, Clone)]
+#[coerce(
+    borrowed_from = "Job<Queued, Absolute, File>",
+    borrowed_to = "Job<AnyStatus, UnknownBase, UnknownType>"
+)]
+#[coerce(
+    owned_from = "Job<Queued, Absolute, _>",
+    owned_to = "Job<AnyStatus, UnknownBase, _>"
+)]
+#[coerce(
+    cloned_from = "Job<Queued, Absolute, File>",
+    cloned_to = "Job<Queued, UnknownBase, File>"
+)]
+struct Job<Status, Base, Type> {
+    status: PhantomData<Status>,
+    #[coerce(deep)]
+    input: TypedPath<Base, Type>,
+    attempts: u32,
+}
+
+fn job() -> Job<Queued, Absolute, File> {
+    Job {
+        status: PhantomData,
+        input: TypedPath {
+            base: PhantomData,
+            ty: PhantomData,
+            path: "/srv/input.csv".to_string(),
+        },
+        attempts: 2,
+    }
+}
+
+#[test]
+fn deep_borrowed() {
+    let job = job();
+    let generic: &Job<AnyStatus, UnknownBase, UnknownType> = job.coerce();
+    assert_eq!(generic.input.path, "/srv/input.csv");
+    assert_eq!(generic.attempts, 2);
+}
+
+#[test]
+fn deep_owned_with_type_hole() {
+    let generic = job().into_coerced::<Job<AnyStatus, UnknownBase, File>>();
+    assert_eq!(generic.input.path, "/srv/input.csv");
+}
+
+#[test]
+fn deep_cloned() {
+    let job = job();
+    let generic: Job<Queued, UnknownBase, File> = job.to_coerced();
+    assert_eq!(generic.input.path, job.input.path);
+}
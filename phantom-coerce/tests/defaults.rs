@@ -0,0 +1,143 @@
+mod markers {
+    pub struct Absolute;
+    pub struct Relative;
+    pub struct UnknownBase;
+    pub struct Json;
+    pub struct Xml;
+    pub struct AnyFormat;
+}
+
+#[phantom_coerce::defaults(trait_vis = "pub", asref, allow(noop))]
+mod shapes {
+    use crate::markers::*;
+    use phantom_coerce::Coerce;
+    use std::marker::PhantomData;
+
+    #[derive(Coerce)]
+    #[coerce(
+        borrowed_from = "TypedPath<Absolute | Relative>",
+        borrowed_to = "TypedPath<UnknownBase>"
+    )]
+    #[coerce(
+        owned_from = "TypedPath<Absolute>",
+        owned_to = "TypedPath<UnknownBase>"
+    )]
+    pub struct TypedPath<Base> {
+        pub base: PhantomData<Base>,
+        pub path: String,
+    }
+
+    // `noop` is allowed by default, so the `Xml => Xml` pair is skipped
+    #[derive(Coerce)]
+    #[coerce(
+        borrowed_from = "Message<Json | Xml>",
+        borrowed_to = "Message<Xml | AnyFormat>"
+    )]
+    pub struct Message<Format> {
+        pub format: PhantomData<Format>,
+        pub content: String,
+    }
+
+    // Its own `trait_name` sits alongside the default `trait_vis`
+    #[derive(Coerce)]
+    #[coerce(trait_name = "Doc")]
+    #[coerce(borrowed_from = "Document<Json>", borrowed_to = "Document<AnyFormat>")]
+    pub struct Document<Format> {
+        pub format: PhantomData<Format>,
+        pub body: String,
+    }
+
+    #[phantom_coerce::defaults(generate = "traits_only")]
+    pub mod nested {
+        use crate::markers::*;
+        use phantom_coerce::Coerce;
+        use std::marker::PhantomData;
+
+        // Seeded from both levels: the outer `trait_vis` and `asref`, the inner `generate`
+        #[derive(Coerce)]
+        #[coerce(borrowed_from = "Record<Json>", borrowed_to = "Record<AnyFormat>")]
+        pub struct Record<Format> {
+            pub format: PhantomData<Format>,
+            pub id: u32,
+        }
+
+        impl<Format> Record<Format> {
+            // Free to define, since the derive adds no inherent `coerce`
+            pub fn coerce(&self) -> u32 {
+                self.id
+            }
+        }
+    }
+}
+
+use markers::*;
+use shapes::nested::{CoerceRefRecord, Record};
+use shapes::{
+    CoerceOwnedTypedPath, CoerceRefDoc, CoerceRefTypedPath, Document, Message, TypedPath,
+};
+use std::marker::PhantomData;
+
+fn path_len<P: CoerceRefTypedPath<TypedPath<UnknownBase>>>(path: &P) -> usize {
+    path.coerce().path.len()
+}
+
+fn body(doc: &impl CoerceRefDoc<Document<AnyFormat>>) -> &str {
+    &doc.coerce().body
+}
+
+fn content(message: impl AsRef<Message<AnyFormat>>) -> String {
+    message.as_ref().content.clone()
+}
+
+#[test]
+fn struct_options_apply_across_the_module() {
+    let path = TypedPath::<Relative> {
+        base: PhantomData,
+        path: "a/b".to_string(),
+    };
+    assert_eq!(path_len(&path), 3);
+
+    let owned = TypedPath::<Absolute> {
+        base: PhantomData,
+        path: "/a".to_string(),
+    };
+    let owned: TypedPath<UnknownBase> = CoerceOwnedTypedPath::into_coerced(owned);
+    assert_eq!(owned.path, "/a");
+
+    let doc = Document::<Json> {
+        format: PhantomData,
+        body: "{}".to_string(),
+    };
+    assert_eq!(body(&doc), "{}");
+}
+
+#[test]
+fn asref_is_added_to_borrowed_coercions() {
+    let message = Message::<Xml> {
+        format: PhantomData,
+        content: "<a/>".to_string(),
+    };
+    assert_eq!(content(&message), "<a/>");
+    assert_eq!(content(Box::new(message)), "<a/>");
+
+    let path = TypedPath::<Absolute> {
+        base: PhantomData,
+        path: "/a".to_string(),
+    };
+    let erased: &TypedPath<UnknownBase> = path.as_ref();
+    assert_eq!(erased.path, "/a");
+}
+
+#[test]
+fn nested_defaults_layer_on_outer_ones() {
+    let record = Record::<Json> {
+        format: PhantomData,
+        id: 7,
+    };
+    assert_eq!(record.coerce(), 7);
+
+    let erased: &Record<AnyFormat> = CoerceRefRecord::coerce(&record);
+    assert_eq!(erased.id, 7);
+    let erased: &Record<AnyFormat> = record.as_ref();
+    assert_eq!(erased.id, 7);
+}
@@ -0,0 +1,38 @@
+use phantom_coerce::Coerce;
+use std::marker::PhantomData;
+use std::path::{Path, PathBuf};
+
+struct Absolute;
+struct UnknownBase;
+struct File;
+
+#[derive(Coerce)]
+#[coerce(
+    borrowed_from = "TypedPath<Absolute, _>",
+    borrowed_to = "TypedPath<UnknownBase, _>"
+)]
+#[coerce(deref_inner = "path")]
+struct TypedPath<Base, Type> {
+    base: PhantomData<Base>,
+    ty: PhantomData<Type>,
+    path: PathBuf,
+    depth: usize,
+}
+
+#[test]
+fn methods_reach_the_data_field() {
+    let path = TypedPath::<Absolute, File> {
+        base: PhantomData,
+        ty: PhantomData,
+        path: PathBuf::from("/etc/hosts"),
+        depth: 2,
+    };
+    assert_eq!(path.extension(), None);
+    assert_eq!(path.file_name().unwrap(), "hosts");
+    assert_eq!(path.depth, 2);
+
+    // Coercion still goes through the struct, not the target
+    let generic: &TypedPath<UnknownBase, File> = path.coerce();
+    let inner: &Path = generic;
+    assert!(inner.is_absolute());
+}
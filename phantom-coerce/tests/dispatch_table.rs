@@ -0,0 +1,84 @@
+use phantom_coerce::tag::{DispatchTable, MarkerTag, Tagged};
+use phantom_coerce::{Coerce, dispatch_table};
+use std::marker::PhantomData;
+
+struct Json;
+struct Xml;
+struct AnyFormat;
+
+impl MarkerTag for Json {
+    const TAG: &'static str = "json";
+}
+
+impl MarkerTag for Xml {
+    const TAG: &'static str = "xml";
+}
+
+#[derive(Coerce)]
+#[coerce(
+    borrowed_from = "Message<Json | Xml>",
+    borrowed_to = "Message<AnyFormat>"
+)]
+#[coerce(owned_from = "Message<Json | Xml>", owned_to = "Message<AnyFormat>")]
+#[coerce(tag = "Format")]
+struct Message<Format> {
+    marker: PhantomData<Format>,
+    body: String,
+}
+
+fn keys(message: &Message<AnyFormat>) -> usize {
+    message.body.matches(':').count()
+}
+
+fn elements(message: &Message<AnyFormat>) -> usize {
+    message.body.matches("</").count()
+}
+
+static ROUTES: DispatchTable<Message<AnyFormat>, usize> = dispatch_table!(Message<AnyFormat> {
+    Json => keys,
+    Xml => elements,
+});
+
+fn message<Format>(body: &str) -> Message<Format> {
+    Message {
+        marker: PhantomData,
+        body: body.to_string(),
+    }
+}
+
+#[test]
+fn routes_by_tag() {
+    let tags: Vec<&str> = ROUTES.routes().iter().map(|(tag, _)| *tag).collect();
+    assert_eq!(tags, ["json", "xml"]);
+
+    let generic = message::<AnyFormat>("<a></a><b></b>");
+    assert_eq!(ROUTES.dispatch("xml", &generic), Some(2));
+    assert_eq!(ROUTES.dispatch("yaml", &generic), None);
+    assert!(ROUTES.get("json").is_some());
+}
+
+#[test]
+fn tagged_values() {
+    let erased: Tagged<Message<AnyFormat>> = message::<Json>(r#"{"a": 1}"#).into_tagged();
+    assert_eq!(ROUTES.dispatch_tagged(&erased), Some(1));
+
+    let xml = message::<Xml>("<a></a>");
+    let borrowed: Tagged<&Message<AnyFormat>> = Tagged::new("xml", xml.coerce());
+    assert_eq!(ROUTES.dispatch_tagged(&borrowed), Some(1));
+}
+
+#[test]
+fn specific_values() {
+    let json = message::<Json>(r#"{"a": 1, "b": 2}"#);
+    assert_eq!(ROUTES.dispatch_specific(json.tagged_ref()), Some(2));
+}
+
+#[test]
+fn local_table() {
+    let lengths = dispatch_table!(Message<AnyFormat> {
+        Xml => |message: &Message<AnyFormat>| message.body.len(),
+        Json => |message: &Message<AnyFormat>| message.body.len() * 2,
+    });
+    assert_eq!(lengths.dispatch("json", &message("ab")), Some(4));
+    assert_eq!(format!("{lengths:?}"), r#"["xml", "json"]"#);
+}
@@ -0,0 +1,73 @@
+mod shapes {
+    use phantom_coerce::Coerce;
+    use std::marker::PhantomData;
+
+    pub struct Absolute;
+    pub struct UnknownBase;
+
+    #[derive(Coerce)]
+    #[coerce(trait_vis = "pub", docs = "hidden")]
+    #[coerce(
+        borrowed_from = "TypedPath<Absolute>",
+        borrowed_to = "TypedPath<UnknownBase>",
+        asref
+    )]
+    #[coerce(
+        owned_from = "TypedPath<Absolute>",
+        owned_to = "TypedPath<UnknownBase>"
+    )]
+    pub struct TypedPath<Base> {
+        pub base: PhantomData<Base>,
+        pub path: String,
+    }
+
+    #[derive(Coerce, Clone)]
+    #[coerce(trait_vis = "pub", docs = "shown")]
+    #[coerce(
+        borrowed_from = "Document<Absolute>",
+        borrowed_to = "Document<UnknownBase>"
+    )]
+    #[coerce(
+        cloned_from = "Document<Absolute>",
+        cloned_to = "Document<UnknownBase>"
+    )]
+    pub struct Document<Base> {
+        pub base: PhantomData<Base>,
+        pub body: String,
+    }
+}
+
+use phantom_coerce::Coercible;
+use shapes::{
+    Absolute, CoerceClonedDocument, CoerceRefTypedPath, Document, TypedPath, UnknownBase,
+};
+use std::marker::PhantomData;
+
+// Hidden traits are still public, only missing from rustdoc
+fn path_len(path: &impl CoerceRefTypedPath<TypedPath<UnknownBase>>) -> usize {
+    path.coerce().path.len()
+}
+
+#[test]
+fn hidden_traits_keep_working() {
+    let path = TypedPath::<Absolute> {
+        base: PhantomData,
+        path: "/a".to_string(),
+    };
+    assert_eq!(path_len(&path), 2);
+    let erased: &TypedPath<UnknownBase> = path.as_ref();
+    assert_eq!(erased.path, "/a");
+
+    let owned: TypedPath<UnknownBase> = Coercible::into_coerced(path);
+    assert_eq!(owned.path, "/a");
+}
+
+#[test]
+fn shown_traits_keep_working() {
+    let doc = Document::<Absolute> {
+        base: PhantomData,
+        body: "text".to_string(),
+    };
+    let copy: Document<UnknownBase> = CoerceClonedDocument::to_coerced(&doc);
+    assert_eq!(copy.body, doc.body);
+}
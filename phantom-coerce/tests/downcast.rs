@@ -0,0 +1,78 @@
+use phantom_coerce::{Coerce, downcast_coerce};
+use std::any::Any;
+use std::marker::PhantomData;
+
+struct Validated;
+struct Unvalidated;
+struct AnyStatus;
+
+#[derive(Coerce)]
+#[coerce(
+    owned_from = "Request<Validated | Unvalidated>",
+    owned_to = "Request<AnyStatus>"
+)]
+struct Request<Status> {
+    marker: PhantomData<Status>,
+    url: String,
+}
+
+fn request<Status: 'static>(url: &str) -> Box<dyn Any> {
+    Box::new(Request::<Status> {
+        marker: PhantomData,
+        url: url.to_string(),
+    })
+}
+
+#[test]
+fn matching_type_is_coerced() {
+    let generic =
+        downcast_coerce::<Request<Validated>, Request<AnyStatus>>(request::<Validated>("/a"))
+            .ok()
+            .unwrap();
+    assert_eq!(generic.url, "/a");
+}
+
+#[test]
+fn other_type_is_returned_unchanged() {
+    let plugin = request::<Unvalidated>("/b");
+    let plugin = downcast_coerce::<Request<Validated>, Request<AnyStatus>>(plugin)
+        .err()
+        .unwrap();
+    assert!(plugin.is::<Request<Unvalidated>>());
+}
+
+#[test]
+fn candidates_chain() {
+    let erased = [
+        request::<Validated>("/a"),
+        request::<Unvalidated>("/b"),
+        request::<AnyStatus>("/c"),
+        Box::new(7_u32),
+    ];
+    let urls: Vec<_> = erased
+        .into_iter()
+        .filter_map(|value| {
+            downcast_coerce::<Request<Validated>, Request<AnyStatus>>(value)
+                .or_else(downcast_coerce::<Request<Unvalidated>, Request<AnyStatus>>)
+                .or_else(downcast_coerce::<Request<AnyStatus>, Request<AnyStatus>>)
+                .ok()
+        })
+        .map(|request| request.url)
+        .collect();
+    assert_eq!(urls, ["/a", "/b", "/c"]);
+}
+
+#[test]
+fn allocation_is_reused() {
+    let plugin = request::<Validated>("/d");
+    let address = (&*plugin as *const dyn Any).cast::<u8>();
+
+    let generic = downcast_coerce::<Request<Validated>, Request<AnyStatus>>(plugin)
+        .ok()
+        .unwrap();
+    assert_eq!(
+        (&*generic as *const Request<AnyStatus>).cast::<u8>(),
+        address
+    );
+    assert_eq!(generic.url, "/d");
+}
@@ -0,0 +1,74 @@
+use phantom_coerce::{Coerce, HasAnyMarker};
+use std::marker::PhantomData;
+
+struct Absolute;
+struct Relative;
+struct UnknownBase;
+struct File;
+struct UnknownType;
+
+#[derive(Coerce)]
+#[coerce(
+    borrowed_from = "TypedPath<Absolute | Relative, File>",
+    borrowed_to = "TypedPath<UnknownBase, UnknownType>"
+)]
+#[coerce(
+    owned_from = "TypedPath<Absolute | Relative, File>",
+    owned_to = "TypedPath<UnknownBase, UnknownType>"
+)]
+#[coerce(
+    borrowed_from = "TypedPath<Absolute, File>",
+    borrowed_to = "TypedPath<UnknownBase, File>"
+)]
+#[coerce(erased = "TypedPath<UnknownBase, UnknownType>")]
+struct TypedPath<Base, Type> {
+    base: PhantomData<Base>,
+    ty: PhantomData<Type>,
+    path: String,
+}
+
+fn path<Base>(path: &str) -> TypedPath<Base, File> {
+    TypedPath {
+        base: PhantomData,
+        ty: PhantomData,
+        path: path.to_string(),
+    }
+}
+
+/// Generic storage needing only the `HasAnyMarker` bound
+struct Store<E>(Vec<E>);
+
+impl<E> Store<E> {
+    fn push<T: HasAnyMarker<Erased = E>>(&mut self, value: T) {
+        self.0.push(value.erase());
+    }
+}
+
+fn erased_path<T: HasAnyMarker<Erased = TypedPath<UnknownBase, UnknownType>>>(value: &T) -> &str {
+    &value.erase_ref().path
+}
+
+#[test]
+fn erase_each_source() {
+    let mut store = Store(Vec::new());
+    store.push(path::<Absolute>("/etc"));
+    store.push(path::<Relative>("src"));
+
+    let paths: Vec<&str> = store.0.iter().map(|path| path.path.as_str()).collect();
+    assert_eq!(paths, ["/etc", "src"]);
+}
+
+#[test]
+fn erase_ref() {
+    assert_eq!(erased_path(&path::<Absolute>("/etc")), "/etc");
+}
+
+#[test]
+fn erased_type_erases_to_itself() {
+    let erased: TypedPath<UnknownBase, UnknownType> = path::<Relative>("src").erase();
+    assert_eq!(erased_path(&erased), "src");
+
+    let mut store = Store(Vec::new());
+    store.push(erased);
+    assert_eq!(store.0[0].path, "src");
+}
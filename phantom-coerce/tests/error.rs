@@ -0,0 +1,34 @@
+use phantom_coerce::CoerceError;
+use std::marker::PhantomData;
+
+struct Validated;
+struct AnyStatus;
+
+struct Request<Status>(PhantomData<Status>);
+
+#[test]
+fn records_type_names_and_message() {
+    let err = CoerceError::new::<Request<AnyStatus>, Request<Validated>>("missing signature");
+    assert_eq!(
+        err.source_type(),
+        std::any::type_name::<Request<AnyStatus>>()
+    );
+    assert_eq!(
+        err.target_type(),
+        std::any::type_name::<Request<Validated>>()
+    );
+    assert_eq!(err.message(), "missing signature");
+}
+
+#[test]
+fn display_names_both_types() {
+    let err = CoerceError::new::<Request<AnyStatus>, Request<Validated>>(format!("{} bytes", 3));
+    assert_eq!(
+        err.to_string(),
+        "cannot coerce error::Request<error::AnyStatus> into \
+         error::Request<error::Validated>: 3 bytes"
+    );
+
+    let boxed: Box<dyn std::error::Error> = Box::new(err);
+    assert!(boxed.source().is_none());
+}
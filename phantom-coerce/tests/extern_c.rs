@@ -0,0 +1,56 @@
+use phantom_coerce::Coerce;
+use std::marker::PhantomData;
+
+struct Validated;
+struct AnyStatus;
+
+#[derive(Coerce)]
+#[repr(C)]
+#[coerce(
+    borrowed_from = "Handle<Validated>",
+    borrowed_to = "Handle<AnyStatus>",
+    extern_c = "handle_view_any"
+)]
+#[coerce(
+    owned_from = "Handle<Validated>",
+    owned_to = "Handle<AnyStatus>",
+    extern_c = "handle_into_any"
+)]
+struct Handle<Status> {
+    marker: PhantomData<Status>,
+    id: u64,
+}
+
+// Declared the way a C header would, to resolve the exported symbols by name
+unsafe extern "C" {
+    #[link_name = "handle_view_any"]
+    fn c_handle_view_any(source: *const Handle<Validated>) -> *const Handle<AnyStatus>;
+    #[link_name = "handle_into_any"]
+    fn c_handle_into_any(source: Handle<Validated>) -> Handle<AnyStatus>;
+}
+
+fn handle(id: u64) -> Handle<Validated> {
+    Handle {
+        marker: PhantomData,
+        id,
+    }
+}
+
+#[test]
+fn shims_are_callable_from_rust() {
+    let validated = handle(7);
+    let view: &Handle<AnyStatus> = handle_view_any(&validated);
+    assert_eq!(view.id, 7);
+    assert_eq!(handle_into_any(validated).id, 7);
+}
+
+#[test]
+fn shims_are_exported_unmangled() {
+    let validated = handle(42);
+    let view = unsafe { c_handle_view_any(&validated) };
+    assert_eq!(
+        view.cast::<()>(),
+        (&validated as *const Handle<Validated>).cast::<()>()
+    );
+    assert_eq!(unsafe { c_handle_into_any(validated) }.id, 42);
+}
@@ -0,0 +1,95 @@
+use std::any::type_name;
+use std::marker::PhantomData;
+
+mod paths {
+    use phantom_coerce::Coerce;
+    use std::marker::PhantomData;
+
+    #[derive(Clone)]
+    pub struct Absolute;
+    #[derive(Clone)]
+    pub struct Relative;
+    #[derive(Clone)]
+    pub struct UnknownBase;
+    #[derive(Clone)]
+    pub struct File;
+
+    #[derive(Coerce, Clone)]
+    #[coerce(
+        borrowed_from = "TypedPath<Absolute | Relative, File>",
+        borrowed_to = "TypedPath<UnknownBase, File>"
+    )]
+    #[coerce(
+        cloned_from = "TypedPath<Relative, File>",
+        cloned_to = "TypedPath<UnknownBase, File>"
+    )]
+    pub struct TypedPath<Base, Type> {
+        pub base: PhantomData<Base>,
+        pub ty: PhantomData<Type>,
+    }
+}
+
+use paths::{Absolute, File, Relative, TypedPath, UnknownBase};
+
+#[derive(phantom_coerce::Coerce)]
+#[coerce(owned_from = "Request<Absolute>", owned_to = "Request<UnknownBase>")]
+struct Request<Status> {
+    marker: PhantomData<Status>,
+}
+
+macro_rules! record {
+    ($routes:ident; $mode:ident $source:ty => $target:ty) => {
+        $routes.push((
+            stringify!($mode),
+            type_name::<$source>(),
+            type_name::<$target>(),
+        ));
+    };
+}
+
+#[test]
+fn invokes_callback_per_coercion() {
+    let mut routes = Vec::new();
+    macro_rules! push {
+        ($($tokens:tt)*) => { record!(routes; $($tokens)*) };
+    }
+    phantom_coerce::for_each_coercion!(paths::TypedPath, push);
+
+    assert_eq!(
+        routes,
+        [
+            (
+                "borrowed",
+                type_name::<TypedPath<Absolute, File>>(),
+                type_name::<TypedPath<UnknownBase, File>>()
+            ),
+            (
+                "borrowed",
+                type_name::<TypedPath<Relative, File>>(),
+                type_name::<TypedPath<UnknownBase, File>>()
+            ),
+            (
+                "cloned",
+                type_name::<TypedPath<Relative, File>>(),
+                type_name::<TypedPath<UnknownBase, File>>()
+            ),
+        ]
+    );
+}
+
+#[test]
+fn callback_can_perform_coercion() {
+    let mut converted = 0;
+    macro_rules! convert {
+        (owned $source:ty => $target:ty) => {{
+            let source: $source = Request {
+                marker: PhantomData,
+            };
+            let _: $target = source.into_coerced();
+            converted += 1;
+        }};
+    }
+    // Generic arguments on the type are ignored
+    phantom_coerce::for_each_coercion!(Request<Absolute>, convert);
+    assert_eq!(converted, 1);
+}
@@ -0,0 +1,79 @@
+use std::any::type_name;
+
+mod paths {
+    use phantom_coerce::Coerce;
+    use std::marker::PhantomData;
+
+    pub struct Absolute;
+    pub struct Relative;
+    pub struct UnknownBase;
+    pub struct File;
+    pub struct Directory;
+
+    #[derive(Coerce)]
+    #[coerce(
+        borrowed_from = "TypedPath<Absolute | Relative, File>",
+        borrowed_to = "TypedPath<UnknownBase, File>"
+    )]
+    #[coerce(
+        owned_from = "TypedPath<Absolute, Directory>",
+        owned_to = "TypedPath<UnknownBase, Directory>"
+    )]
+    #[coerce(
+        owned_from = "TypedPath<Relative, _>",
+        owned_to = "TypedPath<UnknownBase, _>"
+    )]
+    pub struct TypedPath<Base, Type> {
+        pub base: PhantomData<Base>,
+        pub ty: PhantomData<Type>,
+    }
+}
+
+use paths::{Absolute, Directory, File, Relative, TypedPath};
+
+fn push_name<T>(names: &mut Vec<&'static str>) {
+    names.push(type_name::<T>());
+}
+
+fn specific_names() -> Vec<&'static str> {
+    vec![
+        type_name::<TypedPath<Absolute, File>>(),
+        type_name::<TypedPath<Relative, File>>(),
+        type_name::<TypedPath<Absolute, Directory>>(),
+    ]
+}
+
+thread_local! {
+    static REGISTERED: std::cell::RefCell<Vec<&'static str>> = const { std::cell::RefCell::new(Vec::new()) };
+}
+
+fn register<T>() {
+    REGISTERED.with(|names| names.borrow_mut().push(type_name::<T>()));
+}
+
+#[test]
+fn function_per_specific_type() {
+    phantom_coerce::for_each_specific!(paths::TypedPath, register);
+    assert_eq!(REGISTERED.with(|names| names.take()), specific_names());
+}
+
+#[test]
+fn call_with_arguments() {
+    // Generic `_` sources are skipped, and each type is visited once
+    let mut names = Vec::new();
+    phantom_coerce::for_each_specific!(paths::TypedPath, push_name(&mut names));
+    assert_eq!(names, specific_names());
+}
+
+#[test]
+fn closure_names_the_type() {
+    let mut names = Vec::new();
+    phantom_coerce::for_each_specific!(paths::TypedPath, |P| names.push(type_name::<P>()));
+    assert_eq!(names, specific_names());
+
+    let mut sizes = Vec::new();
+    phantom_coerce::for_each_specific!(paths::TypedPath, |P| {
+        sizes.push(std::mem::size_of::<P>());
+    });
+    assert_eq!(sizes, [0, 0, 0]);
+}
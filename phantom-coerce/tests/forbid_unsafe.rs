@@ -0,0 +1,39 @@
+// With the `forbid_unsafe` feature, every derive rebuilds instead of transmuting
+#![cfg(feature = "forbid_unsafe")]
+#![forbid(unsafe_code)]
+
+use phantom_coerce::Coerce;
+use std::marker::PhantomData;
+
+#[derive(Clone)]
+struct Json;
+#[derive(Clone)]
+struct AnyFormat;
+
+#[derive(Coerce, Clone)]
+#[coerce(owned_from = "Message<Json>", owned_to = "Message<AnyFormat>")]
+#[coerce(cloned_from = "Message<Json>", cloned_to = "Message<AnyFormat>")]
+struct Message<Format> {
+    marker: PhantomData<Format>,
+    content: String,
+}
+
+#[test]
+fn coercions_without_unsafe() {
+    let json = Message::<Json> {
+        marker: PhantomData,
+        content: "{}".to_string(),
+    };
+
+    let cloned: Message<AnyFormat> = json.to_coerced();
+    assert_eq!(cloned.content, "{}");
+
+    let owned = json.into_coerced::<Message<AnyFormat>>();
+    assert_eq!(owned.content, "{}");
+}
+
+#[test]
+fn borrowed_rejected() {
+    let t = trybuild::TestCases::new();
+    t.compile_fail("tests/ui-forbid-unsafe/*.rs");
+}
@@ -0,0 +1,62 @@
+use phantom_coerce::Coerce;
+use std::ffi::OsStr;
+use std::marker::PhantomData;
+use std::path::{Path, PathBuf};
+
+struct Absolute;
+struct UnknownBase;
+struct File;
+
+#[derive(Coerce)]
+#[coerce(
+    borrowed_from = "TypedPath<Absolute, _>",
+    borrowed_to = "TypedPath<UnknownBase, _>"
+)]
+#[coerce(forward_asref = "std::path::Path", forward_asref = "OsStr")]
+struct TypedPath<Base, Type> {
+    base: PhantomData<Base>,
+    ty: PhantomData<Type>,
+    path: PathBuf,
+}
+
+fn file_name(path: impl AsRef<Path>) -> Option<String> {
+    path.as_ref()
+        .file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+}
+
+#[test]
+fn forwards_for_every_marker() {
+    let path = TypedPath::<Absolute, File> {
+        base: PhantomData,
+        ty: PhantomData,
+        path: PathBuf::from("/etc/hosts"),
+    };
+    assert_eq!(file_name(&path), Some("hosts".to_string()));
+
+    let generic: &TypedPath<UnknownBase, File> = path.coerce();
+    assert_eq!(file_name(generic), Some("hosts".to_string()));
+    let os: &OsStr = generic.as_ref();
+    assert_eq!(os, "/etc/hosts");
+}
+
+struct Utf8;
+struct AnyEncoding;
+
+#[derive(Coerce)]
+#[coerce(borrowed_from = "Text<Utf8>", borrowed_to = "Text<AnyEncoding>")]
+#[coerce(forward_asref = "[u8]")]
+struct Text<Encoding> {
+    marker: PhantomData<Encoding>,
+    bytes: Vec<u8>,
+}
+
+#[test]
+fn forwards_to_slices() {
+    let text = Text::<Utf8> {
+        marker: PhantomData,
+        bytes: b"abc".to_vec(),
+    };
+    let bytes: &[u8] = text.coerce::<Text<AnyEncoding>>().as_ref();
+    assert_eq!(bytes, b"abc");
+}
@@ -0,0 +1,92 @@
+use phantom_coerce::Coerce;
+use std::collections::{LinkedList, VecDeque};
+use std::marker::PhantomData;
+use std::rc::Rc;
+use std::sync::Arc;
+
+struct Validated;
+struct Unvalidated;
+struct AnyStatus;
+
+#[derive(Coerce)]
+#[coerce(
+    owned_from = "Request<Validated | Unvalidated>",
+    owned_to = "Request<AnyStatus>"
+)]
+#[coerce(from_iterator)]
+struct Request<Status> {
+    marker: PhantomData<Status>,
+    url: String,
+}
+
+fn requests<Status>(urls: &[&str]) -> impl Iterator<Item = Request<Status>> {
+    urls.iter().map(|url| Request {
+        marker: PhantomData,
+        url: url.to_string(),
+    })
+}
+
+fn urls<'a>(requests: impl IntoIterator<Item = &'a Request<AnyStatus>>) -> Vec<&'a str> {
+    requests.into_iter().map(|r| r.url.as_str()).collect()
+}
+
+#[test]
+fn collects_into_generic_vec() {
+    let generic: Vec<Request<AnyStatus>> = requests::<Validated>(&["/a", "/b"])
+        .filter(|r| r.url != "/b")
+        .collect();
+    assert_eq!(urls(&generic), ["/a"]);
+}
+
+#[test]
+fn extends_through_collect() {
+    let mut generic: Vec<Request<AnyStatus>> = requests::<Validated>(&["/a"]).collect();
+    generic.extend(requests::<Unvalidated>(&["/b"]).collect::<Vec<Request<AnyStatus>>>());
+    assert_eq!(urls(&generic), ["/a", "/b"]);
+}
+
+#[test]
+fn collects_into_other_collections() {
+    let deque: VecDeque<Request<AnyStatus>> = requests::<Validated>(&["/a"]).collect();
+    let list: LinkedList<Request<AnyStatus>> = requests::<Unvalidated>(&["/b"]).collect();
+    let boxed: Box<[Request<AnyStatus>]> = requests::<Validated>(&["/c"]).collect();
+    let rc: Rc<[Request<AnyStatus>]> = requests::<Validated>(&["/d"]).collect();
+    let arc: Arc<[Request<AnyStatus>]> = requests::<Unvalidated>(&["/e"]).collect();
+    assert_eq!(urls(&deque), ["/a"]);
+    assert_eq!(urls(&list), ["/b"]);
+    assert_eq!(urls(boxed.iter()), ["/c"]);
+    assert_eq!(urls(rc.iter()), ["/d"]);
+    assert_eq!(urls(arc.iter()), ["/e"]);
+}
+
+mod type_holes {
+    use phantom_coerce::Coerce;
+    use std::marker::PhantomData;
+
+    struct Absolute;
+    struct UnknownBase;
+    struct File;
+
+    #[derive(Coerce)]
+    #[coerce(
+        owned_from = "TypedPath<Absolute, _>",
+        owned_to = "TypedPath<UnknownBase, _>"
+    )]
+    #[coerce(from_iterator)]
+    struct TypedPath<Base, Type> {
+        marker: PhantomData<(Base, Type)>,
+        path: String,
+    }
+
+    #[test]
+    fn collects_with_type_holes() {
+        let paths: Vec<TypedPath<UnknownBase, File>> = ["/a", "/b"]
+            .into_iter()
+            .map(|path| TypedPath::<Absolute, File> {
+                marker: PhantomData,
+                path: path.to_string(),
+            })
+            .collect();
+        assert_eq!(paths[1].path, "/b");
+    }
+}
@@ -0,0 +1,78 @@
+use phantom_coerce::Coerce;
+use std::marker::PhantomData;
+
+struct Absolute;
+struct UnknownBase;
+
+#[derive(Coerce)]
+#[coerce(generate = "traits_only")]
+#[coerce(
+    borrowed_from = "TypedPath<Absolute>",
+    borrowed_to = "TypedPath<UnknownBase>"
+)]
+#[coerce(
+    owned_from = "TypedPath<Absolute>",
+    owned_to = "TypedPath<UnknownBase>"
+)]
+struct TypedPath<Base> {
+    base: PhantomData<Base>,
+    path: String,
+}
+
+// The struct's own methods may use the names the derive would otherwise take
+impl<Base> TypedPath<Base> {
+    fn coerce(&self) -> &str {
+        &self.path
+    }
+
+    fn into_coerced(self) -> String {
+        self.path
+    }
+}
+
+#[test]
+fn traits_only_leaves_method_names_free() {
+    let path = TypedPath::<Absolute> {
+        base: PhantomData,
+        path: "/etc".to_string(),
+    };
+    assert_eq!(path.coerce(), "/etc");
+
+    let any: &TypedPath<UnknownBase> = CoerceRefTypedPath::coerce(&path);
+    assert_eq!(any.path, "/etc");
+
+    let any: TypedPath<UnknownBase> = CoerceOwnedTypedPath::into_coerced(path);
+    assert_eq!(any.into_coerced(), "/etc");
+}
+
+struct Draft;
+struct AnyState;
+
+#[derive(Coerce)]
+#[coerce(generate = "asref_only")]
+#[coerce(borrowed_from = "Document<Draft>", borrowed_to = "Document<AnyState>")]
+struct Document<State> {
+    state: PhantomData<State>,
+    body: String,
+}
+
+impl<State> Document<State> {
+    fn coerce(&self) -> usize {
+        self.body.len()
+    }
+}
+
+fn body_len(doc: impl AsRef<Document<AnyState>>) -> usize {
+    doc.as_ref().body.len()
+}
+
+#[test]
+fn asref_only_generates_asref_impls() {
+    let doc = Document::<Draft> {
+        state: PhantomData,
+        body: "draft".to_string(),
+    };
+    assert_eq!(doc.coerce(), 5);
+    assert_eq!(body_len(&doc), 5);
+    assert_eq!(body_len(std::rc::Rc::new(doc)), 5);
+}
@@ -0,0 +1,77 @@
+use phantom_coerce::Coerce;
+use std::marker::PhantomData;
+
+struct Json;
+struct Xml;
+struct Protobuf;
+struct AnyFormat;
+
+struct High;
+struct Low;
+struct AnyPriority;
+
+#[derive(Coerce)]
+#[coerce(
+    borrowed_from = "Msg<(Json | Xml), (High | Low)> | Msg<Protobuf, Low>",
+    borrowed_to = "Msg<AnyFormat, AnyPriority>"
+)]
+#[coerce(
+    owned_from = "(Msg<Json, High> | Msg<Xml, High>)",
+    owned_to = "Msg<AnyFormat, High>"
+)]
+struct Msg<Format, Priority> {
+    format: PhantomData<Format>,
+    priority: PhantomData<Priority>,
+    body: String,
+}
+
+fn msg<Format, Priority>(body: &str) -> Msg<Format, Priority> {
+    Msg {
+        format: PhantomData,
+        priority: PhantomData,
+        body: body.to_string(),
+    }
+}
+
+#[test]
+fn grouped_parameter_alternatives() {
+    let json_high = msg::<Json, High>("a");
+    let xml_low = msg::<Xml, Low>("b");
+    let proto_low = msg::<Protobuf, Low>("c");
+
+    let generic: &Msg<AnyFormat, AnyPriority> = json_high.coerce();
+    assert_eq!(generic.body, "a");
+    let generic: &Msg<AnyFormat, AnyPriority> = xml_low.coerce();
+    assert_eq!(generic.body, "b");
+    let generic: &Msg<AnyFormat, AnyPriority> = proto_low.coerce();
+    assert_eq!(generic.body, "c");
+}
+
+#[test]
+fn grouped_top_level_alternatives() {
+    let owned: Msg<AnyFormat, High> = msg::<Xml, High>("d").into_coerced();
+    assert_eq!(owned.body, "d");
+}
+
+struct Wrapped<T>(PhantomData<T>);
+
+// Alternatives nested inside an argument expand as well
+#[derive(Coerce)]
+#[coerce(
+    borrowed_from = "Nested<Wrapped<Json | Xml>>",
+    borrowed_to = "Nested<Wrapped<AnyFormat>>"
+)]
+struct Nested<Inner> {
+    inner: PhantomData<Inner>,
+    id: u32,
+}
+
+#[test]
+fn nested_argument_alternatives() {
+    let nested = Nested::<Wrapped<Xml>> {
+        inner: PhantomData,
+        id: 9,
+    };
+    let generic: &Nested<Wrapped<AnyFormat>> = nested.coerce();
+    assert_eq!(generic.id, 9);
+}
@@ -0,0 +1,89 @@
+use phantom_coerce::Coerce;
+use std::cell::RefCell;
+use std::marker::PhantomData;
+
+#[derive(Clone)]
+struct Validated;
+#[derive(Clone)]
+struct Unvalidated;
+#[derive(Clone)]
+struct AnyStatus;
+
+thread_local! {
+    static AUDIT: RefCell<Vec<String>> = const { RefCell::new(Vec::new()) };
+}
+
+fn audit_log() -> Vec<String> {
+    AUDIT.with(|log| log.borrow_mut().drain(..).collect())
+}
+
+mod audit {
+    use super::*;
+
+    // Generic over the status, so one hook serves every source
+    pub fn downgrade<Status>(request: &Request<Status>) {
+        AUDIT.with(|log| log.borrow_mut().push(request.url.clone()));
+    }
+
+    pub fn snapshot(request: &Request<Validated>) {
+        AUDIT.with(|log| log.borrow_mut().push(format!("copy {}", request.url)));
+    }
+}
+
+#[derive(Coerce, Clone)]
+#[coerce(
+    owned_from = "Request<Validated | Unvalidated>",
+    owned_to = "Request<AnyStatus>",
+    guard = "audit::downgrade"
+)]
+#[coerce(
+    cloned_from = "Request<Validated>",
+    cloned_to = "Request<AnyStatus>",
+    guard = "audit::snapshot"
+)]
+struct Request<Status> {
+    marker: PhantomData<Status>,
+    url: String,
+}
+
+fn request<Status>(url: &str) -> Request<Status> {
+    Request {
+        marker: PhantomData,
+        url: url.to_string(),
+    }
+}
+
+#[test]
+fn guard_runs_before_each_coercion() {
+    let validated = request::<Validated>("/checkout");
+    let _cloned: Request<AnyStatus> = validated.to_coerced();
+    let _owned: Request<AnyStatus> = validated.into_coerced();
+    let _other: Request<AnyStatus> = request::<Unvalidated>("/login").into_coerced();
+
+    assert_eq!(audit_log(), ["copy /checkout", "/checkout", "/login"]);
+}
+
+fn count_rebuild(_: &Report<Validated>) {
+    AUDIT.with(|log| log.borrow_mut().push("report".to_string()));
+}
+
+// Guards also run in safe mode
+#[derive(Coerce)]
+#[coerce(safe)]
+#[coerce(
+    owned_from = "Report<Validated>",
+    owned_to = "Report<AnyStatus>",
+    guard = "count_rebuild"
+)]
+struct Report<Status> {
+    marker: PhantomData<Status>,
+}
+
+#[test]
+fn guard_in_safe_mode() {
+    let report = Report::<Validated> {
+        marker: PhantomData,
+    };
+    let _generic: Report<AnyStatus> = report.into_coerced();
+    assert_eq!(audit_log(), ["report"]);
+}
@@ -0,0 +1,57 @@
+use phantom_coerce::Coerce;
+use std::marker::PhantomData;
+
+#[derive(Clone)]
+struct Absolute;
+#[derive(Clone)]
+struct Relative;
+#[derive(Clone)]
+struct UnknownBase;
+#[derive(Clone)]
+struct File;
+#[derive(Clone)]
+struct Directory;
+#[derive(Clone)]
+struct UnknownType;
+
+#[derive(Coerce, Clone)]
+#[coerce(include = "tests/coercions/typed_path.coerce")]
+#[coerce(
+    borrowed_from = "TypedPath<Relative, Directory>",
+    borrowed_to = "TypedPath<UnknownBase, UnknownType>"
+)]
+struct TypedPath<Base, Type> {
+    base: PhantomData<Base>,
+    ty: PhantomData<Type>,
+    path: String,
+}
+
+fn path<Base, Type>(path: &str) -> TypedPath<Base, Type> {
+    TypedPath {
+        base: PhantomData,
+        ty: PhantomData,
+        path: path.to_string(),
+    }
+}
+
+#[test]
+fn included_coercions() {
+    let file = path::<Absolute, File>("/etc/hosts");
+    let by_base: &TypedPath<UnknownBase, File> = file.coerce();
+    let by_type: &TypedPath<Absolute, UnknownType> = file.coerce();
+    assert_eq!(by_base.path, by_type.path);
+
+    let relative = path::<Relative, File>("hosts");
+    let cloned: TypedPath<UnknownBase, File> = relative.to_coerced();
+    assert_eq!(cloned.path, "hosts");
+
+    let owned: TypedPath<UnknownBase, UnknownType> = file.into_coerced();
+    assert_eq!(owned.path, "/etc/hosts");
+}
+
+#[test]
+fn included_alongside_attributes() {
+    let dir = path::<Relative, Directory>("src");
+    let generic: &TypedPath<UnknownBase, UnknownType> = dir.coerce();
+    assert_eq!(generic.path, "src");
+}
@@ -0,0 +1,81 @@
+mod paths {
+    use phantom_coerce::Coerce;
+    use std::marker::PhantomData;
+
+    pub struct Absolute;
+    pub struct Relative;
+    pub struct UnknownBase;
+    pub struct File;
+
+    #[derive(Coerce)]
+    #[coerce(
+        borrowed_from = "TypedPath<Absolute, _>",
+        borrowed_to = "TypedPath<UnknownBase, _>"
+    )]
+    #[coerce(
+        owned_from = "TypedPath<Absolute, _>",
+        owned_to = "TypedPath<UnknownBase, _>"
+    )]
+    #[coerce(
+        cloned_from = "TypedPath<Absolute, _>",
+        cloned_to = "TypedPath<UnknownBase, _>"
+    )]
+    #[coerce(extensible)]
+    pub struct TypedPath<Base, Type> {
+        base: PhantomData<Base>,
+        kind: PhantomData<Type>,
+        path: String,
+    }
+
+    impl<Base, Type> TypedPath<Base, Type> {
+        pub fn new(path: &str) -> Self {
+            Self {
+                base: PhantomData,
+                kind: PhantomData,
+                path: path.to_string(),
+            }
+        }
+
+        pub fn path(&self) -> &str {
+            &self.path
+        }
+    }
+}
+
+mod relative_paths {
+    use crate::paths::{Relative, UnknownBase};
+
+    #[phantom_coerce::coercions(
+        borrowed TypedPath<Relative, _> => TypedPath<UnknownBase, _>;
+        owned TypedPath<Relative, _> => TypedPath<UnknownBase, _>;
+        cloned TypedPath<Relative, _> => TypedPath<UnknownBase, _>;
+    )]
+    impl<Base, Type> crate::paths::TypedPath<Base, Type> {}
+}
+
+// The inherent methods are private to `paths`, so go through the generated traits
+use paths::{
+    CoerceClonedTypedPath, CoerceOwnedTypedPath, CoerceRefTypedPath, File, Relative, TypedPath,
+    UnknownBase,
+};
+
+#[test]
+fn coercions_added_from_another_module() {
+    let relative = TypedPath::<Relative, File>::new("src/lib.rs");
+
+    let view: &TypedPath<UnknownBase, File> = relative.coerce();
+    assert_eq!(view.path(), "src/lib.rs");
+
+    let cloned: TypedPath<UnknownBase, File> = relative.to_coerced();
+    assert_eq!(cloned.path(), "src/lib.rs");
+
+    let owned: TypedPath<UnknownBase, File> = relative.into_coerced();
+    assert_eq!(owned.path(), "src/lib.rs");
+}
+
+#[test]
+fn original_coercions_still_work() {
+    let absolute = TypedPath::<paths::Absolute, File>::new("/etc");
+    let view: &TypedPath<UnknownBase, File> = absolute.coerce();
+    assert_eq!(view.path(), "/etc");
+}
@@ -0,0 +1,54 @@
+use phantom_coerce::Coerce;
+use std::marker::PhantomData;
+
+#[derive(Clone)]
+struct Absolute;
+#[derive(Clone)]
+struct Relative;
+#[derive(Clone)]
+struct UnknownBase;
+#[derive(Clone)]
+struct File;
+
+#[derive(Coerce, Clone)]
+#[coerce(layout_checks)]
+#[coerce(
+    borrowed_from = "TypedPath<Absolute | Relative, File>",
+    borrowed_to = "TypedPath<UnknownBase, File>"
+)]
+#[coerce(
+    owned_from = "TypedPath<Absolute, File>",
+    owned_to = "TypedPath<UnknownBase, File>"
+)]
+#[coerce(
+    cloned_from = "TypedPath<Relative, _>",
+    cloned_to = "TypedPath<UnknownBase, _>"
+)]
+struct TypedPath<Base, Type> {
+    base: PhantomData<Base>,
+    ty: PhantomData<Type>,
+    path: String,
+}
+
+fn path<Base>(path: &str) -> TypedPath<Base, File> {
+    TypedPath {
+        base: PhantomData,
+        ty: PhantomData,
+        path: path.to_string(),
+    }
+}
+
+// The assertions only run in debug builds, where they must hold for every mode
+#[test]
+fn checked_coercions() {
+    let absolute = path::<Absolute>("/etc/hosts");
+    let borrowed: &TypedPath<UnknownBase, File> = absolute.coerce();
+    assert_eq!(borrowed.path, "/etc/hosts");
+
+    let relative = path::<Relative>("hosts");
+    let cloned: TypedPath<UnknownBase, File> = relative.to_coerced();
+    assert_eq!(cloned.path, "hosts");
+
+    let owned: TypedPath<UnknownBase, File> = absolute.into_coerced();
+    assert_eq!(owned.path, "/etc/hosts");
+}
@@ -0,0 +1,130 @@
+use phantom_coerce::{Coerce, Coercible, Upcast};
+use std::marker::PhantomData;
+
+struct Validated;
+struct Unvalidated;
+struct AnyStatus;
+
+#[derive(Coerce)]
+#[coerce(
+    owned_from = "Request<Validated | Unvalidated>",
+    owned_to = "Request<AnyStatus>"
+)]
+struct Request<Status> {
+    marker: PhantomData<Status>,
+    url: String,
+}
+
+fn request<Status>(url: &str) -> Request<Status> {
+    Request {
+        marker: PhantomData,
+        url: url.to_string(),
+    }
+}
+
+#[derive(Upcast)]
+#[upcast(any = "AnyStatus")]
+struct Response<Status> {
+    marker: PhantomData<Status>,
+    code: u16,
+}
+
+#[test]
+fn option_and_box() {
+    let some: Option<Request<AnyStatus>> = Some(request::<Validated>("/a")).into_coerced();
+    assert_eq!(some.unwrap().url, "/a");
+
+    let none: Option<Request<AnyStatus>> = None::<Request<Unvalidated>>.into_coerced();
+    assert!(none.is_none());
+
+    let boxed: Box<Request<AnyStatus>> = Box::new(request::<Validated>("/b")).into_coerced();
+    assert_eq!(boxed.url, "/b");
+}
+
+#[test]
+fn vec_of_requests() {
+    let validated = vec![request::<Validated>("/a"), request("/b")];
+    let generic: Vec<Request<AnyStatus>> = validated.into_coerced();
+    let urls: Vec<_> = generic.iter().map(|request| request.url.as_str()).collect();
+    assert_eq!(urls, ["/a", "/b"]);
+}
+
+#[test]
+fn result_lifts_ok_only() {
+    let ok: Result<Request<Validated>, String> = Ok(request("/a"));
+    let ok: Result<Request<AnyStatus>, String> = ok.into_coerced();
+    assert_eq!(ok.unwrap().url, "/a");
+
+    let err: Result<Request<Validated>, String> = Err("boom".to_string());
+    let err: Result<Request<AnyStatus>, String> = err.into_coerced();
+    assert_eq!(err.err().as_deref(), Some("boom"));
+}
+
+#[test]
+fn tuples_mix_coercions_and_identity() {
+    let pair = (request::<Validated>("/a"), 3u32);
+    let (generic, count): (Request<AnyStatus>, u32) = pair.into_coerced();
+    assert_eq!(generic.url, "/a");
+    assert_eq!(count, 3);
+
+    // Already-generic values take the reflexive impl
+    let triple = (
+        request::<AnyStatus>("/b"),
+        Some(request::<Unvalidated>("/c")),
+        "label",
+    );
+    let (first, second, label): (Request<AnyStatus>, Option<Request<AnyStatus>>, &str) =
+        triple.into_coerced();
+    assert_eq!(first.url, "/b");
+    assert_eq!(second.unwrap().url, "/c");
+    assert_eq!(label, "label");
+}
+
+#[test]
+fn nested_containers() {
+    let nested = vec![Some(Box::new(request::<Validated>("/a"))), None];
+    let generic: Vec<Option<Box<Request<AnyStatus>>>> = nested.into_coerced();
+    assert_eq!(generic[0].as_ref().unwrap().url, "/a");
+    assert!(generic[1].is_none());
+}
+
+#[test]
+fn upcast_implements_coercible() {
+    let responses = vec![
+        Response::<Validated> {
+            marker: PhantomData,
+            code: 200,
+        },
+        Response {
+            marker: PhantomData,
+            code: 404,
+        },
+    ];
+    let generic: Vec<Response<AnyStatus>> = responses.into_coerced();
+    assert_eq!(generic[1].code, 404);
+}
+
+#[test]
+fn poll_lifts_ready() {
+    use std::task::Poll;
+
+    let ready: Poll<Request<AnyStatus>> = Poll::Ready(request::<Validated>("/a")).into_coerced();
+    assert!(matches!(ready, Poll::Ready(ref r) if r.url == "/a"));
+
+    let pending: Poll<Option<Request<AnyStatus>>> =
+        Poll::<Option<Request<Unvalidated>>>::Pending.into_coerced();
+    assert!(pending.is_pending());
+}
+
+#[test]
+fn control_flow_lifts_break_only() {
+    use std::ops::ControlFlow;
+
+    let found: ControlFlow<Request<AnyStatus>, usize> =
+        ControlFlow::<_, usize>::Break(request::<Validated>("/b")).into_coerced();
+    assert!(matches!(found, ControlFlow::Break(ref r) if r.url == "/b"));
+
+    let next: ControlFlow<Request<AnyStatus>, usize> =
+        ControlFlow::<Request<Unvalidated>, usize>::Continue(3).into_coerced();
+    assert_eq!(next.continue_value(), Some(3));
+}
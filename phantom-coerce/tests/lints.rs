@@ -0,0 +1,59 @@
+use phantom_coerce::Coerce;
+use std::marker::PhantomData;
+
+struct Json;
+struct Xml;
+struct AnyFormat;
+
+// `Xml => Xml` is skipped and the repeated `Json => AnyFormat` is dropped
+#[derive(Coerce)]
+#[coerce(allow(noop, duplicate_pair), deny(large_expansion))]
+#[coerce(
+    borrowed_from = "Message<Json | Xml>",
+    borrowed_to = "Message<Xml | AnyFormat>"
+)]
+#[coerce(borrowed_from = "Message<Json>", borrowed_to = "Message<AnyFormat>")]
+struct Message<Format> {
+    marker: PhantomData<Format>,
+    content: String,
+}
+
+#[test]
+fn allowed_lints_skip_pairs() {
+    let json = Message::<Json> {
+        marker: PhantomData,
+        content: "{}".to_string(),
+    };
+    let xml: &Message<Xml> = json.coerce();
+    assert_eq!(xml.content, "{}");
+
+    let any: &Message<AnyFormat> = json.coerce();
+    assert_eq!(any.content, "{}");
+
+    let any: &Message<AnyFormat> = xml.coerce();
+    assert_eq!(any.content, "{}");
+}
+
+struct Celsius;
+struct Kelvin;
+
+// Units convert both ways on purpose
+#[derive(Coerce)]
+#[coerce(allow(coercion_cycle))]
+#[coerce(owned_from = "Reading<Celsius>", owned_to = "Reading<Kelvin>")]
+#[coerce(owned_from = "Reading<Kelvin>", owned_to = "Reading<Celsius>")]
+struct Reading<Unit> {
+    unit: PhantomData<Unit>,
+    raw: u32,
+}
+
+#[test]
+fn allowed_cycle() {
+    let celsius = Reading::<Celsius> {
+        unit: PhantomData,
+        raw: 20,
+    };
+    let kelvin: Reading<Kelvin> = celsius.into_coerced();
+    let celsius: Reading<Celsius> = kelvin.into_coerced();
+    assert_eq!(celsius.raw, 20);
+}
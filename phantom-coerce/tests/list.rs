@@ -0,0 +1,65 @@
+use phantom_coerce::mode::{Borrowed, Cloned, Owned};
+use phantom_coerce::{Coerce, Coercion, CoercionList};
+use std::any::TypeId;
+use std::marker::PhantomData;
+
+#[derive(Clone)]
+struct Absolute;
+#[derive(Clone)]
+struct Relative;
+#[derive(Clone)]
+struct UnknownBase;
+#[derive(Clone)]
+struct File;
+
+#[derive(Coerce, Clone)]
+#[coerce(
+    borrowed_from = "TypedPath<Absolute | Relative, File>",
+    borrowed_to = "TypedPath<UnknownBase, File>"
+)]
+#[coerce(
+    owned_from = "TypedPath<Absolute, _>",
+    owned_to = "TypedPath<UnknownBase, _>"
+)]
+#[coerce(
+    cloned_from = "TypedPath<Relative, File>",
+    cloned_to = "TypedPath<UnknownBase, File>"
+)]
+struct TypedPath<Base, Type> {
+    base: PhantomData<Base>,
+    ty: PhantomData<Type>,
+}
+
+fn list_of<T: CoercionList>() -> TypeId
+where
+    T::Coercions: 'static,
+{
+    TypeId::of::<T::Coercions>()
+}
+
+#[test]
+fn lists_coercions_in_declaration_order() {
+    // The type hole is the struct's own `Type` parameter
+    type Expected<Type> = (
+        Coercion<Borrowed, TypedPath<Absolute, File>, TypedPath<UnknownBase, File>>,
+        (
+            Coercion<Borrowed, TypedPath<Relative, File>, TypedPath<UnknownBase, File>>,
+            (
+                Coercion<Owned, TypedPath<Absolute, Type>, TypedPath<UnknownBase, Type>>,
+                (
+                    Coercion<Cloned, TypedPath<Relative, File>, TypedPath<UnknownBase, File>>,
+                    (),
+                ),
+            ),
+        ),
+    );
+
+    assert_eq!(
+        list_of::<TypedPath<Absolute, File>>(),
+        TypeId::of::<Expected<File>>()
+    );
+    assert_eq!(
+        list_of::<TypedPath<UnknownBase, Relative>>(),
+        TypeId::of::<Expected<Relative>>()
+    );
+}
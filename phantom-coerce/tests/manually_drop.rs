@@ -0,0 +1,51 @@
+use phantom_coerce::Coerce;
+use std::cell::Cell;
+use std::marker::PhantomData;
+use std::mem::ManuallyDrop;
+
+struct Validated;
+struct AnyStatus;
+
+thread_local! {
+    static DROPS: Cell<usize> = const { Cell::new(0) };
+}
+
+#[derive(Coerce)]
+#[coerce(borrowed_from = "Slot<Validated>", borrowed_to = "Slot<AnyStatus>")]
+#[coerce(owned_from = "Slot<Validated>", owned_to = "Slot<AnyStatus>")]
+struct Slot<Status> {
+    marker: PhantomData<Status>,
+    id: u32,
+}
+
+impl<Status> Drop for Slot<Status> {
+    fn drop(&mut self) {
+        DROPS.with(|drops| drops.set(drops.get() + 1));
+    }
+}
+
+fn slot(id: u32) -> ManuallyDrop<Slot<Validated>> {
+    ManuallyDrop::new(Slot {
+        marker: PhantomData,
+        id,
+    })
+}
+
+#[test]
+fn owned_retag_keeps_drop_control() {
+    let validated = slot(1);
+    let mut generic: ManuallyDrop<Slot<AnyStatus>> = Slot::coerce_manually_drop(validated);
+    assert_eq!(generic.id, 1);
+    assert_eq!(DROPS.with(Cell::get), 0);
+
+    // SAFETY: Dropped once, and not used afterwards.
+    unsafe { ManuallyDrop::drop(&mut generic) };
+    assert_eq!(DROPS.with(Cell::get), 1);
+}
+
+#[test]
+fn borrowed_retag() {
+    let validated = slot(2);
+    let generic: &ManuallyDrop<Slot<AnyStatus>> = Slot::coerce_manually_drop_ref(&validated);
+    assert_eq!(generic.id, 2);
+}
@@ -0,0 +1,46 @@
+use phantom_coerce::tag::MarkerTag;
+use phantom_coerce::{Coerce, Marker, PhantomMarker};
+use std::marker::PhantomData;
+
+#[derive(PhantomMarker)]
+struct Validated;
+
+#[derive(PhantomMarker)]
+#[phantom_marker(name = "json", id = 7)]
+struct Json;
+
+#[derive(PhantomMarker)]
+struct AnyFormat;
+
+#[derive(Coerce)]
+#[coerce(owned_from = "Message<Json>", owned_to = "Message<AnyFormat>")]
+#[coerce(tag = "Format")]
+struct Message<Format> {
+    marker: PhantomData<Format>,
+    content: String,
+}
+
+#[test]
+fn default_name_and_id() {
+    assert_eq!(Validated::NAME, "Validated");
+    assert_eq!(Validated::ID, None);
+}
+
+#[test]
+fn overridden_name_and_id() {
+    assert_eq!(Json::NAME, "json");
+    assert_eq!(Json::ID, Some(7));
+}
+
+#[test]
+fn name_feeds_marker_tag() {
+    assert_eq!(Json::TAG, Json::NAME);
+
+    let message = Message::<Json> {
+        marker: PhantomData,
+        content: "{}".to_string(),
+    };
+    let erased = message.into_tagged::<Message<AnyFormat>>();
+    assert_eq!(erased.tag(), "json");
+    assert_eq!(erased.value().content, "{}");
+}
@@ -0,0 +1,89 @@
+use phantom_coerce::tag::UnknownTag;
+use phantom_coerce::{Coerce, PhantomMarker};
+use std::marker::PhantomData;
+
+#[derive(PhantomMarker)]
+#[phantom_marker(name = "absolute")]
+struct Absolute;
+#[derive(PhantomMarker)]
+#[phantom_marker(name = "relative")]
+struct Relative;
+#[derive(PhantomMarker)]
+#[phantom_marker(name = "unknown")]
+struct UnknownBase;
+
+#[derive(PhantomMarker)]
+struct File;
+#[derive(PhantomMarker)]
+struct Directory;
+#[derive(PhantomMarker)]
+struct UnknownType;
+
+#[derive(Coerce)]
+#[coerce(
+    borrowed_from = "TypedPath<Absolute | Relative, _>",
+    borrowed_to = "TypedPath<UnknownBase, _>"
+)]
+#[coerce(
+    borrowed_from = "TypedPath<_, File | Directory>",
+    borrowed_to = "TypedPath<_, UnknownType>"
+)]
+#[coerce(marker_enum(Base = "BaseMarkerKind", Type = "TypeMarkerKind"))]
+struct TypedPath<Base, Type> {
+    base: PhantomData<Base>,
+    ty: PhantomData<Type>,
+    path: String,
+}
+
+#[test]
+fn lists_sources_and_targets() {
+    assert_eq!(
+        BaseMarkerKind::ALL,
+        [
+            BaseMarkerKind::Absolute,
+            BaseMarkerKind::Relative,
+            BaseMarkerKind::UnknownBase
+        ]
+    );
+    assert_eq!(
+        TypeMarkerKind::ALL,
+        [
+            TypeMarkerKind::File,
+            TypeMarkerKind::Directory,
+            TypeMarkerKind::UnknownType
+        ]
+    );
+}
+
+#[test]
+fn displays_and_parses_tags() {
+    assert_eq!(BaseMarkerKind::UnknownBase.to_string(), "unknown");
+    assert_eq!(TypeMarkerKind::Directory.as_str(), "Directory");
+    for kind in BaseMarkerKind::ALL {
+        assert_eq!(kind.as_str().parse::<BaseMarkerKind>(), Ok(kind));
+    }
+    assert_eq!(
+        "File".parse::<BaseMarkerKind>(),
+        Err(UnknownTag::new("File"))
+    );
+}
+
+fn base_kind<Base: 'static, Type>(_: &TypedPath<Base, Type>) -> Option<BaseMarkerKind> {
+    BaseMarkerKind::of::<Base>()
+}
+
+#[test]
+fn of_finds_marker_type() {
+    let path = TypedPath::<Relative, File> {
+        base: PhantomData,
+        ty: PhantomData,
+        path: "src".to_string(),
+    };
+    assert_eq!(path.path, "src");
+    assert_eq!(base_kind(&path), Some(BaseMarkerKind::Relative));
+    assert_eq!(
+        base_kind(path.coerce::<TypedPath<UnknownBase, File>>()),
+        Some(BaseMarkerKind::UnknownBase)
+    );
+    assert_eq!(BaseMarkerKind::of::<File>(), None);
+}
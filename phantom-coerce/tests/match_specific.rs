@@ -0,0 +1,88 @@
+use phantom_coerce::tag::{MarkerTag, Tagged};
+use phantom_coerce::{Coerce, match_specific};
+use std::marker::PhantomData;
+
+struct Json;
+struct Xml;
+struct AnyFormat;
+
+impl MarkerTag for Json {
+    const TAG: &'static str = "json";
+}
+
+impl MarkerTag for Xml {
+    const TAG: &'static str = "xml";
+}
+
+#[derive(Coerce)]
+#[coerce(
+    owned_from = "Message<Json | Xml, _>",
+    owned_to = "Message<AnyFormat, _>"
+)]
+#[coerce(tag = "Format")]
+struct Message<Format, Body> {
+    marker: PhantomData<Format>,
+    body: Body,
+}
+
+impl Message<Json, String> {
+    fn keys(&self) -> usize {
+        self.body.matches(':').count()
+    }
+}
+
+impl Message<Xml, String> {
+    fn elements(&self) -> usize {
+        self.body.matches("</").count()
+    }
+}
+
+fn count(erased: &Tagged<Message<AnyFormat, String>>) -> usize {
+    match_specific!(erased, Message {
+        Json(msg) => msg.keys(),
+        Xml(msg) => msg.elements(),
+        else => 0,
+    })
+}
+
+#[test]
+fn dispatches_on_tag() {
+    let json = Message::<Json, _> {
+        marker: PhantomData,
+        body: r#"{"a": 1, "b": 2}"#.to_string(),
+    };
+    let xml = Message::<Xml, _> {
+        marker: PhantomData,
+        body: "<a></a><b></b><c/>".to_string(),
+    };
+
+    assert_eq!(count(&json.into_tagged()), 2);
+    assert_eq!(count(&xml.into_tagged()), 2);
+}
+
+#[test]
+fn unknown_tag_falls_back() {
+    let erased = Tagged::new(
+        "yaml",
+        Message::<AnyFormat, String> {
+            marker: PhantomData,
+            body: "a: 1".to_string(),
+        },
+    );
+    assert_eq!(count(&erased), 0);
+}
+
+#[test]
+fn borrowed_tagged_values() {
+    let json = Message::<Json, u32> {
+        marker: PhantomData,
+        body: 7,
+    };
+    let tagged = json.tagged_ref();
+    let body = match_specific!(tagged, Message {
+        Json(msg) => msg.body,
+        Xml(_) => unreachable!(),
+        else => unreachable!(),
+    });
+    assert_eq!(body, 7);
+}
@@ -0,0 +1,47 @@
+use phantom_coerce::Coerce;
+use std::marker::PhantomData;
+
+#[derive(Clone)]
+struct Absolute;
+#[derive(Clone)]
+struct Relative;
+#[derive(Clone)]
+struct UnknownBase;
+#[derive(Clone)]
+struct File;
+
+// Patterns wrapped the way rustfmt leaves long strings, with trailing commas
+#[derive(Coerce, Clone)]
+#[coerce(
+    borrowed_from = "TypedPath<
+        Absolute | Relative,
+        _,
+    >",
+    borrowed_to = "TypedPath<
+        UnknownBase,
+        _,
+    >"
+)]
+#[coerce(
+    cloned_from = "TypedPath<Absolute,
+                             File>",
+    cloned_to = "TypedPath< UnknownBase ,File >"
+)]
+struct TypedPath<Base, Type> {
+    base: PhantomData<Base>,
+    ty: PhantomData<Type>,
+}
+
+fn path<Base>() -> TypedPath<Base, File> {
+    TypedPath {
+        base: PhantomData,
+        ty: PhantomData,
+    }
+}
+
+#[test]
+fn wrapped_patterns() {
+    let _: &TypedPath<UnknownBase, File> = path::<Absolute>().coerce();
+    let _: &TypedPath<UnknownBase, File> = path::<Relative>().coerce();
+    let _: TypedPath<UnknownBase, File> = path::<Absolute>().to_coerced();
+}
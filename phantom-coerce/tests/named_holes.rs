@@ -0,0 +1,48 @@
+use phantom_coerce::Coerce;
+use std::marker::PhantomData;
+
+#[derive(Clone)]
+struct Raw;
+#[derive(Clone)]
+struct Parsed;
+#[derive(Clone)]
+struct AnyState;
+
+#[derive(Coerce, Clone)]
+#[coerce(
+    borrowed_from = "Container<T, Raw | Parsed>",
+    borrowed_to = "Container<T, AnyState>"
+)]
+#[coerce(owned_from = "Container<T, Raw>", owned_to = "Container<T, AnyState>")]
+#[coerce(
+    cloned_from = "Container<T, Parsed>",
+    cloned_to = "Container<_, AnyState>"
+)]
+struct Container<T, State> {
+    item: T,
+    state: PhantomData<State>,
+}
+
+fn container<T, State>(item: T) -> Container<T, State> {
+    Container {
+        item,
+        state: PhantomData,
+    }
+}
+
+#[test]
+fn named_parameter_is_preserved() {
+    let raw = container::<u32, Raw>(7);
+    let generic: &Container<u32, AnyState> = raw.coerce();
+    assert_eq!(generic.item, 7);
+
+    let owned: Container<u32, AnyState> = raw.into_coerced();
+    assert_eq!(owned.item, 7);
+}
+
+#[test]
+fn named_and_positional_holes_mix() {
+    let parsed = container::<String, Parsed>("doc".to_string());
+    let cloned: Container<String, AnyState> = parsed.to_coerced();
+    assert_eq!(cloned.item, "doc");
+}
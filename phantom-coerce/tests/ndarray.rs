@@ -0,0 +1,120 @@
+#![cfg(feature = "ndarray")]
+
+use ndarray::{Array2, ArrayView2, Axis, array, s};
+use phantom_coerce::Coerce;
+use std::marker::PhantomData;
+
+struct Celsius;
+struct AnyUnit;
+
+#[derive(Coerce, Clone, Debug, PartialEq)]
+#[coerce(borrowed_from = "Reading<Celsius>", borrowed_to = "Reading<AnyUnit>")]
+#[coerce(owned_from = "Reading<Celsius>", owned_to = "Reading<AnyUnit>")]
+struct Reading<Unit> {
+    marker: PhantomData<Unit>,
+    value: f64,
+}
+
+fn reading<Unit>(value: f64) -> Reading<Unit> {
+    Reading {
+        marker: PhantomData,
+        value,
+    }
+}
+
+fn grid() -> Array2<Reading<Celsius>> {
+    array![[1.0, 2.0, 3.0], [4.0, 5.0, 6.0]].mapv(reading)
+}
+
+fn values<Unit>(view: ArrayView2<'_, Reading<Unit>>) -> Vec<f64> {
+    view.iter().map(|r| r.value).collect()
+}
+
+#[test]
+fn owned_array_in_place() {
+    let celsius = grid();
+    let ptr = celsius.as_ptr() as usize;
+
+    let generic: Array2<Reading<AnyUnit>> = Reading::coerce_array(celsius);
+    assert_eq!(generic.as_ptr() as usize, ptr);
+    assert_eq!(generic.dim(), (2, 3));
+    assert_eq!(values(generic.view()), [1.0, 2.0, 3.0, 4.0, 5.0, 6.0]);
+}
+
+#[test]
+fn owned_array_fortran_order() {
+    let celsius = grid().reversed_axes();
+    let ptr = celsius.as_ptr() as usize;
+
+    let generic = Reading::coerce_array::<Reading<AnyUnit>, _>(celsius);
+    assert_eq!(generic.as_ptr() as usize, ptr);
+    assert_eq!(generic.dim(), (3, 2));
+    assert_eq!(values(generic.view()), [1.0, 4.0, 2.0, 5.0, 3.0, 6.0]);
+}
+
+#[test]
+fn owned_array_sliced_rows() {
+    let mut celsius = grid();
+    celsius.slice_axis_inplace(Axis(0), (1..).into());
+
+    let generic = Reading::coerce_array::<Reading<AnyUnit>, _>(celsius);
+    assert_eq!(generic.dim(), (1, 3));
+    assert_eq!(values(generic.view()), [4.0, 5.0, 6.0]);
+}
+
+#[test]
+fn owned_array_reversed_axis() {
+    let mut celsius = grid();
+    celsius.invert_axis(Axis(1));
+    celsius.slice_axis_inplace(Axis(0), (1..).into());
+
+    let generic = Reading::coerce_array::<Reading<AnyUnit>, _>(celsius);
+    assert_eq!(values(generic.view()), [6.0, 5.0, 4.0]);
+}
+
+#[test]
+fn transposed_view() {
+    let celsius = grid();
+    let view = celsius.t();
+
+    let generic: ArrayView2<'_, Reading<AnyUnit>> = Reading::coerce_array_view(view);
+    assert_eq!(generic.dim(), (3, 2));
+    assert_eq!(values(generic), [1.0, 4.0, 2.0, 5.0, 3.0, 6.0]);
+}
+
+#[test]
+fn sliced_view() {
+    let celsius = grid();
+
+    let generic = Reading::coerce_array_view::<Reading<AnyUnit>, _>(celsius.slice(s![.., 1..]));
+    assert_eq!(values(generic), [2.0, 3.0, 5.0, 6.0]);
+}
+
+mod safe {
+    #![forbid(unsafe_code)]
+
+    use super::{AnyUnit, Celsius};
+    use ndarray::{Array1, array};
+    use phantom_coerce::Coerce;
+    use std::marker::PhantomData;
+
+    #[derive(Coerce)]
+    #[coerce(owned_from = "Sample<Celsius>", owned_to = "Sample<AnyUnit>")]
+    #[coerce(safe)]
+    struct Sample<Unit> {
+        marker: PhantomData<Unit>,
+        value: f64,
+    }
+
+    #[test]
+    fn safe_mode_array() {
+        let celsius: Array1<Sample<Celsius>> = array![1.0, 2.0].mapv(|value| Sample {
+            marker: PhantomData,
+            value,
+        });
+
+        let generic: Array1<Sample<AnyUnit>> = Sample::coerce_array(celsius);
+        let values: Vec<f64> = generic.iter().map(|s| s.value).collect();
+        assert_eq!(values, [1.0, 2.0]);
+    }
+}
@@ -0,0 +1,64 @@
+use phantom_coerce::{Coerce, Upcast};
+use std::marker::PhantomData;
+
+#[derive(Clone)]
+struct Absolute;
+#[derive(Clone)]
+struct Relative;
+#[derive(Clone)]
+struct UnknownBase;
+#[derive(Clone)]
+struct File;
+#[derive(Clone)]
+struct Directory;
+#[derive(Clone)]
+struct UnknownType;
+
+#[derive(Coerce, Clone)]
+#[coerce(
+    borrowed_from = "TypedPath<Absolute | Relative, _>",
+    borrowed_to = "TypedPath<UnknownBase, _>"
+)]
+#[coerce(
+    cloned_from = "TypedPath<Absolute, File>",
+    cloned_to = "TypedPath<Absolute, UnknownType>"
+)]
+struct TypedPath<Base, Type> {
+    base: PhantomData<Base>,
+    ty: PhantomData<Type>,
+    path: String,
+}
+
+struct Validated;
+struct AnyStatus;
+
+#[derive(Upcast)]
+#[upcast(any = "AnyStatus")]
+struct Request<Status> {
+    marker: PhantomData<Status>,
+}
+
+phantom_coerce::compile_fail_tests! {
+    // Erasure never narrows
+    borrowed TypedPath<UnknownBase, File> => TypedPath<Absolute, File>;
+    // The type hole keeps the file type fixed
+    borrowed TypedPath<Absolute, File> => TypedPath<UnknownBase, Directory>;
+    // Only borrowed and cloned coercions were declared
+    owned TypedPath<Absolute, File> => TypedPath<UnknownBase, File>;
+    cloned TypedPath<Relative, File> => TypedPath<Relative, UnknownType>;
+    // Upcasts only go towards the "any" marker
+    borrowed Request<AnyStatus> => Request<Validated>;
+    cloned Request<Validated> => Request<AnyStatus>;
+}
+
+// The declared coercions themselves still work
+#[test]
+fn declared_coercions_compile() {
+    let path = TypedPath::<Absolute, File> {
+        base: PhantomData,
+        ty: PhantomData,
+        path: "/etc/hosts".to_string(),
+    };
+    let generic: &TypedPath<UnknownBase, File> = path.coerce();
+    assert_eq!(generic.path, "/etc/hosts");
+}
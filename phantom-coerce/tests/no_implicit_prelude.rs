@@ -0,0 +1,166 @@
+// Generated code names everything by absolute path, so it expands in modules without the
+// prelude, where even `Clone` and `Vec` must be imported
+#![no_implicit_prelude]
+
+use ::phantom_coerce::tag::Tagged;
+use ::phantom_coerce::{
+    Coerce, Coercible, PhantomMarker, Upcast, coerce, match_specific, phantom_coerce,
+};
+use ::std::borrow::Cow;
+use ::std::marker::PhantomData;
+use ::std::string::{String, ToString};
+use ::std::vec::Vec;
+use ::std::{assert_eq, vec};
+
+#[derive(PhantomMarker, Clone)]
+pub struct Absolute;
+#[derive(PhantomMarker, Clone)]
+pub struct Relative;
+#[derive(PhantomMarker, Clone)]
+pub struct UnknownBase;
+#[derive(PhantomMarker, Clone)]
+pub struct File;
+#[derive(PhantomMarker, Clone)]
+pub struct UnknownType;
+
+#[derive(Coerce, Clone)]
+#[coerce(trait_vis = "pub", cow, from_iterator, debug, layout_checks)]
+#[coerce(tag = "Base", tag_enum = "BaseTag")]
+#[coerce(marker_enum(Type = "TypeKind"), deref_inner = "path")]
+#[coerce(
+    erased = "TypedPath<UnknownBase, UnknownType>",
+    allow(unreachable_erased)
+)]
+#[coerce(
+    borrowed_from = "TypedPath<Absolute | Relative, _>",
+    borrowed_to = "TypedPath<UnknownBase, _>",
+    asref
+)]
+#[coerce(
+    owned_from = "TypedPath<Absolute | Relative, _>",
+    owned_to = "TypedPath<UnknownBase, _>"
+)]
+#[coerce(
+    cloned_from = "TypedPath<Absolute | Relative, _>",
+    cloned_to = "TypedPath<UnknownBase, _>"
+)]
+#[coerce(
+    borrowed_from = "TypedPath<Absolute | Relative, File>",
+    borrowed_to = "TypedPath<UnknownBase, UnknownType>"
+)]
+#[coerce(
+    owned_from = "TypedPath<Absolute | Relative, File>",
+    owned_to = "TypedPath<UnknownBase, UnknownType>"
+)]
+pub struct TypedPath<Base, Type> {
+    pub base: PhantomData<Base>,
+    pub ty: PhantomData<Type>,
+    pub path: String,
+}
+
+#[derive(Coerce)]
+#[coerce(project(field = "output", to = "TypedPath<UnknownBase, File>"))]
+#[coerce(owned_from = "Export<Absolute>", owned_to = "Export<UnknownBase>")]
+pub struct Export<Base> {
+    pub base: PhantomData<Base>,
+    #[coerce(deep)]
+    pub output: TypedPath<Base, File>,
+}
+
+#[derive(Coerce)]
+#[coerce(safe)]
+#[coerce(owned_from = "Request<Absolute>", owned_to = "Request<UnknownBase>")]
+pub struct Request<Status> {
+    pub status: PhantomData<Status>,
+    pub body: String,
+}
+
+#[derive(Upcast)]
+#[upcast(any = "UnknownBase")]
+pub struct Label<Base> {
+    pub base: PhantomData<Base>,
+    pub text: String,
+}
+
+phantom_coerce! {
+    pub struct Note<Base> {
+        pub base: PhantomData<Base>,
+        pub text: String,
+    }
+
+    coercions {
+        borrowed Note<Absolute> => Note<UnknownBase>, asref;
+        owned Note<Absolute> => Note<UnknownBase>;
+    }
+}
+
+fn path<Base>(path: &str) -> TypedPath<Base, File> {
+    TypedPath {
+        base: PhantomData,
+        ty: PhantomData,
+        path: path.to_string(),
+    }
+}
+
+#[test]
+fn derive_expands_without_the_prelude() {
+    let absolute = path::<Absolute>("/etc");
+    let view: &TypedPath<UnknownBase, File> = absolute.coerce();
+    assert_eq!(view.path, "/etc");
+    let cow: Cow<'_, TypedPath<UnknownBase, File>> = ::core::convert::From::from(&absolute);
+    assert_eq!(cow.path, "/etc");
+    assert_eq!(absolute.len(), 4);
+
+    let copy: TypedPath<UnknownBase, File> = absolute.to_coerced();
+    assert_eq!(copy.path, "/etc");
+    let erased = ::phantom_coerce::HasAnyMarker::erase(absolute);
+    assert_eq!(erased.path, "/etc");
+
+    let paths: Vec<TypedPath<UnknownBase, File>> =
+        ::core::iter::FromIterator::from_iter(vec![path::<Relative>("a"), path::<Relative>("b")]);
+    assert_eq!(paths.len(), 2);
+    let paths = TypedPath::coerce_all(vec![path::<Absolute>("/c")]);
+    let _: &Vec<TypedPath<UnknownBase, File>> = &paths;
+
+    let tagged: Tagged<TypedPath<UnknownBase, File>> = path::<Relative>("d").into_tagged();
+    let base = match_specific!(tagged, TypedPath {
+        Absolute(path) => path.path.len(),
+        Relative(path) => path.path.len(),
+        else => 0,
+    });
+    assert_eq!(base, 1);
+
+    let generic = coerce!(path::<Absolute>("/e") as TypedPath<UnknownBase, _>);
+    let _: &TypedPath<UnknownBase, File> = &generic;
+}
+
+#[test]
+fn other_expansions_work_without_the_prelude() {
+    let export = Export::<Absolute> {
+        base: PhantomData,
+        output: path("/out"),
+    };
+    assert_eq!(export.output_coerced().path, "/out");
+    let export: Export<UnknownBase> = Coercible::into_coerced(export);
+    assert_eq!(export.output.path, "/out");
+
+    let request = Request::<Absolute> {
+        status: PhantomData,
+        body: String::new(),
+    };
+    let _: Request<UnknownBase> = request.into_coerced();
+
+    let label = Label::<Relative> {
+        base: PhantomData,
+        text: "l".to_string(),
+    };
+    let generic: &Label<UnknownBase> = label.coerce();
+    assert_eq!(generic.text, "l");
+
+    let note = Note::<Absolute> {
+        base: PhantomData,
+        text: "n".to_string(),
+    };
+    let generic: &Note<UnknownBase> = ::core::convert::AsRef::as_ref(&note);
+    assert_eq!(generic.text, "n");
+}
@@ -0,0 +1,67 @@
+use phantom_coerce::{Coerce, phantom_coerce};
+use std::marker::PhantomData;
+
+#[derive(Clone)]
+struct Json;
+#[derive(Clone)]
+struct Xml;
+#[derive(Clone)]
+struct AnyFormat;
+
+// `Xml => Xml` is skipped; the remaining pairs are generated
+#[derive(Coerce, Clone)]
+#[coerce(
+    owned_from = "Message<Json | Xml>",
+    owned_to = "Message<Xml | AnyFormat>",
+    allow_noop
+)]
+struct Message<Format> {
+    marker: PhantomData<Format>,
+    content: String,
+}
+
+impl<Format> Message<Format> {
+    fn new(content: &str) -> Self {
+        Self {
+            marker: PhantomData,
+            content: content.to_string(),
+        }
+    }
+}
+
+phantom_coerce! {
+    struct Envelope<Format> {
+        marker: PhantomData<Format>,
+        body: String,
+    }
+
+    coercions {
+        borrowed Envelope<Json | Xml> => Envelope<Xml | AnyFormat>, asref, allow_noop;
+    }
+}
+
+#[test]
+fn noop_pairs_are_skipped() {
+    let json = Message::<Json>::new("{}");
+    let xml: Message<Xml> = json.clone().into_coerced();
+    assert_eq!(xml.content, "{}");
+
+    let any: Message<AnyFormat> = json.into_coerced();
+    assert_eq!(any.content, "{}");
+
+    let any: Message<AnyFormat> = Message::<Xml>::new("<a/>").into_coerced();
+    assert_eq!(any.content, "<a/>");
+}
+
+#[test]
+fn block_noop_pairs_are_skipped() {
+    let envelope = Envelope::<Json> {
+        marker: PhantomData,
+        body: "{}".to_string(),
+    };
+    let xml: &Envelope<Xml> = envelope.coerce();
+    assert_eq!(xml.body, "{}");
+
+    let any: &Envelope<AnyFormat> = envelope.as_ref();
+    assert_eq!(any.body, "{}");
+}
@@ -51,3 +51,24 @@ fn borrowed_vs_owned() {
     let owned_coerced: Owned<OtherOwned> = owned1.into_coerced();
     assert_eq!(owned_coerced.get_value(), "borrowed");
 }
+
+#[derive(Coerce)]
+#[coerce(
+    owned_from = "Labeled<OriginalOwned, _>",
+    owned_to = "Labeled<OtherOwned, _>"
+)]
+struct Labeled<Marker, Label> {
+    phantom: PhantomData<Marker>,
+    label: Label,
+}
+
+#[test]
+fn owned_with_generic_data_parameter() {
+    let labeled = Labeled::<OriginalOwned, Vec<u32>> {
+        phantom: PhantomData,
+        label: vec![1, 2, 3],
+    };
+
+    let owned: Labeled<OtherOwned, Vec<u32>> = labeled.into_coerced();
+    assert_eq!(owned.label, [1, 2, 3]);
+}
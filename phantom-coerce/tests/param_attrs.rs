@@ -0,0 +1,87 @@
+use phantom_coerce::{Coerce, phantom_coerce};
+use std::marker::PhantomData;
+
+struct Absolute;
+struct Relative;
+struct UnknownBase;
+
+struct File;
+struct Directory;
+
+#[derive(Coerce)]
+struct TypedPath<#[coerce(any = "UnknownBase")] Base, #[coerce(fixed)] Type> {
+    base: PhantomData<Base>,
+    ty: PhantomData<Type>,
+    path: String,
+}
+
+impl<Base, Type> TypedPath<Base, Type> {
+    fn new(path: &str) -> Self {
+        Self {
+            base: PhantomData,
+            ty: PhantomData,
+            path: path.to_string(),
+        }
+    }
+}
+
+#[test]
+fn any_on_parameter() {
+    let file = TypedPath::<Absolute, File>::new("/etc/hosts");
+    let any: &TypedPath<UnknownBase, File> = file.coerce();
+    assert_eq!(any.path, "/etc/hosts");
+
+    let dir = TypedPath::<Relative, Directory>::new("src");
+    let any: TypedPath<UnknownBase, Directory> = dir.into_coerced();
+    assert_eq!(any.path, "src");
+}
+
+struct Draft;
+struct Published;
+struct AnyState;
+struct En;
+
+#[derive(Coerce)]
+#[coerce(
+    borrowed_from = "Article<Draft | Published, _>",
+    borrowed_to = "Article<AnyState, _>"
+)]
+struct Article<State, #[coerce(fixed)] Lang> {
+    state: PhantomData<State>,
+    lang: PhantomData<Lang>,
+    title: String,
+}
+
+#[test]
+fn fixed_allows_holes() {
+    let article = Article::<Draft, En> {
+        state: PhantomData,
+        lang: PhantomData,
+        title: "Hello".to_string(),
+    };
+    let any: &Article<AnyState, En> = article.coerce();
+    assert_eq!(any.title, "Hello");
+}
+
+phantom_coerce! {
+    struct Note<#[coerce(fixed)] Lang, State> {
+        lang: PhantomData<Lang>,
+        state: PhantomData<State>,
+        body: String,
+    }
+
+    coercions {
+        owned Note<En, Draft | Published> => Note<En, AnyState>;
+    }
+}
+
+#[test]
+fn block_strips_parameter_attributes() {
+    let note = Note::<En, Published> {
+        lang: PhantomData,
+        state: PhantomData,
+        body: "hi".to_string(),
+    };
+    let any: Note<En, AnyState> = note.into_coerced();
+    assert_eq!(any.body, "hi");
+}
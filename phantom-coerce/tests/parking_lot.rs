@@ -0,0 +1,61 @@
+#![cfg(feature = "parking_lot")]
+
+use parking_lot::{ReentrantMutex, RwLock, RwLockReadGuard};
+use phantom_coerce::Coerce;
+use std::marker::PhantomData;
+
+struct Validated;
+struct AnyStatus;
+
+#[derive(Coerce)]
+#[coerce(
+    borrowed_from = "Session<Validated>",
+    borrowed_to = "Session<AnyStatus>"
+)]
+struct Session<Status> {
+    marker: PhantomData<Status>,
+    user: String,
+}
+
+impl<Status> Session<Status> {
+    fn new(user: &str) -> Self {
+        Self {
+            marker: PhantomData,
+            user: user.to_string(),
+        }
+    }
+}
+
+#[test]
+fn read_guard_mapping() {
+    let lock = RwLock::new(Session::<Validated>::new("alice"));
+
+    let generic = Session::coerce_read_guard::<Session<AnyStatus>>(lock.read());
+    assert_eq!(generic.user, "alice");
+
+    // The lock stays held (shared) while the mapped guard is alive
+    assert!(lock.try_write().is_none());
+    drop(generic);
+    assert!(lock.try_write().is_some());
+}
+
+#[test]
+fn mapped_read_guard_mapping() {
+    let lock = RwLock::new(Session::<Validated>::new("bob"));
+
+    let mapped = RwLockReadGuard::map(lock.read(), |session| session);
+    let generic: parking_lot::MappedRwLockReadGuard<'_, Session<AnyStatus>> =
+        Session::coerce_mapped_read_guard(mapped);
+    assert_eq!(generic.user, "bob");
+}
+
+#[test]
+fn reentrant_guard_mapping() {
+    let lock = ReentrantMutex::new(Session::<Validated>::new("carol"));
+
+    let generic = Session::coerce_reentrant_guard::<Session<AnyStatus>>(lock.lock());
+    assert_eq!(generic.user, "carol");
+
+    // Re-entrant locking from the same thread still works while mapped
+    assert_eq!(lock.lock().user, "carol");
+}
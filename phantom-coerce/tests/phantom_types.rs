@@ -0,0 +1,104 @@
+use phantom_coerce::Coerce;
+use std::marker::PhantomData;
+
+struct Absolute;
+struct UnknownBase;
+struct File;
+struct UnknownType;
+
+struct Invariant<T>(PhantomData<fn(T) -> T>);
+
+// Derived `Default` would require `T: Default`
+impl<T> Default for Invariant<T> {
+    fn default() -> Self {
+        Self(PhantomData)
+    }
+}
+
+struct PhantomCovariant<T>(PhantomData<T>);
+
+impl<T> Default for PhantomCovariant<T> {
+    fn default() -> Self {
+        Self(PhantomData)
+    }
+}
+
+#[derive(Coerce)]
+#[coerce(
+    borrowed_from = "TypedPath<Absolute, File>",
+    borrowed_to = "TypedPath<UnknownBase, UnknownType>"
+)]
+#[coerce(
+    owned_from = "TypedPath<Absolute, File>",
+    owned_to = "TypedPath<UnknownBase, UnknownType>"
+)]
+#[coerce(phantom_types = "Invariant, PhantomCovariant")]
+struct TypedPath<Base, Type> {
+    base: Invariant<Base>,
+    ty: PhantomCovariant<Type>,
+    path: String,
+}
+
+fn typed_path<Base, Type>(path: &str) -> TypedPath<Base, Type> {
+    TypedPath {
+        base: Invariant::default(),
+        ty: PhantomCovariant::default(),
+        path: path.to_string(),
+    }
+}
+
+#[test]
+fn listed_wrappers_are_markers() {
+    let path = typed_path::<Absolute, File>("/etc/hosts");
+    let generic: &TypedPath<UnknownBase, UnknownType> = path.coerce();
+    assert_eq!(generic.path, "/etc/hosts");
+    let _: (&Invariant<UnknownBase>, &PhantomCovariant<UnknownType>) = (&generic.base, &generic.ty);
+
+    let owned: TypedPath<UnknownBase, UnknownType> = path.into_coerced();
+    assert_eq!(owned.path, "/etc/hosts");
+}
+
+#[derive(Coerce)]
+#[coerce(owned_from = "Tagged<Absolute>", owned_to = "Tagged<UnknownBase>")]
+struct Tagged<Base> {
+    #[coerce(phantom)]
+    base: Invariant<Base>,
+    value: u32,
+}
+
+#[test]
+fn field_attribute_marks_one_field() {
+    let tagged = Tagged::<Absolute> {
+        base: Invariant::default(),
+        value: 7,
+    };
+    let generic: Tagged<UnknownBase> = tagged.into_coerced();
+    assert_eq!(generic.value, 7);
+    let _: &Invariant<UnknownBase> = &generic.base;
+}
+
+mod safe {
+    #![forbid(unsafe_code)]
+
+    use super::{Absolute, Invariant, UnknownBase};
+    use phantom_coerce::Coerce;
+
+    #[derive(Coerce)]
+    #[coerce(owned_from = "Record<Absolute>", owned_to = "Record<UnknownBase>")]
+    #[coerce(phantom_types = "Invariant", safe)]
+    struct Record<Base> {
+        base: Invariant<Base>,
+        name: String,
+    }
+
+    #[test]
+    fn safe_mode_defaults_the_wrapper() {
+        let record = Record::<Absolute> {
+            base: Invariant::default(),
+            name: "a".to_string(),
+        };
+        let generic: Record<UnknownBase> = record.into_coerced();
+        assert_eq!(generic.name, "a");
+        let _: &Invariant<UnknownBase> = &generic.base;
+    }
+}
@@ -0,0 +1,42 @@
+use phantom_coerce::Coerce;
+use std::marker::PhantomData;
+use std::ptr::NonNull;
+
+struct Validated;
+struct AnyStatus;
+
+#[derive(Coerce)]
+#[coerce(borrowed_from = "Node<Validated>", borrowed_to = "Node<AnyStatus>")]
+struct Node<Status> {
+    marker: PhantomData<Status>,
+    value: u64,
+}
+
+fn node(value: u64) -> Node<Validated> {
+    Node {
+        marker: PhantomData,
+        value,
+    }
+}
+
+#[test]
+fn non_null() {
+    let mut validated = node(1);
+    let ptr: NonNull<Node<AnyStatus>> = Node::coerce_ptr(NonNull::from(&mut validated));
+    // SAFETY: Points at `validated`, which outlives this read.
+    assert_eq!(unsafe { ptr.as_ref() }.value, 1);
+}
+
+#[test]
+fn raw_pointers() {
+    let mut validated = node(2);
+
+    let ptr: *const Node<AnyStatus> = Node::coerce_const_ptr(&raw const validated);
+    // SAFETY: Points at `validated`, which outlives this read.
+    assert_eq!(unsafe { (*ptr).value }, 2);
+
+    let ptr: *mut Node<AnyStatus> = Node::coerce_mut_ptr(&raw mut validated);
+    // SAFETY: Points at `validated`, with no other live references.
+    unsafe { (*ptr).value = 3 };
+    assert_eq!(validated.value, 3);
+}
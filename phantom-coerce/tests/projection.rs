@@ -0,0 +1,63 @@
+use phantom_coerce::Coerce;
+use std::marker::PhantomData;
+
+struct Absolute;
+struct UnknownBase;
+struct File;
+struct UnknownType;
+
+#[derive(Coerce)]
+#[coerce(
+    borrowed_from = "TypedPath<Absolute, _>",
+    borrowed_to = "TypedPath<UnknownBase, _>"
+)]
+#[coerce(
+    borrowed_from = "TypedPath<Absolute, File>",
+    borrowed_to = "TypedPath<UnknownBase, UnknownType>"
+)]
+struct TypedPath<Base, Type> {
+    base: PhantomData<Base>,
+    ty: PhantomData<Type>,
+    path: String,
+}
+
+impl<Base, Type> TypedPath<Base, Type> {
+    fn new(path: &str) -> Self {
+        Self {
+            base: PhantomData,
+            ty: PhantomData,
+            path: path.to_string(),
+        }
+    }
+}
+
+// Only the inner path is generalized; the outer struct has no coercions of its own
+#[derive(Coerce)]
+#[coerce(project(field = "output", to = "TypedPath<UnknownBase, UnknownType>"))]
+#[coerce(project(
+    field = "template",
+    to = "TypedPath<UnknownBase, Type>",
+    name = "any_base_template"
+))]
+struct Export<Type> {
+    output: TypedPath<Absolute, Type>,
+    template: TypedPath<Absolute, Type>,
+    overwrite: bool,
+}
+
+#[test]
+fn projection_accessors() {
+    let export = Export::<File> {
+        output: TypedPath::new("/srv/report.csv"),
+        template: TypedPath::new("/srv/template.csv"),
+        overwrite: true,
+    };
+
+    let generic: &TypedPath<UnknownBase, UnknownType> = export.output_coerced();
+    assert_eq!(generic.path, "/srv/report.csv");
+
+    // Struct generics in the target preserve that parameter
+    let any_base: &TypedPath<UnknownBase, File> = export.any_base_template();
+    assert_eq!(any_base.path, "/srv/template.csv");
+    assert!(export.overwrite);
+}
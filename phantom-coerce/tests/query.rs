@@ -0,0 +1,53 @@
+use phantom_coerce::{Coerce, Upcast, is_coercible};
+use std::marker::PhantomData;
+
+#[derive(Clone)]
+struct Absolute;
+#[derive(Clone)]
+struct UnknownBase;
+#[derive(Clone)]
+struct File;
+#[derive(Clone)]
+struct Directory;
+
+#[derive(Coerce, Clone)]
+#[coerce(
+    borrowed_from = "TypedPath<Absolute, _>",
+    borrowed_to = "TypedPath<UnknownBase, _>"
+)]
+#[coerce(
+    cloned_from = "TypedPath<Absolute, File>",
+    cloned_to = "TypedPath<UnknownBase, File>"
+)]
+struct TypedPath<Base, Type> {
+    base: PhantomData<Base>,
+    ty: PhantomData<Type>,
+}
+
+// Type holes are answered for every instantiation of the hole
+const _: () =
+    assert!(is_coercible!(borrowed TypedPath<Absolute, File> => TypedPath<UnknownBase, File>));
+const _: () = assert!(
+    is_coercible!(borrowed TypedPath<Absolute, Directory> => TypedPath<UnknownBase, Directory>)
+);
+const _: () = assert!(
+    !is_coercible!(borrowed TypedPath<Absolute, File> => TypedPath<UnknownBase, Directory>)
+);
+
+struct Validated;
+struct AnyStatus;
+
+#[derive(Upcast)]
+#[upcast(any = "AnyStatus")]
+struct Request<Status> {
+    marker: PhantomData<Status>,
+}
+
+#[test]
+fn query_by_mode() {
+    assert!(is_coercible!(cloned TypedPath<Absolute, File> => TypedPath<UnknownBase, File>));
+    assert!(!is_coercible!(owned TypedPath<Absolute, File> => TypedPath<UnknownBase, File>));
+
+    assert!(is_coercible!(owned Request<Validated> => Request<AnyStatus>));
+    assert!(!is_coercible!(owned Request<AnyStatus> => Request<Validated>));
+}
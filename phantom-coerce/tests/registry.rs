@@ -0,0 +1,102 @@
+#![cfg(feature = "registry")]
+
+use phantom_coerce::Coerce;
+use phantom_coerce::registry::{dyn_coerce_ref, is_registered, register};
+use std::any::{Any, TypeId};
+use std::marker::PhantomData;
+
+struct Validated;
+struct Unvalidated;
+struct AnyStatus;
+
+struct Draft;
+struct Reviewed;
+struct Published;
+
+#[derive(Coerce)]
+#[coerce(
+    borrowed_from = "Request<Validated | Unvalidated>",
+    borrowed_to = "Request<AnyStatus>"
+)]
+struct Request<Status> {
+    marker: PhantomData<Status>,
+    url: String,
+}
+
+fn request<Status>(url: &str) -> Request<Status> {
+    Request {
+        marker: PhantomData,
+        url: url.to_string(),
+    }
+}
+
+fn url(value: &dyn Any) -> &str {
+    &value.downcast_ref::<Request<AnyStatus>>().unwrap().url
+}
+
+#[test]
+fn registered_pair_is_found() {
+    register::<Request<Validated>, Request<AnyStatus>>();
+    assert!(is_registered(
+        TypeId::of::<Request<Validated>>(),
+        TypeId::of::<Request<AnyStatus>>()
+    ));
+
+    let validated = request::<Validated>("/a");
+    let generic = dyn_coerce_ref(&validated, TypeId::of::<Request<AnyStatus>>()).unwrap();
+    assert_eq!(url(generic), "/a");
+    assert!(std::ptr::eq(
+        (generic as *const dyn Any).cast::<u8>(),
+        (&validated as *const Request<Validated>).cast::<u8>(),
+    ));
+}
+
+#[test]
+fn unregistered_pair_is_missing() {
+    let unvalidated = request::<Unvalidated>("/b");
+    assert!(dyn_coerce_ref(&unvalidated, TypeId::of::<Request<AnyStatus>>()).is_none());
+    assert!(dyn_coerce_ref(&unvalidated, TypeId::of::<String>()).is_none());
+}
+
+#[test]
+fn same_type_is_returned_as_is() {
+    let generic = request::<AnyStatus>("/c");
+    let same = dyn_coerce_ref(&generic, TypeId::of::<Request<AnyStatus>>()).unwrap();
+    assert_eq!(url(same), "/c");
+}
+
+#[derive(Coerce)]
+#[coerce(borrowed_from = "Article<Draft>", borrowed_to = "Article<Reviewed>")]
+#[coerce(
+    borrowed_from = "Article<Reviewed>",
+    borrowed_to = "Article<Published>"
+)]
+struct Article<Stage> {
+    marker: PhantomData<Stage>,
+    title: String,
+}
+
+#[test]
+fn registered_pairs_chain() {
+    register::<Article<Draft>, Article<Reviewed>>();
+    register::<Article<Reviewed>, Article<Published>>();
+    assert!(!is_registered(
+        TypeId::of::<Article<Draft>>(),
+        TypeId::of::<Article<Published>>()
+    ));
+
+    let draft = Article::<Draft> {
+        marker: PhantomData,
+        title: "hops".to_string(),
+    };
+    let published = dyn_coerce_ref(&draft, TypeId::of::<Article<Published>>()).unwrap();
+    let published = published.downcast_ref::<Article<Published>>().unwrap();
+    assert_eq!(published.title, "hops");
+
+    // Chains only run forward
+    let reviewed = Article::<Reviewed> {
+        marker: PhantomData,
+        title: "back".to_string(),
+    };
+    assert!(dyn_coerce_ref(&reviewed, TypeId::of::<Article<Draft>>()).is_none());
+}
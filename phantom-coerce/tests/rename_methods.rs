@@ -0,0 +1,78 @@
+use phantom_coerce::Coerce;
+use std::marker::PhantomData;
+
+#[derive(Clone)]
+struct Absolute;
+#[derive(Clone)]
+struct UnknownBase;
+
+#[derive(Coerce, Clone)]
+#[coerce(rename(coerce = "view", into_coerced = "into_view", to_coerced = "to_view"))]
+#[coerce(
+    borrowed_from = "TypedPath<Absolute>",
+    borrowed_to = "TypedPath<UnknownBase>"
+)]
+#[coerce(
+    owned_from = "TypedPath<Absolute>",
+    owned_to = "TypedPath<UnknownBase>"
+)]
+#[coerce(
+    cloned_from = "TypedPath<Absolute>",
+    cloned_to = "TypedPath<UnknownBase>"
+)]
+struct TypedPath<Base> {
+    base: PhantomData<Base>,
+    path: String,
+}
+
+// The struct keeps its own methods under the default names
+impl<Base> TypedPath<Base> {
+    fn coerce(&self) -> &str {
+        &self.path
+    }
+
+    fn into_coerced(self) -> String {
+        self.path
+    }
+
+    fn to_coerced(&self) -> usize {
+        self.path.len()
+    }
+}
+
+fn path() -> TypedPath<Absolute> {
+    TypedPath {
+        base: PhantomData,
+        path: "/etc".to_string(),
+    }
+}
+
+#[test]
+fn renamed_methods_coerce() {
+    let path = path();
+    let view: &TypedPath<UnknownBase> = path.view();
+    assert_eq!(view.path, "/etc");
+
+    let copy = path.to_view::<TypedPath<UnknownBase>>();
+    assert_eq!(copy.path, "/etc");
+
+    let owned: TypedPath<UnknownBase> = path.into_view();
+    assert_eq!(owned.path, "/etc");
+}
+
+#[test]
+fn own_methods_keep_their_names() {
+    let path = path();
+    assert_eq!(path.coerce(), "/etc");
+    assert_eq!(path.to_coerced(), 4);
+    assert_eq!(path.into_coerced(), "/etc");
+}
+
+#[test]
+fn trait_methods_keep_their_names() {
+    let path = path();
+    let view: &TypedPath<UnknownBase> = CoerceRefTypedPath::coerce(&path);
+    assert_eq!(view.path, "/etc");
+    let owned: TypedPath<UnknownBase> = CoerceOwnedTypedPath::into_coerced(path);
+    assert_eq!(owned.path, "/etc");
+}
@@ -0,0 +1,64 @@
+use phantom_coerce::Coerce;
+use std::marker::PhantomData;
+
+#[derive(Clone)]
+struct Raw;
+#[derive(Clone)]
+struct Validated;
+#[derive(Clone)]
+struct AnyStage;
+
+#[derive(Clone)]
+struct Json;
+#[derive(Clone)]
+struct Fast;
+#[derive(Clone)]
+struct Local;
+
+#[derive(Coerce, Clone)]
+#[coerce(
+    borrowed_from = "Pipeline<Raw | Validated, ..>",
+    borrowed_to = "Pipeline<AnyStage, ..>"
+)]
+#[coerce(
+    owned_from = "Pipeline<Validated, ..>",
+    owned_to = "Pipeline<AnyStage, _, _, _>"
+)]
+#[coerce(
+    cloned_from = "Pipeline<Raw, Json, ..>",
+    cloned_to = "Pipeline<AnyStage, Json, ..>"
+)]
+struct Pipeline<Stage, Format, Speed, Region> {
+    stage: PhantomData<Stage>,
+    format: PhantomData<Format>,
+    speed: PhantomData<Speed>,
+    region: PhantomData<Region>,
+    name: String,
+}
+
+fn pipeline<Stage, Format, Speed, Region>(name: &str) -> Pipeline<Stage, Format, Speed, Region> {
+    Pipeline {
+        stage: PhantomData,
+        format: PhantomData,
+        speed: PhantomData,
+        region: PhantomData,
+        name: name.to_string(),
+    }
+}
+
+#[test]
+fn rest_covers_remaining_parameters() {
+    let raw = pipeline::<Raw, Json, Fast, Local>("ingest");
+    let generic: &Pipeline<AnyStage, Json, Fast, Local> = raw.coerce();
+    assert_eq!(generic.name, "ingest");
+
+    let cloned: Pipeline<AnyStage, Json, Fast, Local> = raw.to_coerced();
+    assert_eq!(cloned.name, "ingest");
+}
+
+#[test]
+fn rest_matches_spelled_out_holes() {
+    let validated = pipeline::<Validated, Json, Fast, Local>("publish");
+    let owned: Pipeline<AnyStage, Json, Fast, Local> = validated.into_coerced();
+    assert_eq!(owned.name, "publish");
+}
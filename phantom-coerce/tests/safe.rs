@@ -0,0 +1,85 @@
+// The generated code must compile under a crate-wide unsafe ban
+#![forbid(unsafe_code)]
+
+use phantom_coerce::Coerce;
+use std::marker::PhantomData;
+
+#[derive(Clone)]
+struct Validated;
+#[derive(Clone)]
+struct AnyStatus;
+#[derive(Clone)]
+struct Absolute;
+#[derive(Clone)]
+struct UnknownBase;
+
+#[derive(Coerce, Clone)]
+#[coerce(safe)]
+#[coerce(owned_from = "Request<Validated>", owned_to = "Request<AnyStatus>")]
+#[coerce(cloned_from = "Request<Validated>", cloned_to = "Request<AnyStatus>")]
+struct Request<Status> {
+    marker: PhantomData<Status>,
+    url: String,
+    headers: Vec<(String, String)>,
+}
+
+fn request() -> Request<Validated> {
+    Request {
+        marker: PhantomData,
+        url: "https://api.example.com".to_string(),
+        headers: vec![("Accept".to_string(), "*/*".to_string())],
+    }
+}
+
+#[test]
+fn safe_owned() {
+    let any: Request<AnyStatus> = request().into_coerced();
+    assert_eq!(any.url, "https://api.example.com");
+    assert_eq!(any.headers.len(), 1);
+}
+
+#[test]
+fn safe_cloned() {
+    let validated = request();
+    let any = validated.to_coerced::<Request<AnyStatus>>();
+    assert_eq!(any.url, validated.url);
+}
+
+#[derive(Coerce, Clone)]
+#[coerce(safe)]
+#[coerce(
+    owned_from = "TypedPath<Absolute>",
+    owned_to = "TypedPath<UnknownBase>"
+)]
+struct TypedPath<Base> {
+    base: PhantomData<Base>,
+    path: String,
+}
+
+// Deep fields are rebuilt through their own owned coercion, in both modes
+#[derive(Coerce, Clone)]
+#[coerce(safe)]
+#[coerce(owned_from = "Upload<Absolute>", owned_to = "Upload<UnknownBase>")]
+#[coerce(cloned_from = "Upload<Absolute>", cloned_to = "Upload<UnknownBase>")]
+struct Upload<Base> {
+    #[coerce(deep)]
+    destination: TypedPath<Base>,
+    bytes: Vec<u8>,
+}
+
+#[test]
+fn safe_deep() {
+    let upload = Upload::<Absolute> {
+        destination: TypedPath {
+            base: PhantomData,
+            path: "/srv/upload.bin".to_string(),
+        },
+        bytes: vec![1, 2, 3],
+    };
+
+    let cloned: Upload<UnknownBase> = upload.to_coerced();
+    assert_eq!(cloned.destination.path, "/srv/upload.bin");
+
+    let owned: Upload<UnknownBase> = upload.into_coerced();
+    assert_eq!(owned.bytes, vec![1, 2, 3]);
+}
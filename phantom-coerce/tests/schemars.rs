@@ -0,0 +1,75 @@
+#![cfg(feature = "schemars")]
+
+use phantom_coerce::Coerce;
+use phantom_coerce::tag::Tagged;
+use schemars::schema_for;
+use std::marker::PhantomData;
+
+struct Validated;
+struct AnyStatus;
+
+#[derive(Coerce, serde::Serialize)]
+#[coerce(json_schema)]
+#[coerce(owned_from = "Request<Validated>", owned_to = "Request<AnyStatus>")]
+struct Request<Status> {
+    #[serde(skip)]
+    marker: PhantomData<Status>,
+    path: String,
+    signature: Option<String>,
+}
+
+#[derive(Coerce, serde::Serialize)]
+#[serde(bound = "T: serde::Serialize")]
+#[coerce(json_schema)]
+#[coerce(
+    owned_from = "Labeled<_, Validated>",
+    owned_to = "Labeled<_, AnyStatus>"
+)]
+struct Labeled<T, Status> {
+    marker: PhantomData<Status>,
+    value: T,
+}
+
+#[test]
+fn schema_is_marker_independent() {
+    let validated = serde_json::to_value(schema_for!(Request<Validated>)).unwrap();
+    let any = serde_json::to_value(schema_for!(Request<AnyStatus>)).unwrap();
+    assert_eq!(validated, any);
+
+    assert_eq!(validated["title"], "Request");
+    assert_eq!(validated["type"], "object");
+    let properties = validated["properties"].as_object().unwrap();
+    assert!(properties.contains_key("path"));
+    assert!(properties.contains_key("signature"));
+    assert!(!properties.contains_key("marker"));
+    assert_eq!(validated["required"], serde_json::json!(["path"]));
+}
+
+#[test]
+fn data_parameters_name_the_schema() {
+    let schema = serde_json::to_value(schema_for!(Labeled<u32, Validated>)).unwrap();
+    assert_eq!(schema["title"], "Labeled_for_uint32");
+    assert_eq!(schema["properties"]["marker"]["type"], "null");
+    assert_eq!(schema["properties"]["value"]["type"], "integer");
+}
+
+#[test]
+fn schema_matches_serialized_fields() {
+    let labeled = Labeled::<u32, Validated> {
+        marker: PhantomData,
+        value: 7,
+    };
+    let encoded = serde_json::to_value(&labeled).unwrap();
+    assert_eq!(encoded, serde_json::json!({ "marker": null, "value": 7 }));
+    assert_eq!(labeled.value, 7);
+}
+
+#[test]
+fn tagged_schema_wraps_value() {
+    let schema = serde_json::to_value(schema_for!(Tagged<Request<AnyStatus>>)).unwrap();
+    assert_eq!(schema["properties"]["tag"]["type"], "string");
+    assert_eq!(
+        schema["properties"]["value"]["$ref"],
+        "#/definitions/Request"
+    );
+}
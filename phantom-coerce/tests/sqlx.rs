@@ -0,0 +1,55 @@
+#![cfg(feature = "sqlx")]
+
+use phantom_coerce::Coerce;
+use sqlx::{Connection, Row, SqliteConnection};
+use std::marker::PhantomData;
+
+struct Users;
+struct Orders;
+struct AnyTable;
+
+#[derive(Coerce)]
+#[coerce(sqlx)]
+#[coerce(owned_from = "Id<Users | Orders>", owned_to = "Id<AnyTable>")]
+struct Id<Table> {
+    table: PhantomData<Table>,
+    value: i64,
+}
+
+impl<Table> Id<Table> {
+    fn new(value: i64) -> Self {
+        Self {
+            table: PhantomData,
+            value,
+        }
+    }
+}
+
+#[tokio::test]
+async fn ids_round_trip_through_the_inner_column() {
+    let mut conn = SqliteConnection::connect("sqlite::memory:").await.unwrap();
+    sqlx::query("CREATE TABLE orders (id INTEGER NOT NULL, user_id INTEGER NOT NULL)")
+        .execute(&mut conn)
+        .await
+        .unwrap();
+
+    sqlx::query("INSERT INTO orders (id, user_id) VALUES (?, ?)")
+        .bind(Id::<Orders>::new(7))
+        .bind(Id::<Users>::new(42))
+        .execute(&mut conn)
+        .await
+        .unwrap();
+
+    let row = sqlx::query("SELECT id, user_id FROM orders")
+        .fetch_one(&mut conn)
+        .await
+        .unwrap();
+    let order: Id<Orders> = row.get("id");
+    let user: Id<Users> = row.get("user_id");
+    assert_eq!(order.value, 7);
+    assert_eq!(user.value, 42);
+
+    // Erased IDs decode the same column
+    let any: Id<AnyTable> = row.get("user_id");
+    assert_eq!(any.value, user.into_coerced::<Id<AnyTable>>().value);
+}
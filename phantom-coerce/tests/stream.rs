@@ -0,0 +1,67 @@
+#![cfg(feature = "futures")]
+
+use futures::executor::block_on;
+use futures::{Stream, StreamExt};
+use phantom_coerce::Coerce;
+use phantom_coerce::stream::CoerceStreamExt;
+use std::marker::PhantomData;
+
+struct Raw;
+struct Parsed;
+struct AnyStage;
+
+#[derive(Coerce)]
+#[coerce(owned_from = "Record<Raw | Parsed>", owned_to = "Record<AnyStage>")]
+#[coerce(
+    borrowed_from = "Record<Raw | Parsed>",
+    borrowed_to = "Record<AnyStage>"
+)]
+struct Record<Stage> {
+    stage: PhantomData<Stage>,
+    id: u32,
+}
+
+fn record<Stage>(id: u32) -> Record<Stage> {
+    Record {
+        stage: PhantomData,
+        id,
+    }
+}
+
+#[test]
+fn owned_items() {
+    let raw = futures::stream::iter(vec![record::<Raw>(1), record(2)]);
+    let ids: Vec<u32> = block_on(
+        raw.coerce_items::<Record<AnyStage>>()
+            .map(|record| record.id)
+            .collect(),
+    );
+    assert_eq!(ids, [1, 2]);
+}
+
+#[test]
+fn borrowed_items() {
+    let parsed = [record::<Parsed>(3), record(4)];
+    let generic = futures::stream::iter(&parsed).coerce_items::<&Record<AnyStage>>();
+    let generic: Vec<&Record<AnyStage>> = block_on(generic.collect());
+    assert_eq!(generic[1].id, 4);
+}
+
+#[test]
+fn pinned_streams() {
+    // Unpin is not required of the underlying stream
+    let stream =
+        futures::stream::unfold(
+            0,
+            |n| async move { (n < 3).then(|| (record::<Raw>(n), n + 1)) },
+        );
+    let generic = stream.coerce_items::<Record<AnyStage>>();
+    let ids: Vec<u32> = block_on(generic.map(|record| record.id).collect());
+    assert_eq!(ids, [0, 1, 2]);
+    assert_eq!(
+        futures::stream::iter([record::<Raw>(0)])
+            .coerce_items::<Record<AnyStage>>()
+            .size_hint(),
+        (1, Some(1))
+    );
+}
@@ -0,0 +1,57 @@
+use phantom_coerce::Coerce;
+use phantom_coerce::tag::{MarkerTag, UnknownTag};
+use std::marker::PhantomData;
+
+struct Json;
+struct Xml;
+struct AnyFormat;
+
+impl MarkerTag for Json {
+    const TAG: &'static str = "json";
+}
+
+impl MarkerTag for Xml {
+    const TAG: &'static str = "xml";
+}
+
+#[derive(Coerce)]
+#[coerce(owned_from = "Message<Json | Xml>", owned_to = "Message<AnyFormat>")]
+#[coerce(tag = "Format", tag_enum = "FormatTag")]
+struct Message<Format> {
+    marker: PhantomData<Format>,
+    content: String,
+}
+
+#[test]
+fn all_lists_markers_in_order() {
+    assert_eq!(FormatTag::ALL, [FormatTag::Json, FormatTag::Xml]);
+}
+
+#[test]
+fn display_writes_tag() {
+    assert_eq!(FormatTag::Json.to_string(), "json");
+    assert_eq!(format!("format={}", FormatTag::Xml), "format=xml");
+    assert_eq!(FormatTag::Xml.as_str(), Xml::TAG);
+}
+
+#[test]
+fn from_str_round_trips() {
+    for tag in FormatTag::ALL {
+        assert_eq!(tag.to_string().parse::<FormatTag>(), Ok(tag));
+    }
+
+    let error = "yaml".parse::<FormatTag>().unwrap_err();
+    assert_eq!(error, UnknownTag::new("yaml"));
+    assert_eq!(error.to_string(), "unknown marker tag `yaml`");
+}
+
+#[test]
+fn parses_recorded_tag() {
+    let message = Message::<Xml> {
+        marker: PhantomData,
+        content: "<a/>".to_string(),
+    };
+    let erased = message.into_tagged::<Message<AnyFormat>>();
+    assert_eq!(erased.tag().parse(), Ok(FormatTag::Xml));
+    assert_eq!(erased.value().content, "<a/>");
+}
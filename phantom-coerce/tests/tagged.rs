@@ -0,0 +1,76 @@
+use phantom_coerce::Coerce;
+use phantom_coerce::tag::{MarkerTag, Tagged};
+use std::marker::PhantomData;
+
+#[derive(Clone)]
+struct Json;
+#[derive(Clone)]
+struct Xml;
+#[derive(Clone)]
+struct AnyFormat;
+
+impl MarkerTag for Json {
+    const TAG: &'static str = "json";
+}
+
+impl MarkerTag for Xml {
+    const TAG: &'static str = "xml";
+}
+
+#[derive(Coerce, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[coerce(owned_from = "Message<Json | Xml>", owned_to = "Message<AnyFormat>")]
+#[coerce(cloned_from = "Message<Json | Xml>", cloned_to = "Message<AnyFormat>")]
+#[coerce(tag = "Format")]
+struct Message<Format> {
+    marker: PhantomData<Format>,
+    content: String,
+}
+
+impl<Format> Message<Format> {
+    fn new(content: &str) -> Self {
+        Self {
+            marker: PhantomData,
+            content: content.to_string(),
+        }
+    }
+}
+
+#[test]
+fn tagged_records_specific_marker() {
+    let json = Message::<Json>::new("{}");
+    assert_eq!(json.tagged_ref().tag(), "json");
+    assert_eq!(json.marker_name(), "json");
+    assert_eq!(json.tagged().value().content, "{}");
+}
+
+#[test]
+fn erasure_keeps_marker_tag() {
+    let xml = Message::<Xml>::new("<a/>");
+
+    let cloned: Tagged<Message<AnyFormat>> = xml.to_tagged();
+    assert_eq!(cloned.tag(), "xml");
+
+    let owned = xml.into_tagged::<Message<AnyFormat>>();
+    assert_eq!(owned.tag(), "xml");
+    assert!(owned.is::<Xml>());
+    assert!(!owned.is::<Json>());
+    assert_eq!(owned.into_value().content, "<a/>");
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn json_round_trip() {
+    let json = Message::<Json>::new("payload");
+    let encoded = serde_json::to_string(&json.tagged_ref()).unwrap();
+    assert_eq!(
+        encoded,
+        r#"{"tag":"json","value":{"marker":null,"content":"payload"}}"#
+    );
+
+    // Decoding into the generic type keeps the specific marker's tag
+    let decoded: Tagged<Message<AnyFormat>> = serde_json::from_str(&encoded).unwrap();
+    assert_eq!(decoded.tag(), Json::TAG);
+    assert!(decoded.is::<Json>());
+    assert_eq!(decoded.value().content, "payload");
+}
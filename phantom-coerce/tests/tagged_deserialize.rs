@@ -0,0 +1,106 @@
+#![cfg(feature = "serde")]
+
+use phantom_coerce::Coerce;
+use phantom_coerce::tag::MarkerTag;
+use std::cell::Cell;
+use std::marker::PhantomData;
+
+struct Json;
+struct Xml;
+struct AnyFormat;
+
+impl MarkerTag for Json {
+    const TAG: &'static str = "json";
+}
+
+impl MarkerTag for Xml {
+    const TAG: &'static str = "xml";
+}
+
+thread_local! {
+    static ERASED: Cell<usize> = const { Cell::new(0) };
+}
+
+fn count_erased<Format>(_: &Message<Format>) {
+    ERASED.with(|count| count.set(count.get() + 1));
+}
+
+#[derive(Coerce)]
+#[coerce(
+    owned_from = "Message<Json | Xml>",
+    owned_to = "Message<AnyFormat>",
+    guard = "count_erased"
+)]
+#[coerce(tag = "Format", tag_field = "format")]
+struct Message<Format> {
+    marker: PhantomData<Format>,
+    content: String,
+    priority: Option<u8>,
+}
+
+#[test]
+fn known_tags_dispatch() {
+    let json: Message<AnyFormat> =
+        serde_json::from_str(r#"{"format":"json","content":"{}","priority":3}"#).unwrap();
+    assert_eq!(json.content, "{}");
+    assert_eq!(json.priority, Some(3));
+
+    // The discriminant may come after the data, and optional fields may be left out
+    let xml: Message<AnyFormat> =
+        serde_json::from_str(r#"{"content":"<a/>","format":"xml"}"#).unwrap();
+    assert_eq!(xml.content, "<a/>");
+    assert_eq!(xml.priority, None);
+    assert_eq!(ERASED.with(Cell::get), 2);
+}
+
+#[test]
+fn unknown_tag_is_rejected() {
+    let error = serde_json::from_str::<Message<AnyFormat>>(r#"{"format":"yaml","content":"a"}"#)
+        .err()
+        .unwrap();
+    assert!(
+        error
+            .to_string()
+            .starts_with("unknown variant `yaml`, expected `json` or `xml`"),
+        "{error}"
+    );
+}
+
+#[test]
+fn missing_fields_are_rejected() {
+    let error = serde_json::from_str::<Message<AnyFormat>>(r#"{"content":"a"}"#)
+        .err()
+        .unwrap();
+    assert!(
+        error.to_string().starts_with("missing field `format`"),
+        "{error}"
+    );
+
+    let error = serde_json::from_str::<Message<AnyFormat>>(r#"{"format":"json"}"#)
+        .err()
+        .unwrap();
+    assert!(
+        error.to_string().starts_with("missing field `content`"),
+        "{error}"
+    );
+}
+
+#[test]
+fn unknown_fields_are_ignored() {
+    let message: Message<AnyFormat> =
+        serde_json::from_str(r#"{"format":"json","content":"{}","extra":[1,2]}"#).unwrap();
+    assert_eq!(message.content, "{}");
+}
+
+#[test]
+fn duplicate_tag_is_rejected() {
+    let error = serde_json::from_str::<Message<AnyFormat>>(
+        r#"{"format":"json","format":"xml","content":"a"}"#,
+    )
+    .err()
+    .unwrap();
+    assert!(
+        error.to_string().starts_with("duplicate field `format`"),
+        "{error}"
+    );
+}
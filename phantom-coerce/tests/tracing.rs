@@ -0,0 +1,130 @@
+#![cfg(feature = "tracing")]
+
+use phantom_coerce::Coerce;
+use std::marker::PhantomData;
+use std::sync::{Arc, Mutex};
+use tracing::field::{Field, Visit};
+use tracing::span::{Attributes, Id, Record};
+use tracing::{Event, Metadata, Subscriber};
+
+#[derive(Clone)]
+struct Validated;
+#[derive(Clone)]
+struct AnyStatus;
+#[derive(Clone)]
+struct LegacyStatus;
+
+#[derive(Coerce, Clone)]
+#[coerce(
+    borrowed_from = "Request<Validated>",
+    borrowed_to = "Request<AnyStatus>"
+)]
+#[coerce(owned_from = "Request<Validated>", owned_to = "Request<AnyStatus>")]
+#[coerce(cloned_from = "Request<Validated>", cloned_to = "Request<AnyStatus>")]
+struct Request<Status> {
+    marker: PhantomData<Status>,
+    url: String,
+}
+
+#[derive(Coerce)]
+#[coerce(
+    owned_from = "Job<LegacyStatus>",
+    owned_to = "Job<AnyStatus>",
+    deprecated = "queue a Job<Validated> instead"
+)]
+struct Job<Status> {
+    marker: PhantomData<Status>,
+    id: u64,
+}
+
+type Fields = Vec<(String, String)>;
+
+/// Records the string fields of every event, followed by its level
+#[derive(Clone, Default)]
+struct Recorder(Arc<Mutex<Vec<Fields>>>);
+
+struct FieldVisitor(Fields);
+
+impl Visit for FieldVisitor {
+    fn record_str(&mut self, field: &Field, value: &str) {
+        self.0.push((field.name().to_string(), value.to_string()));
+    }
+
+    fn record_debug(&mut self, _: &Field, _: &dyn std::fmt::Debug) {}
+}
+
+impl Subscriber for Recorder {
+    fn enabled(&self, _: &Metadata<'_>) -> bool {
+        true
+    }
+
+    fn new_span(&self, _: &Attributes<'_>) -> Id {
+        Id::from_u64(1)
+    }
+
+    fn record(&self, _: &Id, _: &Record<'_>) {}
+
+    fn record_follows_from(&self, _: &Id, _: &Id) {}
+
+    fn event(&self, event: &Event<'_>) {
+        let mut visitor = FieldVisitor(Vec::new());
+        event.record(&mut visitor);
+        let level = event.metadata().level().to_string();
+        visitor.0.push(("level".to_string(), level));
+        self.0.lock().unwrap().push(visitor.0);
+    }
+
+    fn enter(&self, _: &Id) {}
+
+    fn exit(&self, _: &Id) {}
+}
+
+#[test]
+fn coercions_emit_trace_events() {
+    let recorder = Recorder::default();
+    tracing::subscriber::with_default(recorder.clone(), || {
+        let request = Request::<Validated> {
+            marker: PhantomData,
+            url: "/checkout".to_string(),
+        };
+        let _borrowed: &Request<AnyStatus> = request.coerce();
+        let _cloned: Request<AnyStatus> = request.to_coerced();
+        let owned: Request<AnyStatus> = request.into_coerced();
+        assert_eq!(owned.url, "/checkout");
+    });
+
+    let events = recorder.0.lock().unwrap();
+    let modes: Vec<&str> = events.iter().map(|fields| fields[0].1.as_str()).collect();
+    assert_eq!(modes, ["borrowed", "cloned", "owned"]);
+
+    let (source, target) = (&events[0][1], &events[0][2]);
+    assert_eq!(source.0, "source");
+    assert!(source.1.ends_with("Request<tracing::Validated>"));
+    assert_eq!(target.0, "target");
+    assert!(target.1.ends_with("Request<tracing::AnyStatus>"));
+}
+
+#[test]
+fn deprecated_coercions_emit_warnings() {
+    let recorder = Recorder::default();
+    tracing::subscriber::with_default(recorder.clone(), || {
+        let job = Job::<LegacyStatus> {
+            marker: PhantomData,
+            id: 7,
+        };
+        let owned: Job<AnyStatus> = job.into_coerced();
+        assert_eq!(owned.id, 7);
+    });
+
+    let events = recorder.0.lock().unwrap();
+    assert_eq!(events.len(), 1);
+    let fields = &events[0];
+    assert!(fields.contains(&(
+        "note".to_string(),
+        "queue a Job<Validated> instead".to_string()
+    )));
+    assert_eq!(
+        fields.last().unwrap(),
+        &("level".to_string(), "WARN".to_string())
+    );
+}
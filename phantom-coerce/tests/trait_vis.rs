@@ -0,0 +1,58 @@
+mod paths {
+    use phantom_coerce::Coerce;
+    use std::marker::PhantomData;
+
+    #[derive(Clone)]
+    pub struct Absolute;
+    #[derive(Clone)]
+    pub struct UnknownBase;
+
+    #[derive(Coerce, Clone)]
+    #[coerce(trait_vis = "pub", trait_name = "Path")]
+    #[coerce(
+        borrowed_from = "TypedPath<Absolute>",
+        borrowed_to = "TypedPath<UnknownBase>"
+    )]
+    #[coerce(
+        owned_from = "TypedPath<Absolute>",
+        owned_to = "TypedPath<UnknownBase>"
+    )]
+    #[coerce(
+        cloned_from = "TypedPath<Absolute>",
+        cloned_to = "TypedPath<UnknownBase>"
+    )]
+    pub struct TypedPath<Base> {
+        base: PhantomData<Base>,
+        pub path: String,
+    }
+
+    impl TypedPath<Absolute> {
+        pub fn new(path: &str) -> Self {
+            Self {
+                base: PhantomData,
+                path: path.to_string(),
+            }
+        }
+    }
+}
+
+use paths::{Absolute, CoerceClonedPath, CoerceOwnedPath, CoerceRefPath, TypedPath, UnknownBase};
+
+// Bounds written outside the struct's module, against the re-exportable trait names
+fn display<P: CoerceRefPath<TypedPath<UnknownBase>>>(path: &P) -> &str {
+    &path.coerce().path
+}
+
+fn store<P: CoerceOwnedPath<TypedPath<UnknownBase>>>(path: P) -> TypedPath<UnknownBase> {
+    path.into_coerced()
+}
+
+#[test]
+fn traits_usable_outside_module() {
+    let path = TypedPath::<Absolute>::new("/etc");
+    assert_eq!(display(&path), "/etc");
+
+    let cloned: TypedPath<UnknownBase> = path.to_coerced();
+    assert_eq!(cloned.path, "/etc");
+    assert_eq!(store(path).path, "/etc");
+}
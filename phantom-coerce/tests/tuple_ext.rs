@@ -0,0 +1,93 @@
+use phantom_coerce::{Coerce, CoerceExt};
+use std::marker::PhantomData;
+
+struct Absolute;
+struct UnknownBase;
+struct Json;
+struct AnyFormat;
+
+#[derive(Coerce)]
+#[coerce(
+    borrowed_from = "TypedPath<Absolute>",
+    borrowed_to = "TypedPath<UnknownBase>"
+)]
+#[coerce(
+    owned_from = "TypedPath<Absolute>",
+    owned_to = "TypedPath<UnknownBase>"
+)]
+struct TypedPath<Base> {
+    base: PhantomData<Base>,
+    path: String,
+}
+
+#[derive(Coerce)]
+#[coerce(borrowed_from = "Document<Json>", borrowed_to = "Document<AnyFormat>")]
+#[coerce(owned_from = "Document<Json>", owned_to = "Document<AnyFormat>")]
+struct Document<Format> {
+    format: PhantomData<Format>,
+    body: String,
+}
+
+/// Not coercible into anything, not even itself
+struct Metadata {
+    size: usize,
+}
+
+fn load() -> (TypedPath<Absolute>, Document<Json>) {
+    (
+        TypedPath {
+            base: PhantomData,
+            path: "/data.json".to_string(),
+        },
+        Document {
+            format: PhantomData,
+            body: "{}".to_string(),
+        },
+    )
+}
+
+#[test]
+fn first_with_arbitrary_second() {
+    let pair = (load().0, Metadata { size: 2 });
+    let (path, metadata): (TypedPath<UnknownBase>, Metadata) = pair.coerce_first();
+    assert_eq!((path.path.as_str(), metadata.size), ("/data.json", 2));
+}
+
+#[test]
+fn second_with_arbitrary_first() {
+    let pair = (Metadata { size: 2 }, load().1);
+    let (metadata, document) = pair.coerce_second::<Document<AnyFormat>>();
+    assert_eq!((metadata.size, document.body.as_str()), (2, "{}"));
+}
+
+#[test]
+fn both_owned() {
+    let (path, document): (TypedPath<UnknownBase>, Document<AnyFormat>) = load().coerce_both();
+    assert_eq!(
+        (path.path.as_str(), document.body.as_str()),
+        ("/data.json", "{}")
+    );
+}
+
+#[test]
+fn pair_of_references() {
+    let (path, document) = load();
+    let (path, document): (&TypedPath<UnknownBase>, &Document<AnyFormat>) =
+        (&path, &document).coerce_both();
+    assert_eq!(
+        (path.path.as_str(), document.body.as_str()),
+        ("/data.json", "{}")
+    );
+}
+
+#[test]
+fn borrowed_pair() {
+    let pair = load();
+    let (path, document) = pair.coerce_refs::<TypedPath<UnknownBase>, Document<AnyFormat>>();
+    assert_eq!(
+        (path.path.as_str(), document.body.as_str()),
+        ("/data.json", "{}")
+    );
+    // The pair is still usable afterwards
+    assert_eq!(pair.0.path, "/data.json");
+}
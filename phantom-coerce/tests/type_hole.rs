@@ -48,3 +48,13 @@ fn type_hole_in_second_position() {
     let coerced_b: &TypeHoleSecond<GenericParam, ParamY> = test_b.coerce();
     assert_eq!(coerced_b.get_value(), "type hole second B");
 }
+
+#[test]
+fn partial_turbofish() {
+    let test_a = TypeHoleSecond::<ParamA, ParamX>::new("partial turbofish");
+
+    // Only one declared target matches, so inference fills the hole
+    let coerced = test_a.coerce::<TypeHoleSecond<GenericParam, _>>();
+    let _: &TypeHoleSecond<GenericParam, ParamX> = coerced;
+    assert_eq!(coerced.get_value(), "partial turbofish");
+}
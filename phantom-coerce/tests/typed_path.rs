@@ -10,7 +10,8 @@ struct SomeType;
 #[derive(Coerce)]
 #[coerce(
     borrowed_from = "TypedPath<Absolute, File>",
-    borrowed_to = "TypedPath<SomeBase | Absolute, File | SomeType>"
+    borrowed_to = "TypedPath<SomeBase | Absolute, File | SomeType>",
+    allow_noop
 )]
 struct TypedPath<Base, Type> {
     base: PhantomData<Base>,
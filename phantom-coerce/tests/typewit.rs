@@ -0,0 +1,95 @@
+#![cfg(feature = "typewit")]
+
+use phantom_coerce::Coerce;
+use phantom_coerce::witness::{CoerceRefWitness, CoerceWitness};
+use std::marker::PhantomData;
+use typewit::{HasTypeWitness, TypeEq};
+
+struct Absolute;
+struct Relative;
+struct UnknownBase;
+struct File;
+
+#[derive(Coerce)]
+#[coerce(
+    borrowed_from = "TypedPath<Absolute | Relative, _>",
+    borrowed_to = "TypedPath<UnknownBase, _>"
+)]
+#[coerce(
+    owned_from = "TypedPath<Absolute | Relative, _>",
+    owned_to = "TypedPath<UnknownBase, _>"
+)]
+#[coerce(type_fn(Base = "TypedPathBase"))]
+struct TypedPath<Base, Type> {
+    base: PhantomData<Base>,
+    ty: PhantomData<Type>,
+    path: String,
+}
+
+fn path<Base>(path: &str) -> TypedPath<Base, File> {
+    TypedPath {
+        base: PhantomData,
+        ty: PhantomData,
+        path: path.to_string(),
+    }
+}
+
+typewit::simple_type_witness! {
+    enum BaseWitness {
+        Absolute = Absolute,
+        Relative = Relative,
+    }
+}
+
+// Narrows a generic marker through `TypeEq`, with no unsafe code and no coercion bound
+fn describe<Base: HasTypeWitness<BaseWitness<Base>>>(path: &TypedPath<Base, File>) -> String {
+    match Base::WITNESS {
+        BaseWitness::Absolute(te) => {
+            let absolute: &TypedPath<Absolute, File> =
+                te.map(TypedPathBase::NEW).in_ref().to_right(path);
+            format!("absolute {}", absolute.path)
+        }
+        BaseWitness::Relative(te) => {
+            let relative: &TypedPath<Relative, File> =
+                te.map(TypedPathBase::NEW).in_ref().to_right(path);
+            format!(
+                "relative {}",
+                relative.coerce::<TypedPath<UnknownBase, File>>().path
+            )
+        }
+    }
+}
+
+#[test]
+fn type_fn_maps_marker_equality() {
+    assert_eq!(describe(&path::<Absolute>("/etc")), "absolute /etc");
+    assert_eq!(describe(&path::<Relative>("src")), "relative src");
+}
+
+#[test]
+fn type_fn_round_trips_owned_values() {
+    let te: TypeEq<Absolute, Absolute> = TypeEq::NEW;
+    let mapped = te.map(TypedPathBase::<File>::NEW);
+    let absolute: TypedPath<Absolute, File> = mapped.to_left(path("/a"));
+    assert_eq!(absolute.path, "/a");
+}
+
+fn erase_all<S, T>(values: Vec<S>, witness: CoerceWitness<S, T>) -> Vec<T> {
+    values
+        .into_iter()
+        .map(|value| witness.into_coerced(value))
+        .collect()
+}
+
+#[test]
+fn witnesses_coerce_without_bounds() {
+    const ERASE_REF: CoerceRefWitness<TypedPath<Relative, File>, TypedPath<UnknownBase, File>> =
+        CoerceRefWitness::NEW;
+    let relative = path::<Relative>("src");
+    assert_eq!(ERASE_REF.coerce(&relative).path, "src");
+
+    let erased: Vec<TypedPath<UnknownBase, File>> =
+        erase_all(vec![path::<Absolute>("/a"), path("/b")], CoerceWitness::NEW);
+    let paths: Vec<&str> = erased.iter().map(|path| path.path.as_str()).collect();
+    assert_eq!(paths, ["/a", "/b"]);
+}
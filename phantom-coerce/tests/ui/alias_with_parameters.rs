@@ -0,0 +1,17 @@
+use phantom_coerce::Coerce;
+use std::marker::PhantomData;
+
+struct Absolute;
+struct UnknownBase;
+struct File;
+
+#[derive(Coerce)]
+#[coerce(alias(type Abs<Type> = TypedPath<Absolute, Type>;))]
+#[coerce(borrowed_from = "Abs<File>", borrowed_to = "TypedPath<UnknownBase, File>")]
+struct TypedPath<Base, Type> {
+    base: PhantomData<Base>,
+    ty: PhantomData<Type>,
+    path: String,
+}
+
+fn main() {}
@@ -0,0 +1,13 @@
+use phantom_coerce::Coerce;
+use std::marker::PhantomData;
+
+struct UnknownBase;
+
+#[derive(Coerce)]
+#[coerce(any(Base = "UnknownBase"))]
+struct TypedPath<Base> {
+    base: PhantomData<Base>,
+    root: Vec<Base>,
+}
+
+fn main() {}
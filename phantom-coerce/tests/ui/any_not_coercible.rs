@@ -0,0 +1,24 @@
+use phantom_coerce::{Coerce, Coercible};
+use std::marker::PhantomData;
+
+struct Absolute;
+struct UnknownBase;
+
+#[derive(Coerce)]
+#[coerce(any(Base = "UnknownBase"))]
+struct TypedPath<Base> {
+    base: PhantomData<Base>,
+    path: String,
+}
+
+fn store(path: impl Coercible<TypedPath<UnknownBase>>) -> String {
+    path.into_coerced().path
+}
+
+fn main() {
+    // any(...) upcasts have no `Coercible` impls: call `into_coerced` directly instead
+    store(TypedPath::<Absolute> {
+        base: PhantomData,
+        path: String::new(),
+    });
+}
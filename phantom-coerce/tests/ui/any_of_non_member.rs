@@ -0,0 +1,18 @@
+use phantom_coerce::Coerce;
+use std::marker::PhantomData;
+
+struct Json;
+struct Xml;
+struct Protobuf;
+
+#[derive(Coerce)]
+#[coerce(
+    borrowed_from = "Message<Json | Protobuf>",
+    borrowed_to = "Message<AnyOf<(Json, Xml)>>"
+)]
+struct Message<Format> {
+    marker: PhantomData<Format>,
+    content: String,
+}
+
+fn main() {}
@@ -0,0 +1,15 @@
+use phantom_coerce::Coerce;
+use std::marker::PhantomData;
+
+struct Absolute;
+struct UnknownBase;
+
+#[derive(Coerce)]
+#[coerce(any(Base = "UnknownBase"))]
+#[coerce(borrowed_from = "TypedPath<Absolute>", borrowed_to = "TypedPath<UnknownBase>")]
+struct TypedPath<Base> {
+    base: PhantomData<Base>,
+    path: String,
+}
+
+fn main() {}
@@ -0,0 +1,15 @@
+use phantom_coerce::Coerce;
+use std::marker::PhantomData;
+
+struct Draft;
+struct AnyState;
+
+#[derive(Coerce)]
+#[coerce(generate = "asref_only")]
+#[coerce(owned_from = "Document<Draft>", owned_to = "Document<AnyState>")]
+struct Document<State> {
+    state: PhantomData<State>,
+    body: String,
+}
+
+fn main() {}
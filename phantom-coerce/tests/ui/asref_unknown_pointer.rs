@@ -0,0 +1,16 @@
+// This should fail because asref(...) only accepts Box, Rc, and Arc
+
+use std::marker::PhantomData;
+use phantom_coerce::Coerce;
+
+struct Marker1;
+struct Marker2;
+
+#[derive(Coerce)]
+#[coerce(borrowed_from = "Pointer<Marker1>", borrowed_to = "Pointer<Marker2>", asref(Box, Cell))]
+struct Pointer<M> {
+    phantom: PhantomData<M>,
+    value: String,
+}
+
+fn main() {}
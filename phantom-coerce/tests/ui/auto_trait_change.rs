@@ -0,0 +1,16 @@
+use phantom_coerce::Coerce;
+use std::marker::PhantomData;
+use std::rc::Rc;
+
+// Not Send or Sync: a handle pinned to the thread that created it
+struct LocalOnly(PhantomData<Rc<()>>);
+struct AnyThread;
+
+#[derive(Coerce)]
+#[coerce(owned_from = "Handle<LocalOnly>", owned_to = "Handle<AnyThread>")]
+struct Handle<Affinity> {
+    marker: PhantomData<Affinity>,
+    id: u64,
+}
+
+fn main() {}
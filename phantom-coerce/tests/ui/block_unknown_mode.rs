@@ -0,0 +1,17 @@
+use phantom_coerce::phantom_coerce;
+
+struct Absolute;
+struct UnknownBase;
+
+phantom_coerce! {
+    struct TypedPath<Base> {
+        base: std::marker::PhantomData<Base>,
+        path: String,
+    }
+
+    coercions {
+        mutable TypedPath<Absolute> => TypedPath<UnknownBase>;
+    }
+}
+
+fn main() {}
@@ -0,0 +1,25 @@
+use phantom_coerce::Coerce;
+use std::marker::PhantomData;
+use std::rc::Rc;
+
+struct Ready;
+struct AnyState;
+
+#[derive(Coerce)]
+#[coerce(
+    owned_from = "Job<Ready, _>",
+    owned_to = "Job<AnyState, _>",
+    bound = "Output: Send + 'static"
+)]
+struct Job<State, Output> {
+    state: PhantomData<State>,
+    output: PhantomData<Output>,
+}
+
+fn main() {
+    let job = Job::<Ready, Rc<()>> {
+        state: PhantomData,
+        output: PhantomData,
+    };
+    let _: Job<AnyState, Rc<()>> = job.into_coerced();
+}
@@ -0,0 +1,24 @@
+use phantom_coerce::{Coerce, coerce_chain};
+use std::marker::PhantomData;
+
+struct Validated;
+struct AnyStatus;
+struct Get;
+struct AnyMethod;
+
+#[derive(Coerce)]
+#[coerce(owned_from = "Request<Validated, Get>", owned_to = "Request<AnyStatus, Get>")]
+struct Request<Status, Method> {
+    status: PhantomData<Status>,
+    method: PhantomData<Method>,
+}
+
+fn main() {
+    let request = Request::<Validated, Get> {
+        status: PhantomData,
+        method: PhantomData,
+    };
+
+    // The second hop was never declared
+    let _ = coerce_chain!(request => Request<AnyStatus, Get> => Request<AnyStatus, AnyMethod>);
+}
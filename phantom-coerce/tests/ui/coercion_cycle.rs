@@ -0,0 +1,17 @@
+use phantom_coerce::Coerce;
+use std::marker::PhantomData;
+
+struct Validated;
+struct Reviewed;
+struct AnyStatus;
+
+#[derive(Coerce)]
+#[coerce(owned_from = "Request<Validated>", owned_to = "Request<Reviewed>")]
+#[coerce(owned_from = "Request<Reviewed>", owned_to = "Request<AnyStatus>")]
+#[coerce(owned_from = "Request<AnyStatus>", owned_to = "Request<Validated>")]
+struct Request<Status> {
+    marker: PhantomData<Status>,
+    url: String,
+}
+
+fn main() {}
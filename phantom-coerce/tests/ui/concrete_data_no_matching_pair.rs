@@ -0,0 +1,17 @@
+use phantom_coerce::Coerce;
+use std::marker::PhantomData;
+
+struct Raw;
+struct AnyState;
+
+#[derive(Coerce)]
+#[coerce(
+    borrowed_from = "Container<String, Raw>",
+    borrowed_to = "Container<u32 | Vec<u8>, AnyState>"
+)]
+struct Container<T, State> {
+    data: T,
+    state: PhantomData<State>,
+}
+
+fn main() {}
@@ -0,0 +1,17 @@
+use phantom_coerce::Coerce;
+use std::marker::PhantomData;
+
+struct Raw;
+struct AnyState;
+
+#[derive(Coerce)]
+#[coerce(borrowed_from = "Container<String, Raw>", borrowed_to = "Container<String, AnyState>")]
+struct Container<T, State> {
+    data: T,
+    state: PhantomData<State>,
+}
+
+fn main() {
+    let numbers = Container::<u32, Raw> { data: 1, state: PhantomData };
+    let _: &Container<u32, AnyState> = numbers.coerce();
+}
@@ -0,0 +1,14 @@
+use std::marker::PhantomData;
+use phantom_coerce::Coerce;
+
+struct Data;
+struct AnyKind;
+
+#[derive(Coerce)]
+#[coerce(borrowed_from = "Frame<Data, 16>", borrowed_to = "Frame<AnyKind, { 8 * 4 }>")]
+struct Frame<Kind, const N: usize> {
+    kind: PhantomData<Kind>,
+    bytes: [u8; N],
+}
+
+fn main() {}
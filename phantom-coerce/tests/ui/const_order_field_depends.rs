@@ -0,0 +1,11 @@
+// This should fail because changing LEVEL would change the `slots` field's type
+
+use phantom_coerce::Coerce;
+
+#[derive(Coerce)]
+#[coerce(owned_from = "Quota<LEVEL>", owned_to = "Quota<TO>", when = "TO <= LEVEL")]
+struct Quota<const LEVEL: usize> {
+    slots: [u8; LEVEL],
+}
+
+fn main() {}
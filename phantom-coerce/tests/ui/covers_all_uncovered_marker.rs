@@ -0,0 +1,26 @@
+use phantom_coerce::Coerce;
+use std::marker::PhantomData;
+
+trait Status: RequestCoversStatus {}
+
+struct Pending;
+struct Approved;
+struct Rejected;
+struct AnyStatus;
+
+impl Status for Pending {}
+impl Status for Approved {}
+impl Status for Rejected {}
+
+#[derive(Coerce)]
+#[coerce(
+    borrowed_from = "Request<Pending | Approved>",
+    borrowed_to = "Request<AnyStatus>",
+    covers_all = "Status"
+)]
+struct Request<State> {
+    marker: PhantomData<State>,
+    id: u32,
+}
+
+fn main() {}
@@ -0,0 +1,22 @@
+use phantom_coerce::Coerce;
+use std::marker::PhantomData;
+
+trait Status {}
+
+struct Pending;
+struct AnyStatus;
+
+impl Status for Pending {}
+
+#[derive(Coerce)]
+#[coerce(
+    borrowed_from = "Request<Pending>",
+    borrowed_to = "Request<AnyStatus>",
+    covers_all = "Status"
+)]
+struct Request<State> {
+    marker: PhantomData<State>,
+    id: u32,
+}
+
+fn main() {}
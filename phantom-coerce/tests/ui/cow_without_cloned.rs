@@ -0,0 +1,21 @@
+use phantom_coerce::Coerce;
+use std::marker::PhantomData;
+
+#[derive(Clone)]
+struct Absolute;
+#[derive(Clone)]
+struct Relative;
+#[derive(Clone)]
+struct UnknownBase;
+
+// The cloned pair starts from `Relative`, so no pair is declared in both modes
+#[derive(Coerce, Clone)]
+#[coerce(cow)]
+#[coerce(borrowed_from = "TypedPath<Absolute>", borrowed_to = "TypedPath<UnknownBase>")]
+#[coerce(cloned_from = "TypedPath<Relative>", cloned_to = "TypedPath<UnknownBase>")]
+struct TypedPath<Base> {
+    base: PhantomData<Base>,
+    path: String,
+}
+
+fn main() {}
@@ -0,0 +1,24 @@
+// This should fail because the deep field's own type doesn't declare the matching coercion
+
+use std::marker::PhantomData;
+use phantom_coerce::Coerce;
+
+struct Absolute;
+struct Relative;
+struct UnknownBase;
+
+#[derive(Coerce)]
+#[coerce(borrowed_from = "TypedPath<Absolute>", borrowed_to = "TypedPath<UnknownBase>")]
+struct TypedPath<Base> {
+    base: PhantomData<Base>,
+    path: String,
+}
+
+#[derive(Coerce)]
+#[coerce(borrowed_from = "Job<Relative>", borrowed_to = "Job<UnknownBase>")]
+struct Job<Base> {
+    #[coerce(deep)]
+    input: TypedPath<Base>,
+}
+
+fn main() {}
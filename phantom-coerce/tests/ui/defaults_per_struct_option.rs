@@ -0,0 +1,17 @@
+#[phantom_coerce::defaults(trait_vis = "pub", trait_name = "Path")]
+mod paths {
+    use phantom_coerce::Coerce;
+    use std::marker::PhantomData;
+
+    pub struct Absolute;
+    pub struct UnknownBase;
+
+    #[derive(Coerce)]
+    #[coerce(borrowed_from = "TypedPath<Absolute>", borrowed_to = "TypedPath<UnknownBase>")]
+    pub struct TypedPath<Base> {
+        base: PhantomData<Base>,
+        path: String,
+    }
+}
+
+fn main() {}
@@ -0,0 +1,28 @@
+#![deny(deprecated)]
+
+use phantom_coerce::Coerce;
+use std::marker::PhantomData;
+
+struct LegacyStatus;
+struct AnyStatus;
+
+#[derive(Coerce)]
+#[repr(C)]
+#[coerce(
+    owned_from = "Handle<LegacyStatus>",
+    owned_to = "Handle<AnyStatus>",
+    extern_c = "legacy_handle_into_any",
+    deprecated = "validate the handle first"
+)]
+struct Handle<Status> {
+    marker: PhantomData<Status>,
+    id: u64,
+}
+
+fn main() {
+    let handle = Handle::<LegacyStatus> {
+        marker: PhantomData,
+        id: 7,
+    };
+    let _any = legacy_handle_into_any(handle);
+}
@@ -0,0 +1,35 @@
+use phantom_coerce::tag::{DispatchTable, MarkerTag};
+use phantom_coerce::{Coerce, dispatch_table};
+use std::marker::PhantomData;
+
+struct Json;
+struct Xml;
+struct AnyFormat;
+
+impl MarkerTag for Json {
+    const TAG: &'static str = "json";
+}
+
+impl MarkerTag for Xml {
+    const TAG: &'static str = "xml";
+}
+
+#[derive(Coerce)]
+#[coerce(owned_from = "Message<Json | Xml>", owned_to = "Message<AnyFormat>")]
+#[coerce(tag = "Format")]
+struct Message<Format> {
+    marker: PhantomData<Format>,
+    body: String,
+}
+
+fn keys(message: &Message<AnyFormat>) -> usize {
+    message.body.len()
+}
+
+static ROUTES: DispatchTable<Message<AnyFormat>, usize> = dispatch_table!(Message<AnyFormat> {
+    Json => keys,
+});
+
+fn main() {
+    let _ = ROUTES;
+}
@@ -0,0 +1,16 @@
+use phantom_coerce::Coerce;
+use std::marker::PhantomData;
+
+struct Json;
+struct Xml;
+struct AnyFormat;
+
+// `Json => AnyFormat` is declared by both attributes
+#[derive(Coerce)]
+#[coerce(borrowed_from = "Message<Json | Xml>", borrowed_to = "Message<AnyFormat>")]
+#[coerce(borrowed_from = "Message<Json>", borrowed_to = "Message<AnyFormat>")]
+struct Message<Format> {
+    marker: PhantomData<Format>,
+}
+
+fn main() {}
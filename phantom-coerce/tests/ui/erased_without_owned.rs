@@ -0,0 +1,16 @@
+use phantom_coerce::Coerce;
+use std::marker::PhantomData;
+
+struct Validated;
+struct AnyStatus;
+
+// `erase` needs an owned coercion alongside the borrowed one
+#[derive(Coerce)]
+#[coerce(borrowed_from = "Request<Validated>", borrowed_to = "Request<AnyStatus>")]
+#[coerce(erased = "Request<AnyStatus>")]
+struct Request<Status> {
+    marker: PhantomData<Status>,
+    url: String,
+}
+
+fn main() {}
@@ -0,0 +1,18 @@
+use phantom_coerce::Coerce;
+use std::marker::PhantomData;
+
+struct Validated;
+struct AnyStatus;
+
+#[derive(Coerce)]
+#[coerce(
+    owned_from = "Handle<Validated>",
+    owned_to = "Handle<AnyStatus>",
+    extern_c = "handle_into_any"
+)]
+struct Handle<Status> {
+    marker: PhantomData<Status>,
+    id: u64,
+}
+
+fn main() {}
@@ -0,0 +1,17 @@
+use phantom_coerce::Coerce;
+use std::marker::PhantomData;
+
+struct Absolute;
+struct UnknownBase;
+struct File;
+struct UnknownType;
+
+#[derive(Coerce)]
+#[coerce(borrowed_from = "TypedPath<Absolute, File>", borrowed_to = "TypedPath<UnknownBase, UnknownType>")]
+struct TypedPath<Base, #[coerce(fixed)] Type> {
+    base: PhantomData<Base>,
+    ty: PhantomData<Type>,
+    path: String,
+}
+
+fn main() {}
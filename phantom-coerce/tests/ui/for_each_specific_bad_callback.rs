@@ -0,0 +1,16 @@
+use phantom_coerce::Coerce;
+use std::marker::PhantomData;
+
+struct Json;
+struct AnyFormat;
+
+#[derive(Coerce)]
+#[coerce(owned_from = "Message<Json>", owned_to = "Message<AnyFormat>")]
+struct Message<Format> {
+    marker: PhantomData<Format>,
+    content: String,
+}
+
+fn main() {
+    phantom_coerce::for_each_specific!(Message, |A, B| ());
+}
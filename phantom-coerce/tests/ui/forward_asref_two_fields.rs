@@ -0,0 +1,17 @@
+use phantom_coerce::Coerce;
+use std::marker::PhantomData;
+use std::path::PathBuf;
+
+struct Absolute;
+struct UnknownBase;
+
+#[derive(Coerce)]
+#[coerce(borrowed_from = "TypedPath<Absolute>", borrowed_to = "TypedPath<UnknownBase>")]
+#[coerce(forward_asref = "std::path::Path")]
+struct TypedPath<Base> {
+    base: PhantomData<Base>,
+    path: PathBuf,
+    display: String,
+}
+
+fn main() {}
@@ -0,0 +1,20 @@
+use phantom_coerce::Coerce;
+use std::marker::PhantomData;
+
+struct Validated;
+struct AnyStatus;
+
+fn audit<Status>(_: &Request<Status>) {}
+
+#[derive(Coerce)]
+#[coerce(
+    borrowed_from = "Request<Validated>",
+    borrowed_to = "Request<AnyStatus>",
+    guard = "audit"
+)]
+struct Request<Status> {
+    marker: PhantomData<Status>,
+    url: String,
+}
+
+fn main() {}
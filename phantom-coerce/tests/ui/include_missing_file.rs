@@ -0,0 +1,11 @@
+use phantom_coerce::Coerce;
+use std::marker::PhantomData;
+
+#[derive(Coerce)]
+#[coerce(include = "coercions/missing.coerce")]
+struct TypedPath<Base> {
+    base: PhantomData<Base>,
+    path: String,
+}
+
+fn main() {}
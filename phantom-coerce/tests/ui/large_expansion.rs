@@ -0,0 +1,27 @@
+use phantom_coerce::Coerce;
+use std::marker::PhantomData;
+
+struct A;
+struct B;
+struct C;
+struct D;
+struct E;
+struct F;
+struct G;
+struct H;
+struct I;
+struct Any;
+
+// 9 x 9 sources expand to 81 coercions, above the limit
+#[derive(Coerce)]
+#[coerce(deny(large_expansion))]
+#[coerce(
+    borrowed_from = "Grid<A | B | C | D | E | F | G | H | I, A | B | C | D | E | F | G | H | I>",
+    borrowed_to = "Grid<Any, Any>"
+)]
+struct Grid<Row, Col> {
+    row: PhantomData<Row>,
+    col: PhantomData<Col>,
+}
+
+fn main() {}
@@ -0,0 +1,21 @@
+mod paths {
+    use phantom_coerce::Coerce;
+    use std::marker::PhantomData;
+
+    pub struct Absolute;
+    pub struct Relative;
+    pub struct UnknownBase;
+
+    #[derive(Coerce)]
+    #[coerce(borrowed_from = "TypedPath<Absolute>", borrowed_to = "TypedPath<UnknownBase>")]
+    pub struct TypedPath<Base> {
+        base: PhantomData<Base>,
+    }
+}
+
+use paths::{Relative, UnknownBase};
+
+#[phantom_coerce::coercions(borrowed TypedPath<Relative> => TypedPath<UnknownBase>;)]
+impl<Base> paths::TypedPath<Base> {}
+
+fn main() {}
@@ -0,0 +1,32 @@
+use phantom_coerce::tag::{MarkerTag, Tagged};
+use phantom_coerce::{Coerce, match_specific};
+use std::marker::PhantomData;
+
+struct Json;
+struct Xml;
+struct AnyFormat;
+
+impl MarkerTag for Json {
+    const TAG: &'static str = "json";
+}
+
+impl MarkerTag for Xml {
+    const TAG: &'static str = "xml";
+}
+
+#[derive(Coerce)]
+#[coerce(owned_from = "Message<Json | Xml>", owned_to = "Message<AnyFormat>")]
+#[coerce(tag = "Format")]
+struct Message<Format> {
+    marker: PhantomData<Format>,
+    content: String,
+}
+
+fn len(erased: &Tagged<Message<AnyFormat>>) -> usize {
+    match_specific!(erased, Message {
+        Json(msg) => msg.content.len(),
+        else => 0,
+    })
+}
+
+fn main() {}
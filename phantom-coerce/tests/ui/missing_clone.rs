@@ -1,4 +1,4 @@
-// This should fail because the type doesn't implement Clone but uses cloned coercion
+// This should fail because a data field doesn't implement Clone but uses cloned coercion
 
 use std::marker::PhantomData;
 use phantom_coerce::Coerce;
@@ -7,17 +7,19 @@ struct Marker1;
 struct Marker2;
 
 // Missing #[derive(Clone)]
+struct Handle;
+
 #[derive(Coerce)]
 #[coerce(cloned_from = "NoClone<Marker1>", cloned_to = "NoClone<Marker2>")]
 struct NoClone<M> {
     phantom: PhantomData<M>,
-    value: String,
+    handle: Handle,
 }
 
 fn main() {
     let no_clone = NoClone::<Marker1> {
         phantom: PhantomData,
-        value: "test".to_string(),
+        handle: Handle,
     };
 
     let _: NoClone<Marker2> = no_clone.to_coerced();
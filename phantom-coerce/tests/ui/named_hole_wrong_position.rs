@@ -0,0 +1,14 @@
+use phantom_coerce::Coerce;
+use std::marker::PhantomData;
+
+struct Raw;
+struct AnyState;
+
+#[derive(Coerce)]
+#[coerce(borrowed_from = "Container<Raw, T>", borrowed_to = "Container<AnyState, T>")]
+struct Container<T, State> {
+    item: PhantomData<T>,
+    state: PhantomData<State>,
+}
+
+fn main() {}
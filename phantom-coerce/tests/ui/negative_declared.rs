@@ -0,0 +1,18 @@
+use phantom_coerce::Coerce;
+use std::marker::PhantomData;
+
+struct Validated;
+struct AnyStatus;
+
+#[derive(Coerce)]
+#[coerce(owned_from = "Request<Validated>", owned_to = "Request<AnyStatus>")]
+struct Request<Status> {
+    marker: PhantomData<Status>,
+    url: String,
+}
+
+phantom_coerce::compile_fail_tests! {
+    owned Request<Validated> => Request<AnyStatus>;
+}
+
+fn main() {}
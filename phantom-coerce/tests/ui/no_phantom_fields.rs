@@ -0,0 +1,17 @@
+// This should fail because no PhantomData field backs the coerced parameter
+
+use phantom_coerce::Coerce;
+
+type Marker<T> = std::marker::PhantomData<T>;
+
+struct Meters;
+struct AnyUnit;
+
+#[derive(Coerce)]
+#[coerce(borrowed_from = "Reading<Meters>", borrowed_to = "Reading<AnyUnit>")]
+struct Reading<Unit> {
+    unit: Marker<Unit>,
+    value: f64,
+}
+
+fn main() {}
@@ -0,0 +1,17 @@
+// This should fail because a non-PhantomData field would change type without #[coerce(deep)]
+
+use phantom_coerce::Coerce;
+use std::marker::PhantomData;
+
+struct Meters;
+struct AnyUnit;
+
+#[derive(Coerce)]
+#[coerce(owned_from = "Reading<Meters>", owned_to = "Reading<AnyUnit>")]
+struct Reading<Unit> {
+    marker: PhantomData<Unit>,
+    unit: Vec<Unit>,
+    value: f64,
+}
+
+fn main() {}
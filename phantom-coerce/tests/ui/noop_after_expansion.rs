@@ -0,0 +1,14 @@
+use phantom_coerce::Coerce;
+use std::marker::PhantomData;
+
+struct Json;
+struct Xml;
+
+// `Json => Json` is a no-op once the alternatives are expanded
+#[derive(Coerce)]
+#[coerce(owned_from = "Message<Json | Xml>", owned_to = "Message<Json>")]
+struct Message<Format> {
+    marker: PhantomData<Format>,
+}
+
+fn main() {}
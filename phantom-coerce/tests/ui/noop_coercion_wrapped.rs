@@ -0,0 +1,18 @@
+use phantom_coerce::Coerce;
+use std::marker::PhantomData;
+
+struct Absolute;
+
+// Spacing differs, but both patterns name the same type
+#[derive(Coerce)]
+#[coerce(
+    owned_from = "TypedPath<Absolute>",
+    owned_to = "TypedPath<
+        Absolute,
+    >"
+)]
+struct TypedPath<Base> {
+    base: PhantomData<Base>,
+}
+
+fn main() {}
@@ -0,0 +1,8 @@
+use phantom_coerce::PhantomMarker;
+
+#[derive(PhantomMarker)]
+struct Json {
+    pretty: bool,
+}
+
+fn main() {}
@@ -0,0 +1,32 @@
+use phantom_coerce::Coerce;
+use std::marker::PhantomData;
+
+struct Absolute;
+struct UnknownBase;
+
+struct Tag<T> {
+    marker: PhantomData<T>,
+    id: u8,
+}
+
+impl<T> Default for Tag<T> {
+    fn default() -> Self {
+        Self { marker: PhantomData, id: 0 }
+    }
+}
+
+#[derive(Coerce)]
+#[coerce(borrowed_from = "TypedPath<Absolute>", borrowed_to = "TypedPath<UnknownBase>")]
+#[coerce(phantom_types = "Tag")]
+struct TypedPath<Base> {
+    base: Tag<Base>,
+    path: String,
+}
+
+fn main() {
+    let path = TypedPath::<Absolute> {
+        base: Tag::default(),
+        path: String::new(),
+    };
+    let _: &TypedPath<UnknownBase> = path.coerce();
+}
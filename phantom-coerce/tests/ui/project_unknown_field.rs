@@ -0,0 +1,21 @@
+// This should fail because project(...) names a field that doesn't exist
+
+use std::marker::PhantomData;
+use phantom_coerce::Coerce;
+
+struct Marker1;
+struct Marker2;
+
+#[derive(Coerce)]
+#[coerce(borrowed_from = "Inner<Marker1>", borrowed_to = "Inner<Marker2>")]
+struct Inner<M> {
+    phantom: PhantomData<M>,
+}
+
+#[derive(Coerce)]
+#[coerce(project(field = "missing", to = "Inner<Marker2>"))]
+struct Outer {
+    inner: Inner<Marker1>,
+}
+
+fn main() {}
@@ -0,0 +1,14 @@
+use phantom_coerce::Coerce;
+use std::marker::PhantomData;
+
+struct Raw;
+struct AnyStage;
+
+#[derive(Coerce)]
+#[coerce(borrowed_from = "Pipeline<.., Raw>", borrowed_to = "Pipeline<.., AnyStage>")]
+struct Pipeline<Format, Stage> {
+    format: PhantomData<Format>,
+    stage: PhantomData<Stage>,
+}
+
+fn main() {}
@@ -0,0 +1,18 @@
+// This should fail because borrowed coercions require a transmute, which safe mode forbids
+
+use std::marker::PhantomData;
+use phantom_coerce::Coerce;
+
+struct Marker1;
+struct Marker2;
+
+#[derive(Coerce)]
+#[coerce(safe)]
+#[coerce(owned_from = "Container<Marker1>", owned_to = "Container<Marker2>")]
+#[coerce(borrowed_from = "Container<Marker1>", borrowed_to = "Container<Marker2>")]
+struct Container<M> {
+    phantom: PhantomData<M>,
+    value: String,
+}
+
+fn main() {}
@@ -0,0 +1,15 @@
+use phantom_coerce::Coerce;
+use std::marker::PhantomData;
+
+struct Json;
+struct AnyFormat;
+
+#[derive(Coerce)]
+#[coerce(owned_from = "Message<Json>", owned_to = "Message<AnyFormat>")]
+#[coerce(tag_field = "format")]
+struct Message<Format> {
+    marker: PhantomData<Format>,
+    content: String,
+}
+
+fn main() {}
@@ -0,0 +1,27 @@
+mod paths {
+    use phantom_coerce::Coerce;
+    use std::marker::PhantomData;
+
+    pub struct Absolute;
+    pub struct UnknownBase;
+
+    #[derive(Coerce)]
+    #[coerce(trait_vis = "pub")]
+    #[coerce(borrowed_from = "TypedPath<Absolute>", borrowed_to = "TypedPath<UnknownBase>")]
+    pub struct TypedPath<Base> {
+        base: PhantomData<Base>,
+    }
+}
+
+use paths::{CoerceRefTypedPath, TypedPath, UnknownBase};
+
+struct Outsider;
+
+// Only the derive may implement the coercion traits
+impl CoerceRefTypedPath<TypedPath<UnknownBase>> for Outsider {
+    fn coerce(&self) -> &TypedPath<UnknownBase> {
+        unimplemented!()
+    }
+}
+
+fn main() {}
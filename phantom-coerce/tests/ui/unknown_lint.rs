@@ -0,0 +1,14 @@
+use phantom_coerce::Coerce;
+use std::marker::PhantomData;
+
+struct Json;
+struct AnyFormat;
+
+#[derive(Coerce)]
+#[coerce(allow(noop, overlap))]
+#[coerce(borrowed_from = "Message<Json>", borrowed_to = "Message<AnyFormat>")]
+struct Message<Format> {
+    marker: PhantomData<Format>,
+}
+
+fn main() {}
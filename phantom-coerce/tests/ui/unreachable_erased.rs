@@ -0,0 +1,20 @@
+use phantom_coerce::Coerce;
+use std::marker::PhantomData;
+
+struct Network;
+struct Disk;
+struct Cache;
+struct Local;
+struct AnySource;
+
+#[derive(Coerce)]
+#[coerce(borrowed_from = "Item<Network | Disk>", borrowed_to = "Item<AnySource>")]
+#[coerce(owned_from = "Item<Network | Disk>", owned_to = "Item<AnySource>")]
+#[coerce(borrowed_from = "Item<Cache>", borrowed_to = "Item<Local>")]
+#[coerce(erased = "Item<AnySource>")]
+struct Item<Source> {
+    marker: PhantomData<Source>,
+    bytes: Vec<u8>,
+}
+
+fn main() {}
@@ -0,0 +1,14 @@
+use std::marker::PhantomData;
+use phantom_coerce::Coerce;
+
+struct Utf8;
+struct AnyEncoding;
+
+#[derive(Coerce)]
+#[coerce(owned_from = "TypedStr<Utf8>", owned_to = "TypedStr<AnyEncoding>")]
+struct TypedStr<Encoding> {
+    tag: PhantomData<Encoding>,
+    data: str,
+}
+
+fn main() {}
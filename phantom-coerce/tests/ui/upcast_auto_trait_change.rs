@@ -0,0 +1,23 @@
+use phantom_coerce::Upcast;
+use std::marker::PhantomData;
+use std::rc::Rc;
+
+// Not Send or Sync: a handle pinned to the thread that created it
+struct LocalOnly(PhantomData<Rc<()>>);
+struct AnyThread;
+
+// Every marker upcasts to `AnyThread`, which is Send and Sync
+#[derive(Upcast)]
+#[upcast(any = "AnyThread")]
+struct Handle<Affinity> {
+    marker: PhantomData<Affinity>,
+    id: u64,
+}
+
+fn main() {
+    let local = Handle::<LocalOnly> {
+        marker: PhantomData,
+        id: 1,
+    };
+    let _erased: Handle<AnyThread> = local.into_coerced();
+}
@@ -0,0 +1,14 @@
+use phantom_coerce::Upcast;
+use std::marker::PhantomData;
+
+struct UnknownBase;
+
+#[derive(Upcast)]
+#[upcast(any = "UnknownBase")]
+struct TypedPath<Base, Type> {
+    base: PhantomData<Base>,
+    ty: PhantomData<Type>,
+    path: String,
+}
+
+fn main() {}
@@ -0,0 +1,21 @@
+use phantom_coerce::Upcast;
+use std::marker::PhantomData;
+
+struct Validated;
+struct Unvalidated;
+struct AnyStatus;
+
+#[derive(Upcast)]
+#[upcast(any = "AnyStatus", from = "Validated")]
+struct Response<Status> {
+    marker: PhantomData<Status>,
+    body: Vec<u8>,
+}
+
+fn main() {
+    let response = Response::<Unvalidated> {
+        marker: PhantomData,
+        body: Vec::new(),
+    };
+    let _generic: &Response<AnyStatus> = response.coerce();
+}
@@ -0,0 +1,21 @@
+use phantom_coerce::Coerce;
+use std::marker::PhantomData;
+use std::rc::Rc;
+
+// Not Send or Sync: a handle pinned to the thread that created it
+struct LocalOnly(PhantomData<Rc<()>>);
+struct AnyThread;
+
+// The `when` rule only governs LEVEL; the affinity marker still changes
+#[derive(Coerce)]
+#[coerce(
+    borrowed_from = "Permission<LocalOnly, LEVEL>",
+    borrowed_to = "Permission<AnyThread, TO>",
+    when = "TO <= LEVEL"
+)]
+struct Permission<Affinity, const LEVEL: u8> {
+    marker: PhantomData<Affinity>,
+    id: u64,
+}
+
+fn main() {}
@@ -0,0 +1,58 @@
+use phantom_coerce::Coerce;
+use std::marker::PhantomData;
+use std::mem::MaybeUninit;
+
+struct Validated;
+struct AnyStatus;
+
+#[derive(Coerce)]
+#[coerce(
+    borrowed_from = "Request<Validated>",
+    borrowed_to = "Request<AnyStatus>"
+)]
+#[coerce(owned_from = "Request<Validated>", owned_to = "Request<AnyStatus>")]
+struct Request<Status> {
+    marker: PhantomData<Status>,
+    url: String,
+}
+
+#[test]
+fn owned_slot() {
+    let mut slot = MaybeUninit::<Request<Validated>>::uninit();
+    slot.write(Request {
+        marker: PhantomData,
+        url: "/a".to_string(),
+    });
+    let slot: MaybeUninit<Request<AnyStatus>> = Request::coerce_uninit(slot);
+    // SAFETY: The slot was initialized before the coercion.
+    let request = unsafe { slot.assume_init() };
+    assert_eq!(request.url, "/a");
+}
+
+#[test]
+fn uninitialized_slot() {
+    // No value is read, so an uninitialized slot is fine
+    let slot = MaybeUninit::<Request<Validated>>::uninit();
+    let mut slot = Request::coerce_uninit::<Request<AnyStatus>>(slot);
+    slot.write(Request {
+        marker: PhantomData,
+        url: "/b".to_string(),
+    });
+    // SAFETY: Written just above.
+    assert_eq!(unsafe { slot.assume_init_ref() }.url, "/b");
+    // SAFETY: Still initialized; dropped exactly once here.
+    unsafe { slot.assume_init_drop() };
+}
+
+#[test]
+fn borrowed_slot() {
+    let slot = MaybeUninit::new(Request::<Validated> {
+        marker: PhantomData,
+        url: "/c".to_string(),
+    });
+    let generic: &MaybeUninit<Request<AnyStatus>> = Request::coerce_uninit_ref(&slot);
+    // SAFETY: The slot is initialized.
+    assert_eq!(unsafe { generic.assume_init_ref() }.url, "/c");
+    // SAFETY: Initialized, and not used again.
+    drop(unsafe { slot.assume_init() });
+}
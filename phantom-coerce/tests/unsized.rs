@@ -0,0 +1,115 @@
+use phantom_coerce::{Coerce, Coercible, Upcast};
+use std::fmt::Debug;
+use std::marker::PhantomData;
+
+struct AnyBrand;
+
+// Unpin too, so erasing `str` keeps every auto trait
+type Erased = dyn Debug + Send + Sync + Unpin;
+
+// Unsized types brand values without declaring a marker struct for each
+#[derive(Coerce)]
+#[coerce(borrowed_from = "Branded<str | [u8]>", borrowed_to = "Branded<Erased>")]
+#[coerce(
+    owned_from = "Branded<str | [u8] | Erased>",
+    owned_to = "Branded<AnyBrand>"
+)]
+#[coerce(cloned_from = "Branded<str, _>", cloned_to = "Branded<Erased, _>")]
+struct Branded<Brand: ?Sized, Extra: ?Sized = ()> {
+    brand: PhantomData<Brand>,
+    extra: PhantomData<Extra>,
+    id: u32,
+}
+
+// `derive(Clone)` would require `Brand: Clone`, which unsized types can't implement
+impl<Brand: ?Sized, Extra: ?Sized> Clone for Branded<Brand, Extra> {
+    fn clone(&self) -> Self {
+        branded(self.id)
+    }
+}
+
+fn branded<Brand: ?Sized, Extra: ?Sized>(id: u32) -> Branded<Brand, Extra> {
+    Branded {
+        brand: PhantomData,
+        extra: PhantomData,
+        id,
+    }
+}
+
+#[test]
+fn unsized_markers() {
+    let text = branded::<str, ()>(1);
+    let view: &Branded<Erased> = text.coerce();
+    assert_eq!(view.id, 1);
+
+    let bytes = branded::<[u8], ()>(2);
+    let erased: Branded<AnyBrand> = bytes.into_coerced();
+    assert_eq!(erased.id, 2);
+
+    let erased: Branded<AnyBrand> = Coercible::into_coerced(branded::<Erased, ()>(3));
+    assert_eq!(erased.id, 3);
+}
+
+#[test]
+fn unsized_type_holes() {
+    let text = branded::<str, [u8]>(4);
+    let cloned: Branded<Erased, [u8]> = text.to_coerced();
+    assert_eq!(cloned.id, 4);
+}
+
+#[derive(Upcast)]
+#[upcast(any = "Erased")]
+struct Tag<Brand: ?Sized> {
+    brand: PhantomData<Brand>,
+    name: &'static str,
+}
+
+#[test]
+fn upcast_unsized() {
+    let tag = Tag::<str> {
+        brand: PhantomData,
+        name: "text",
+    };
+    let view: &Tag<Erased> = tag.coerce();
+    assert_eq!(view.name, "text");
+
+    let owned: Tag<Erased> = tag.into_coerced();
+    assert_eq!(owned.name, "text");
+}
+
+// Bounds in the where clause reach the hole parameters like inline ones
+#[derive(Coerce)]
+#[coerce(trait_vis = "pub(crate)")]
+#[coerce(borrowed_from = "Slot<str, _>", borrowed_to = "Slot<Erased, _>")]
+#[coerce(owned_from = "Slot<str, _>", owned_to = "Slot<AnyBrand, _>")]
+#[coerce(cloned_from = "Slot<[u8], _>", cloned_to = "Slot<Erased, _>")]
+struct Slot<Brand, Value>
+where
+    Brand: ?Sized,
+    Value: Default + Clone,
+{
+    brand: PhantomData<Brand>,
+    value: Value,
+}
+
+impl<Brand: ?Sized, Value: Default + Clone> Slot<Brand, Value> {
+    fn empty() -> Self {
+        Slot {
+            brand: PhantomData,
+            value: Value::default(),
+        }
+    }
+}
+
+#[test]
+fn where_clause_bounds_on_type_holes() {
+    let slot = Slot::<str, u32>::empty();
+    let view: &Slot<Erased, u32> = slot.coerce();
+    assert_eq!(view.value, 0);
+    let owned: Slot<AnyBrand, u32> = slot.into_coerced();
+    assert_eq!(owned.value, 0);
+
+    let bytes = Slot::<[u8], String>::empty();
+    let cloned: Slot<Erased, String> = bytes.to_coerced();
+    assert!(cloned.value.is_empty());
+}
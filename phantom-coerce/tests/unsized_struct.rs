@@ -0,0 +1,66 @@
+use phantom_coerce::Coerce;
+use std::marker::PhantomData;
+
+struct Utf8;
+struct Ascii;
+struct AnyEncoding;
+
+#[derive(Coerce)]
+#[coerce(
+    borrowed_from = "TypedStr<Utf8 | Ascii>",
+    borrowed_to = "TypedStr<AnyEncoding>",
+    asref
+)]
+#[repr(transparent)]
+struct TypedStr<Encoding> {
+    tag: PhantomData<Encoding>,
+    data: str,
+}
+
+impl<Encoding> TypedStr<Encoding> {
+    fn new(data: &str) -> &Self {
+        // SAFETY: `TypedStr` is a transparent wrapper around `str`.
+        unsafe { &*(data as *const str as *const Self) }
+    }
+}
+
+struct Checked;
+struct Unchecked;
+
+#[derive(Coerce)]
+#[coerce(
+    borrowed_from = "Frames<Checked, _>",
+    borrowed_to = "Frames<Unchecked, _>"
+)]
+#[coerce(layout_checks)]
+#[repr(transparent)]
+struct Frames<State, T> {
+    state: PhantomData<State>,
+    items: [T],
+}
+
+impl<State, T> Frames<State, T> {
+    fn new(items: &[T]) -> &Self {
+        // SAFETY: `Frames` is a transparent wrapper around `[T]`.
+        unsafe { &*(items as *const [T] as *const Self) }
+    }
+}
+
+#[test]
+fn unsized_str_keeps_length() {
+    let utf8 = TypedStr::<Utf8>::new("héllo");
+    let any: &TypedStr<AnyEncoding> = utf8.coerce();
+    assert_eq!(&any.data, "héllo");
+    assert_eq!(std::mem::size_of_val(any), "héllo".len());
+
+    let ascii = TypedStr::<Ascii>::new("hi");
+    let any: &TypedStr<AnyEncoding> = ascii.as_ref();
+    assert_eq!(&any.data, "hi");
+}
+
+#[test]
+fn unsized_slice_with_type_hole() {
+    let checked = Frames::<Checked, u16>::new(&[1, 2, 3]);
+    let unchecked = checked.coerce::<Frames<Unchecked, u16>>();
+    assert_eq!(&unchecked.items, &[1, 2, 3]);
+}
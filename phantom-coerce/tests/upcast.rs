@@ -0,0 +1,65 @@
+use phantom_coerce::Upcast;
+use std::marker::PhantomData;
+
+#[derive(Clone)]
+struct Validated;
+#[derive(Clone)]
+struct Unvalidated;
+#[derive(Clone)]
+struct AnyStatus;
+
+// Every marker upcasts to the designated "any" marker
+#[derive(Upcast, Clone)]
+#[upcast(any = "AnyStatus", cloned)]
+struct Request<Status> {
+    marker: PhantomData<Status>,
+    url: String,
+}
+
+fn request<Status>(url: &str) -> Request<Status> {
+    Request {
+        marker: PhantomData,
+        url: url.to_string(),
+    }
+}
+
+#[test]
+fn upcast_any_marker() {
+    let validated = request::<Validated>("https://a.example.com");
+    let borrowed: &Request<AnyStatus> = validated.coerce();
+    assert_eq!(borrowed.url, "https://a.example.com");
+
+    let cloned = validated.to_coerced::<Request<AnyStatus>>();
+    assert_eq!(cloned.url, validated.url);
+
+    let unvalidated = request::<Unvalidated>("https://b.example.com");
+    let owned: Request<AnyStatus> = unvalidated.into_coerced();
+    assert_eq!(owned.url, "https://b.example.com");
+}
+
+#[test]
+fn heterogeneous_collection() {
+    let requests: Vec<Request<AnyStatus>> = vec![
+        request::<Validated>("https://a.example.com").into_coerced(),
+        request::<Unvalidated>("https://b.example.com").into_coerced(),
+    ];
+    assert_eq!(requests.len(), 2);
+}
+
+// `from` restricts which markers may upcast
+#[derive(Upcast)]
+#[upcast(any = "AnyStatus", from = "Validated")]
+struct Response<Status> {
+    marker: PhantomData<Status>,
+    body: Vec<u8>,
+}
+
+#[test]
+fn upcast_listed_marker() {
+    let response = Response::<Validated> {
+        marker: PhantomData,
+        body: vec![1, 2],
+    };
+    let generic: &Response<AnyStatus> = response.coerce();
+    assert_eq!(generic.body, vec![1, 2]);
+}
@@ -0,0 +1,83 @@
+#![cfg(feature = "verify")]
+
+use phantom_coerce::Coerce;
+use std::marker::PhantomData;
+
+struct Validated;
+struct Pending;
+struct AnyStatus;
+
+#[derive(Coerce)]
+#[coerce(
+    borrowed_from = "Session<Validated | Pending>",
+    borrowed_to = "Session<AnyStatus>"
+)]
+#[coerce(owned_from = "Session<Validated>", owned_to = "Session<AnyStatus>")]
+struct Session<Status> {
+    marker: PhantomData<Status>,
+    user: String,
+}
+
+fn session<Status>(user: &str) -> Session<Status> {
+    Session {
+        marker: PhantomData,
+        user: user.to_string(),
+    }
+}
+
+#[test]
+fn borrowed_wrappers_are_identity() {
+    let validated = session::<Validated>("alice");
+    let generic = phantom_coerce_verify_session_borrowed_0(&validated);
+    assert!(std::ptr::eq(
+        (generic as *const Session<AnyStatus>).cast::<u8>(),
+        (&validated as *const Session<Validated>).cast::<u8>(),
+    ));
+
+    let pending = session::<Pending>("bob");
+    let generic = phantom_coerce_verify_session_borrowed_1(&pending);
+    assert!(std::ptr::eq(
+        (generic as *const Session<AnyStatus>).cast::<u8>(),
+        (&pending as *const Session<Pending>).cast::<u8>(),
+    ));
+}
+
+#[test]
+fn owned_wrapper_keeps_data_in_place() {
+    let validated = session::<Validated>("alice");
+    let buffer = validated.user.as_ptr();
+
+    let generic = phantom_coerce_verify_session_owned_0(validated);
+    assert_eq!(generic.user.as_ptr(), buffer);
+    assert_eq!(generic.user, "alice");
+}
+
+// A second `Session` would define the same unmangled symbols without a prefix
+mod audit {
+    use phantom_coerce::Coerce;
+    use std::marker::PhantomData;
+
+    struct Recorded;
+    struct AnyStatus;
+
+    #[derive(Coerce)]
+    #[coerce(verify_prefix = "audit")]
+    #[coerce(
+        borrowed_from = "Session<Recorded>",
+        borrowed_to = "Session<AnyStatus>"
+    )]
+    struct Session<Status> {
+        marker: PhantomData<Status>,
+        id: u64,
+    }
+
+    #[test]
+    fn prefixed_wrappers_keep_same_named_structs_apart() {
+        let recorded = Session::<Recorded> {
+            marker: PhantomData,
+            id: 7,
+        };
+        let generic = phantom_coerce_verify_audit_session_borrowed_0(&recorded);
+        assert_eq!(generic.id, 7);
+    }
+}